@@ -40,6 +40,7 @@ impl ConnHandler for TestConnHandler {
         let status = match status {
             ConnStatus::Closing => "status: closing",
             ConnStatus::Closed => "status: closed",
+            ConnStatus::Failed => "status: failed",
             ConnStatus::Connecting => "status: connecting",
             ConnStatus::Connected => "status: connected",
             ConnStatus::Disconnected => "status: disconnected",
@@ -63,6 +64,7 @@ impl QueueHandler for TestQueueHandler {
         let status = match status {
             QueueStatus::Closing => "status: closing",
             QueueStatus::Closed => "status: closed",
+            QueueStatus::Failed => "status: failed",
             QueueStatus::Connecting => "status: connecting",
             QueueStatus::Connected => "status: connected",
             QueueStatus::Disconnected => "status: disconnected",
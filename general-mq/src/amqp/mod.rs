@@ -4,4 +4,4 @@ mod connection;
 mod queue;
 
 pub use connection::{AmqpConnection, AmqpConnectionOptions};
-pub use queue::{AmqpQueue, AmqpQueueOptions};
+pub use queue::{AmqpOverflow, AmqpQueue, AmqpQueueOptions, AmqpQueueType};
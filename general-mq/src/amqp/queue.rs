@@ -1,32 +1,41 @@
 use std::{
+    collections::BTreeSet,
     error::Error as StdError,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use amqprs::{
-    BasicProperties, Deliver,
+    Ack, BasicProperties, Cancel, CloseChannel, Deliver, FieldTable, FieldValue, Nack, Return,
+    callbacks::ChannelCallback,
     channel::{
-        BasicAckArguments, BasicConsumeArguments, BasicNackArguments, BasicPublishArguments,
-        BasicQosArguments, Channel, ConfirmSelectArguments, ExchangeDeclareArguments, ExchangeType,
-        QueueBindArguments, QueueDeclareArguments,
+        BasicAckArguments, BasicCancelArguments, BasicConsumeArguments, BasicNackArguments,
+        BasicPublishArguments, BasicQosArguments, Channel, ConfirmSelectArguments,
+        ExchangeDeclareArguments, ExchangeType, QueueBindArguments, QueueDeclareArguments,
     },
     consumer::AsyncConsumer,
     error::Error as AmqprsError,
 };
 use async_trait::async_trait;
 use tokio::{
+    sync::Semaphore,
     task::{self, JoinHandle},
     time,
 };
 
 use super::connection::AmqpConnection;
 use crate::{
-    Error,
+    Error, ID_SIZE,
+    compression::{self, Compression},
     connection::{GmqConnection, Status as ConnStatus},
+    metrics::{Metrics, QueueMetrics},
+    outbox::{Outbox, OutboxOptions, Publisher},
     queue::{
-        EventHandler, GmqQueue, Message, MessageHandler, QUEUE_NAME_PATTERN, Status, name_validate,
+        self, EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode,
+        QUEUE_NAME_PATTERN, Status, name_validate,
     },
+    randomstring,
+    reconnect::{Backoff, ReconnectPolicy},
 };
 
 /// Manages an AMQP queue.
@@ -34,6 +43,8 @@ use crate::{
 pub struct AmqpQueue {
     /// Options of the queue.
     opts: AmqpQueueOptions,
+    /// The identifier of the queue in the connection.
+    id: String,
     /// The associated [`crate::AmqpConnection`].
     conn: Arc<Mutex<AmqpConnection>>,
     /// Hold the channel instance.
@@ -46,6 +57,20 @@ pub struct AmqpQueue {
     msg_handler: Arc<Mutex<Option<Arc<dyn MessageHandler>>>>,
     /// The event loop to manage and monitor the channel instance.
     ev_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Limits the number of messages in process (for receivers).
+    limiter: Option<Arc<Semaphore>>,
+    /// `true` to stop consuming the queue.
+    paused: Arc<Mutex<bool>>,
+    /// The queue name to consume (for receivers). Broadcast receivers use server-named queues.
+    consume_queue: Arc<Mutex<Option<String>>>,
+    /// The consumer tag of the current channel.
+    consumer_tag: Arc<Mutex<Option<String>>>,
+    /// The on-disk outbox (for senders).
+    outbox: Option<Arc<Outbox>>,
+    /// Metrics of the queue.
+    metrics: Arc<Metrics>,
+    /// Publisher confirms of the current channel (for reliable senders).
+    confirms: Arc<Confirms>,
 }
 
 /// The queue options.
@@ -71,6 +96,78 @@ pub struct AmqpQueueOptions {
     pub prefetch: u16,
     /// Use persistent delivery mode.
     pub persistent: bool,
+    /// The reconnect policy with exponential backoff for declaring the queue. Use `None` to retry
+    /// every `reconnect_millis` forever.
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// The dead-letter queue name. Messages of [`NackMode::DeadLetter`], expired messages and
+    /// messages that exceed `max_length` are moved to this queue. The dead-letter queue is declared
+    /// by receivers.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    ///
+    /// **Note**: `dead_letter`, `message_ttl_millis`, `max_length`, `overflow`, `max_priority` and
+    /// `queue_type` are queue arguments. Senders and receivers **MUST** use the same values for
+    /// unicast queues.
+    pub dead_letter: Option<String>,
+    /// The time-to-live in milliseconds of messages in the queue (`x-message-ttl`).
+    pub message_ttl_millis: Option<u32>,
+    /// The maximum number of ready messages in the queue (`x-max-length`). The oldest messages are
+    /// dropped or dead-lettered when the limit is reached unless `overflow` is specified.
+    pub max_length: Option<u32>,
+    /// The behavior when the queue reaches `max_length` (`x-overflow`). Use `None` for the server
+    /// default [`AmqpOverflow::DropHead`].
+    pub overflow: Option<AmqpOverflow>,
+    /// The maximum priority of messages to declare a priority queue (`x-max-priority`).
+    ///
+    /// **Note**: this value **MUST** be a positive value and only classic queues support it.
+    pub max_priority: Option<u8>,
+    /// The type of unicast queues (`x-queue-type`). Broadcast receivers always use server-named
+    /// classic queues.
+    pub queue_type: AmqpQueueType,
+    /// Delete the unicast queue after the last consumer is cancelled. Only classic queues support
+    /// it.
+    pub auto_delete: bool,
+    /// Declare the unicast queue that is used only by the connection and is deleted after the
+    /// connection is closed. Only classic queues support it.
+    pub exclusive: bool,
+    /// The maximum number of messages that are processed by the message handler concurrently (for
+    /// receivers). Deliveries wait until a message handler returns.
+    ///
+    /// Default or zero value is unlimited. Reliable receivers are also limited by `prefetch`.
+    pub max_concurrency: usize,
+    /// The on-disk outbox to store messages while the queue is not connected (for senders).
+    /// Stored messages are sent in order after the queue is connected. Use `None` to return
+    /// [`Error::NotConnected`] instead.
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of payloads (for senders). Receivers decompress payloads
+    /// automatically.
+    pub compression: Compression,
+}
+
+/// The type of AMQP queues.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AmqpQueueType {
+    /// Classic queues.
+    #[default]
+    Classic,
+    /// Replicated quorum queues for high availability.
+    Quorum,
+    /// Append-only stream queues.
+    ///
+    /// **Note**: stream queues are not supported yet because consumers need `x-stream-offset` and
+    /// manual acknowledgements. [`AmqpQueue::new`] returns an error for this type.
+    Stream,
+}
+
+/// The behavior when an AMQP queue reaches its maximum length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AmqpOverflow {
+    /// Drop or dead-letter the oldest messages.
+    DropHead,
+    /// Reject new messages. Reliable senders get negative acknowledgements.
+    RejectPublish,
+    /// Reject and dead-letter new messages.
+    RejectPublishDlx,
 }
 
 /// The AMQP [`Message`] implementation.
@@ -81,6 +178,10 @@ struct AmqpMessage {
     delivery_tag: u64,
     /// Hold the consumer callback content.
     content: Vec<u8>,
+    /// Properties converted from the basic properties.
+    props: MessageProperties,
+    /// Metrics of the receiver.
+    metrics: Arc<Metrics>,
 }
 
 /// The [`amqprs::consumer::AsyncConsumer`] implementation.
@@ -89,6 +190,28 @@ struct Consumer {
     queue: Arc<AmqpQueue>,
 }
 
+/// Publisher confirms of a channel.
+#[derive(Default)]
+struct Confirms {
+    state: Mutex<ConfirmState>,
+}
+
+/// Delivery tags of published and confirmed messages. Tags start from `1` for each channel.
+#[derive(Default)]
+struct ConfirmState {
+    /// The number of published messages.
+    published: u64,
+    /// Messages with tags up to this value are confirmed.
+    confirmed_to: u64,
+    /// Confirmed tags that are greater than `confirmed_to`.
+    confirmed: BTreeSet<u64>,
+}
+
+/// The [`amqprs::callbacks::ChannelCallback`] implementation to receive publisher confirms.
+struct ConfirmCallback {
+    confirms: Arc<Confirms>,
+}
+
 /// Default reconnect time in milliseconds.
 const DEF_RECONN_TIME_MS: u64 = 1000;
 
@@ -106,22 +229,116 @@ impl AmqpQueue {
         } else if opts.is_recv && opts.prefetch == 0 {
             return Err("prefetch cannot be zero for a receiver".to_string());
         }
+        if let Some(dead_letter) = opts.dead_letter.as_ref()
+            && !name_validate(dead_letter)
+        {
+            return Err(format!(
+                "dead-letter queue name {} is not match {}",
+                dead_letter, QUEUE_NAME_PATTERN
+            ));
+        }
+        if opts.max_priority == Some(0) {
+            return Err("max_priority cannot be zero".to_string());
+        }
+        if opts.queue_type != AmqpQueueType::Classic {
+            if opts.max_priority.is_some() {
+                return Err("max_priority is only supported by classic queues".to_string());
+            } else if opts.auto_delete || opts.exclusive {
+                return Err(
+                    "auto_delete and exclusive are only supported by classic queues".to_string(),
+                );
+            }
+        }
+        if opts.queue_type == AmqpQueueType::Stream {
+            return Err("stream queues are not supported".to_string());
+        }
         let mut opts = opts;
         if opts.reconnect_millis == 0 {
             opts.reconnect_millis = DEF_RECONN_TIME_MS;
         }
+        let limiter = match opts.max_concurrency {
+            0 => None,
+            permits => Some(Arc::new(Semaphore::new(permits))),
+        };
+        let outbox = match opts.outbox.as_ref() {
+            None => None,
+            Some(outbox_opts) => match opts.is_recv {
+                true => return Err("outbox cannot be used by a receiver".to_string()),
+                false => Some(Arc::new(Outbox::new(outbox_opts)?)),
+            },
+        };
+
+        let metrics = Arc::new(Metrics::new(opts.name.as_str(), opts.is_recv));
 
         Ok(AmqpQueue {
             opts,
+            id: randomstring(ID_SIZE),
             conn: Arc::new(Mutex::new(conn.clone())),
             channel: Arc::new(Mutex::new(None)),
             status: Arc::new(Mutex::new(Status::Closed)),
             handler: Arc::new(Mutex::new(None)),
             msg_handler: Arc::new(Mutex::new(None)),
             ev_loop: Arc::new(Mutex::new(None)),
+            limiter,
+            paused: Arc::new(Mutex::new(false)),
+            consume_queue: Arc::new(Mutex::new(None)),
+            consumer_tag: Arc::new(Mutex::new(None)),
+            outbox,
+            metrics,
+            confirms: Arc::new(Confirms::default()),
         })
     }
 
+    /// To get the number of messages in the outbox.
+    pub async fn outbox_len(&self) -> usize {
+        match self.outbox.as_ref() {
+            None => 0,
+            Some(outbox) => outbox.len().await,
+        }
+    }
+
+    /// To get the snapshot of metrics.
+    pub fn metrics(&self) -> QueueMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// To publish a message to the queue or the exchange.
+    async fn publish_msg(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let channel = {
+            match self.channel.lock().unwrap().as_ref() {
+                None => return Err(Box::new(Error::NotConnected)),
+                Some(channel) => channel.clone(),
+            }
+        };
+
+        let mut prop = to_basic_properties(&props)?;
+        if self.opts.persistent {
+            prop.with_persistence(true);
+        }
+        let mut args = match self.opts.reliable {
+            false => BasicPublishArguments::default(),
+            true => BasicPublishArguments {
+                mandatory: true,
+                ..Default::default()
+            },
+        };
+        if self.opts.broadcast {
+            args.exchange(self.opts.name.clone());
+        } else {
+            args.routing_key(self.opts.name.clone());
+        }
+
+        channel.basic_publish(prop, payload, args).await?;
+        if self.opts.reliable {
+            self.confirms.on_published();
+        }
+        Ok(())
+    }
+
     /// To get the associated connection status.
     fn conn_status(&self) -> ConnStatus {
         self.conn.lock().unwrap().status()
@@ -137,6 +354,97 @@ impl AmqpQueue {
         self.msg_handler.lock().unwrap().clone()
     }
 
+    /// To get the arguments for declaring queues. `x-queue-type` is only used for unicast queues.
+    fn queue_arguments(&self) -> FieldTable {
+        let mut args = FieldTable::new();
+        if let Some(dead_letter) = self.opts.dead_letter.as_ref() {
+            args.insert(
+                "x-dead-letter-exchange".try_into().unwrap(),
+                FieldValue::S("".try_into().unwrap()),
+            );
+            if let Ok(dead_letter) = dead_letter.as_str().try_into() {
+                args.insert(
+                    "x-dead-letter-routing-key".try_into().unwrap(),
+                    FieldValue::S(dead_letter),
+                );
+            }
+        }
+        if let Some(ttl) = self.opts.message_ttl_millis {
+            args.insert(
+                "x-message-ttl".try_into().unwrap(),
+                FieldValue::l(ttl as i64),
+            );
+        }
+        if let Some(max_length) = self.opts.max_length {
+            args.insert(
+                "x-max-length".try_into().unwrap(),
+                FieldValue::l(max_length as i64),
+            );
+        }
+        if let Some(overflow) = self.opts.overflow {
+            args.insert(
+                "x-overflow".try_into().unwrap(),
+                FieldValue::S(overflow.as_str().try_into().unwrap()),
+            );
+        }
+        if let Some(max_priority) = self.opts.max_priority {
+            args.insert(
+                "x-max-priority".try_into().unwrap(),
+                FieldValue::l(max_priority as i64),
+            );
+        }
+        if !self.opts.broadcast && self.opts.queue_type != AmqpQueueType::Classic {
+            args.insert(
+                "x-queue-type".try_into().unwrap(),
+                FieldValue::S(self.opts.queue_type.as_str().try_into().unwrap()),
+            );
+        }
+        args
+    }
+
+    /// To declare the dead-letter queue if it is specified (for receivers).
+    async fn declare_dead_letter(&self, channel: &Channel) -> Result<(), AmqprsError> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        if let Some(dead_letter) = self.opts.dead_letter.as_ref() {
+            let mut args = QueueDeclareArguments::new(dead_letter.as_str());
+            args.durable(true);
+            channel.queue_declare(args).await?;
+        }
+        Ok(())
+    }
+
+    /// To start consuming the queue with the channel if the queue is not paused.
+    async fn consume(&self, channel: &Channel, queue_name: &str) -> Result<(), AmqprsError> {
+        {
+            *self.consume_queue.lock().unwrap() = Some(queue_name.to_string());
+            *self.consumer_tag.lock().unwrap() = None;
+        }
+        if self.is_paused() {
+            return Ok(());
+        }
+
+        let args = BasicConsumeArguments::new(queue_name, "");
+        let consumer = Consumer {
+            queue: Arc::new(self.clone()),
+        };
+        let tag = channel.basic_consume(consumer, args).await?;
+        {
+            *self.consumer_tag.lock().unwrap() = Some(tag);
+        }
+        // The queue may be paused before the channel is ready.
+        if self.is_paused() {
+            let tag = { self.consumer_tag.lock().unwrap().take() };
+            if let Some(tag) = tag {
+                channel
+                    .basic_cancel(BasicCancelArguments::new(tag.as_str()))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     /// The error handling.
     fn on_error(&self, err: Box<dyn StdError + Send + Sync>) {
         let handler = { (*self.handler.lock().unwrap()).clone() };
@@ -182,12 +490,15 @@ impl GmqQueue for AmqpQueue {
 
         {
             let mut task_handle_mutex = self.ev_loop.lock().unwrap();
-            if (*task_handle_mutex).is_some() {
+            if (*task_handle_mutex).is_some() && self.status() != Status::Failed {
                 return Ok(());
             }
             *self.status.lock().unwrap() = Status::Connecting;
+            self.metrics.on_disconnected();
             *task_handle_mutex = Some(create_event_loop(self));
         }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.add(self.id.as_str(), Box::new(self.clone()));
         Ok(())
     }
 
@@ -196,6 +507,8 @@ impl GmqQueue for AmqpQueue {
             None => return Ok(()),
             Some(handle) => handle.abort(),
         }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.remove(self.id.as_str());
         {
             *self.status.lock().unwrap() = Status::Closing;
         }
@@ -210,6 +523,7 @@ impl GmqQueue for AmqpQueue {
         {
             *self.status.lock().unwrap() = Status::Closed;
         }
+        self.metrics.on_closed();
         if let Some(handler) = { (*self.handler.lock().unwrap()).clone() } {
             let queue = Arc::new(self.clone());
             task::spawn(async move {
@@ -221,38 +535,129 @@ impl GmqQueue for AmqpQueue {
         Ok(())
     }
 
-    async fn send_msg(&self, payload: Vec<u8>) -> Result<(), Box<dyn StdError + Send + Sync>> {
-        if self.opts.is_recv {
-            return Err(Box::new(Error::QueueIsReceiver));
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        let confirms = self.confirms.clone();
+        let drained = queue::drain(self, timeout, || {
+            metrics.in_process() == 0 && confirms.pending() == 0
+        })
+        .await;
+        self.close().await?;
+        match drained {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
         }
+    }
 
-        let channel = {
-            match self.channel.lock().unwrap().as_ref() {
-                None => return Err(Box::new(Error::NotConnected)),
-                Some(channel) => channel.clone(),
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        match queue::drain(self, timeout, || metrics.in_process() == 0).await {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        {
+            *self.paused.lock().unwrap() = true;
+        }
+
+        let channel = { self.channel.lock().unwrap().clone() };
+        if let Some(channel) = channel {
+            let tag = { self.consumer_tag.lock().unwrap().take() };
+            if let Some(tag) = tag {
+                channel
+                    .basic_cancel(BasicCancelArguments::new(tag.as_str()))
+                    .await?;
             }
-        };
+        }
+        Ok(())
+    }
 
-        let mut prop = BasicProperties::default();
-        if self.opts.persistent {
-            prop.with_persistence(true);
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
         }
-        let mut args = match self.opts.reliable {
-            false => BasicPublishArguments::default(),
-            true => BasicPublishArguments {
-                mandatory: true,
-                ..Default::default()
-            },
-        };
-        if self.opts.broadcast {
-            args.exchange(self.opts.name.clone());
-        } else {
-            args.routing_key(self.opts.name.clone());
+        {
+            *self.paused.lock().unwrap() = false;
         }
 
-        channel.basic_publish(prop, payload, args).await?;
+        let channel = { self.channel.lock().unwrap().clone() };
+        let queue_name = { self.consume_queue.lock().unwrap().clone() };
+        let consuming = { self.consumer_tag.lock().unwrap().is_some() };
+        if let Some(channel) = channel
+            && let Some(queue_name) = queue_name
+            && !consuming
+        {
+            self.consume(&channel, queue_name.as_str()).await?;
+        }
         Ok(())
     }
+
+    fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.opts.is_recv {
+            return Err(Box::new(Error::QueueIsReceiver));
+        }
+
+        let (payload, props) = compression::compress(self.opts.compression, payload, props)?;
+        match self.outbox.as_ref() {
+            None => self.publish(payload, props).await,
+            Some(outbox) => outbox.send(Arc::new(self.clone()), payload, props).await,
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for AmqpQueue {
+    fn is_connected(&self) -> bool {
+        self.status() == Status::Connected
+    }
+
+    async fn publish(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.publish_msg(payload, props).await;
+        self.metrics.on_sent(result.is_ok());
+        result
+    }
+}
+
+impl AmqpQueueType {
+    /// The value of the `x-queue-type` argument.
+    fn as_str(&self) -> &'static str {
+        match self {
+            AmqpQueueType::Classic => "classic",
+            AmqpQueueType::Quorum => "quorum",
+            AmqpQueueType::Stream => "stream",
+        }
+    }
+}
+
+impl AmqpOverflow {
+    /// The value of the `x-overflow` argument.
+    fn as_str(&self) -> &'static str {
+        match self {
+            AmqpOverflow::DropHead => "drop-head",
+            AmqpOverflow::RejectPublish => "reject-publish",
+            AmqpOverflow::RejectPublishDlx => "reject-publish-dlx",
+        }
+    }
 }
 
 impl Default for AmqpQueueOptions {
@@ -265,6 +670,18 @@ impl Default for AmqpQueueOptions {
             reconnect_millis: DEF_RECONN_TIME_MS,
             prefetch: 1,
             persistent: false,
+            reconnect_policy: None,
+            dead_letter: None,
+            message_ttl_millis: None,
+            max_length: None,
+            overflow: None,
+            max_priority: None,
+            queue_type: AmqpQueueType::Classic,
+            auto_delete: false,
+            exclusive: false,
+            max_concurrency: 0,
+            outbox: None,
+            compression: Compression::None,
         }
     }
 }
@@ -275,22 +692,37 @@ impl Message for AmqpMessage {
         &self.content
     }
 
+    fn properties(&self) -> &MessageProperties {
+        &self.props
+    }
+
     async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
         let args = BasicAckArguments {
             delivery_tag: self.delivery_tag,
             ..Default::default()
         };
         self.channel.basic_ack(args).await?;
+        self.metrics.on_ack(true);
         Ok(())
     }
 
-    async fn nack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
-        let args = BasicNackArguments {
-            delivery_tag: self.delivery_tag,
-            requeue: true,
-            ..Default::default()
-        };
-        self.channel.basic_nack(args).await?;
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if mode == NackMode::Drop {
+            // Acknowledge to drop the message without dead-lettering.
+            let args = BasicAckArguments {
+                delivery_tag: self.delivery_tag,
+                ..Default::default()
+            };
+            self.channel.basic_ack(args).await?;
+        } else {
+            let args = BasicNackArguments {
+                delivery_tag: self.delivery_tag,
+                requeue: mode == NackMode::Requeue,
+                ..Default::default()
+            };
+            self.channel.basic_nack(args).await?;
+        }
+        self.metrics.on_ack(false);
         Ok(())
     }
 }
@@ -301,7 +733,7 @@ impl AsyncConsumer for Consumer {
         &mut self,
         channel: &Channel,
         deliver: Deliver,
-        _basic_properties: BasicProperties,
+        basic_properties: BasicProperties,
         content: Vec<u8>,
     ) {
         let queue = self.queue.clone();
@@ -315,23 +747,164 @@ impl AsyncConsumer for Consumer {
             channel: channel.clone(),
             delivery_tag: deliver.delivery_tag(),
             content,
+            props: from_basic_properties(&basic_properties),
+            metrics: self.queue.metrics.clone(),
         });
+        // Wait for a permit to stop taking deliveries when the limit is reached.
+        let permit = match handler.limiter().or_else(|| self.queue.limiter.clone()) {
+            None => None,
+            Some(limiter) => limiter.acquire_owned().await.ok(),
+        };
 
+        self.queue.metrics.on_received();
+        let metrics = self.queue.metrics.clone();
         task::spawn(async move {
+            let message = match compression::decompress(message, false).await {
+                None => {
+                    metrics.on_rejected();
+                    return;
+                }
+                Some(message) => message,
+            };
+            let start = Instant::now();
             handler.on_message(queue, message).await;
+            metrics.on_handled(start.elapsed());
+            drop(permit);
         });
     }
 }
 
+impl Confirms {
+    /// To clear tags for a new channel. Messages of the previous channel cannot be confirmed.
+    fn reset(&self) {
+        *self.state.lock().unwrap() = ConfirmState::default();
+    }
+
+    fn on_published(&self) {
+        self.state.lock().unwrap().published += 1;
+    }
+
+    fn on_confirmed(&self, tag: u64, multiple: bool) {
+        let mut state = self.state.lock().unwrap();
+        if multiple {
+            state.confirmed_to = state.confirmed_to.max(tag);
+            let to = state.confirmed_to;
+            state.confirmed.retain(|t| *t > to);
+        } else if tag > state.confirmed_to {
+            state.confirmed.insert(tag);
+        }
+        loop {
+            let next = state.confirmed_to + 1;
+            if !state.confirmed.remove(&next) {
+                break;
+            }
+            state.confirmed_to = next;
+        }
+    }
+
+    /// The number of published messages that are not confirmed.
+    fn pending(&self) -> u64 {
+        let state = self.state.lock().unwrap();
+        let confirmed = state.confirmed_to + state.confirmed.len() as u64;
+        state.published.saturating_sub(confirmed)
+    }
+}
+
+#[async_trait]
+impl ChannelCallback for ConfirmCallback {
+    async fn close(&mut self, _channel: &Channel, _close: CloseChannel) -> Result<(), AmqprsError> {
+        Ok(())
+    }
+
+    async fn cancel(&mut self, _channel: &Channel, _cancel: Cancel) -> Result<(), AmqprsError> {
+        Ok(())
+    }
+
+    async fn flow(&mut self, _channel: &Channel, active: bool) -> Result<bool, AmqprsError> {
+        Ok(active)
+    }
+
+    async fn publish_ack(&mut self, _channel: &Channel, ack: Ack) {
+        self.confirms
+            .on_confirmed(ack.delivery_tag(), ack.mutiple());
+    }
+
+    async fn publish_nack(&mut self, _channel: &Channel, nack: Nack) {
+        self.confirms
+            .on_confirmed(nack.delivery_tag(), nack.multiple());
+    }
+
+    async fn publish_return(
+        &mut self,
+        _channel: &Channel,
+        _ret: Return,
+        _basic_properties: BasicProperties,
+        _content: Vec<u8>,
+    ) {
+    }
+}
+
+/// To convert message properties to AMQP basic properties.
+fn to_basic_properties(
+    props: &MessageProperties,
+) -> Result<BasicProperties, Box<dyn StdError + Send + Sync>> {
+    let mut prop = BasicProperties::default();
+    if let Some(content_type) = props.content_type.as_ref() {
+        prop.with_content_type(content_type.as_str());
+    }
+    if let Some(correlation_id) = props.correlation_id.as_ref() {
+        prop.with_correlation_id(correlation_id.as_str());
+    }
+    if let Some(timestamp) = props.timestamp {
+        prop.with_timestamp(timestamp);
+    }
+    if !props.headers.is_empty() {
+        let mut headers = FieldTable::new();
+        for (key, value) in props.headers.iter() {
+            let key = key.as_str().try_into()?;
+            let value = value.as_str().try_into()?;
+            headers.insert(key, FieldValue::S(value));
+        }
+        prop.with_headers(headers);
+    }
+    Ok(prop)
+}
+
+/// To convert AMQP basic properties to message properties. Only string headers are kept.
+fn from_basic_properties(prop: &BasicProperties) -> MessageProperties {
+    let mut props = MessageProperties {
+        content_type: prop.content_type().cloned(),
+        correlation_id: prop.correlation_id().cloned(),
+        timestamp: prop.timestamp(),
+        ..Default::default()
+    };
+    if let Some(headers) = prop.headers() {
+        for (key, value) in headers.as_ref().iter() {
+            if let FieldValue::S(value) = value {
+                let value: &String = value.as_ref();
+                props.headers.insert(key.to_string(), value.clone());
+            }
+        }
+    }
+    props
+}
+
 /// To create an event loop runtime task.
 fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
     let this = Arc::new(queue.clone());
     task::spawn(async move {
+        let mut backoff = Backoff::new(
+            this.opts.reconnect_policy.as_ref(),
+            this.opts.reconnect_millis,
+        );
         loop {
             match this.status() {
-                Status::Closing | Status::Closed => break,
+                Status::Closing | Status::Closed | Status::Failed => break,
                 Status::Connecting => {
-                    if this.conn_status() != ConnStatus::Connected {
+                    if this.conn_status() == ConnStatus::Failed {
+                        to_failed(&this);
+                        break;
+                    } else if this.conn_status() != ConnStatus::Connected {
                         time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
                         continue;
                     }
@@ -341,8 +914,9 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                         match raw_conn.open_channel(None).await {
                             Err(e) => {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
                             Ok(channel) => channel,
@@ -352,10 +926,25 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                         continue;
                     };
                     if this.opts.reliable {
+                        this.confirms.reset();
+                        if !this.opts.is_recv {
+                            let callback = ConfirmCallback {
+                                confirms: this.confirms.clone(),
+                            };
+                            if let Err(e) = channel.register_callback(callback).await {
+                                this.on_error(Box::new(e));
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
+                                continue;
+                            }
+                        }
                         let args = ConfirmSelectArguments::default();
                         if let Err(e) = channel.confirm_select(args).await {
                             this.on_error(Box::new(e));
-                            time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                            if !wait_retry(&this, &mut backoff).await {
+                                break;
+                            }
                             continue;
                         }
                     }
@@ -365,18 +954,28 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                         let args = ExchangeDeclareArguments::of_type(name, ExchangeType::Fanout);
                         if let Err(e) = channel.exchange_declare(args).await {
                             this.on_error(Box::new(e));
-                            time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                            if !wait_retry(&this, &mut backoff).await {
+                                break;
+                            }
                             continue;
                         }
 
                         if this.opts.is_recv {
+                            if let Err(e) = this.declare_dead_letter(&channel).await {
+                                this.on_error(Box::new(e));
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
+                                continue;
+                            }
                             let mut args = QueueDeclareArguments::default();
-                            args.exclusive(true);
+                            args.exclusive(true).arguments(this.queue_arguments());
                             let queue_name = match channel.queue_declare(args).await {
                                 Err(e) => {
                                     this.on_error(Box::new(e));
-                                    time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                        .await;
+                                    if !wait_retry(&this, &mut backoff).await {
+                                        break;
+                                    }
                                     continue;
                                 }
                                 Ok(Some((queue_name, _, _))) => queue_name,
@@ -384,8 +983,9 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                                     this.on_error(Box::new(AmqprsError::ChannelUseError(
                                         "unknown queue_declare error".to_string(),
                                     )));
-                                    time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                        .await;
+                                    if !wait_retry(&this, &mut backoff).await {
+                                        break;
+                                    }
                                     continue;
                                 }
                             };
@@ -398,8 +998,9 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                             };
                             if let Err(e) = channel.queue_bind(args).await {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
 
@@ -409,28 +1010,38 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                             };
                             if let Err(e) = channel.basic_qos(args).await {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
 
-                            let args = BasicConsumeArguments::new(&queue_name, "");
-                            let consumer = Consumer {
-                                queue: this.clone(),
-                            };
-                            if let Err(e) = channel.basic_consume(consumer, args).await {
+                            if let Err(e) = this.consume(&channel, queue_name.as_str()).await {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
                         }
                     } else {
+                        if let Err(e) = this.declare_dead_letter(&channel).await {
+                            this.on_error(Box::new(e));
+                            if !wait_retry(&this, &mut backoff).await {
+                                break;
+                            }
+                            continue;
+                        }
                         let mut args = QueueDeclareArguments::new(name);
-                        args.durable(true);
+                        args.durable(true)
+                            .auto_delete(this.opts.auto_delete)
+                            .exclusive(this.opts.exclusive)
+                            .arguments(this.queue_arguments());
                         if let Err(e) = channel.queue_declare(args).await {
                             this.on_error(Box::new(e));
-                            time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                            if !wait_retry(&this, &mut backoff).await {
+                                break;
+                            }
                             continue;
                         }
 
@@ -441,34 +1052,37 @@ fn create_event_loop(queue: &AmqpQueue) -> JoinHandle<()> {
                             };
                             if let Err(e) = channel.basic_qos(args).await {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
 
-                            let args = BasicConsumeArguments::new(name, "");
-                            let consumer = Consumer {
-                                queue: this.clone(),
-                            };
-                            if let Err(e) = channel.basic_consume(consumer, args).await {
+                            if let Err(e) = this.consume(&channel, name).await {
                                 this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
                         }
                     }
 
+                    backoff.reset();
                     {
                         *this.channel.lock().unwrap() = Some(channel);
                         *this.status.lock().unwrap() = Status::Connected;
                     }
+                    this.metrics.on_connected();
                     if let Some(handler) = this.handler() {
                         let queue = this.clone();
                         task::spawn(async move {
                             handler.on_status(queue, Status::Connected).await;
                         });
                     }
+                    if let Some(outbox) = this.outbox.as_ref() {
+                        outbox.spawn_flush(this.clone());
+                    }
                 }
                 Status::Connected => {
                     time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
@@ -502,6 +1116,7 @@ async fn to_disconnected_fn(queue: Arc<AmqpQueue>) {
         queue.channel.lock().unwrap().take();
         *status_mutex = Status::Disconnected;
     }
+    queue.metrics.on_disconnected();
 
     let handler = { (*queue.handler.lock().unwrap()).clone() };
     if let Some(handler) = handler {
@@ -527,3 +1142,34 @@ async fn to_disconnected_fn(queue: Arc<AmqpQueue>) {
         });
     }
 }
+
+/// To wait for the next attempt after a failed attempt. Returns `false` and changes the status to
+/// [`Status::Failed`] if the attempts reach the limit.
+async fn wait_retry(queue: &Arc<AmqpQueue>, backoff: &mut Backoff) -> bool {
+    if !backoff.fail() {
+        to_failed(queue);
+        return false;
+    }
+    time::sleep(backoff.delay()).await;
+    true
+}
+
+/// To change the status to [`Status::Failed`].
+fn to_failed(queue: &Arc<AmqpQueue>) {
+    {
+        let mut status_mutex = queue.status.lock().unwrap();
+        if *status_mutex == Status::Closing || *status_mutex == Status::Closed {
+            return;
+        }
+        queue.channel.lock().unwrap().take();
+        *status_mutex = Status::Failed;
+    }
+    queue.metrics.on_disconnected();
+
+    if let Some(handler) = queue.handler() {
+        let q = queue.clone();
+        task::spawn(async move {
+            handler.on_status(q, Status::Failed).await;
+        });
+    }
+}
@@ -0,0 +1,172 @@
+//! Transparent payload compression.
+//!
+//! Senders compress payloads with [`Compression`] and mark them with the `content-encoding`
+//! header. Receivers always decompress marked payloads before calling the message handler, so
+//! compressed and uncompressed peers can share one queue. MQTT 3.1.1 does not carry headers and
+//! its receivers detect compressed payloads by the gzip or zstd magic prefix.
+
+use std::{
+    error::Error as StdError,
+    io::{Error as IoError, Read, Write},
+};
+
+use async_trait::async_trait;
+use flate2::{Compression as GzLevel, read::GzDecoder, write::GzEncoder};
+
+use crate::queue::{Message, MessageProperties, NackMode};
+
+/// The compression algorithm of payloads (for senders).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Compression {
+    /// Send payloads as they are.
+    #[default]
+    None,
+    /// gzip with the default level.
+    Gzip,
+    /// zstd with the default level.
+    Zstd,
+}
+
+/// The [`Message`] with the decompressed payload. Operations are forwarded to the received
+/// message, so dead letters keep the compressed payload and the header.
+struct DecodedMessage {
+    /// The received message.
+    inner: Box<dyn Message>,
+    /// The decompressed payload.
+    payload: Vec<u8>,
+    /// Properties without the `content-encoding` header.
+    props: MessageProperties,
+}
+
+/// The header key of the compression algorithm.
+const CONTENT_ENCODING_KEY: &str = "content-encoding";
+
+/// The header value of gzip.
+const GZIP_ENCODING: &str = "gzip";
+/// The magic prefix of gzip.
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b, 0x08];
+/// The header value of zstd.
+const ZSTD_ENCODING: &str = "zstd";
+/// The magic prefix of zstd.
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+/// The maximum size of decompressed payloads to prevent decompression bombs.
+const MAX_DECODED_SIZE: u64 = 64 * 1024 * 1024;
+
+impl Compression {
+    /// The header value of the algorithm.
+    fn encoding(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some(GZIP_ENCODING),
+            Compression::Zstd => Some(ZSTD_ENCODING),
+        }
+    }
+}
+
+#[async_trait]
+impl Message for DecodedMessage {
+    fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    fn properties(&self) -> &MessageProperties {
+        &self.props
+    }
+
+    async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.inner.ack().await
+    }
+
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.inner.nack_with_mode(mode).await
+    }
+}
+
+/// To compress the payload and to add the `content-encoding` header. The payload is sent as it is
+/// if the compressed one is not smaller or the payload has been encoded by the application.
+pub(crate) fn compress(
+    compression: Compression,
+    payload: Vec<u8>,
+    props: MessageProperties,
+) -> Result<(Vec<u8>, MessageProperties), IoError> {
+    let encoding = match compression.encoding() {
+        None => return Ok((payload, props)),
+        Some(encoding) => encoding,
+    };
+    if props.headers.contains_key(CONTENT_ENCODING_KEY) {
+        return Ok((payload, props));
+    }
+    let compressed = match compression {
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(vec![], GzLevel::default());
+            encoder.write_all(payload.as_slice())?;
+            encoder.finish()?
+        }
+        _ => zstd::encode_all(payload.as_slice(), 0)?,
+    };
+    if compressed.len() >= payload.len() {
+        return Ok((payload, props));
+    }
+    let mut props = props;
+    props
+        .headers
+        .insert(CONTENT_ENCODING_KEY.to_string(), encoding.to_string());
+    Ok((compressed, props))
+}
+
+/// To decompress the payload of the received message. The message is returned as it is if it is
+/// not compressed or it cannot be decompressed.
+///
+/// Messages that are larger than [`MAX_DECODED_SIZE`] after decompression are NACKed with
+/// [`NackMode::DeadLetter`] and [`None`] is returned.
+///
+/// `detect_magic` is used by receivers that cannot receive headers to detect compressed payloads by
+/// the magic prefix.
+pub(crate) async fn decompress(
+    msg: Box<dyn Message>,
+    detect_magic: bool,
+) -> Option<Box<dyn Message>> {
+    let props = msg.properties();
+    let encoding = match props.headers.get(CONTENT_ENCODING_KEY) {
+        Some(encoding) => encoding.as_str(),
+        None => match detect_magic {
+            false => return Some(msg),
+            true => {
+                let payload = msg.payload();
+                if payload.starts_with(GZIP_MAGIC) {
+                    GZIP_ENCODING
+                } else if payload.starts_with(ZSTD_MAGIC) {
+                    ZSTD_ENCODING
+                } else {
+                    return Some(msg);
+                }
+            }
+        },
+    };
+    // Read one more byte to know if the payload exceeds the limit.
+    let mut payload = vec![];
+    let result = match encoding {
+        GZIP_ENCODING => GzDecoder::new(msg.payload())
+            .take(MAX_DECODED_SIZE + 1)
+            .read_to_end(&mut payload),
+        ZSTD_ENCODING => match zstd::Decoder::new(msg.payload()) {
+            Err(e) => Err(e),
+            Ok(decoder) => decoder.take(MAX_DECODED_SIZE + 1).read_to_end(&mut payload),
+        },
+        _ => return Some(msg),
+    };
+    if result.is_err() {
+        return Some(msg);
+    }
+    if payload.len() as u64 > MAX_DECODED_SIZE {
+        let _ = msg.nack_with_mode(NackMode::DeadLetter).await;
+        return None;
+    }
+    let mut props = props.clone();
+    props.headers.remove(CONTENT_ENCODING_KEY);
+    Some(Box::new(DecodedMessage {
+        inner: msg,
+        payload,
+        props,
+    }))
+}
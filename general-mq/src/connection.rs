@@ -1,8 +1,16 @@
 //! Traits and enumerations for connections.
 
-use std::{error::Error as StdError, sync::Arc};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use futures::future;
+
+use crate::{Error, queue::GmqQueue};
 
 /// Connection status.
 #[derive(Debug, PartialEq)]
@@ -17,6 +25,9 @@ pub enum Status {
     Connected,
     /// The connection is not connected. It will retry connecting to the broker automatically.
     Disconnected,
+    /// Reconnecting reached the limit of [`crate::ReconnectPolicy`]. Use `connect()` to connect
+    /// again.
+    Failed,
 }
 
 /// The operations for connections.
@@ -38,6 +49,22 @@ pub trait GmqConnection: Send + Sync {
 
     /// To close the connection.
     async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// To close all connected queues of this connection with [`GmqQueue::close_graceful()`] and
+    /// then to close the connection. The `timeout` is shared by all queues.
+    ///
+    /// [`Error::Timeout`] is returned after closing if some queues are not drained in time.
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// To stop receiving of all connected queues with [`GmqQueue::drain()`] without closing them.
+    /// The `timeout` is shared by all queues.
+    ///
+    /// Programs that forward messages between connections drain all connections before calling
+    /// [`GmqConnection::close_graceful()`], so that no message handler sends with closed queues.
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>>;
 }
 
 /// The event handler for connections.
@@ -55,6 +82,14 @@ pub trait EventHandler: Send + Sync {
     async fn on_status(&self, handler_id: String, conn: Arc<dyn GmqConnection>, status: Status);
 }
 
+/// Queues that are connected with a connection. The connection closes them in
+/// [`GmqConnection::close_graceful()`].
+#[derive(Clone, Default)]
+pub(crate) struct QueueRegistry {
+    /// Clones of queues with queue identifiers.
+    queues: Arc<Mutex<HashMap<String, Box<dyn GmqQueue>>>>,
+}
+
 impl Copy for Status {}
 
 impl Clone for Status {
@@ -62,3 +97,56 @@ impl Clone for Status {
         *self
     }
 }
+
+impl QueueRegistry {
+    /// To add a queue when the queue is connected.
+    pub fn add(&self, id: &str, queue: Box<dyn GmqQueue>) {
+        self.queues.lock().unwrap().insert(id.to_string(), queue);
+    }
+
+    /// To remove a queue when the queue is closed.
+    pub fn remove(&self, id: &str) {
+        let _ = self.queues.lock().unwrap().remove(id);
+    }
+
+    /// To drain all queues at the same time. Queues are kept in the registry.
+    pub async fn drain(&self, timeout: Duration) -> Result<(), Error> {
+        let queues: Vec<(String, Box<dyn GmqQueue>)> = {
+            let mut mutex = self.queues.lock().unwrap();
+            mutex.drain().collect()
+        };
+        let drained = future::join_all(queues.iter().map(|(_, queue)| queue.drain(timeout)))
+            .await
+            .into_iter()
+            .all(|result| result.is_ok());
+        {
+            let mut mutex = self.queues.lock().unwrap();
+            for (id, queue) in queues {
+                mutex.entry(id).or_insert(queue);
+            }
+        }
+        match drained {
+            false => Err(Error::Timeout),
+            true => Ok(()),
+        }
+    }
+
+    /// To close all queues gracefully at the same time.
+    pub async fn close_graceful(&self, timeout: Duration) -> Result<(), Error> {
+        let queues: Vec<Box<dyn GmqQueue>> = {
+            let mut mutex = self.queues.lock().unwrap();
+            mutex.drain().map(|(_, queue)| queue).collect()
+        };
+        let futures = queues
+            .into_iter()
+            .map(|mut queue| async move { queue.close_graceful(timeout).await.is_ok() });
+        match future::join_all(futures)
+            .await
+            .into_iter()
+            .all(|drained| drained)
+        {
+            false => Err(Error::Timeout),
+            true => Ok(()),
+        }
+    }
+}
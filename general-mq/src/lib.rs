@@ -1,7 +1,9 @@
 //! General purposed interfaces for message queues. Now we provide the following implementations:
 //!
 //! - AMQP 0-9-1
-//! - MQTT
+//! - MQTT (3.1.1 and 5)
+//! - In-process memory
+//! - Redis Streams
 //!
 //! By using these classes, you can configure queues with the following properties:
 //!
@@ -10,9 +12,55 @@
 //!
 //! **Notes**
 //!
-//! - MQTT uses **shared queues** to implement unicast.
+//! - MQTT uses **shared queues** to implement unicast. MQTT 5 connections use native shared
+//!   subscriptions (`$share/general-mq/`) by default.
+//! - The memory implementation uses an in-process broker that is shared by all connections with
+//!   the same URI host (`mem://name`) within the program. It needs no external brokers.
+//! - Redis uses **consumer groups** of streams. Unicast receivers share one group and each
+//!   broadcast receiver has its own group. The unicast group receives messages that are sent
+//!   before receivers connect (up to `max_len`) and broadcast groups receive messages that are
+//!   sent after they are created. Reliable receivers keep messages in the pending entries list until `ack()`, and
+//!   receivers read no more than `prefetch` messages in process.
 //! - AMQP uses **confirm channels** to implement reliable publish, and MQTT uses **QoS 1** to
 //!   implement reliable publish/subscribe.
+//! - Connections and queues reconnect every `reconnect_millis` forever by default. Use
+//!   [`ReconnectPolicy`] for exponential backoff with a limit of attempts. The status becomes
+//!   `Failed` after reaching the limit and `connect()` can be called again to restart.
+//! - Receivers can use `msg_stream()` to read messages with
+//!   `while let Some(msg) = stream.next().await` instead of implementing `MessageHandler`.
+//!   Receivers stop taking deliveries when the bounded buffer of the stream is full. Messages
+//!   keep the delivery order with a buffer of `1`.
+//! - `nack_with_mode()` can requeue, drop or move messages to the `dead_letter` queue of the
+//!   receiver. AMQP uses queue arguments for dead-letter queues so senders must use the same
+//!   options. MQTT messages cannot be requeued.
+//! - AMQP and MQTT receivers can limit concurrent handlers with `max_concurrency`. Receivers can
+//!   use `pause()` and `resume()` to stop and continue receiving messages.
+//! - `amqps` and `mqtts` connections can use `TlsOptions` to set custom CA certificates, client
+//!   certificates for mutual TLS and the server name to verify.
+//! - AMQP and MQTT connection URIs can list several hosts such as
+//!   `amqp://host1:5673,host2/vhost`. Hosts without ports use the port of the last host, so
+//!   `amqp://host1,host2:5673/vhost` connects to port 5673 of both hosts. The connection rotates to
+//!   the next host when it fails to connect and `active_host()` reports the connected host.
+//! - Senders can use `OutboxOptions` to store messages on disk while the queue is not connected.
+//!   Stored messages are sent in order after reconnecting. `outbox_len()` reports the number of
+//!   them.
+//! - [`rpc::RpcClient`] sends requests and waits for replies that are matched by correlation IDs
+//!   with `let resp = rpc.call(payload, timeout).await`.
+//! - Queues count sent, received, acknowledged and failed messages, reconnections, disconnected
+//!   time and handler latency. Use `metrics()` to read them. The values are also recorded with the
+//!   `metrics` facade, so they are exported by the Prometheus recorder of `axum-prometheus`. Use
+//!   [`metrics::register()`] to describe them.
+//! - [`typed::TypedQueue`] sends and receives `serde` data as JSON payloads. Malformed payloads are
+//!   rejected and reported with `on_decode_error()` of [`typed::TypedMessageHandler`].
+//! - Senders can use the `compression` option to compress payloads with gzip or zstd. Compressed
+//!   payloads are marked with the `content-encoding` header and receivers decompress them
+//!   automatically, so compressed and uncompressed peers can share one queue.
+//! - `close_graceful(timeout)` of queues and connections waits for in-process messages before
+//!   closing. Use it to stop services without redelivering or losing messages.
+//!   `drain(timeout)` only stops receiving and waits for message handlers, so that all connections
+//!   can be drained before closing any of them.
+//! - AMQP queues can be declared as quorum or stream queues with `queue_type`, and with
+//!   `max_priority`, `message_ttl_millis`, `max_length`, `overflow`, `auto_delete` and `exclusive`.
 //!
 //! # Relationships of Connections and Queues
 //!
@@ -36,13 +84,14 @@
 //!
 //! # Test
 //!
-//! Please prepare a [RabbitMQ](https://www.rabbitmq.com/) broker and a [EMQX](https://emqx.io/)
-//! broker at **localhost** for testing.
+//! Please prepare a [RabbitMQ](https://www.rabbitmq.com/) broker, a [EMQX](https://emqx.io/)
+//! broker and a [Redis](https://redis.io/) server (6.2 or later) at **localhost** for testing.
 //!
 //! - To install using Docker:
 //!
 //!       $ docker run --rm --name rabbitmq -d -p 5672:5672 rabbitmq:management-alpine
 //!       $ docker run --rm --name emqx -d -p 1883:1883 emqx/emqx
+//!       $ docker run --rm --name redis -d -p 6379:6379 redis:alpine
 //!
 //! Then run the test:
 //!
@@ -58,20 +107,39 @@
 //!
 //!     $ RUN_MQTT= cargo run --example simple
 
-use std::{error::Error as StdError, fmt, sync::Arc};
+use std::{error::Error as StdError, fmt, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use rand::{RngExt, distr::Alphanumeric};
 
 pub mod connection;
+pub mod metrics;
 pub mod queue;
+pub mod rpc;
+pub mod typed;
 
 mod amqp;
+mod compression;
+mod hosts;
+mod memory;
 mod mqtt;
+mod outbox;
+mod reconnect;
+mod redis;
+mod tls;
 
-pub use amqp::{AmqpConnection, AmqpConnectionOptions, AmqpQueue, AmqpQueueOptions};
-pub use mqtt::{MqttConnection, MqttConnectionOptions, MqttQueue, MqttQueueOptions};
-use queue::{EventHandler, GmqQueue, MessageHandler, Status};
+pub use amqp::{
+    AmqpConnection, AmqpConnectionOptions, AmqpOverflow, AmqpQueue, AmqpQueueOptions, AmqpQueueType,
+};
+pub use compression::Compression;
+pub use memory::{MemoryConnection, MemoryConnectionOptions, MemoryQueue, MemoryQueueOptions};
+use metrics::QueueMetrics;
+pub use mqtt::{MqttConnection, MqttConnectionOptions, MqttQueue, MqttQueueOptions, MqttVersion};
+pub use outbox::OutboxOptions;
+use queue::{EventHandler, GmqQueue, MessageHandler, MessageProperties, Status};
+pub use reconnect::ReconnectPolicy;
+pub use redis::{RedisConnection, RedisConnectionOptions, RedisQueue, RedisQueueOptions};
+pub use tls::TlsOptions;
 
 /// general-mq error.
 #[derive(Clone, Debug)]
@@ -83,18 +151,26 @@ pub enum Error {
     NotConnected,
     /// The queue is a receiver that cannot send messages.
     QueueIsReceiver,
+    /// The reason code (and the reason string) from the MQTT 5 broker.
+    MqttReason(String),
+    /// The RPC call does not receive the reply in time.
+    Timeout,
 }
 
 #[derive(Clone)]
 pub enum Queue {
     Amqp(AmqpQueue),
     Mqtt(MqttQueue),
+    Memory(MemoryQueue),
+    Redis(RedisQueue),
 }
 
 #[derive(Clone)]
 pub enum QueueOptions<'a> {
     Amqp(AmqpQueueOptions, &'a AmqpConnection),
     Mqtt(MqttQueueOptions, &'a MqttConnection),
+    Memory(MemoryQueueOptions, &'a MemoryConnection),
+    Redis(RedisQueueOptions, &'a RedisConnection),
 }
 
 /// Identifier length of inner handlers.
@@ -102,10 +178,12 @@ pub(crate) const ID_SIZE: usize = 24;
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             Error::NoMsgHandler => write!(f, "no message handler"),
             Error::NotConnected => write!(f, "not connected"),
             Error::QueueIsReceiver => write!(f, "this queue is a receiver"),
+            Error::MqttReason(reason) => write!(f, "MQTT reason: {}", reason),
+            Error::Timeout => write!(f, "timeout"),
         }
     }
 }
@@ -117,6 +195,18 @@ impl Queue {
         match opts {
             QueueOptions::Amqp(opts, conn) => Ok(Queue::Amqp(AmqpQueue::new(opts, conn)?)),
             QueueOptions::Mqtt(opts, conn) => Ok(Queue::Mqtt(MqttQueue::new(opts, conn)?)),
+            QueueOptions::Memory(opts, conn) => Ok(Queue::Memory(MemoryQueue::new(opts, conn)?)),
+            QueueOptions::Redis(opts, conn) => Ok(Queue::Redis(RedisQueue::new(opts, conn)?)),
+        }
+    }
+
+    /// To get the snapshot of metrics.
+    pub fn metrics(&self) -> QueueMetrics {
+        match self {
+            Queue::Amqp(q) => q.metrics(),
+            Queue::Mqtt(q) => q.metrics(),
+            Queue::Memory(q) => q.metrics(),
+            Queue::Redis(q) => q.metrics(),
         }
     }
 }
@@ -127,6 +217,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.name(),
             Queue::Mqtt(q) => q.name(),
+            Queue::Memory(q) => q.name(),
+            Queue::Redis(q) => q.name(),
         }
     }
 
@@ -134,6 +226,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.is_recv(),
             Queue::Mqtt(q) => q.is_recv(),
+            Queue::Memory(q) => q.is_recv(),
+            Queue::Redis(q) => q.is_recv(),
         }
     }
 
@@ -141,6 +235,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.status(),
             Queue::Mqtt(q) => q.status(),
+            Queue::Memory(q) => q.status(),
+            Queue::Redis(q) => q.status(),
         }
     }
 
@@ -148,6 +244,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.set_handler(handler),
             Queue::Mqtt(q) => q.set_handler(handler),
+            Queue::Memory(q) => q.set_handler(handler),
+            Queue::Redis(q) => q.set_handler(handler),
         }
     }
 
@@ -155,6 +253,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.clear_handler(),
             Queue::Mqtt(q) => q.clear_handler(),
+            Queue::Memory(q) => q.clear_handler(),
+            Queue::Redis(q) => q.clear_handler(),
         }
     }
 
@@ -162,6 +262,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.set_msg_handler(handler),
             Queue::Mqtt(q) => q.set_msg_handler(handler),
+            Queue::Memory(q) => q.set_msg_handler(handler),
+            Queue::Redis(q) => q.set_msg_handler(handler),
         }
     }
 
@@ -169,6 +271,8 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.connect(),
             Queue::Mqtt(q) => q.connect(),
+            Queue::Memory(q) => q.connect(),
+            Queue::Redis(q) => q.connect(),
         }
     }
 
@@ -176,13 +280,69 @@ impl GmqQueue for Queue {
         match self {
             Queue::Amqp(q) => q.close().await,
             Queue::Mqtt(q) => q.close().await,
+            Queue::Memory(q) => q.close().await,
+            Queue::Redis(q) => q.close().await,
+        }
+    }
+
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match self {
+            Queue::Amqp(q) => q.close_graceful(timeout).await,
+            Queue::Mqtt(q) => q.close_graceful(timeout).await,
+            Queue::Memory(q) => q.close_graceful(timeout).await,
+            Queue::Redis(q) => q.close_graceful(timeout).await,
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match self {
+            Queue::Amqp(q) => q.drain(timeout).await,
+            Queue::Mqtt(q) => q.drain(timeout).await,
+            Queue::Memory(q) => q.drain(timeout).await,
+            Queue::Redis(q) => q.drain(timeout).await,
+        }
+    }
+
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match self {
+            Queue::Amqp(q) => q.pause().await,
+            Queue::Mqtt(q) => q.pause().await,
+            Queue::Memory(q) => q.pause().await,
+            Queue::Redis(q) => q.pause().await,
+        }
+    }
+
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match self {
+            Queue::Amqp(q) => q.resume().await,
+            Queue::Mqtt(q) => q.resume().await,
+            Queue::Memory(q) => q.resume().await,
+            Queue::Redis(q) => q.resume().await,
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        match self {
+            Queue::Amqp(q) => q.is_paused(),
+            Queue::Mqtt(q) => q.is_paused(),
+            Queue::Memory(q) => q.is_paused(),
+            Queue::Redis(q) => q.is_paused(),
         }
     }
 
-    async fn send_msg(&self, payload: Vec<u8>) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
         match self {
-            Queue::Amqp(q) => q.send_msg(payload).await,
-            Queue::Mqtt(q) => q.send_msg(payload).await,
+            Queue::Amqp(q) => q.send_msg_with_props(payload, props).await,
+            Queue::Mqtt(q) => q.send_msg_with_props(payload, props).await,
+            Queue::Memory(q) => q.send_msg_with_props(payload, props).await,
+            Queue::Redis(q) => q.send_msg_with_props(payload, props).await,
         }
     }
 }
@@ -0,0 +1,323 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::queue::MessageProperties;
+
+/// Packet handler definitions.
+///
+/// Receiver [`crate::MemoryQueue`] queues must register a handler to receive packets.
+pub(super) trait PacketHandler: Send + Sync {
+    /// For delivered packets.
+    fn on_packet(&self, packet: Packet);
+}
+
+/// A packet delivered from the broker to a subscriber.
+pub(super) struct Packet {
+    /// The delivery tag for ack/nack.
+    pub tag: u64,
+    /// The message content.
+    pub content: Content,
+}
+
+/// A message with its properties.
+#[derive(Clone)]
+pub(super) struct Content {
+    /// The message payload.
+    pub payload: Vec<u8>,
+    /// The message properties.
+    pub props: MessageProperties,
+}
+
+/// The in-process message broker. Brokers are shared by all connections with the same name within
+/// the process.
+pub(super) struct Broker {
+    inner: Mutex<Inner>,
+}
+
+/// The broker states.
+#[derive(Default)]
+struct Inner {
+    /// Unicast queues. The key is **the queue name**.
+    unicast: HashMap<String, Unicast>,
+    /// Broadcast topics. The keys are **the queue name** and **the subscriber identifier**.
+    broadcast: HashMap<String, HashMap<String, Subscriber>>,
+    /// The next delivery tag.
+    next_tag: u64,
+}
+
+/// A unicast queue.
+#[derive(Default)]
+struct Unicast {
+    /// Subscribers that consume messages in round-robin order.
+    subscribers: Vec<Subscriber>,
+    /// The index of the next subscriber.
+    next: usize,
+    /// Messages that are waiting for subscribers.
+    pending: VecDeque<Content>,
+}
+
+/// A subscriber of a queue.
+struct Subscriber {
+    /// The subscriber identifier.
+    id: String,
+    /// Track delivered messages until ack/nack.
+    reliable: bool,
+    /// Stop delivering messages to this subscriber.
+    paused: bool,
+    /// The packet handler.
+    handler: Arc<dyn PacketHandler>,
+    /// Delivered messages that are not acknowledged. The key is the delivery tag.
+    unacked: HashMap<u64, Content>,
+}
+
+/// Packets to be delivered after releasing the broker lock.
+type Deliveries = Vec<(Arc<dyn PacketHandler>, Packet)>;
+
+/// All brokers in this process. The key is the broker name.
+static BROKERS: OnceLock<Mutex<HashMap<String, Arc<Broker>>>> = OnceLock::new();
+
+/// To get the broker with the specified name. A new broker will be created if it does not exist.
+pub(super) fn get_broker(name: &str) -> Arc<Broker> {
+    let brokers = BROKERS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut mutex = brokers.lock().unwrap();
+    match mutex.get(name) {
+        Some(broker) => broker.clone(),
+        None => {
+            let broker = Arc::new(Broker {
+                inner: Mutex::new(Inner::default()),
+            });
+            mutex.insert(name.to_string(), broker.clone());
+            broker
+        }
+    }
+}
+
+impl Broker {
+    /// To add a subscriber to a queue. Pending messages of a unicast queue will be delivered.
+    pub fn subscribe(
+        &self,
+        name: &str,
+        broadcast: bool,
+        id: &str,
+        reliable: bool,
+        paused: bool,
+        handler: Arc<dyn PacketHandler>,
+    ) {
+        let subscriber = Subscriber {
+            id: id.to_string(),
+            reliable,
+            paused,
+            handler,
+            unacked: HashMap::new(),
+        };
+        let deliveries = {
+            let mut inner = self.inner.lock().unwrap();
+            if broadcast {
+                inner
+                    .broadcast
+                    .entry(name.to_string())
+                    .or_default()
+                    .insert(id.to_string(), subscriber);
+                return;
+            }
+            let inner = &mut *inner;
+            let queue = inner.unicast.entry(name.to_string()).or_default();
+            queue.subscribers.retain(|s| s.id.as_str() != id);
+            queue.subscribers.push(subscriber);
+            queue.dispatch(&mut inner.next_tag)
+        };
+        deliver(deliveries);
+    }
+
+    /// To remove a subscriber from a queue. Unacknowledged messages of a unicast queue will be
+    /// requeued.
+    pub fn unsubscribe(&self, name: &str, broadcast: bool, id: &str) {
+        let deliveries = {
+            let mut inner = self.inner.lock().unwrap();
+            if broadcast {
+                if let Some(topic) = inner.broadcast.get_mut(name) {
+                    topic.remove(id);
+                    if topic.is_empty() {
+                        inner.broadcast.remove(name);
+                    }
+                }
+                return;
+            }
+            let inner = &mut *inner;
+            let queue = match inner.unicast.get_mut(name) {
+                None => return,
+                Some(queue) => queue,
+            };
+            let index = match queue.subscribers.iter().position(|s| s.id.as_str() == id) {
+                None => return,
+                Some(index) => index,
+            };
+            let subscriber = queue.subscribers.remove(index);
+            let mut unacked: Vec<(u64, Content)> = subscriber.unacked.into_iter().collect();
+            unacked.sort_by_key(|(tag, _)| *tag);
+            for (_, content) in unacked.into_iter().rev() {
+                queue.pending.push_front(content);
+            }
+            queue.dispatch(&mut inner.next_tag)
+        };
+        deliver(deliveries);
+    }
+
+    /// To pause or resume delivering messages to a subscriber. Pending messages of a unicast queue
+    /// will be delivered after resuming.
+    pub fn set_paused(&self, name: &str, broadcast: bool, id: &str, paused: bool) {
+        let deliveries = {
+            let mut inner = self.inner.lock().unwrap();
+            let inner = &mut *inner;
+            if let Some(subscriber) = inner.subscriber(name, broadcast, id) {
+                subscriber.paused = paused;
+            }
+            if broadcast || paused {
+                return;
+            }
+            match inner.unicast.get_mut(name) {
+                None => return,
+                Some(queue) => queue.dispatch(&mut inner.next_tag),
+            }
+        };
+        deliver(deliveries);
+    }
+
+    /// To publish a message to a queue.
+    ///
+    /// Messages of a unicast queue are kept until there are subscribers. Messages of a broadcast
+    /// queue are dropped if there are no subscribers or the subscribers are paused.
+    pub fn publish(&self, name: &str, broadcast: bool, content: Content) {
+        let deliveries = {
+            let mut inner = self.inner.lock().unwrap();
+            let inner = &mut *inner;
+            if broadcast {
+                let mut deliveries = vec![];
+                if let Some(topic) = inner.broadcast.get_mut(name) {
+                    for subscriber in topic.values_mut().filter(|s| !s.paused) {
+                        deliveries.push(subscriber.deliver(&mut inner.next_tag, content.clone()));
+                    }
+                }
+                deliveries
+            } else {
+                let queue = inner.unicast.entry(name.to_string()).or_default();
+                queue.pending.push_back(content);
+                queue.dispatch(&mut inner.next_tag)
+            }
+        };
+        deliver(deliveries);
+    }
+
+    /// To acknowledge a delivered message.
+    pub fn ack(&self, name: &str, broadcast: bool, id: &str, tag: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(subscriber) = inner.subscriber(name, broadcast, id) {
+            subscriber.unacked.remove(&tag);
+        }
+    }
+
+    /// To reject a delivered message. The message will be delivered again for reliable
+    /// subscribers.
+    pub fn nack(&self, name: &str, broadcast: bool, id: &str, tag: u64) {
+        let deliveries = {
+            let mut inner = self.inner.lock().unwrap();
+            let inner = &mut *inner;
+            if broadcast {
+                let subscriber = match inner
+                    .broadcast
+                    .get_mut(name)
+                    .and_then(|topic| topic.get_mut(id))
+                {
+                    None => return,
+                    Some(subscriber) => subscriber,
+                };
+                match subscriber.unacked.remove(&tag) {
+                    None => return,
+                    Some(content) => vec![subscriber.deliver(&mut inner.next_tag, content)],
+                }
+            } else {
+                let queue = match inner.unicast.get_mut(name) {
+                    None => return,
+                    Some(queue) => queue,
+                };
+                let content = match queue
+                    .subscribers
+                    .iter_mut()
+                    .find(|s| s.id.as_str() == id)
+                    .and_then(|s| s.unacked.remove(&tag))
+                {
+                    None => return,
+                    Some(content) => content,
+                };
+                queue.pending.push_front(content);
+                queue.dispatch(&mut inner.next_tag)
+            }
+        };
+        deliver(deliveries);
+    }
+}
+
+impl Inner {
+    /// To get the subscriber of a queue.
+    fn subscriber(&mut self, name: &str, broadcast: bool, id: &str) -> Option<&mut Subscriber> {
+        match broadcast {
+            false => self
+                .unicast
+                .get_mut(name)?
+                .subscribers
+                .iter_mut()
+                .find(|s| s.id.as_str() == id),
+            true => self.broadcast.get_mut(name)?.get_mut(id),
+        }
+    }
+}
+
+impl Unicast {
+    /// To deliver pending messages to subscribers that are not paused in round-robin order.
+    fn dispatch(&mut self, next_tag: &mut u64) -> Deliveries {
+        let mut deliveries = vec![];
+        if self.subscribers.iter().all(|s| s.paused) {
+            return deliveries;
+        }
+        while let Some(content) = self.pending.pop_front() {
+            loop {
+                if self.next >= self.subscribers.len() {
+                    self.next = 0;
+                }
+                if !self.subscribers[self.next].paused {
+                    break;
+                }
+                self.next += 1;
+            }
+            let subscriber = &mut self.subscribers[self.next];
+            deliveries.push(subscriber.deliver(next_tag, content));
+            self.next += 1;
+        }
+        deliveries
+    }
+}
+
+impl Subscriber {
+    /// To generate a delivery for this subscriber.
+    fn deliver(
+        &mut self,
+        next_tag: &mut u64,
+        content: Content,
+    ) -> (Arc<dyn PacketHandler>, Packet) {
+        *next_tag += 1;
+        let tag = *next_tag;
+        if self.reliable {
+            self.unacked.insert(tag, content.clone());
+        }
+        (self.handler.clone(), Packet { tag, content })
+    }
+}
+
+/// To call packet handlers.
+fn deliver(deliveries: Deliveries) {
+    for (handler, packet) in deliveries {
+        handler.on_packet(packet);
+    }
+}
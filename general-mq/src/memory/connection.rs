@@ -0,0 +1,238 @@
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::task::{self, JoinHandle};
+use url::Url;
+
+use super::broker::{self, Broker, PacketHandler};
+use crate::{
+    ID_SIZE,
+    connection::{EventHandler, GmqConnection, QueueRegistry, Status},
+    randomstring,
+};
+
+/// Manages a connection to an in-process memory broker.
+#[derive(Clone)]
+pub struct MemoryConnection {
+    /// Options of the connection.
+    opts: InnerOptions,
+    /// Connection status.
+    status: Arc<Mutex<Status>>,
+    /// Hold the broker instance.
+    broker: Arc<Mutex<Option<Arc<Broker>>>>,
+    /// Event handlers.
+    handlers: Arc<Mutex<HashMap<String, Arc<dyn EventHandler>>>>,
+    /// Subscriptions of receiver queues. The key is **the subscriber identifier** and the value is
+    /// the queue name and the broadcast flag.
+    ///
+    /// All subscriptions will be removed when closing the connection.
+    subscriptions: Arc<Mutex<HashMap<String, (String, bool)>>>,
+    /// The task to report connected status.
+    ev_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Queues that are connected with this connection.
+    queues: QueueRegistry,
+}
+
+/// The connection options.
+pub struct MemoryConnectionOptions {
+    /// Connection URI. Use `mem://name` format. Connections with the same name share the same
+    /// in-process broker.
+    ///
+    /// Default is `mem://localhost`.
+    pub uri: String,
+}
+
+/// The validated options for management.
+#[derive(Clone)]
+struct InnerOptions {
+    /// The broker name.
+    name: String,
+}
+
+/// Default broker name.
+const DEF_BROKER_NAME: &str = "localhost";
+
+impl MemoryConnection {
+    /// Create a connection instance.
+    pub fn new(opts: MemoryConnectionOptions) -> Result<MemoryConnection, String> {
+        let uri = Url::parse(opts.uri.as_str()).map_err(|e| e.to_string())?;
+        if uri.scheme() != "mem" {
+            return Err(format!("Invalid memory scheme: {}", uri.scheme()));
+        }
+        let name = match uri.host_str() {
+            None | Some("") => DEF_BROKER_NAME.to_string(),
+            Some(host) => host.to_string(),
+        };
+
+        Ok(MemoryConnection {
+            opts: InnerOptions { name },
+            status: Arc::new(Mutex::new(Status::Closed)),
+            broker: Arc::new(Mutex::new(None)),
+            handlers: Arc::new(Mutex::new(HashMap::<String, Arc<dyn EventHandler>>::new())),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            ev_loop: Arc::new(Mutex::new(None)),
+            queues: QueueRegistry::default(),
+        })
+    }
+
+    /// To get the broker instance for publishing messages.
+    pub(super) fn get_broker(&self) -> Option<Arc<Broker>> {
+        self.broker.lock().unwrap().clone()
+    }
+
+    /// To subscribe a queue for [`crate::MemoryQueue`]. The `id` is the subscriber identifier.
+    pub(super) fn subscribe(
+        &self,
+        name: &str,
+        broadcast: bool,
+        id: &str,
+        reliable: bool,
+        paused: bool,
+        handler: Arc<dyn PacketHandler>,
+    ) -> bool {
+        let broker = match self.get_broker() {
+            None => return false,
+            Some(broker) => broker,
+        };
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), (name.to_string(), broadcast));
+        broker.subscribe(name, broadcast, id, reliable, paused, handler);
+        true
+    }
+
+    /// To pause or resume the subscription. The `id` is the subscriber identifier.
+    pub(super) fn set_paused(&self, id: &str, paused: bool) {
+        let subscription = { self.subscriptions.lock().unwrap().get(id).cloned() };
+        if let (Some((name, broadcast)), Some(broker)) = (subscription, self.get_broker()) {
+            broker.set_paused(name.as_str(), broadcast, id, paused);
+        }
+    }
+
+    /// To unsubscribe a queue. The `id` is the subscriber identifier.
+    pub(super) fn unsubscribe(&self, id: &str) {
+        let subscription = { self.subscriptions.lock().unwrap().remove(id) };
+        if let (Some((name, broadcast)), Some(broker)) = (subscription, self.get_broker()) {
+            broker.unsubscribe(name.as_str(), broadcast, id);
+        }
+    }
+
+    /// To get the registry of connected queues.
+    pub(crate) fn queues(&self) -> QueueRegistry {
+        self.queues.clone()
+    }
+}
+
+#[async_trait]
+impl GmqConnection for MemoryConnection {
+    fn status(&self) -> Status {
+        *self.status.lock().unwrap()
+    }
+
+    fn add_handler(&mut self, handler: Arc<dyn EventHandler>) -> String {
+        let id = randomstring(ID_SIZE);
+        self.handlers.lock().unwrap().insert(id.clone(), handler);
+        id
+    }
+
+    fn remove_handler(&mut self, id: &str) {
+        self.handlers.lock().unwrap().remove(id);
+    }
+
+    fn connect(&mut self) -> Result<(), Box<dyn StdError>> {
+        {
+            let mut task_handle_mutex = self.ev_loop.lock().unwrap();
+            if (*task_handle_mutex).is_some() {
+                return Ok(());
+            }
+            *self.status.lock().unwrap() = Status::Connecting;
+            *task_handle_mutex = Some(create_event_loop(self));
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let ev_loop = { self.ev_loop.lock().unwrap().take() };
+        match ev_loop {
+            None => return Ok(()),
+            Some(handle) => handle.abort(),
+        }
+        {
+            *self.status.lock().unwrap() = Status::Closing;
+        }
+
+        let subscriptions: Vec<(String, (String, bool))> =
+            { self.subscriptions.lock().unwrap().drain().collect() };
+        let broker = { self.broker.lock().unwrap().take() };
+        if let Some(broker) = broker {
+            for (id, (name, broadcast)) in subscriptions {
+                broker.unsubscribe(name.as_str(), broadcast, id.as_str());
+            }
+        }
+
+        {
+            *self.status.lock().unwrap() = Status::Closed;
+        }
+        let handlers = { (*self.handlers.lock().unwrap()).clone() };
+        for (id, handler) in handlers {
+            let conn = Arc::new(self.clone());
+            task::spawn(async move {
+                handler.on_status(id.clone(), conn, Status::Closed).await;
+            });
+        }
+        Ok(())
+    }
+
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.queues.close_graceful(timeout).await;
+        self.close().await?;
+        result?;
+        Ok(())
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.queues.drain(timeout).await?;
+        Ok(())
+    }
+}
+
+impl Default for MemoryConnectionOptions {
+    fn default() -> Self {
+        MemoryConnectionOptions {
+            uri: format!("mem://{}", DEF_BROKER_NAME),
+        }
+    }
+}
+
+/// To create a runtime task that attaches the broker. The memory broker is always available, so
+/// the connection will be connected immediately.
+fn create_event_loop(conn: &MemoryConnection) -> JoinHandle<()> {
+    let this = Arc::new(conn.clone());
+    task::spawn(async move {
+        {
+            let mut status_mutex = this.status.lock().unwrap();
+            if *status_mutex != Status::Connecting {
+                return;
+            }
+            *this.broker.lock().unwrap() = Some(broker::get_broker(this.opts.name.as_str()));
+            *status_mutex = Status::Connected;
+        }
+
+        let handlers = { (*this.handlers.lock().unwrap()).clone() };
+        for (id, handler) in handlers {
+            let conn = this.clone();
+            task::spawn(async move {
+                handler.on_status(id.clone(), conn, Status::Connected).await;
+            });
+        }
+    })
+}
@@ -0,0 +1,8 @@
+//! In-process memory implementation.
+
+mod broker;
+mod connection;
+mod queue;
+
+pub use connection::{MemoryConnection, MemoryConnectionOptions};
+pub use queue::{MemoryQueue, MemoryQueueOptions};
@@ -0,0 +1,574 @@
+use std::{
+    collections::VecDeque,
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use tokio::{
+    task::{self, JoinHandle},
+    time,
+};
+
+use super::{
+    broker::{Broker, Content, Packet, PacketHandler},
+    connection::MemoryConnection,
+};
+use crate::{
+    Error, ID_SIZE,
+    compression::{self, Compression},
+    connection::{GmqConnection, Status as ConnStatus},
+    metrics::{Metrics, QueueMetrics},
+    outbox::{Outbox, OutboxOptions, Publisher},
+    queue::{
+        self, EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode,
+        QUEUE_NAME_PATTERN, Status, name_validate,
+    },
+    randomstring,
+};
+
+/// Manages a memory queue.
+#[derive(Clone)]
+pub struct MemoryQueue {
+    /// Options of the queue.
+    opts: MemoryQueueOptions,
+    /// The subscriber identifier of the receiver.
+    id: String,
+    /// The associated [`crate::MemoryConnection`].
+    conn: Arc<Mutex<MemoryConnection>>,
+    /// Queue status.
+    status: Arc<Mutex<Status>>,
+    /// The event handler.
+    handler: Arc<Mutex<Option<Arc<dyn EventHandler>>>>,
+    /// The message handler.
+    msg_handler: Arc<Mutex<Option<Arc<dyn MessageHandler>>>>,
+    /// The event loop to manage and monitor the connection.
+    ev_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// `true` to stop delivering messages to this receiver.
+    paused: Arc<Mutex<bool>>,
+    /// Packets that are waiting for a permit of the message handler limiter.
+    pending: Arc<Mutex<VecDeque<Packet>>>,
+    /// The on-disk outbox (for senders).
+    outbox: Option<Arc<Outbox>>,
+    /// Metrics of the queue.
+    metrics: Arc<Metrics>,
+}
+
+/// The queue options.
+#[derive(Clone)]
+pub struct MemoryQueueOptions {
+    /// The queue name.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    pub name: String,
+    /// `true` for the receiver and `false` for the sender.
+    pub is_recv: bool,
+    /// Reliable by tracking delivered messages until ack/nack (for receivers). Unacknowledged
+    /// messages will be delivered again after `nack()` or closing the receiver.
+    pub reliable: bool,
+    /// `true` for broadcast and `false` for unicast.
+    pub broadcast: bool,
+    /// Time in milliseconds from disconnection to reconnection.
+    ///
+    /// Default or zero value is `1000`.
+    pub reconnect_millis: u64,
+    /// The dead-letter queue name (for receivers). Messages of [`NackMode::DeadLetter`] are sent to
+    /// this unicast queue.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    pub dead_letter: Option<String>,
+    /// The on-disk outbox to store messages while the queue is not connected (for senders).
+    /// Stored messages are sent in order after the queue is connected. Use `None` to return
+    /// [`Error::NotConnected`] instead.
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of payloads (for senders). Receivers decompress payloads
+    /// automatically.
+    pub compression: Compression,
+}
+
+/// The memory [`Message`] implementation.
+struct MemoryMessage {
+    /// The broker to operate ack/nack.
+    broker: Arc<Broker>,
+    /// The queue name.
+    name: String,
+    /// The broadcast flag of the queue.
+    broadcast: bool,
+    /// The subscriber identifier of the receiver.
+    id: String,
+    /// The dead-letter queue name.
+    dead_letter: Option<String>,
+    /// Hold the delivered packet.
+    packet: Packet,
+    /// Metrics of the receiver.
+    metrics: Arc<Metrics>,
+}
+
+/// Default reconnect time in milliseconds.
+const DEF_RECONN_TIME_MS: u64 = 1000;
+
+impl MemoryQueue {
+    /// Create a queue instance.
+    pub fn new(opts: MemoryQueueOptions, conn: &MemoryConnection) -> Result<MemoryQueue, String> {
+        let name = opts.name.as_str();
+        if name.is_empty() {
+            return Err("queue name cannot be empty".to_string());
+        } else if !name_validate(name) {
+            return Err(format!(
+                "queue name {} is not match {}",
+                name, QUEUE_NAME_PATTERN
+            ));
+        }
+        if let Some(dead_letter) = opts.dead_letter.as_ref()
+            && !name_validate(dead_letter)
+        {
+            return Err(format!(
+                "dead-letter queue name {} is not match {}",
+                dead_letter, QUEUE_NAME_PATTERN
+            ));
+        }
+        let mut opts = opts;
+        if opts.reconnect_millis == 0 {
+            opts.reconnect_millis = DEF_RECONN_TIME_MS;
+        }
+        let outbox = match opts.outbox.as_ref() {
+            None => None,
+            Some(outbox_opts) => match opts.is_recv {
+                true => return Err("outbox cannot be used by a receiver".to_string()),
+                false => Some(Arc::new(Outbox::new(outbox_opts)?)),
+            },
+        };
+
+        let metrics = Arc::new(Metrics::new(opts.name.as_str(), opts.is_recv));
+
+        Ok(MemoryQueue {
+            opts,
+            id: randomstring(ID_SIZE),
+            conn: Arc::new(Mutex::new(conn.clone())),
+            status: Arc::new(Mutex::new(Status::Closed)),
+            handler: Arc::new(Mutex::new(None)),
+            msg_handler: Arc::new(Mutex::new(None)),
+            ev_loop: Arc::new(Mutex::new(None)),
+            paused: Arc::new(Mutex::new(false)),
+            pending: Arc::new(Mutex::new(VecDeque::new())),
+            outbox,
+            metrics,
+        })
+    }
+
+    /// To get the number of messages in the outbox.
+    pub async fn outbox_len(&self) -> usize {
+        match self.outbox.as_ref() {
+            None => 0,
+            Some(outbox) => outbox.len().await,
+        }
+    }
+
+    /// To get the snapshot of metrics.
+    pub fn metrics(&self) -> QueueMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// To publish a message to the broker.
+    fn publish_content(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.status() != Status::Connected {
+            return Err(Box::new(Error::NotConnected));
+        }
+
+        let broker = {
+            match self.conn.lock().unwrap().get_broker() {
+                None => return Err(Box::new(Error::NotConnected)),
+                Some(broker) => broker,
+            }
+        };
+        let content = Content { payload, props };
+        broker.publish(self.opts.name.as_str(), self.opts.broadcast, content);
+        Ok(())
+    }
+
+    /// To get the associated connection status.
+    fn conn_status(&self) -> ConnStatus {
+        self.conn.lock().unwrap().status()
+    }
+
+    /// To get the event handler.
+    fn handler(&self) -> Option<Arc<dyn EventHandler>> {
+        self.handler.lock().unwrap().clone()
+    }
+
+    /// To get the message handler.
+    fn msg_handler(&self) -> Option<Arc<dyn MessageHandler>> {
+        self.msg_handler.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl GmqQueue for MemoryQueue {
+    fn name(&self) -> &str {
+        self.opts.name.as_str()
+    }
+
+    fn is_recv(&self) -> bool {
+        self.opts.is_recv
+    }
+
+    fn status(&self) -> Status {
+        *self.status.lock().unwrap()
+    }
+
+    fn set_handler(&mut self, handler: Arc<dyn EventHandler>) {
+        *self.handler.lock().unwrap() = Some(handler);
+    }
+
+    fn clear_handler(&mut self) {
+        let _ = (*self.handler.lock().unwrap()).take();
+    }
+
+    fn set_msg_handler(&mut self, handler: Arc<dyn MessageHandler>) {
+        *self.msg_handler.lock().unwrap() = Some(handler);
+    }
+
+    fn connect(&mut self) -> Result<(), Box<dyn StdError>> {
+        if self.opts.is_recv && self.msg_handler().is_none() {
+            return Err(Box::new(Error::NoMsgHandler));
+        }
+
+        {
+            let mut task_handle_mutex = self.ev_loop.lock().unwrap();
+            if (*task_handle_mutex).is_some() {
+                return Ok(());
+            }
+            *self.status.lock().unwrap() = Status::Connecting;
+            self.metrics.on_disconnected();
+            *task_handle_mutex = Some(create_event_loop(self));
+        }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.add(self.id.as_str(), Box::new(self.clone()));
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let ev_loop = { self.ev_loop.lock().unwrap().take() };
+        match ev_loop {
+            None => return Ok(()),
+            Some(handle) => handle.abort(),
+        }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.remove(self.id.as_str());
+        {
+            *self.status.lock().unwrap() = Status::Closing;
+        }
+
+        if self.opts.is_recv {
+            let conn = { self.conn.lock().unwrap().clone() };
+            conn.unsubscribe(self.id.as_str());
+        }
+
+        {
+            *self.status.lock().unwrap() = Status::Closed;
+        }
+        self.metrics.on_closed();
+        if let Some(handler) = { (*self.handler.lock().unwrap()).clone() } {
+            let queue = Arc::new(self.clone());
+            task::spawn(async move {
+                handler.on_status(queue, Status::Closed).await;
+            });
+        }
+        Ok(())
+    }
+
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        let drained = queue::drain(self, timeout, || metrics.in_process() == 0).await;
+        self.close().await?;
+        match drained {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        match queue::drain(self, timeout, || metrics.in_process() == 0).await {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        {
+            *self.paused.lock().unwrap() = true;
+        }
+        let conn = { self.conn.lock().unwrap().clone() };
+        conn.set_paused(self.id.as_str(), true);
+        Ok(())
+    }
+
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        {
+            *self.paused.lock().unwrap() = false;
+        }
+        let conn = { self.conn.lock().unwrap().clone() };
+        conn.set_paused(self.id.as_str(), false);
+        Ok(())
+    }
+
+    fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.opts.is_recv {
+            return Err(Box::new(Error::QueueIsReceiver));
+        }
+
+        let (payload, props) = compression::compress(self.opts.compression, payload, props)?;
+        match self.outbox.as_ref() {
+            None => self.publish(payload, props).await,
+            Some(outbox) => outbox.send(Arc::new(self.clone()), payload, props).await,
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for MemoryQueue {
+    fn is_connected(&self) -> bool {
+        self.status() == Status::Connected
+    }
+
+    async fn publish(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.publish_content(payload, props);
+        self.metrics.on_sent(result.is_ok());
+        result
+    }
+}
+
+impl MemoryQueue {
+    /// To deliver one packet to the message handler.
+    async fn handle_packet(self: &Arc<Self>, handler: &Arc<dyn MessageHandler>, packet: Packet) {
+        let broker = { self.conn.lock().unwrap().get_broker() };
+        let broker = match broker {
+            None => return,
+            Some(broker) => broker,
+        };
+        let message = Box::new(MemoryMessage {
+            broker,
+            name: self.opts.name.clone(),
+            broadcast: self.opts.broadcast,
+            id: self.id.clone(),
+            dead_letter: self.opts.dead_letter.clone(),
+            packet,
+            metrics: self.metrics.clone(),
+        });
+        self.metrics.on_received();
+        match compression::decompress(message, false).await {
+            None => self.metrics.on_rejected(),
+            Some(message) => {
+                let start = Instant::now();
+                handler.on_message(self.clone(), message).await;
+                self.metrics.on_handled(start.elapsed());
+            }
+        }
+    }
+}
+
+impl PacketHandler for MemoryQueue {
+    fn on_packet(&self, packet: Packet) {
+        let handler = match self.msg_handler() {
+            None => return,
+            Some(handler) => handler,
+        };
+        let permit = match handler.limiter() {
+            None => None,
+            Some(limiter) => {
+                let mut pending = self.pending.lock().unwrap();
+                match limiter.try_acquire_owned() {
+                    Err(_) => {
+                        pending.push_back(packet);
+                        return;
+                    }
+                    Ok(permit) => Some(permit),
+                }
+            }
+        };
+
+        // The task keeps the permit to process buffered packets.
+        let this = Arc::new(self.clone());
+        task::spawn(async move {
+            let mut packet = packet;
+            let mut permit = permit;
+            loop {
+                this.handle_packet(&handler, packet).await;
+                if permit.is_none() {
+                    break;
+                }
+                // Release the permit with the lock so that no packets are left in the buffer.
+                let next = {
+                    let mut pending = this.pending.lock().unwrap();
+                    match pending.pop_front() {
+                        None => {
+                            permit = None;
+                            None
+                        }
+                        Some(next) => Some(next),
+                    }
+                };
+                match next {
+                    None => break,
+                    Some(next) => packet = next,
+                }
+            }
+        });
+    }
+}
+
+impl Default for MemoryQueueOptions {
+    fn default() -> Self {
+        MemoryQueueOptions {
+            name: "".to_string(),
+            is_recv: false,
+            reliable: false,
+            broadcast: false,
+            reconnect_millis: DEF_RECONN_TIME_MS,
+            dead_letter: None,
+            outbox: None,
+            compression: Compression::None,
+        }
+    }
+}
+
+impl MemoryMessage {
+    /// To remove the packet from the broker.
+    fn ack_packet(&self) {
+        self.broker.ack(
+            self.name.as_str(),
+            self.broadcast,
+            self.id.as_str(),
+            self.packet.tag,
+        );
+    }
+}
+
+#[async_trait]
+impl Message for MemoryMessage {
+    fn payload(&self) -> &[u8] {
+        &self.packet.content.payload
+    }
+
+    fn properties(&self) -> &MessageProperties {
+        &self.packet.content.props
+    }
+
+    async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.ack_packet();
+        self.metrics.on_ack(true);
+        Ok(())
+    }
+
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match mode {
+            NackMode::Requeue => self.broker.nack(
+                self.name.as_str(),
+                self.broadcast,
+                self.id.as_str(),
+                self.packet.tag,
+            ),
+            NackMode::Drop => self.ack_packet(),
+            NackMode::DeadLetter => {
+                if let Some(dead_letter) = self.dead_letter.as_ref() {
+                    let content = self.packet.content.clone();
+                    self.broker.publish(dead_letter.as_str(), false, content);
+                }
+                self.ack_packet();
+            }
+        }
+        self.metrics.on_ack(false);
+        Ok(())
+    }
+}
+
+/// To create an event loop runtime task.
+fn create_event_loop(queue: &MemoryQueue) -> JoinHandle<()> {
+    let this = Arc::new(queue.clone());
+    task::spawn(async move {
+        loop {
+            match this.status() {
+                Status::Closing | Status::Closed | Status::Failed => break,
+                Status::Connecting => {
+                    if this.conn_status() != ConnStatus::Connected {
+                        time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                        continue;
+                    }
+
+                    if this.opts.is_recv {
+                        let conn = { this.conn.lock().unwrap().clone() };
+                        let subscribed = conn.subscribe(
+                            this.opts.name.as_str(),
+                            this.opts.broadcast,
+                            this.id.as_str(),
+                            this.opts.reliable,
+                            this.is_paused(),
+                            this.clone(),
+                        );
+                        if !subscribed {
+                            time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                            continue;
+                        }
+                    }
+
+                    {
+                        *this.status.lock().unwrap() = Status::Connected;
+                    }
+                    this.metrics.on_connected();
+                    if let Some(handler) = this.handler() {
+                        let queue = this.clone();
+                        task::spawn(async move {
+                            handler.on_status(queue, Status::Connected).await;
+                        });
+                    }
+                    if let Some(outbox) = this.outbox.as_ref() {
+                        outbox.spawn_flush(this.clone());
+                    }
+                }
+                Status::Connected => {
+                    time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                    if this.conn_status() != ConnStatus::Connected {
+                        if let Some(handler) = this.handler() {
+                            let queue = this.clone();
+                            task::spawn(async move {
+                                handler.on_status(queue, Status::Connecting).await;
+                            });
+                        }
+                        *this.status.lock().unwrap() = Status::Connecting;
+                        this.metrics.on_disconnected();
+                    }
+                }
+                Status::Disconnected => {
+                    *this.status.lock().unwrap() = Status::Connecting;
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,272 @@
+//! Metrics of queues.
+//!
+//! Each queue keeps its own counters that can be read with `metrics()`. The values are also
+//! recorded with the [`metrics`](::metrics) facade labeled by `queue` (the queue name) and `role`
+//! (`sender` or `receiver`), so they are exported by the installed recorder such as the Prometheus
+//! recorder of `axum-prometheus`. Use [`register()`] to describe them.
+//!
+//! **Note**: values that are recorded before installing the recorder are not exported.
+
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use ::metrics::{
+    Label, Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+
+/// A snapshot of the metrics of a queue.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueueMetrics {
+    /// The number of messages that are sent successfully.
+    pub sent: u64,
+    /// The number of messages that fail to be sent.
+    pub send_errors: u64,
+    /// The number of messages that are delivered to the message handler.
+    pub received: u64,
+    /// The number of acknowledged messages.
+    pub acked: u64,
+    /// The number of negatively acknowledged messages.
+    pub nacked: u64,
+    /// The number of times that the queue is connected again after the first connection.
+    pub reconnects: u64,
+    /// The queue is connected.
+    pub connected: bool,
+    /// Total time in milliseconds that the queue is not connected after `connect()`, including
+    /// the current disconnection.
+    pub disconnected_millis: u64,
+    /// The number of messages that the message handler has returned.
+    pub handled: u64,
+    /// Total time in microseconds spent by the message handler.
+    pub handler_micros: u64,
+    /// The maximum time in microseconds spent by the message handler for one message.
+    pub handler_max_micros: u64,
+}
+
+/// Counters of a queue.
+pub(crate) struct Metrics {
+    /// Labels of recorded metrics.
+    labels: Vec<Label>,
+    sent: AtomicU64,
+    send_errors: AtomicU64,
+    received: AtomicU64,
+    acked: AtomicU64,
+    nacked: AtomicU64,
+    reconnects: AtomicU64,
+    handled: AtomicU64,
+    handler_micros: AtomicU64,
+    handler_max_micros: AtomicU64,
+    /// Messages that are received and neither handled nor rejected.
+    in_process: AtomicU64,
+    /// The connection state.
+    conn: Mutex<ConnState>,
+}
+
+/// The connection state for measuring the disconnected time.
+#[derive(Default)]
+struct ConnState {
+    connected: bool,
+    /// The queue has been connected.
+    ever_connected: bool,
+    /// The start time of the current disconnection.
+    disconnected_since: Option<Instant>,
+    /// Total time of past disconnections.
+    disconnected: Duration,
+}
+
+const SENT: &str = "general_mq_messages_sent_total";
+const SEND_ERRORS: &str = "general_mq_send_errors_total";
+const RECEIVED: &str = "general_mq_messages_received_total";
+const ACKED: &str = "general_mq_messages_acked_total";
+const NACKED: &str = "general_mq_messages_nacked_total";
+const RECONNECTS: &str = "general_mq_reconnects_total";
+const CONNECTED: &str = "general_mq_connected";
+const DISCONNECTED: &str = "general_mq_disconnected_seconds";
+const HANDLER_DURATION: &str = "general_mq_handler_duration_seconds";
+
+/// To describe queue metrics to the installed recorder. Call this after installing the recorder
+/// (such as `PrometheusMetricLayer::pair()`) and before creating queues.
+pub fn register() {
+    describe_counter!(SENT, "The number of messages that are sent successfully.");
+    describe_counter!(SEND_ERRORS, "The number of messages that fail to be sent.");
+    describe_counter!(
+        RECEIVED,
+        "The number of messages that are delivered to the message handler."
+    );
+    describe_counter!(ACKED, "The number of acknowledged messages.");
+    describe_counter!(NACKED, "The number of negatively acknowledged messages.");
+    describe_counter!(
+        RECONNECTS,
+        "The number of times that the queue is connected again."
+    );
+    describe_gauge!(CONNECTED, "1 if the queue is connected, otherwise 0.");
+    describe_gauge!(
+        DISCONNECTED,
+        Unit::Seconds,
+        "Total time of past disconnections of the queue."
+    );
+    describe_histogram!(
+        HANDLER_DURATION,
+        Unit::Seconds,
+        "Time spent by the message handler."
+    );
+}
+
+impl Metrics {
+    pub fn new(name: &str, is_recv: bool) -> Self {
+        let role = match is_recv {
+            false => "sender",
+            true => "receiver",
+        };
+        Metrics {
+            labels: vec![
+                Label::new("queue", name.to_string()),
+                Label::new("role", role),
+            ],
+            sent: AtomicU64::new(0),
+            send_errors: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            acked: AtomicU64::new(0),
+            nacked: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            handled: AtomicU64::new(0),
+            handler_micros: AtomicU64::new(0),
+            handler_max_micros: AtomicU64::new(0),
+            in_process: AtomicU64::new(0),
+            conn: Mutex::new(ConnState::default()),
+        }
+    }
+
+    /// To get the snapshot.
+    pub fn snapshot(&self) -> QueueMetrics {
+        let (connected, disconnected) = {
+            let conn = self.conn.lock().unwrap();
+            let current = match conn.disconnected_since {
+                None => Duration::ZERO,
+                Some(since) => since.elapsed(),
+            };
+            (conn.connected, conn.disconnected + current)
+        };
+        QueueMetrics {
+            sent: self.sent.load(Ordering::Relaxed),
+            send_errors: self.send_errors.load(Ordering::Relaxed),
+            received: self.received.load(Ordering::Relaxed),
+            acked: self.acked.load(Ordering::Relaxed),
+            nacked: self.nacked.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            connected,
+            disconnected_millis: disconnected.as_millis() as u64,
+            handled: self.handled.load(Ordering::Relaxed),
+            handler_micros: self.handler_micros.load(Ordering::Relaxed),
+            handler_max_micros: self.handler_max_micros.load(Ordering::Relaxed),
+        }
+    }
+
+    /// The number of messages that are received and the message handler has not returned or the
+    /// message has not been rejected.
+    pub fn in_process(&self) -> u64 {
+        self.in_process.load(Ordering::Relaxed)
+    }
+
+    /// To record the result of sending a message.
+    pub fn on_sent(&self, success: bool) {
+        match success {
+            false => {
+                self.send_errors.fetch_add(1, Ordering::Relaxed);
+                counter!(SEND_ERRORS, self.labels.iter()).increment(1);
+            }
+            true => {
+                self.sent.fetch_add(1, Ordering::Relaxed);
+                counter!(SENT, self.labels.iter()).increment(1);
+            }
+        }
+    }
+
+    /// To record a message that is delivered to the message handler.
+    pub fn on_received(&self) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+        self.in_process.fetch_add(1, Ordering::Relaxed);
+        counter!(RECEIVED, self.labels.iter()).increment(1);
+    }
+
+    /// To record the time spent by the message handler.
+    pub fn on_handled(&self, elapsed: Duration) {
+        let micros = elapsed.as_micros() as u64;
+        self.handled.fetch_add(1, Ordering::Relaxed);
+        self.in_process.fetch_sub(1, Ordering::Relaxed);
+        self.handler_micros.fetch_add(micros, Ordering::Relaxed);
+        self.handler_max_micros.fetch_max(micros, Ordering::Relaxed);
+        histogram!(HANDLER_DURATION, self.labels.iter()).record(elapsed.as_secs_f64());
+    }
+
+    /// To record a received message that is rejected before the message handler is called.
+    pub fn on_rejected(&self) {
+        self.in_process.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// To record an ack (`true`) or a nack (`false`).
+    pub fn on_ack(&self, ack: bool) {
+        match ack {
+            false => {
+                self.nacked.fetch_add(1, Ordering::Relaxed);
+                counter!(NACKED, self.labels.iter()).increment(1);
+            }
+            true => {
+                self.acked.fetch_add(1, Ordering::Relaxed);
+                counter!(ACKED, self.labels.iter()).increment(1);
+            }
+        }
+    }
+
+    /// To record that the queue is connected.
+    pub fn on_connected(&self) {
+        let disconnected = {
+            let mut conn = self.conn.lock().unwrap();
+            if conn.connected {
+                return;
+            }
+            if conn.ever_connected {
+                self.reconnects.fetch_add(1, Ordering::Relaxed);
+                counter!(RECONNECTS, self.labels.iter()).increment(1);
+            }
+            conn.connected = true;
+            conn.ever_connected = true;
+            if let Some(since) = conn.disconnected_since.take() {
+                conn.disconnected += since.elapsed();
+            }
+            conn.disconnected
+        };
+        gauge!(CONNECTED, self.labels.iter()).set(1.0);
+        gauge!(DISCONNECTED, self.labels.iter()).set(disconnected.as_secs_f64());
+    }
+
+    /// To record that the queue starts connecting or is disconnected.
+    pub fn on_disconnected(&self) {
+        {
+            let mut conn = self.conn.lock().unwrap();
+            conn.connected = false;
+            if conn.disconnected_since.is_none() {
+                conn.disconnected_since = Some(Instant::now());
+            }
+        }
+        gauge!(CONNECTED, self.labels.iter()).set(0.0);
+    }
+
+    /// To record that the queue is closed. The time after closing is not counted.
+    pub fn on_closed(&self) {
+        let disconnected = {
+            let mut conn = self.conn.lock().unwrap();
+            conn.connected = false;
+            if let Some(since) = conn.disconnected_since.take() {
+                conn.disconnected += since.elapsed();
+            }
+            conn.disconnected
+        };
+        gauge!(CONNECTED, self.labels.iter()).set(0.0);
+        gauge!(DISCONNECTED, self.labels.iter()).set(disconnected.as_secs_f64());
+    }
+}
@@ -4,5 +4,5 @@ mod connection;
 mod queue;
 mod uri;
 
-pub use connection::{MqttConnection, MqttConnectionOptions};
+pub use connection::{MqttConnection, MqttConnectionOptions, MqttVersion};
 pub use queue::{MqttQueue, MqttQueueOptions};
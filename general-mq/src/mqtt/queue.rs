@@ -1,23 +1,31 @@
 use std::{
+    collections::VecDeque,
     error::Error as StdError,
     sync::{Arc, Mutex},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use async_trait::async_trait;
-use rumqttc::{ClientError as RumqttError, Publish, QoS};
+use rumqttc::v5::mqttbytes::v5::PublishProperties;
 use tokio::{
+    sync::Semaphore,
     task::{self, JoinHandle},
     time,
 };
 
-use super::connection::{MqttConnection, PacketHandler};
+use super::connection::{MqttConnection, MqttVersion, PacketHandler, PublishPacket};
 use crate::{
-    Error,
+    Error, ID_SIZE,
+    compression::{self, Compression},
     connection::{GmqConnection, Status as ConnStatus},
+    metrics::{Metrics, QueueMetrics},
+    outbox::{Outbox, OutboxOptions, Publisher},
     queue::{
-        EventHandler, GmqQueue, Message, MessageHandler, QUEUE_NAME_PATTERN, Status, name_validate,
+        self, EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode,
+        QUEUE_NAME_PATTERN, Status, name_validate,
     },
+    randomstring,
+    reconnect::{Backoff, ReconnectPolicy},
 };
 
 /// Manages a MQTT queue.
@@ -25,6 +33,8 @@ use crate::{
 pub struct MqttQueue {
     /// Options of the queue.
     opts: MqttQueueOptions,
+    /// The identifier of the queue in the connection.
+    id: String,
     /// The associated [`crate::MqttConnection`].
     conn: Arc<Mutex<MqttConnection>>,
     /// Queue status.
@@ -35,6 +45,16 @@ pub struct MqttQueue {
     msg_handler: Arc<Mutex<Option<Arc<dyn MessageHandler>>>>,
     /// The event loop to manage and monitor the connection.
     ev_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Limits the number of messages in process (for receivers).
+    limiter: Option<Arc<Semaphore>>,
+    /// Packets that wait for the message handler when the limit is reached.
+    pending: Arc<Mutex<VecDeque<PublishPacket>>>,
+    /// `true` to unsubscribe the topic.
+    paused: Arc<Mutex<bool>>,
+    /// The on-disk outbox (for senders).
+    outbox: Option<Arc<Outbox>>,
+    /// Metrics of the queue.
+    metrics: Arc<Metrics>,
 }
 
 /// The queue options.
@@ -56,18 +76,58 @@ pub struct MqttQueueOptions {
     ///
     /// Default or zero value is `1000`.
     pub reconnect_millis: u64,
+    /// The reconnect policy with exponential backoff for subscribing the topic. Use `None` to
+    /// retry every `reconnect_millis` forever.
+    pub reconnect_policy: Option<ReconnectPolicy>,
     /// Used for `broadcast=false`.
+    ///
+    /// For [`MqttVersion::V5`] connections, `None` means to use the native shared subscription
+    /// with the `$share/general-mq/` prefix.
     pub shared_prefix: Option<String>,
+    /// Message expiry interval in seconds for senders of [`MqttVersion::V5`] connections. The
+    /// broker drops messages that are not delivered before expiry. Use `None` to never expire.
+    pub message_expiry_secs: Option<u32>,
+    /// User properties that are attached to all messages for senders of [`MqttVersion::V5`]
+    /// connections.
+    pub user_properties: Vec<(String, String)>,
+    /// The dead-letter queue name (for receivers). Messages of [`NackMode::DeadLetter`] are
+    /// published to this topic.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    pub dead_letter: Option<String>,
+    /// The maximum number of messages that are processed by the message handler concurrently (for
+    /// receivers). Other messages are buffered in the client until a message handler returns.
+    ///
+    /// Default or zero value is unlimited.
+    ///
+    /// **Note**: MQTT brokers do not wait for the client. Use `pause()` to stop receiving when the
+    /// buffer grows.
+    pub max_concurrency: usize,
+    /// The on-disk outbox to store messages while the queue is not connected (for senders).
+    /// Stored messages are sent in order after the queue is connected. Use `None` to return
+    /// [`Error::NotConnected`] instead.
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of payloads (for senders). Receivers decompress payloads
+    /// automatically.
+    pub compression: Compression,
 }
 
 /// The MQTT [`Message`] implementation.
 pub struct MqttMessage {
-    /// Hold the [`rumqttc::Publish`] instance.
-    packet: Publish,
+    /// The associated queue to publish dead letters.
+    queue: Arc<MqttQueue>,
+    /// Hold the Publish packet instance.
+    packet: PublishPacket,
+    /// Properties converted from the MQTT 5 publish properties.
+    props: MessageProperties,
 }
 
 /// Default reconnect time in milliseconds.
 const DEF_RECONN_TIME_MS: u64 = 1000;
+/// The user property key of the timestamp for MQTT 5.
+const TIMESTAMP_KEY: &str = "timestamp";
+/// The shared subscription prefix for MQTT 5 unicast receivers.
+const DEF_V5_SHARED_PREFIX: &str = "$share/general-mq/";
 
 impl MqttQueue {
     /// Create a queue instance.
@@ -81,21 +141,88 @@ impl MqttQueue {
                 name, QUEUE_NAME_PATTERN
             ));
         }
+        if let Some(dead_letter) = opts.dead_letter.as_ref()
+            && !name_validate(dead_letter)
+        {
+            return Err(format!(
+                "dead-letter queue name {} is not match {}",
+                dead_letter, QUEUE_NAME_PATTERN
+            ));
+        }
         let mut opts = opts;
         if opts.reconnect_millis == 0 {
             opts.reconnect_millis = DEF_RECONN_TIME_MS;
         }
+        let limiter = match opts.max_concurrency {
+            0 => None,
+            permits => Some(Arc::new(Semaphore::new(permits))),
+        };
+        let outbox = match opts.outbox.as_ref() {
+            None => None,
+            Some(outbox_opts) => match opts.is_recv {
+                true => return Err("outbox cannot be used by a receiver".to_string()),
+                false => Some(Arc::new(Outbox::new(outbox_opts)?)),
+            },
+        };
+
+        let metrics = Arc::new(Metrics::new(opts.name.as_str(), opts.is_recv));
 
         Ok(MqttQueue {
             opts,
+            id: randomstring(ID_SIZE),
             conn: Arc::new(Mutex::new(conn.clone())),
             status: Arc::new(Mutex::new(Status::Closed)),
             handler: Arc::new(Mutex::new(None)),
             msg_handler: Arc::new(Mutex::new(None)),
             ev_loop: Arc::new(Mutex::new(None)),
+            limiter,
+            pending: Arc::new(Mutex::new(VecDeque::new())),
+            paused: Arc::new(Mutex::new(false)),
+            outbox,
+            metrics,
         })
     }
 
+    /// To get the number of messages in the outbox.
+    pub async fn outbox_len(&self) -> usize {
+        match self.outbox.as_ref() {
+            None => 0,
+            Some(outbox) => outbox.len().await,
+        }
+    }
+
+    /// To get the snapshot of metrics.
+    pub fn metrics(&self) -> QueueMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// To publish a message to the topic.
+    async fn publish_packet(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.status() != Status::Connected {
+            return Err(Box::new(Error::NotConnected));
+        }
+
+        let raw_conn = {
+            match self.conn.lock().unwrap().get_raw_connection() {
+                None => return Err(Box::new(Error::NotConnected)),
+                Some(raw_conn) => raw_conn,
+            }
+        };
+
+        raw_conn
+            .publish(
+                self.topic(),
+                self.opts.reliable,
+                payload,
+                self.properties(props),
+            )
+            .await
+    }
+
     /// To get the associated connection status.
     fn conn_status(&self) -> ConnStatus {
         self.conn.lock().unwrap().status()
@@ -127,17 +254,34 @@ impl MqttQueue {
         if self.opts.is_recv && !self.opts.broadcast {
             if let Some(prefix) = self.opts.shared_prefix.as_ref() {
                 return format!("{}{}", prefix.as_str(), self.opts.name.as_str());
+            } else if self.conn.lock().unwrap().version() == MqttVersion::V5 {
+                return format!("{}{}", DEF_V5_SHARED_PREFIX, self.opts.name.as_str());
             }
         }
         self.opts.name.clone()
     }
 
-    /// To get the associated QoS.
-    fn qos(&self) -> QoS {
-        match self.opts.reliable {
-            false => QoS::AtMostOnce,
-            true => QoS::AtLeastOnce,
+    /// To get the MQTT 5 properties for publishing messages.
+    fn properties(&self, props: MessageProperties) -> Option<PublishProperties> {
+        let mut user_properties = self.opts.user_properties.clone();
+        if let Some(timestamp) = props.timestamp {
+            user_properties.push((TIMESTAMP_KEY.to_string(), timestamp.to_string()));
         }
+        user_properties.extend(props.headers);
+        if self.opts.message_expiry_secs.is_none()
+            && user_properties.is_empty()
+            && props.content_type.is_none()
+            && props.correlation_id.is_none()
+        {
+            return None;
+        }
+        Some(PublishProperties {
+            message_expiry_interval: self.opts.message_expiry_secs,
+            user_properties,
+            content_type: props.content_type,
+            correlation_data: props.correlation_id.map(|id| id.into_bytes().into()),
+            ..Default::default()
+        })
     }
 }
 
@@ -174,12 +318,15 @@ impl GmqQueue for MqttQueue {
 
         {
             let mut task_handle_mutex = self.ev_loop.lock().unwrap();
-            if (*task_handle_mutex).is_some() {
+            if (*task_handle_mutex).is_some() && self.status() != Status::Failed {
                 return Ok(());
             }
             *self.status.lock().unwrap() = Status::Connecting;
+            self.metrics.on_disconnected();
             *task_handle_mutex = Some(create_event_loop(self));
         }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.add(self.id.as_str(), Box::new(self.clone()));
         Ok(())
     }
 
@@ -188,6 +335,8 @@ impl GmqQueue for MqttQueue {
             None => return Ok(()),
             Some(handle) => handle.abort(),
         }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.remove(self.id.as_str());
         {
             *self.status.lock().unwrap() = Status::Closing;
         }
@@ -199,7 +348,7 @@ impl GmqQueue for MqttQueue {
             raw_conn = conn.get_raw_connection();
         }
 
-        let mut result: Result<(), RumqttError> = Ok(());
+        let mut result: Result<(), Box<dyn StdError + Send + Sync>> = Ok(());
         if let Some(raw_conn) = raw_conn {
             result = raw_conn.unsubscribe(self.topic()).await;
         }
@@ -207,6 +356,7 @@ impl GmqQueue for MqttQueue {
         {
             *self.status.lock().unwrap() = Status::Closed;
         }
+        self.metrics.on_closed();
         if let Some(handler) = { (*self.handler.lock().unwrap()).clone() } {
             let queue = Arc::new(self.clone());
             task::spawn(async move {
@@ -218,37 +368,159 @@ impl GmqQueue for MqttQueue {
         Ok(())
     }
 
-    async fn send_msg(&self, payload: Vec<u8>) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        let drained = queue::drain(self, timeout, || metrics.in_process() == 0).await;
+        self.close().await?;
+        match drained {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        match queue::drain(self, timeout, || metrics.in_process() == 0).await {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        {
+            *self.paused.lock().unwrap() = true;
+        }
+
+        if self.status() != Status::Connected {
+            return Ok(());
+        }
+        let raw_conn = { self.conn.lock().unwrap().get_raw_connection() };
+        if let Some(raw_conn) = raw_conn {
+            raw_conn.unsubscribe(self.topic()).await?;
+        }
+        Ok(())
+    }
+
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.opts.is_recv {
+            return Ok(());
+        }
+        {
+            *self.paused.lock().unwrap() = false;
+        }
+
+        if self.status() != Status::Connected {
+            return Ok(());
+        }
+        let raw_conn = { self.conn.lock().unwrap().get_raw_connection() };
+        if let Some(raw_conn) = raw_conn {
+            raw_conn.subscribe(self.topic(), self.opts.reliable).await?;
+        }
+        Ok(())
+    }
+
+    fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
         if self.opts.is_recv {
             return Err(Box::new(Error::QueueIsReceiver));
-        } else if self.status() != Status::Connected {
-            return Err(Box::new(Error::NotConnected));
         }
 
-        let raw_conn = {
-            match self.conn.lock().unwrap().get_raw_connection() {
-                None => return Err(Box::new(Error::NotConnected)),
-                Some(raw_conn) => raw_conn,
-            }
-        };
+        let (payload, props) = compression::compress(self.opts.compression, payload, props)?;
+        match self.outbox.as_ref() {
+            None => self.publish(payload, props).await,
+            Some(outbox) => outbox.send(Arc::new(self.clone()), payload, props).await,
+        }
+    }
+}
 
-        raw_conn
-            .publish(self.topic(), self.qos(), false, payload)
-            .await?;
-        Ok(())
+#[async_trait]
+impl Publisher for MqttQueue {
+    fn is_connected(&self) -> bool {
+        self.status() == Status::Connected
+    }
+
+    async fn publish(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.publish_packet(payload, props).await;
+        self.metrics.on_sent(result.is_ok());
+        result
     }
 }
 
 impl PacketHandler for MqttQueue {
-    fn on_publish(&self, packet: Publish) {
-        if let Some(handler) = self.msg_handler() {
-            let this = Arc::new(self.clone());
-            task::spawn(async move {
-                handler
-                    .on_message(this, Box::new(MqttMessage::new(packet)))
-                    .await;
-            });
-        }
+    fn on_publish(&self, packet: PublishPacket) {
+        let handler = match self.msg_handler() {
+            None => return,
+            Some(handler) => handler,
+        };
+        let permit = match handler.limiter().or_else(|| self.limiter.clone()) {
+            None => None,
+            Some(limiter) => {
+                let mut pending = self.pending.lock().unwrap();
+                match limiter.try_acquire_owned() {
+                    Err(_) => {
+                        pending.push_back(packet);
+                        return;
+                    }
+                    Ok(permit) => Some(permit),
+                }
+            }
+        };
+
+        // The task keeps the permit to process buffered packets.
+        let this = Arc::new(self.clone());
+        task::spawn(async move {
+            let mut packet = packet;
+            let mut permit = permit;
+            loop {
+                // MQTT 3.1.1 does not carry headers, so compressed payloads are detected by magic.
+                let detect_magic = matches!(packet, PublishPacket::V3(_));
+                let message = Box::new(MqttMessage::new(this.clone(), packet));
+                this.metrics.on_received();
+                match compression::decompress(message, detect_magic).await {
+                    None => this.metrics.on_rejected(),
+                    Some(message) => {
+                        let start = Instant::now();
+                        handler.on_message(this.clone(), message).await;
+                        this.metrics.on_handled(start.elapsed());
+                    }
+                }
+                if permit.is_none() {
+                    break;
+                }
+                // Release the permit with the lock so that no packets are left in the buffer.
+                let next = {
+                    let mut pending = this.pending.lock().unwrap();
+                    match pending.pop_front() {
+                        None => {
+                            permit = None;
+                            None
+                        }
+                        Some(next) => Some(next),
+                    }
+                };
+                match next {
+                    None => break,
+                    Some(next) => packet = next,
+                }
+            }
+        });
     }
 }
 
@@ -260,30 +532,89 @@ impl Default for MqttQueueOptions {
             reliable: false,
             broadcast: false,
             reconnect_millis: DEF_RECONN_TIME_MS,
+            reconnect_policy: None,
             shared_prefix: None,
+            message_expiry_secs: None,
+            user_properties: vec![],
+            dead_letter: None,
+            max_concurrency: 0,
+            outbox: None,
+            compression: Compression::None,
         }
     }
 }
 
 impl MqttMessage {
     /// Create a message instance.
-    pub fn new(packet: Publish) -> Self {
-        MqttMessage { packet }
+    pub(super) fn new(queue: Arc<MqttQueue>, packet: PublishPacket) -> Self {
+        let mut props = MessageProperties::default();
+        if let PublishPacket::V5(packet) = &packet
+            && let Some(properties) = packet.properties.as_ref()
+        {
+            props.content_type = properties.content_type.clone();
+            props.correlation_id = properties
+                .correlation_data
+                .as_ref()
+                .map(|data| String::from_utf8_lossy(data).to_string());
+            for (key, value) in properties.user_properties.iter() {
+                if key.as_str() == TIMESTAMP_KEY
+                    && let Ok(timestamp) = value.parse()
+                {
+                    props.timestamp = Some(timestamp);
+                    continue;
+                }
+                props.headers.insert(key.clone(), value.clone());
+            }
+        }
+        MqttMessage {
+            queue,
+            packet,
+            props,
+        }
     }
 }
 
 #[async_trait]
 impl Message for MqttMessage {
     fn payload(&self) -> &[u8] {
-        self.packet.payload.as_ref()
+        match &self.packet {
+            PublishPacket::V3(packet) => packet.payload.as_ref(),
+            PublishPacket::V5(packet) => packet.payload.as_ref(),
+        }
+    }
+
+    fn properties(&self) -> &MessageProperties {
+        &self.props
     }
 
     async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.queue.metrics.on_ack(true);
         Ok(())
     }
 
-    async fn nack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
-        Ok(())
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.queue.metrics.on_ack(false);
+        if mode != NackMode::DeadLetter {
+            return Ok(());
+        }
+        let dead_letter = match self.queue.opts.dead_letter.as_ref() {
+            None => return Ok(()),
+            Some(dead_letter) => dead_letter,
+        };
+        let raw_conn = {
+            match self.queue.conn.lock().unwrap().get_raw_connection() {
+                None => return Err(Box::new(Error::NotConnected)),
+                Some(raw_conn) => raw_conn,
+            }
+        };
+        raw_conn
+            .publish(
+                dead_letter.clone(),
+                self.queue.opts.reliable,
+                self.payload().to_vec(),
+                self.queue.properties(self.props.clone()),
+            )
+            .await
     }
 }
 
@@ -291,11 +622,18 @@ impl Message for MqttMessage {
 fn create_event_loop(queue: &MqttQueue) -> JoinHandle<()> {
     let this = Arc::new(queue.clone());
     task::spawn(async move {
+        let mut backoff = Backoff::new(
+            this.opts.reconnect_policy.as_ref(),
+            this.opts.reconnect_millis,
+        );
         loop {
             match this.status() {
-                Status::Closing | Status::Closed => break,
+                Status::Closing | Status::Closed | Status::Failed => break,
                 Status::Connecting => {
-                    if this.conn_status() != ConnStatus::Connected {
+                    if this.conn_status() == ConnStatus::Failed {
+                        to_failed(&this);
+                        break;
+                    } else if this.conn_status() != ConnStatus::Connected {
                         time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
                         continue;
                     }
@@ -307,15 +645,20 @@ fn create_event_loop(queue: &MqttQueue) -> JoinHandle<()> {
                             conn.add_packet_handler(this.opts.name.as_str(), this.clone());
                             raw_conn = conn.get_raw_connection();
                         }
-                        if let Some(raw_conn) = raw_conn {
-                            if let Err(e) = raw_conn.subscribe(this.topic(), this.qos()).await {
+                        if this.is_paused() {
+                            // Subscribe the topic after resume().
+                        } else if let Some(raw_conn) = raw_conn {
+                            if let Err(e) =
+                                raw_conn.subscribe(this.topic(), this.opts.reliable).await
+                            {
                                 {
                                     let mut conn = this.conn.lock().unwrap();
                                     conn.remove_packet_handler(this.opts.name.as_str());
                                 }
-                                this.on_error(Box::new(e));
-                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
-                                    .await;
+                                this.on_error(e);
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
                                 continue;
                             }
                         } else {
@@ -324,15 +667,20 @@ fn create_event_loop(queue: &MqttQueue) -> JoinHandle<()> {
                         }
                     }
 
+                    backoff.reset();
                     {
                         *this.status.lock().unwrap() = Status::Connected;
                     }
+                    this.metrics.on_connected();
                     if let Some(handler) = this.handler() {
                         let queue = this.clone();
                         task::spawn(async move {
                             handler.on_status(queue, Status::Connected).await;
                         });
                     }
+                    if let Some(outbox) = this.outbox.as_ref() {
+                        outbox.spawn_flush(this.clone());
+                    }
                 }
                 Status::Connected => {
                     time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
@@ -344,6 +692,7 @@ fn create_event_loop(queue: &MqttQueue) -> JoinHandle<()> {
                             });
                         }
                         *this.status.lock().unwrap() = Status::Connecting;
+                        this.metrics.on_disconnected();
                     }
                 }
                 Status::Disconnected => {
@@ -353,3 +702,33 @@ fn create_event_loop(queue: &MqttQueue) -> JoinHandle<()> {
         }
     })
 }
+
+/// To wait for the next attempt after a failed attempt. Returns `false` and changes the status to
+/// [`Status::Failed`] if the attempts reach the limit.
+async fn wait_retry(queue: &Arc<MqttQueue>, backoff: &mut Backoff) -> bool {
+    if !backoff.fail() {
+        to_failed(queue);
+        return false;
+    }
+    time::sleep(backoff.delay()).await;
+    true
+}
+
+/// To change the status to [`Status::Failed`].
+fn to_failed(queue: &Arc<MqttQueue>) {
+    {
+        let mut status_mutex = queue.status.lock().unwrap();
+        if *status_mutex == Status::Closing || *status_mutex == Status::Closed {
+            return;
+        }
+        *status_mutex = Status::Failed;
+    }
+    queue.metrics.on_disconnected();
+
+    if let Some(handler) = queue.handler() {
+        let q = queue.clone();
+        task::spawn(async move {
+            handler.on_status(q, Status::Failed).await;
+        });
+    }
+}
@@ -0,0 +1,440 @@
+//! Durable local outbox of senders.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error as StdError,
+    fs,
+    io::{Error as IoError, ErrorKind},
+    path::PathBuf,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use tokio::{fs as async_fs, io::AsyncWriteExt, sync::Mutex, task};
+
+use crate::queue::MessageProperties;
+
+/// Options of the on-disk outbox of a sender. Messages are stored in the outbox while the queue is
+/// not connected and they are sent in order after the queue is connected.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OutboxOptions {
+    /// The directory to store messages. The directory is created if it does not exist.
+    ///
+    /// **Note**: each sender **MUST** use its own directory.
+    pub path: String,
+    /// The maximum number of stored messages. The oldest messages are dropped when the limit is
+    /// reached.
+    ///
+    /// Default or zero value is `10000`.
+    pub max_messages: usize,
+    /// The maximum total size in bytes of stored messages. The oldest messages are dropped when the
+    /// limit is reached.
+    ///
+    /// Default or zero value is unlimited.
+    pub max_bytes: u64,
+    /// Messages that are stored longer than this time in milliseconds are dropped instead of being
+    /// sent.
+    ///
+    /// Default or zero value is unlimited.
+    pub max_age_millis: u64,
+}
+
+/// The operations of queues to send messages through the outbox.
+#[async_trait]
+pub(crate) trait Publisher: Send + Sync {
+    /// Is the queue connected.
+    fn is_connected(&self) -> bool;
+
+    /// To publish a message to the broker without the outbox.
+    async fn publish(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>>;
+}
+
+/// The on-disk outbox. Each message is stored in one file that is named by the sequence number.
+pub(crate) struct Outbox {
+    /// Options with default values.
+    opts: OutboxOptions,
+    /// The directory of message files.
+    path: PathBuf,
+    /// Stored messages. The lock is held while sending to keep messages in order.
+    state: Mutex<State>,
+}
+
+/// Stored messages in order.
+struct State {
+    entries: VecDeque<Entry>,
+    /// The sequence number of the next message.
+    next_seq: u64,
+    /// The total size of stored messages.
+    bytes: u64,
+}
+
+/// A stored message.
+#[derive(Clone, Copy)]
+struct Entry {
+    seq: u64,
+    /// Unix timestamp in milliseconds when the message is stored.
+    created: u64,
+    /// The file size.
+    size: u64,
+}
+
+/// A decoded message file.
+struct Record {
+    created: u64,
+    payload: Vec<u8>,
+    props: MessageProperties,
+}
+
+/// Default maximum number of stored messages.
+const DEF_MAX_MESSAGES: usize = 10000;
+/// The version of the message file format.
+const FORMAT_VERSION: u8 = 1;
+/// The extension of message files.
+const MSG_EXT: &str = "msg";
+/// The extension of message files that are being written.
+const TEMP_EXT: &str = "tmp";
+
+impl Outbox {
+    /// Create an outbox and load stored messages from the directory.
+    pub(crate) fn new(opts: &OutboxOptions) -> Result<Outbox, String> {
+        if opts.path.is_empty() {
+            return Err("outbox path cannot be empty".to_string());
+        }
+        let mut opts = opts.clone();
+        if opts.max_messages == 0 {
+            opts.max_messages = DEF_MAX_MESSAGES;
+        }
+        let path = PathBuf::from(opts.path.as_str());
+        if let Err(e) = fs::create_dir_all(&path) {
+            return Err(format!("outbox path error: {}", e));
+        }
+        let dir = match fs::read_dir(&path) {
+            Err(e) => return Err(format!("outbox path error: {}", e)),
+            Ok(dir) => dir,
+        };
+
+        let mut entries = vec![];
+        for item in dir {
+            let file = match item {
+                Err(e) => return Err(format!("outbox path error: {}", e)),
+                Ok(item) => item.path(),
+            };
+            let ext = file.extension().and_then(|ext| ext.to_str());
+            if ext == Some(TEMP_EXT) {
+                // Incomplete files of the last run.
+                let _ = fs::remove_file(&file);
+                continue;
+            } else if ext != Some(MSG_EXT) {
+                continue;
+            }
+            let seq = match file
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+            {
+                None => continue,
+                Some(seq) => seq,
+            };
+            let record = match fs::read(&file) {
+                Err(_) => None,
+                Ok(data) => decode(data.as_slice()).map(|record| (record, data.len() as u64)),
+            };
+            match record {
+                None => {
+                    let _ = fs::remove_file(&file);
+                }
+                Some((record, size)) => entries.push(Entry {
+                    seq,
+                    created: record.created,
+                    size,
+                }),
+            }
+        }
+        entries.sort_by_key(|entry| entry.seq);
+
+        let state = State {
+            next_seq: match entries.last() {
+                None => 0,
+                Some(entry) => entry.seq + 1,
+            },
+            bytes: entries.iter().map(|entry| entry.size).sum(),
+            entries: entries.into(),
+        };
+        Ok(Outbox {
+            opts,
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    /// To get the number of stored messages.
+    pub(crate) async fn len(&self) -> usize {
+        self.state.lock().await.entries.len()
+    }
+
+    /// To send a message. The message is stored if the queue is not connected or there are stored
+    /// messages to be sent.
+    pub(crate) async fn send(
+        self: &Arc<Self>,
+        queue: Arc<dyn Publisher>,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        {
+            let mut state = self.state.lock().await;
+            if !queue.is_connected() || !state.entries.is_empty() {
+                self.push(&mut state, payload, &props).await?;
+                if queue.is_connected() {
+                    self.spawn_flush(queue);
+                }
+                return Ok(());
+            }
+        }
+        queue.publish(payload, props).await
+    }
+
+    /// To send stored messages using another runtime task.
+    pub(crate) fn spawn_flush(self: &Arc<Self>, queue: Arc<dyn Publisher>) {
+        let outbox = self.clone();
+        task::spawn(async move {
+            outbox.flush(queue).await;
+        });
+    }
+
+    /// To send stored messages in order. Expired messages are dropped.
+    ///
+    /// This stops at the first failure and the remaining messages are sent by the next `flush()`.
+    async fn flush(&self, queue: Arc<dyn Publisher>) {
+        loop {
+            let mut state = self.state.lock().await;
+            if !queue.is_connected() {
+                return;
+            }
+            let entry = match state.entries.front() {
+                None => return,
+                Some(entry) => *entry,
+            };
+            if self.opts.max_age_millis > 0
+                && now_millis().saturating_sub(entry.created) > self.opts.max_age_millis
+            {
+                self.pop_front(&mut state).await;
+                continue;
+            }
+            let record = match async_fs::read(self.file_path(entry.seq, MSG_EXT)).await {
+                Err(_) => None,
+                Ok(data) => decode(data.as_slice()),
+            };
+            let record = match record {
+                None => {
+                    self.pop_front(&mut state).await;
+                    continue;
+                }
+                Some(record) => record,
+            };
+            if queue.publish(record.payload, record.props).await.is_err() {
+                return;
+            }
+            self.pop_front(&mut state).await;
+        }
+    }
+
+    /// To store a message. The oldest messages are dropped to meet the limits.
+    async fn push(
+        &self,
+        state: &mut State,
+        payload: Vec<u8>,
+        props: &MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let created = now_millis();
+        let data = encode(created, payload.as_slice(), props);
+        let size = data.len() as u64;
+        if self.opts.max_bytes > 0 && size > self.opts.max_bytes {
+            return Err(Box::new(IoError::new(
+                ErrorKind::InvalidInput,
+                "message is larger than the outbox max_bytes",
+            )));
+        }
+        while state.entries.len() >= self.opts.max_messages
+            || (self.opts.max_bytes > 0 && state.bytes + size > self.opts.max_bytes)
+        {
+            self.pop_front(state).await;
+        }
+
+        let seq = state.next_seq;
+        let temp_path = self.file_path(seq, TEMP_EXT);
+        let mut file = async_fs::File::create(&temp_path).await?;
+        file.write_all(data.as_slice()).await?;
+        file.sync_all().await?;
+        drop(file);
+        async_fs::rename(&temp_path, self.file_path(seq, MSG_EXT)).await?;
+        self.sync_dir().await?;
+        state.next_seq += 1;
+        state.entries.push_back(Entry { seq, created, size });
+        state.bytes += size;
+        Ok(())
+    }
+
+    /// To remove the oldest message.
+    async fn pop_front(&self, state: &mut State) {
+        if let Some(entry) = state.entries.pop_front() {
+            let _ = async_fs::remove_file(self.file_path(entry.seq, MSG_EXT)).await;
+            state.bytes -= entry.size;
+        }
+    }
+
+    /// To flush the directory entries so that renamed files survive power loss.
+    #[cfg(unix)]
+    async fn sync_dir(&self) -> Result<(), IoError> {
+        async_fs::File::open(&self.path).await?.sync_all().await
+    }
+
+    /// Directories cannot be opened as files on this platform.
+    #[cfg(not(unix))]
+    async fn sync_dir(&self) -> Result<(), IoError> {
+        Ok(())
+    }
+
+    /// To get the file path of the message.
+    fn file_path(&self, seq: u64, ext: &str) -> PathBuf {
+        self.path.join(format!("{:020}.{}", seq, ext))
+    }
+}
+
+/// To get the current Unix timestamp in milliseconds.
+fn now_millis() -> u64 {
+    match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Err(_) => 0,
+        Ok(duration) => duration.as_millis() as u64,
+    }
+}
+
+/// To encode a message file:
+///
+/// - version (u8)
+/// - created time (u64)
+/// - content type, correlation ID and timestamp (optional fields with a u8 flag)
+/// - headers (u32 count and key/value bytes)
+/// - payload bytes
+///
+/// All integers are big-endian and all bytes are prefixed with u32 length.
+fn encode(created: u64, payload: &[u8], props: &MessageProperties) -> Vec<u8> {
+    let mut data = vec![FORMAT_VERSION];
+    data.extend_from_slice(&created.to_be_bytes());
+    encode_opt_bytes(&mut data, props.content_type.as_ref().map(|v| v.as_bytes()));
+    encode_opt_bytes(
+        &mut data,
+        props.correlation_id.as_ref().map(|v| v.as_bytes()),
+    );
+    match props.timestamp {
+        None => data.push(0),
+        Some(timestamp) => {
+            data.push(1);
+            data.extend_from_slice(&timestamp.to_be_bytes());
+        }
+    }
+    data.extend_from_slice(&(props.headers.len() as u32).to_be_bytes());
+    for (key, value) in props.headers.iter() {
+        encode_bytes(&mut data, key.as_bytes());
+        encode_bytes(&mut data, value.as_bytes());
+    }
+    encode_bytes(&mut data, payload);
+    data
+}
+
+fn encode_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    data.extend_from_slice(bytes);
+}
+
+fn encode_opt_bytes(data: &mut Vec<u8>, bytes: Option<&[u8]>) {
+    match bytes {
+        None => data.push(0),
+        Some(bytes) => {
+            data.push(1);
+            encode_bytes(data, bytes);
+        }
+    }
+}
+
+/// To decode a message file. Returns `None` for unknown versions or corrupted files.
+fn decode(data: &[u8]) -> Option<Record> {
+    let mut reader = Reader { data };
+    if reader.take(1)?[0] != FORMAT_VERSION {
+        return None;
+    }
+    let created = reader.u64()?;
+    let content_type = reader.opt_string()?;
+    let correlation_id = reader.opt_string()?;
+    let timestamp = match reader.take(1)?[0] {
+        0 => None,
+        _ => Some(reader.u64()?),
+    };
+    let count = reader.u32()?;
+    let mut headers = HashMap::new();
+    for _ in 0..count {
+        let key = reader.string()?;
+        let value = reader.string()?;
+        headers.insert(key, value);
+    }
+    let payload = reader.bytes()?.to_vec();
+    if !reader.data.is_empty() {
+        return None;
+    }
+
+    Some(Record {
+        created,
+        payload,
+        props: MessageProperties {
+            content_type,
+            correlation_id,
+            timestamp,
+            headers,
+        },
+    })
+}
+
+/// The cursor to decode message files.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(bytes)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_be_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Option<String> {
+        String::from_utf8(self.bytes()?.to_vec()).ok()
+    }
+
+    /// Returns `Some(None)` for absent fields and `None` for errors.
+    fn opt_string(&mut self) -> Option<Option<String>> {
+        match self.take(1)?[0] {
+            0 => Some(None),
+            _ => Some(Some(self.string()?)),
+        }
+    }
+}
@@ -1,9 +1,21 @@
 //! Traits and enumerations for queues.
 
-use std::{error::Error as StdError, sync::Arc};
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use futures::Stream;
 use regex::Regex;
+use tokio::{
+    sync::{Semaphore, mpsc},
+    time::{self, Instant},
+};
 
 /// Queue status.
 #[derive(Debug, PartialEq)]
@@ -18,8 +30,75 @@ pub enum Status {
     Connected,
     /// The queue is not connected. It will retry connecting to the queue automatically.
     Disconnected,
+    /// Reconnecting reached the limit of [`crate::ReconnectPolicy`] or the connection is failed.
+    /// Use `connect()` to connect again.
+    Failed,
 }
 
+/// The action of [`Message::nack_with_mode()`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum NackMode {
+    /// Requeue the message and the broker will send the message again.
+    #[default]
+    Requeue,
+    /// Drop the message.
+    Drop,
+    /// Move the message to the dead-letter queue. The message is dropped if the queue does not
+    /// have a dead-letter queue.
+    DeadLetter,
+}
+
+/// Message properties.
+///
+/// The properties are mapped to:
+///
+/// - AMQP: basic properties `content_type`, `correlation_id`, `timestamp` and `headers`.
+/// - MQTT 5: publish properties `content_type`, `correlation_data` and user properties. The
+///   timestamp is carried as the `timestamp` user property.
+/// - Redis Streams: fields of stream entries.
+///
+/// **Note**: MQTT 3.1.1 does not support properties and they are dropped.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MessageProperties {
+    /// The MIME content type of the payload.
+    pub content_type: Option<String>,
+    /// The correlation identifier.
+    pub correlation_id: Option<String>,
+    /// Unix timestamp in seconds.
+    pub timestamp: Option<u64>,
+    /// Application headers.
+    pub headers: HashMap<String, String>,
+}
+
+/// The stream of incoming [`Message`]s for receivers. Use [`GmqQueue::msg_stream()`] to create a
+/// stream instead of implementing [`MessageHandler`].
+///
+/// The stream has a bounded buffer and installs a limiter of `buffer` permits with
+/// [`MessageHandler::limiter()`]. Receivers stop taking deliveries when the buffer is full, so the
+/// number of messages in process is limited by reading the stream. AMQP and Redis receivers leave
+/// messages in the broker, while MQTT and memory receivers keep deliveries in memory.
+///
+/// Messages are read in delivery order only when `buffer` is `1`. Larger buffers handle
+/// deliveries concurrently and messages may be reordered.
+///
+/// The stream ends when the queue and all its clones are dropped. Messages delivered after dropping
+/// the stream are left unacknowledged.
+pub struct MessageStream {
+    /// The receiver of the bounded buffer.
+    rx: mpsc::Receiver<Box<dyn Message>>,
+}
+
+/// The [`MessageHandler`] that forwards messages to a [`MessageStream`].
+struct StreamHandler {
+    /// The sender of the bounded buffer.
+    tx: mpsc::Sender<Box<dyn Message>>,
+    /// The limiter of deliveries that are waiting for the buffer.
+    limiter: Arc<Semaphore>,
+}
+
+/// Interval in milliseconds to check in-process messages while draining.
+const DRAIN_INTERVAL_MS: u64 = 10;
+
 /// The accepted pattern of the queue name.
 pub const QUEUE_NAME_PATTERN: &'static str = r"^[a-z0-9_-]+([\.]{1}[a-z0-9_-]+)*$";
 
@@ -44,6 +123,18 @@ pub trait GmqQueue: Send + Sync {
     /// To set the queue message handler.
     fn set_msg_handler(&mut self, handler: Arc<dyn MessageHandler>);
 
+    /// To receive messages with a [`MessageStream`] instead of a [`MessageHandler`]. This replaces
+    /// the message handler.
+    ///
+    /// The `buffer` is the capacity of the stream. Zero value is treated as `1`.
+    fn msg_stream(&mut self, buffer: usize) -> MessageStream {
+        let buffer = buffer.max(1);
+        let (tx, rx) = mpsc::channel(buffer);
+        let limiter = Arc::new(Semaphore::new(buffer));
+        self.set_msg_handler(Arc::new(StreamHandler { tx, limiter }));
+        MessageStream { rx }
+    }
+
     /// To connect to the message queue. The [`GmqQueue`] will connect to the queue using another
     /// runtime task and report status with [`Status`]s.
     ///
@@ -53,8 +144,49 @@ pub trait GmqQueue: Send + Sync {
     /// To close the queue.
     async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>>;
 
+    /// To close the queue after in-process messages are done. Receivers stop receiving and wait
+    /// for message handlers to return. AMQP reliable senders wait for publisher confirms of sent
+    /// messages. Messages in the outbox are kept on disk.
+    ///
+    /// The queue is closed even if it is not drained within `timeout`, and then
+    /// [`crate::Error::Timeout`] is returned.
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// To stop receiving messages and to wait for message handlers to return without closing the
+    /// queue. Senders keep working so that handlers of other queues can still send messages. Use
+    /// `close_graceful()` after draining all receivers to wait for publisher confirms and to close.
+    ///
+    /// [`crate::Error::Timeout`] is returned if the queue is not drained within `timeout`.
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// To stop receiving messages (for **receivers** only). Messages that have been delivered are
+    /// still processed. The queue keeps paused after reconnection until `resume()`.
+    ///
+    /// **Note**: MQTT and memory broadcast receivers lose messages that are published during the
+    /// pause.
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// To continue receiving messages after `pause()`.
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>>;
+
+    /// Is the queue paused.
+    fn is_paused(&self) -> bool;
+
     /// To send a message (for **senders** only).
-    async fn send_msg(&self, payload: Vec<u8>) -> Result<(), Box<dyn StdError + Send + Sync>>;
+    async fn send_msg(&self, payload: Vec<u8>) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.send_msg_with_props(payload, MessageProperties::default())
+            .await
+    }
+
+    /// To send a message with properties (for **senders** only).
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>>;
 }
 
 /// The operations for incoming messages.
@@ -63,13 +195,23 @@ pub trait Message: Send + Sync {
     /// To get the payload.
     fn payload(&self) -> &[u8];
 
+    /// To get the properties.
+    fn properties(&self) -> &MessageProperties;
+
     /// Use this if the message is processed successfully.
     async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>>;
 
     /// To requeue the message and the broker will send the message in the future.
     ///
     /// **Note**: only AMQP or protocols that support requeuing are effective.
-    async fn nack(&self) -> Result<(), Box<dyn StdError + Send + Sync>>;
+    async fn nack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.nack_with_mode(NackMode::Requeue).await
+    }
+
+    /// To reject the message with the specified [`NackMode`].
+    ///
+    /// **Note**: MQTT does not support requeuing, so [`NackMode::Requeue`] has no effect.
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>>;
 }
 
 /// The event handler for queues.
@@ -87,6 +229,33 @@ pub trait EventHandler: Send + Sync {
 pub trait MessageHandler: Send + Sync {
     /// Triggered for new incoming [`Message`]s.
     async fn on_message(&self, queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>);
+
+    /// The limiter of messages in process. Receivers stop taking deliveries until a permit is
+    /// available and release the permit after `on_message()` returns. This replaces the
+    /// `max_concurrency` limiter of the queue. The default is no limit.
+    fn limiter(&self) -> Option<Arc<Semaphore>> {
+        None
+    }
+}
+
+impl Stream for MessageStream {
+    type Item = Box<dyn Message>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+#[async_trait]
+impl MessageHandler for StreamHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        // The stream has been dropped. Leave the message unacknowledged.
+        let _ = self.tx.send(msg).await;
+    }
+
+    fn limiter(&self) -> Option<Arc<Semaphore>> {
+        Some(self.limiter.clone())
+    }
 }
 
 impl Copy for Status {}
@@ -97,6 +266,25 @@ impl Clone for Status {
     }
 }
 
+/// To stop receiving (for receivers) and to wait until `is_idle` returns `true`. Returns `false`
+/// if the queue is not idle within `timeout`.
+pub(crate) async fn drain<F>(queue: &dyn GmqQueue, timeout: Duration, is_idle: F) -> bool
+where
+    F: Fn() -> bool,
+{
+    if queue.is_recv() {
+        let _ = queue.pause().await;
+    }
+    let deadline = Instant::now() + timeout;
+    while !is_idle() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        time::sleep(Duration::from_millis(DRAIN_INTERVAL_MS)).await;
+    }
+    true
+}
+
 /// To validate the queue name.
 pub(crate) fn name_validate(name: &str) -> bool {
     let re = Regex::new(QUEUE_NAME_PATTERN).unwrap();
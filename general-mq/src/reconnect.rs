@@ -0,0 +1,122 @@
+//! Reconnect policies of connections and queues.
+
+use std::time::Duration;
+
+use rand::RngExt;
+
+/// The reconnect policy with exponential backoff.
+///
+/// The delay after `n` consecutive failed attempts is `initial_millis * multiplier^n` and it is
+/// limited by `max_millis`. Random jitter is applied to the delay to prevent clients from
+/// reconnecting in lockstep.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+    /// The delay in milliseconds before the first attempt.
+    ///
+    /// Default or zero value is `1000`.
+    pub initial_millis: u64,
+    /// The maximum delay in milliseconds.
+    ///
+    /// Default or zero value is `30000`.
+    pub max_millis: u64,
+    /// The multiplier of the delay for each failed attempt.
+    ///
+    /// Default is `2.0`. Values less than `1.0` are treated as `1.0`.
+    pub multiplier: f64,
+    /// The ratio of the random jitter. The delay `d` will be in the range of `d * (1 - jitter)` to
+    /// `d`.
+    ///
+    /// Default is `0.5`. Values are limited to `0.0` to `1.0`.
+    pub jitter: f64,
+    /// The maximum number of consecutive failed attempts. The connection or the queue will be
+    /// [`crate::connection::Status::Failed`] or [`crate::queue::Status::Failed`] after reaching
+    /// the limit.
+    ///
+    /// Use `None` to retry forever.
+    pub max_attempts: Option<usize>,
+}
+
+/// Tracks consecutive failed attempts to compute delays.
+pub(crate) struct Backoff {
+    /// The policy. Use `None` for fixed delays forever.
+    policy: Option<ReconnectPolicy>,
+    /// The fixed delay in milliseconds for `None` policy.
+    reconnect_millis: u64,
+    /// The number of consecutive failed attempts.
+    failures: usize,
+}
+
+/// Default initial delay in milliseconds.
+const DEF_INITIAL_MS: u64 = 1000;
+/// Default maximum delay in milliseconds.
+const DEF_MAX_MS: u64 = 30000;
+/// Default multiplier.
+const DEF_MULTIPLIER: f64 = 2.0;
+/// Default jitter ratio.
+const DEF_JITTER: f64 = 0.5;
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_millis: DEF_INITIAL_MS,
+            max_millis: DEF_MAX_MS,
+            multiplier: DEF_MULTIPLIER,
+            jitter: DEF_JITTER,
+            max_attempts: None,
+        }
+    }
+}
+
+impl Backoff {
+    /// Create a backoff instance. The `reconnect_millis` is used when `policy` is `None`.
+    pub fn new(policy: Option<&ReconnectPolicy>, reconnect_millis: u64) -> Self {
+        let policy = policy.map(|policy| ReconnectPolicy {
+            initial_millis: match policy.initial_millis {
+                0 => DEF_INITIAL_MS,
+                _ => policy.initial_millis,
+            },
+            max_millis: match policy.max_millis {
+                0 => DEF_MAX_MS,
+                _ => policy.max_millis,
+            },
+            multiplier: policy.multiplier.max(1.0),
+            jitter: policy.jitter.clamp(0.0, 1.0),
+            max_attempts: policy.max_attempts,
+        });
+        Backoff {
+            policy,
+            reconnect_millis,
+            failures: 0,
+        }
+    }
+
+    /// To get the delay before the next attempt.
+    pub fn delay(&self) -> Duration {
+        let policy = match self.policy.as_ref() {
+            None => return Duration::from_millis(self.reconnect_millis),
+            Some(policy) => policy,
+        };
+        let exp = i32::try_from(self.failures).unwrap_or(i32::MAX);
+        let millis = (policy.initial_millis as f64 * policy.multiplier.powi(exp))
+            .min(policy.max_millis as f64);
+        let jitter = match policy.jitter > 0.0 {
+            false => 0.0,
+            true => rand::rng().random_range(0.0..=policy.jitter),
+        };
+        Duration::from_millis((millis * (1.0 - jitter)) as u64)
+    }
+
+    /// To record a failed attempt. Returns `false` if the attempts reach the limit.
+    pub fn fail(&mut self) -> bool {
+        self.failures = self.failures.saturating_add(1);
+        match self.policy.as_ref().and_then(|policy| policy.max_attempts) {
+            None => true,
+            Some(max_attempts) => self.failures < max_attempts,
+        }
+    }
+
+    /// To reset the failed attempts after a successful attempt.
+    pub fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
@@ -0,0 +1,7 @@
+//! Redis Streams implementation.
+
+mod connection;
+mod queue;
+
+pub use connection::{RedisConnection, RedisConnectionOptions};
+pub use queue::{RedisQueue, RedisQueueOptions};
@@ -0,0 +1,871 @@
+use std::{
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use redis::{
+    AsyncCommands, RedisError,
+    aio::MultiplexedConnection,
+    streams::{
+        StreamAutoClaimOptions, StreamAutoClaimReply, StreamClaimOptions, StreamId, StreamMaxlen,
+        StreamReadOptions, StreamReadReply,
+    },
+};
+use tokio::{
+    sync::{OwnedSemaphorePermit, Semaphore},
+    task::{self, JoinHandle},
+    time,
+};
+
+use super::connection::RedisConnection;
+use crate::{
+    Error, ID_SIZE,
+    compression::{self, Compression},
+    connection::{GmqConnection, Status as ConnStatus},
+    metrics::{Metrics, QueueMetrics},
+    outbox::{Outbox, OutboxOptions, Publisher},
+    queue::{
+        self, EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode,
+        QUEUE_NAME_PATTERN, Status, name_validate,
+    },
+    randomstring,
+    reconnect::{Backoff, ReconnectPolicy},
+};
+
+/// Manages a Redis stream queue.
+#[derive(Clone)]
+pub struct RedisQueue {
+    /// Options of the queue.
+    opts: RedisQueueOptions,
+    /// The consumer name of the receiver. This is also the consumer group name of broadcast
+    /// receivers.
+    id: String,
+    /// The associated [`crate::RedisConnection`].
+    conn: Arc<Mutex<RedisConnection>>,
+    /// Hold the dedicated connection for blocking reads (for receivers).
+    reader: Arc<Mutex<Option<MultiplexedConnection>>>,
+    /// Queue status.
+    status: Arc<Mutex<Status>>,
+    /// The event handler.
+    handler: Arc<Mutex<Option<Arc<dyn EventHandler>>>>,
+    /// The message handler.
+    msg_handler: Arc<Mutex<Option<Arc<dyn MessageHandler>>>>,
+    /// The event loop to manage and monitor the connection.
+    ev_loop: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// `true` to stop reading messages.
+    paused: Arc<Mutex<bool>>,
+    /// Permits of messages in process. Receivers read messages only when there are available
+    /// permits.
+    inflight: Arc<Semaphore>,
+    /// The on-disk outbox (for senders).
+    outbox: Option<Arc<Outbox>>,
+    /// Metrics of the queue.
+    metrics: Arc<Metrics>,
+}
+
+/// The queue options.
+#[derive(Clone)]
+pub struct RedisQueueOptions {
+    /// The queue name that is used to map a Redis stream key.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    pub name: String,
+    /// `true` for the receiver and `false` for the sender.
+    pub is_recv: bool,
+    /// Reliable by keeping delivered messages in the pending entries list until ack (for
+    /// receivers). Unreliable receivers read messages with `NOACK`.
+    pub reliable: bool,
+    /// `true` for broadcast and `false` for unicast.
+    ///
+    /// Unicast receivers share one consumer group and each broadcast receiver has its own consumer
+    /// group.
+    pub broadcast: bool,
+    /// Time in milliseconds from disconnection to reconnection. This is also the block time of
+    /// reading messages.
+    ///
+    /// Default or zero value is `1000`.
+    pub reconnect_millis: u64,
+    /// The reconnect policy with exponential backoff for creating the consumer group. Use `None`
+    /// to retry every `reconnect_millis` forever.
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// The maximum number of messages in process (for receivers). Messages are not read until
+    /// message handlers of previous messages return.
+    ///
+    /// **Note**: this value **MUST** be a positive value.
+    pub prefetch: u16,
+    /// Idle time in milliseconds before unacknowledged messages are claimed and delivered again
+    /// (for reliable receivers). Messages of `nack()` will be delivered again immediately.
+    ///
+    /// Default or zero value is `30000`.
+    pub claim_millis: u64,
+    /// The approximate maximum length of the stream (for senders). Old messages will be trimmed.
+    ///
+    /// Default or zero value is `100000`.
+    pub max_len: usize,
+    /// The dead-letter queue name (for receivers). Messages of [`NackMode::DeadLetter`] are added
+    /// to this stream with `max_len`.
+    ///
+    /// The pattern is [`QUEUE_NAME_PATTERN`].
+    pub dead_letter: Option<String>,
+    /// The on-disk outbox to store messages while the queue is not connected (for senders).
+    /// Stored messages are sent in order after the queue is connected. Use `None` to return
+    /// [`Error::NotConnected`] instead.
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of payloads (for senders). Receivers decompress payloads
+    /// automatically.
+    pub compression: Compression,
+}
+
+/// The Redis [`Message`] implementation.
+struct RedisMessage {
+    /// Hold the connection to operate ack/nack.
+    conn: MultiplexedConnection,
+    /// The stream key.
+    key: String,
+    /// The consumer group name.
+    group: String,
+    /// The consumer name.
+    consumer: String,
+    /// The stream entry identifier.
+    id: String,
+    /// Idle time to mark the message as claimable for `nack()`.
+    claim_millis: u64,
+    /// `false` for messages without pending entries.
+    reliable: bool,
+    /// The dead-letter stream key.
+    dead_letter: Option<String>,
+    /// The approximate maximum length of the dead-letter stream.
+    max_len: usize,
+    /// Hold the entry payload.
+    payload: Vec<u8>,
+    /// Properties converted from the entry fields.
+    props: MessageProperties,
+    /// Metrics of the receiver.
+    metrics: Arc<Metrics>,
+}
+
+/// Default reconnect time in milliseconds.
+const DEF_RECONN_TIME_MS: u64 = 1000;
+/// Default claim time in milliseconds.
+const DEF_CLAIM_TIME_MS: u64 = 30000;
+/// Default maximum stream length.
+const DEF_MAX_LEN: usize = 100000;
+/// The consumer group name of unicast queues.
+const UNICAST_GROUP: &str = "general-mq";
+/// The field name of the entry payload.
+const PAYLOAD_FIELD: &str = "payload";
+/// The field name of the content type property.
+const CONTENT_TYPE_FIELD: &str = "content-type";
+/// The field name of the correlation identifier property.
+const CORRELATION_ID_FIELD: &str = "correlation-id";
+/// The field name of the timestamp property.
+const TIMESTAMP_FIELD: &str = "timestamp";
+/// The field name prefix of headers.
+const HEADER_PREFIX: &str = "header:";
+
+impl RedisQueue {
+    /// Create a queue instance.
+    pub fn new(opts: RedisQueueOptions, conn: &RedisConnection) -> Result<RedisQueue, String> {
+        let name = opts.name.as_str();
+        if name.is_empty() {
+            return Err("queue name cannot be empty".to_string());
+        } else if !name_validate(name) {
+            return Err(format!(
+                "queue name {} is not match {}",
+                name, QUEUE_NAME_PATTERN
+            ));
+        } else if opts.is_recv && opts.prefetch == 0 {
+            return Err("prefetch cannot be zero for a receiver".to_string());
+        }
+        if let Some(dead_letter) = opts.dead_letter.as_ref()
+            && !name_validate(dead_letter)
+        {
+            return Err(format!(
+                "dead-letter queue name {} is not match {}",
+                dead_letter, QUEUE_NAME_PATTERN
+            ));
+        }
+        let mut opts = opts;
+        if opts.reconnect_millis == 0 {
+            opts.reconnect_millis = DEF_RECONN_TIME_MS;
+        }
+        if opts.claim_millis == 0 {
+            opts.claim_millis = DEF_CLAIM_TIME_MS;
+        }
+        if opts.max_len == 0 {
+            opts.max_len = DEF_MAX_LEN;
+        }
+        let outbox = match opts.outbox.as_ref() {
+            None => None,
+            Some(outbox_opts) => match opts.is_recv {
+                true => return Err("outbox cannot be used by a receiver".to_string()),
+                false => Some(Arc::new(Outbox::new(outbox_opts)?)),
+            },
+        };
+
+        let metrics = Arc::new(Metrics::new(opts.name.as_str(), opts.is_recv));
+        let inflight = Arc::new(Semaphore::new(opts.prefetch as usize));
+
+        Ok(RedisQueue {
+            opts,
+            id: randomstring(ID_SIZE),
+            conn: Arc::new(Mutex::new(conn.clone())),
+            reader: Arc::new(Mutex::new(None)),
+            status: Arc::new(Mutex::new(Status::Closed)),
+            handler: Arc::new(Mutex::new(None)),
+            msg_handler: Arc::new(Mutex::new(None)),
+            ev_loop: Arc::new(Mutex::new(None)),
+            paused: Arc::new(Mutex::new(false)),
+            inflight,
+            outbox,
+            metrics,
+        })
+    }
+
+    /// To get the number of messages in the outbox.
+    pub async fn outbox_len(&self) -> usize {
+        match self.outbox.as_ref() {
+            None => 0,
+            Some(outbox) => outbox.len().await,
+        }
+    }
+
+    /// To get the snapshot of metrics.
+    pub fn metrics(&self) -> QueueMetrics {
+        self.metrics.snapshot()
+    }
+
+    /// To add a message to the stream.
+    async fn publish_entry(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.status() != Status::Connected {
+            return Err(Box::new(Error::NotConnected));
+        }
+
+        let mut conn = match self.raw_conn() {
+            None => return Err(Box::new(Error::NotConnected)),
+            Some(conn) => conn,
+        };
+        let items = to_fields(payload, props);
+        let _: String = conn
+            .xadd_maxlen(
+                self.opts.name.as_str(),
+                StreamMaxlen::Approx(self.opts.max_len),
+                "*",
+                items.as_slice(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// To get the associated connection status.
+    fn conn_status(&self) -> ConnStatus {
+        self.conn.lock().unwrap().status()
+    }
+
+    /// To get the associated raw connection.
+    fn raw_conn(&self) -> Option<MultiplexedConnection> {
+        self.conn.lock().unwrap().get_raw_connection()
+    }
+
+    /// To get the event handler.
+    fn handler(&self) -> Option<Arc<dyn EventHandler>> {
+        self.handler.lock().unwrap().clone()
+    }
+
+    /// To get the message handler.
+    fn msg_handler(&self) -> Option<Arc<dyn MessageHandler>> {
+        self.msg_handler.lock().unwrap().clone()
+    }
+
+    /// To get the consumer group name.
+    fn group(&self) -> &str {
+        match self.opts.broadcast {
+            false => UNICAST_GROUP,
+            true => self.id.as_str(),
+        }
+    }
+
+    /// To create the consumer group and the stream if they do not exist.
+    ///
+    /// Unicast groups start from the beginning of the stream to receive messages that are sent
+    /// before any receiver is connected (up to `max_len`). Broadcast groups only receive new
+    /// messages.
+    async fn create_group(&self, conn: &mut MultiplexedConnection) -> Result<(), RedisError> {
+        let start = match self.opts.broadcast {
+            false => "0",
+            true => "$",
+        };
+        let result: Result<(), RedisError> = conn
+            .xgroup_create_mkstream(self.opts.name.as_str(), self.group(), start)
+            .await;
+        match result {
+            Err(e) if e.code() != Some("BUSYGROUP") => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// To read messages with the dedicated connection and deliver them to the message handler.
+    async fn read(&self, reader: &mut MultiplexedConnection) -> Result<(), RedisError> {
+        let conn = match self.raw_conn() {
+            None => return Ok(()),
+            Some(conn) => conn,
+        };
+        let key = self.opts.name.as_str();
+
+        // Wait until messages in process are less than `prefetch` and take all available permits.
+        let mut permits = match self.inflight.clone().acquire_owned().await {
+            Err(_) => return Ok(()),
+            Ok(permit) => permit,
+        };
+        let available = self.inflight.available_permits() as u32;
+        if available > 0
+            && let Ok(more) = self.inflight.clone().try_acquire_many_owned(available)
+        {
+            permits.merge(more);
+        }
+        let count = permits.num_permits();
+
+        if self.opts.reliable {
+            let reply: StreamAutoClaimReply = reader
+                .xautoclaim_options(
+                    key,
+                    self.group(),
+                    self.id.as_str(),
+                    self.opts.claim_millis,
+                    "0-0",
+                    StreamAutoClaimOptions::default().count(count),
+                )
+                .await?;
+            for entry in reply.claimed {
+                if let Some(permit) = permits.split(1) {
+                    self.deliver(&conn, entry, permit).await;
+                }
+            }
+        }
+        let count = permits.num_permits();
+        if count == 0 {
+            return Ok(());
+        }
+
+        let mut opts = StreamReadOptions::default()
+            .group(self.group(), self.id.as_str())
+            .count(count)
+            .block(self.opts.reconnect_millis as usize);
+        if !self.opts.reliable {
+            opts = opts.noack();
+        }
+        let reply: Option<StreamReadReply> = reader.xread_options(&[key], &[">"], &opts).await?;
+        if let Some(reply) = reply {
+            for stream in reply.keys {
+                for entry in stream.ids {
+                    if let Some(permit) = permits.split(1) {
+                        self.deliver(&conn, entry, permit).await;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// To deliver one stream entry to the message handler. This waits for a permit of the handler
+    /// limiter to stop reading when the limit is reached. The `inflight` permit is released after
+    /// the message handler returns.
+    async fn deliver(
+        &self,
+        conn: &MultiplexedConnection,
+        entry: StreamId,
+        inflight: OwnedSemaphorePermit,
+    ) {
+        let handler = match self.msg_handler() {
+            None => return,
+            Some(handler) => handler,
+        };
+        let payload: Vec<u8> = entry.get(PAYLOAD_FIELD).unwrap_or_default();
+        let mut props = MessageProperties {
+            content_type: entry.get(CONTENT_TYPE_FIELD),
+            correlation_id: entry.get(CORRELATION_ID_FIELD),
+            timestamp: entry.get(TIMESTAMP_FIELD),
+            ..Default::default()
+        };
+        for field in entry.map.keys() {
+            if let Some(key) = field.strip_prefix(HEADER_PREFIX)
+                && let Some(value) = entry.get(field.as_str())
+            {
+                props.headers.insert(key.to_string(), value);
+            }
+        }
+        let this = Arc::new(self.clone());
+        let message = Box::new(RedisMessage {
+            conn: conn.clone(),
+            key: self.opts.name.clone(),
+            group: self.group().to_string(),
+            consumer: self.id.clone(),
+            id: entry.id,
+            claim_millis: self.opts.claim_millis,
+            reliable: self.opts.reliable,
+            dead_letter: self.opts.dead_letter.clone(),
+            max_len: self.opts.max_len,
+            payload,
+            props,
+            metrics: self.metrics.clone(),
+        });
+        let permit = match handler.limiter() {
+            None => None,
+            Some(limiter) => limiter.acquire_owned().await.ok(),
+        };
+
+        self.metrics.on_received();
+        let metrics = self.metrics.clone();
+        task::spawn(async move {
+            let message = match compression::decompress(message, false).await {
+                None => {
+                    metrics.on_rejected();
+                    return;
+                }
+                Some(message) => message,
+            };
+            let start = Instant::now();
+            handler.on_message(this, message).await;
+            metrics.on_handled(start.elapsed());
+            drop(permit);
+            drop(inflight);
+        });
+    }
+}
+
+#[async_trait]
+impl GmqQueue for RedisQueue {
+    fn name(&self) -> &str {
+        self.opts.name.as_str()
+    }
+
+    fn is_recv(&self) -> bool {
+        self.opts.is_recv
+    }
+
+    fn status(&self) -> Status {
+        *self.status.lock().unwrap()
+    }
+
+    fn set_handler(&mut self, handler: Arc<dyn EventHandler>) {
+        *self.handler.lock().unwrap() = Some(handler);
+    }
+
+    fn clear_handler(&mut self) {
+        let _ = (*self.handler.lock().unwrap()).take();
+    }
+
+    fn set_msg_handler(&mut self, handler: Arc<dyn MessageHandler>) {
+        *self.msg_handler.lock().unwrap() = Some(handler);
+    }
+
+    fn connect(&mut self) -> Result<(), Box<dyn StdError>> {
+        if self.opts.is_recv && self.msg_handler().is_none() {
+            return Err(Box::new(Error::NoMsgHandler));
+        }
+
+        {
+            let mut task_handle_mutex = self.ev_loop.lock().unwrap();
+            if (*task_handle_mutex).is_some() && self.status() != Status::Failed {
+                return Ok(());
+            }
+            *self.status.lock().unwrap() = Status::Connecting;
+            self.metrics.on_disconnected();
+            *task_handle_mutex = Some(create_event_loop(self));
+        }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.add(self.id.as_str(), Box::new(self.clone()));
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let ev_loop = { self.ev_loop.lock().unwrap().take() };
+        match ev_loop {
+            None => return Ok(()),
+            Some(handle) => handle.abort(),
+        }
+        let queues = { self.conn.lock().unwrap().queues() };
+        queues.remove(self.id.as_str());
+        {
+            *self.status.lock().unwrap() = Status::Closing;
+        }
+
+        let _ = { self.reader.lock().unwrap().take() };
+        let mut result: Result<(), RedisError> = Ok(());
+        if self.opts.is_recv
+            && let Some(mut conn) = self.raw_conn()
+        {
+            let key = self.opts.name.as_str();
+            if self.opts.broadcast {
+                // Each broadcast receiver has its own group, so remove the group.
+                result = conn.xgroup_destroy(key, self.group()).await;
+            } else if !self.opts.reliable {
+                // Keep consumers of reliable receivers to let other receivers claim pending
+                // messages.
+                result = conn
+                    .xgroup_delconsumer(key, self.group(), self.id.as_str())
+                    .await;
+            }
+        }
+
+        {
+            *self.status.lock().unwrap() = Status::Closed;
+        }
+        self.metrics.on_closed();
+        if let Some(handler) = { (*self.handler.lock().unwrap()).clone() } {
+            let queue = Arc::new(self.clone());
+            task::spawn(async move {
+                handler.on_status(queue, Status::Closed).await;
+            });
+        }
+
+        result?;
+        Ok(())
+    }
+
+    async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        let drained = queue::drain(self, timeout, || metrics.in_process() == 0).await;
+        self.close().await?;
+        match drained {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn drain(&self, timeout: Duration) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let metrics = self.metrics.clone();
+        match queue::drain(self, timeout, || metrics.in_process() == 0).await {
+            false => Err(Box::new(Error::Timeout)),
+            true => Ok(()),
+        }
+    }
+
+    async fn pause(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.opts.is_recv {
+            *self.paused.lock().unwrap() = true;
+        }
+        Ok(())
+    }
+
+    async fn resume(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        *self.paused.lock().unwrap() = false;
+        Ok(())
+    }
+
+    fn is_paused(&self) -> bool {
+        *self.paused.lock().unwrap()
+    }
+
+    async fn send_msg_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if self.opts.is_recv {
+            return Err(Box::new(Error::QueueIsReceiver));
+        }
+
+        let (payload, props) = compression::compress(self.opts.compression, payload, props)?;
+        match self.outbox.as_ref() {
+            None => self.publish(payload, props).await,
+            Some(outbox) => outbox.send(Arc::new(self.clone()), payload, props).await,
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for RedisQueue {
+    fn is_connected(&self) -> bool {
+        self.status() == Status::Connected
+    }
+
+    async fn publish(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.publish_entry(payload, props).await;
+        self.metrics.on_sent(result.is_ok());
+        result
+    }
+}
+
+impl Default for RedisQueueOptions {
+    fn default() -> Self {
+        RedisQueueOptions {
+            name: "".to_string(),
+            is_recv: false,
+            reliable: false,
+            broadcast: false,
+            reconnect_millis: DEF_RECONN_TIME_MS,
+            reconnect_policy: None,
+            prefetch: 1,
+            claim_millis: DEF_CLAIM_TIME_MS,
+            max_len: DEF_MAX_LEN,
+            dead_letter: None,
+            outbox: None,
+            compression: Compression::None,
+        }
+    }
+}
+
+impl RedisMessage {
+    /// To acknowledge the pending entry.
+    async fn ack_entry(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        if !self.reliable {
+            return Ok(());
+        }
+        let mut conn = self.conn.clone();
+        let _: usize = conn
+            .xack(self.key.as_str(), self.group.as_str(), &[self.id.as_str()])
+            .await?;
+        Ok(())
+    }
+
+    /// To drop, dead-letter or release the pending entry.
+    async fn nack_entry(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        match mode {
+            NackMode::Requeue => (),
+            NackMode::Drop => return self.ack_entry().await,
+            NackMode::DeadLetter => {
+                if let Some(dead_letter) = self.dead_letter.as_ref() {
+                    let mut conn = self.conn.clone();
+                    let items = to_fields(self.payload.clone(), self.props.clone());
+                    let _: String = conn
+                        .xadd_maxlen(
+                            dead_letter.as_str(),
+                            StreamMaxlen::Approx(self.max_len),
+                            "*",
+                            items.as_slice(),
+                        )
+                        .await?;
+                }
+                return self.ack_entry().await;
+            }
+        }
+        if !self.reliable {
+            return Ok(());
+        }
+        // Redis streams do not support requeuing, so mark the pending entry as idle to let the
+        // receivers claim it immediately.
+        let mut conn = self.conn.clone();
+        let opts = StreamClaimOptions::default()
+            .idle(self.claim_millis as usize)
+            .with_justid();
+        let _: Vec<String> = conn
+            .xclaim_options(
+                self.key.as_str(),
+                self.group.as_str(),
+                self.consumer.as_str(),
+                0,
+                &[self.id.as_str()],
+                opts,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Message for RedisMessage {
+    fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    fn properties(&self) -> &MessageProperties {
+        &self.props
+    }
+
+    async fn ack(&self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.ack_entry().await?;
+        self.metrics.on_ack(true);
+        Ok(())
+    }
+
+    async fn nack_with_mode(&self, mode: NackMode) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.nack_entry(mode).await?;
+        self.metrics.on_ack(false);
+        Ok(())
+    }
+}
+
+/// To convert the payload and properties to fields of a stream entry.
+fn to_fields(payload: Vec<u8>, props: MessageProperties) -> Vec<(String, Vec<u8>)> {
+    let mut items = vec![(PAYLOAD_FIELD.to_string(), payload)];
+    if let Some(content_type) = props.content_type {
+        items.push((CONTENT_TYPE_FIELD.to_string(), content_type.into_bytes()));
+    }
+    if let Some(correlation_id) = props.correlation_id {
+        items.push((
+            CORRELATION_ID_FIELD.to_string(),
+            correlation_id.into_bytes(),
+        ));
+    }
+    if let Some(timestamp) = props.timestamp {
+        items.push((
+            TIMESTAMP_FIELD.to_string(),
+            timestamp.to_string().into_bytes(),
+        ));
+    }
+    for (key, value) in props.headers {
+        items.push((format!("{}{}", HEADER_PREFIX, key), value.into_bytes()));
+    }
+    items
+}
+
+/// To create an event loop runtime task.
+fn create_event_loop(queue: &RedisQueue) -> JoinHandle<()> {
+    let this = Arc::new(queue.clone());
+    task::spawn(async move {
+        let mut backoff = Backoff::new(
+            this.opts.reconnect_policy.as_ref(),
+            this.opts.reconnect_millis,
+        );
+        loop {
+            match this.status() {
+                Status::Closing | Status::Closed | Status::Failed => break,
+                Status::Connecting => {
+                    if this.conn_status() == ConnStatus::Failed {
+                        to_failed(&this);
+                        break;
+                    } else if this.conn_status() != ConnStatus::Connected {
+                        time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                        continue;
+                    }
+
+                    if this.opts.is_recv {
+                        let mut conn = match this.raw_conn() {
+                            None => {
+                                time::sleep(Duration::from_millis(this.opts.reconnect_millis))
+                                    .await;
+                                continue;
+                            }
+                            Some(conn) => conn,
+                        };
+                        if let Err(e) = this.create_group(&mut conn).await {
+                            if let Some(handler) = this.handler() {
+                                let queue = this.clone();
+                                task::spawn(async move {
+                                    handler.on_error(queue, Box::new(e)).await;
+                                });
+                            }
+                            if !wait_retry(&this, &mut backoff).await {
+                                break;
+                            }
+                            continue;
+                        }
+                        let redis_conn = { this.conn.lock().unwrap().clone() };
+                        match redis_conn.new_blocking_connection().await {
+                            Err(_) => {
+                                if !wait_retry(&this, &mut backoff).await {
+                                    break;
+                                }
+                                continue;
+                            }
+                            Ok(reader) => {
+                                *this.reader.lock().unwrap() = Some(reader);
+                            }
+                        }
+                    }
+
+                    {
+                        let mut status_mutex = this.status.lock().unwrap();
+                        if *status_mutex == Status::Closing || *status_mutex == Status::Closed {
+                            continue;
+                        }
+                        *status_mutex = Status::Connected;
+                    }
+                    this.metrics.on_connected();
+                    backoff.reset();
+                    if let Some(handler) = this.handler() {
+                        let queue = this.clone();
+                        task::spawn(async move {
+                            handler.on_status(queue, Status::Connected).await;
+                        });
+                    }
+                    if let Some(outbox) = this.outbox.as_ref() {
+                        outbox.spawn_flush(this.clone());
+                    }
+                }
+                Status::Connected => {
+                    let mut to_connecting = this.conn_status() != ConnStatus::Connected;
+                    if !to_connecting {
+                        if !this.opts.is_recv || this.is_paused() {
+                            time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                            continue;
+                        }
+                        let reader = { this.reader.lock().unwrap().clone() };
+                        to_connecting = match reader {
+                            None => true,
+                            Some(mut reader) => this.read(&mut reader).await.is_err(),
+                        };
+                    }
+                    if !to_connecting {
+                        continue;
+                    }
+
+                    {
+                        let mut status_mutex = this.status.lock().unwrap();
+                        if *status_mutex == Status::Closing || *status_mutex == Status::Closed {
+                            continue;
+                        }
+                        *status_mutex = Status::Connecting;
+                    }
+                    this.metrics.on_disconnected();
+                    let _ = { this.reader.lock().unwrap().take() };
+                    if let Some(handler) = this.handler() {
+                        let queue = this.clone();
+                        task::spawn(async move {
+                            handler.on_status(queue, Status::Connecting).await;
+                        });
+                    }
+                    time::sleep(Duration::from_millis(this.opts.reconnect_millis)).await;
+                }
+                Status::Disconnected => {
+                    *this.status.lock().unwrap() = Status::Connecting;
+                }
+            }
+        }
+    })
+}
+
+/// To wait for the next attempt after a failed attempt. Returns `false` and changes the status to
+/// [`Status::Failed`] if the attempts reach the limit.
+async fn wait_retry(queue: &Arc<RedisQueue>, backoff: &mut Backoff) -> bool {
+    if !backoff.fail() {
+        to_failed(queue);
+        return false;
+    }
+    time::sleep(backoff.delay()).await;
+    true
+}
+
+/// To change the status to [`Status::Failed`].
+fn to_failed(queue: &Arc<RedisQueue>) {
+    {
+        let mut status_mutex = queue.status.lock().unwrap();
+        if *status_mutex == Status::Closing || *status_mutex == Status::Closed {
+            return;
+        }
+        *status_mutex = Status::Failed;
+    }
+    queue.metrics.on_disconnected();
+    let _ = { queue.reader.lock().unwrap().take() };
+
+    if let Some(handler) = queue.handler() {
+        let q = queue.clone();
+        task::spawn(async move {
+            handler.on_status(q, Status::Failed).await;
+        });
+    }
+}
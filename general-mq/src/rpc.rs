@@ -0,0 +1,179 @@
+//! Request/reply RPC on top of queues.
+//!
+//! The [`RpcClient`] sends requests with a random correlation ID and the name of its private reply
+//! queue in the [`REPLY_TO_HEADER`] header. The responder sends the reply to that queue with
+//! [`reply()`] and the client matches it with the request by the correlation ID.
+//!
+//! **Note**: MQTT 3.1.1 does not support properties so it cannot be used for RPC.
+
+use std::{
+    collections::HashMap,
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use tokio::{sync::oneshot, time};
+
+use crate::{
+    Error, ID_SIZE, Queue,
+    queue::{GmqQueue, Message, MessageHandler, MessageProperties},
+    randomstring,
+};
+
+/// The RPC client that pairs a request sender queue with a private reply receiver queue.
+#[derive(Clone)]
+pub struct RpcClient {
+    /// The sender queue of requests.
+    request: Queue,
+    /// The receiver queue of replies.
+    reply: Queue,
+    /// Calls that are waiting for replies, keyed by correlation IDs.
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<Vec<u8>>>>>,
+}
+
+/// The [`MessageHandler`] of the reply queue that resolves pending calls.
+struct ReplyHandler {
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<Vec<u8>>>>>,
+}
+
+/// The header that carries the reply queue name of requests.
+pub const REPLY_TO_HEADER: &str = "reply-to";
+
+impl RpcClient {
+    /// Create an RPC client. The message handler of the `reply` queue is replaced.
+    ///
+    /// The `reply` queue should be used by this client only, such as a unicast queue with a
+    /// random name.
+    pub fn new(request: Queue, reply: Queue) -> Result<Self, String> {
+        if request.is_recv() {
+            return Err("the request queue must be a sender".to_string());
+        } else if !reply.is_recv() {
+            return Err("the reply queue must be a receiver".to_string());
+        }
+
+        let pending = Arc::new(Mutex::new(HashMap::new()));
+        let mut reply = reply;
+        reply.set_msg_handler(Arc::new(ReplyHandler {
+            pending: pending.clone(),
+        }));
+        Ok(RpcClient {
+            request,
+            reply,
+            pending,
+        })
+    }
+
+    /// To get the request queue.
+    pub fn request(&self) -> &Queue {
+        &self.request
+    }
+
+    /// To get the reply queue.
+    pub fn reply(&self) -> &Queue {
+        &self.reply
+    }
+
+    /// To connect the reply queue and the request queue.
+    ///
+    /// **Note**: replies that are sent before the reply queue is connected may be lost.
+    pub fn connect(&mut self) -> Result<(), Box<dyn StdError>> {
+        self.reply.connect()?;
+        self.request.connect()
+    }
+
+    /// To close both queues. Pending calls return [`Error::NotConnected`].
+    pub async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let result = self.request.close().await;
+        self.reply.close().await?;
+        self.pending.lock().unwrap().clear();
+        result
+    }
+
+    /// The number of calls that are waiting for replies.
+    pub fn pending_len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// To send a request and wait for the reply payload.
+    ///
+    /// Returns [`Error::Timeout`] if the reply is not received within `timeout`.
+    pub async fn call(
+        &self,
+        payload: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        self.call_with_props(payload, MessageProperties::default(), timeout)
+            .await
+    }
+
+    /// To send a request with properties and wait for the reply payload. The correlation ID and
+    /// the [`REPLY_TO_HEADER`] header are overwritten.
+    pub async fn call_with_props(
+        &self,
+        payload: Vec<u8>,
+        props: MessageProperties,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, Box<dyn StdError + Send + Sync>> {
+        let id = randomstring(ID_SIZE);
+        let mut props = props;
+        props.correlation_id = Some(id.clone());
+        props
+            .headers
+            .insert(REPLY_TO_HEADER.to_string(), self.reply.name().to_string());
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id.clone(), tx);
+        if let Err(e) = self.request.send_msg_with_props(payload, props).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        match time::timeout(timeout, rx).await {
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(Box::new(Error::Timeout))
+            }
+            Ok(Err(_)) => Err(Box::new(Error::NotConnected)),
+            Ok(Ok(payload)) => Ok(payload),
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for ReplyHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        let _ = msg.ack().await;
+
+        // Replies of timed out calls are dropped.
+        let tx = match msg.properties().correlation_id.as_ref() {
+            None => return,
+            Some(id) => match self.pending.lock().unwrap().remove(id) {
+                None => return,
+                Some(tx) => tx,
+            },
+        };
+        let _ = tx.send(msg.payload().to_vec());
+    }
+}
+
+/// To get the reply queue name of a request.
+pub fn reply_to(props: &MessageProperties) -> Option<&str> {
+    match props.headers.get(REPLY_TO_HEADER) {
+        None => None,
+        Some(name) => Some(name.as_str()),
+    }
+}
+
+/// To send the reply of the `request` with the sender `queue` of the [`reply_to()`] queue.
+pub async fn reply(
+    queue: &dyn GmqQueue,
+    request: &dyn Message,
+    payload: Vec<u8>,
+) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    let props = MessageProperties {
+        correlation_id: request.properties().correlation_id.clone(),
+        ..Default::default()
+    };
+    queue.send_msg_with_props(payload, props).await
+}
@@ -0,0 +1,239 @@
+//! TLS options of secure connections.
+
+use std::sync::Arc;
+
+use rustls::{
+    ClientConfig, DigitallySignedStruct, Error as RustlsError, RootCertStore, SignatureScheme,
+    client::{
+        WebPkiServerVerifier,
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    },
+    crypto::{self, CryptoProvider, WebPkiSupportedAlgorithms, aws_lc_rs},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime, pem::PemObject},
+};
+
+/// TLS options of `amqps` and `mqtts` connections.
+///
+/// All files are PEM format.
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// CA certificates to verify the server. Use `None` to use the platform root certificates.
+    pub cacert_file: Option<String>,
+    /// Client certificate chain for mutual TLS. `key_file` must also be set.
+    pub cert_file: Option<String>,
+    /// Client private key for mutual TLS. `cert_file` must also be set.
+    pub key_file: Option<String>,
+    /// The server name to verify the server certificate. Use `None` to use the host of the URI.
+    ///
+    /// **Note**: AMQP also uses this name for SNI.
+    pub server_name: Option<String>,
+    /// Skip verifying the server certificate chain and name.
+    ///
+    /// **Note**: this is insecure and is only for testing.
+    pub insecure: bool,
+}
+
+/// Verifies the server certificate with the specified name instead of the host of the URI.
+#[derive(Debug)]
+struct NameVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    name: ServerName<'static>,
+}
+
+/// Accepts all server certificates. Only handshake signatures are verified.
+#[derive(Debug)]
+struct InsecureVerifier {
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl TlsOptions {
+    /// To create options with configured values. Returns `None` if no option is set so that
+    /// connections use the default options.
+    pub fn from_config(
+        cacert_file: Option<String>,
+        cert_file: Option<String>,
+        key_file: Option<String>,
+        server_name: Option<String>,
+        insecure: bool,
+    ) -> Option<Self> {
+        if cacert_file.is_none()
+            && cert_file.is_none()
+            && key_file.is_none()
+            && server_name.is_none()
+            && !insecure
+        {
+            return None;
+        }
+        Some(TlsOptions {
+            cacert_file,
+            cert_file,
+            key_file,
+            server_name,
+            insecure,
+        })
+    }
+
+    /// To build the rustls client configuration. The process-level crypto provider is used if it
+    /// has been installed, otherwise `aws-lc-rs` is used.
+    pub(crate) fn client_config(&self) -> Result<ClientConfig, String> {
+        let provider = match CryptoProvider::get_default() {
+            None => Arc::new(aws_lc_rs::default_provider()),
+            Some(provider) => provider.clone(),
+        };
+        let builder = match ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+        {
+            Err(e) => return Err(e.to_string()),
+            Ok(builder) => builder,
+        };
+        let builder = if self.insecure {
+            let algorithms = provider.signature_verification_algorithms;
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(InsecureVerifier { algorithms }))
+        } else {
+            let roots = self.root_store()?;
+            match self.server_name.as_ref() {
+                None => builder.with_root_certificates(roots),
+                Some(name) => {
+                    let name = match ServerName::try_from(name.clone()) {
+                        Err(e) => return Err(format!("server_name error: {}", e)),
+                        Ok(name) => name,
+                    };
+                    let inner = match WebPkiServerVerifier::builder_with_provider(
+                        Arc::new(roots),
+                        provider,
+                    )
+                    .build()
+                    {
+                        Err(e) => return Err(e.to_string()),
+                        Ok(verifier) => verifier,
+                    };
+                    builder
+                        .dangerous()
+                        .with_custom_certificate_verifier(Arc::new(NameVerifier { inner, name }))
+                }
+            }
+        };
+
+        match (self.cert_file.as_ref(), self.key_file.as_ref()) {
+            (None, None) => Ok(builder.with_no_client_auth()),
+            (Some(cert_file), Some(key_file)) => {
+                let certs = match CertificateDer::pem_file_iter(cert_file) {
+                    Err(e) => return Err(format!("cert_file error: {}", e)),
+                    Ok(iter) => match iter.collect::<Result<Vec<_>, _>>() {
+                        Err(e) => return Err(format!("cert_file error: {}", e)),
+                        Ok(certs) => certs,
+                    },
+                };
+                let key = match PrivateKeyDer::from_pem_file(key_file) {
+                    Err(e) => return Err(format!("key_file error: {}", e)),
+                    Ok(key) => key,
+                };
+                match builder.with_client_auth_cert(certs, key) {
+                    Err(e) => Err(e.to_string()),
+                    Ok(config) => Ok(config),
+                }
+            }
+            _ => Err("cert_file and key_file must be set together".to_string()),
+        }
+    }
+
+    /// To load CA certificates from `cacert_file` or the platform.
+    fn root_store(&self) -> Result<RootCertStore, String> {
+        let mut roots = RootCertStore::empty();
+        match self.cacert_file.as_ref() {
+            None => {
+                for cert in rustls_native_certs::load_native_certs().certs {
+                    let _ = roots.add(cert);
+                }
+            }
+            Some(cacert_file) => {
+                let certs = match CertificateDer::pem_file_iter(cacert_file) {
+                    Err(e) => return Err(format!("cacert_file error: {}", e)),
+                    Ok(iter) => match iter.collect::<Result<Vec<_>, _>>() {
+                        Err(e) => return Err(format!("cacert_file error: {}", e)),
+                        Ok(certs) => certs,
+                    },
+                };
+                for cert in certs {
+                    if let Err(e) = roots.add(cert) {
+                        return Err(format!("cacert_file error: {}", e));
+                    }
+                }
+            }
+        }
+        Ok(roots)
+    }
+}
+
+impl ServerCertVerifier for NameVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, RustlsError> {
+        self.inner
+            .verify_server_cert(end_entity, intermediates, &self.name, ocsp_response, now)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+impl ServerCertVerifier for InsecureVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, RustlsError> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
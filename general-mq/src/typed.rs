@@ -0,0 +1,157 @@
+//! Queues of typed messages.
+//!
+//! The [`TypedQueue`] serializes data into JSON payloads when sending and deserializes payloads
+//! before calling the [`TypedMessageHandler`]. Malformed payloads are rejected with
+//! [`NackMode::DeadLetter`] automatically and reported with
+//! [`TypedMessageHandler::on_decode_error()`].
+
+use std::{error::Error as StdError, marker::PhantomData, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    Queue,
+    queue::{EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode, Status},
+};
+
+/// The content type of payloads that are sent by [`TypedQueue`]s.
+pub const CONTENT_TYPE_JSON: &str = "application/json";
+
+/// The queue that sends and receives data of type `T` as JSON payloads.
+pub struct TypedQueue<T> {
+    /// The inner queue.
+    queue: Queue,
+    _data: PhantomData<fn() -> T>,
+}
+
+/// The message handler for [`TypedQueue`]s.
+#[async_trait]
+pub trait TypedMessageHandler<T>: Send + Sync {
+    /// Triggered for new incoming [`Message`]s with the deserialized data.
+    async fn on_message(&self, queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>, data: T);
+
+    /// Triggered for messages that cannot be deserialized. The message has been rejected with
+    /// [`NackMode::DeadLetter`] before calling this.
+    async fn on_decode_error(
+        &self,
+        queue: Arc<dyn GmqQueue>,
+        msg: Box<dyn Message>,
+        err: serde_json::Error,
+    );
+}
+
+/// The [`MessageHandler`] of the inner queue that deserializes payloads.
+struct TypedHandler<T> {
+    handler: Arc<dyn TypedMessageHandler<T>>,
+}
+
+impl<T> Clone for TypedQueue<T> {
+    fn clone(&self) -> Self {
+        TypedQueue {
+            queue: self.queue.clone(),
+            _data: PhantomData,
+        }
+    }
+}
+
+impl<T> TypedQueue<T>
+where
+    T: Serialize + DeserializeOwned + Send + 'static,
+{
+    /// Create a typed queue with a created queue.
+    pub fn new(queue: Queue) -> Self {
+        TypedQueue {
+            queue,
+            _data: PhantomData,
+        }
+    }
+
+    /// To get the inner queue.
+    pub fn queue(&self) -> &Queue {
+        &self.queue
+    }
+
+    /// To get the queue name.
+    pub fn name(&self) -> &str {
+        self.queue.name()
+    }
+
+    /// Is the queue a receiver.
+    pub fn is_recv(&self) -> bool {
+        self.queue.is_recv()
+    }
+
+    /// To get the connection status.
+    pub fn status(&self) -> Status {
+        self.queue.status()
+    }
+
+    /// To set the queue event handler.
+    pub fn set_handler(&mut self, handler: Arc<dyn EventHandler>) {
+        self.queue.set_handler(handler)
+    }
+
+    /// To set the typed message handler.
+    pub fn set_msg_handler(&mut self, handler: Arc<dyn TypedMessageHandler<T>>) {
+        self.queue
+            .set_msg_handler(Arc::new(TypedHandler { handler }))
+    }
+
+    /// To connect to the message queue.
+    ///
+    /// **Note** You MUST call `set_msg_handler()` before `connect()` for receivers.
+    pub fn connect(&mut self) -> Result<(), Box<dyn StdError>> {
+        self.queue.connect()
+    }
+
+    /// To close the queue.
+    pub async fn close(&mut self) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.queue.close().await
+    }
+
+    /// To close the queue after in-process messages are done. See [`GmqQueue::close_graceful()`].
+    pub async fn close_graceful(
+        &mut self,
+        timeout: Duration,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.queue.close_graceful(timeout).await
+    }
+
+    /// To serialize and send data (for **senders** only).
+    pub async fn send(&self, data: &T) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        self.send_with_props(data, MessageProperties::default())
+            .await
+    }
+
+    /// To serialize and send data with properties (for **senders** only). The content type is
+    /// [`CONTENT_TYPE_JSON`] if it is not specified.
+    pub async fn send_with_props(
+        &self,
+        data: &T,
+        props: MessageProperties,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let payload = serde_json::to_vec(data)?;
+        let mut props = props;
+        if props.content_type.is_none() {
+            props.content_type = Some(CONTENT_TYPE_JSON.to_string());
+        }
+        self.queue.send_msg_with_props(payload, props).await
+    }
+}
+
+#[async_trait]
+impl<T> MessageHandler for TypedHandler<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    async fn on_message(&self, queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        match serde_json::from_slice::<T>(msg.payload()) {
+            Err(e) => {
+                let _ = msg.nack_with_mode(NackMode::DeadLetter).await;
+                self.handler.on_decode_error(queue, msg, e).await;
+            }
+            Ok(data) => self.handler.on_message(queue, msg, data).await,
+        }
+    }
+}
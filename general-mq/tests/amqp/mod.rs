@@ -18,6 +18,8 @@ pub fn suite() -> Suite<TestState> {
             context.it("new() with default", connection::new_default);
             context.it("new() with zero", connection::new_zero);
             context.it("new() with wrong opts", connection::new_wrong_opts);
+            context.it("new() with TLS", connection::new_tls);
+            context.it("new() with multiple hosts", connection::new_multi_host);
 
             context.it("status()", connection::properties);
 
@@ -27,6 +29,15 @@ pub fn suite() -> Suite<TestState> {
                 "connect() after connect()",
                 connection::connect_after_connect,
             );
+            context.it(
+                "connect() with multiple hosts",
+                connection::connect_multi_host,
+            );
+            context.it(
+                "connect() with multiple hosts and a shared port",
+                connection::connect_multi_host_port,
+            );
+            context.it("connect() with reconnect limit", connection::connect_failed);
 
             context.it("remove_handler()", connection::remove_handler);
 
@@ -91,6 +102,12 @@ pub fn suite() -> Suite<TestState> {
             context.it("persistent", queue::data_persistent);
             context.it("nack", queue::data_nack);
 
+            context.it("stream", queue::data_stream);
+            context.it("dead letter", queue::data_dead_letter);
+            context.it("quorum", queue::data_quorum);
+            context.it("pause", queue::data_pause);
+            context.it("max concurrency", queue::data_max_concurrency);
+
             context.after_each(clear_state);
         });
 
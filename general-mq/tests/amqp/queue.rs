@@ -6,13 +6,15 @@ use std::{
 };
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use laboratory::{SpecContext, expect};
 use tokio::{task, time};
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, AmqpQueue, AmqpQueueOptions, Queue, QueueOptions,
+    AmqpConnection, AmqpConnectionOptions, AmqpOverflow, AmqpQueue, AmqpQueueOptions,
+    AmqpQueueType, Queue, QueueOptions,
     connection::GmqConnection,
-    queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
+    queue::{EventHandler, GmqQueue, Message, MessageHandler, NackMode, Status},
 };
 
 use super::{STATE, TestState};
@@ -54,6 +56,13 @@ struct TestRecvMsgHandler {
     pub nack_errors: Arc<Mutex<Vec<String>>>,
 }
 
+#[derive(Clone)]
+struct TestConcurrencyHandler {
+    pub running: Arc<Mutex<usize>>,
+    pub max_running: Arc<Mutex<usize>>,
+    pub count: Arc<Mutex<usize>>,
+}
+
 const RETRY_10MS: usize = 100;
 
 #[async_trait]
@@ -136,6 +145,24 @@ impl MessageHandler for TestRecvMsgHandler {
     }
 }
 
+#[async_trait]
+impl MessageHandler for TestConcurrencyHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        {
+            let mut running = self.running.lock().unwrap();
+            *running += 1;
+            let mut max_running = self.max_running.lock().unwrap();
+            if *running > *max_running {
+                *max_running = *running;
+            }
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        let _ = msg.ack().await;
+        *self.running.lock().unwrap() -= 1;
+        *self.count.lock().unwrap() += 1;
+    }
+}
+
 /// Test default options.
 pub fn new_default(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     let conn = match AmqpConnection::new(AmqpConnectionOptions::default()) {
@@ -180,6 +207,40 @@ pub fn new_wrong_opts(_context: &mut SpecContext<TestState>) -> Result<(), Strin
         ..Default::default()
     };
     let queue = AmqpQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = AmqpQueueOptions {
+        name: "name".to_string(),
+        max_priority: Some(0),
+        ..Default::default()
+    };
+    let queue = AmqpQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = AmqpQueueOptions {
+        name: "name".to_string(),
+        queue_type: AmqpQueueType::Quorum,
+        max_priority: Some(10),
+        ..Default::default()
+    };
+    let queue = AmqpQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = AmqpQueueOptions {
+        name: "name".to_string(),
+        queue_type: AmqpQueueType::Quorum,
+        exclusive: true,
+        ..Default::default()
+    };
+    let queue = AmqpQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = AmqpQueueOptions {
+        name: "name".to_string(),
+        queue_type: AmqpQueueType::Stream,
+        ..Default::default()
+    };
+    let queue = AmqpQueue::new(opts, &conn);
     expect(queue.is_err()).to_equal(true)
 }
 
@@ -1667,6 +1728,273 @@ pub fn data_nack(context: &mut SpecContext<TestState>) -> Result<(), String> {
     })
 }
 
+/// Test `nack_with_mode()` with a dead-letter queue.
+pub fn data_dead_letter(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = AmqpQueueOptions {
+        name: "dead-letter".to_string(),
+        reliable: true,
+        dead_letter: Some("dead-letter.dlq".to_string()),
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    let opts = AmqpQueueOptions {
+        name: "dead-letter.dlq".to_string(),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let mut dlq = match AmqpQueue::new(opts, &resources.conn[0]) {
+        Err(e) => return Err(format!("AmqpQueue::new() dead-letter error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut dlq_stream = dlq.msg_stream(1);
+    if let Err(e) = dlq.connect() {
+        return Err(format!("GmqQueue::connect() dead-letter error: {}", e));
+    }
+    state.queues.push(Box::new(dlq.clone()));
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        wait_connected(&dlq, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..2 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err("cannot receive messages".to_string()),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            let mode = match msg.payload() {
+                b"1" => NackMode::Drop,
+                _ => NackMode::DeadLetter,
+            };
+            if let Err(e) = msg.nack_with_mode(mode).await {
+                return Err(format!("nack {:?} error: {}", mode, e));
+            }
+        }
+
+        let msg = match time::timeout(Duration::from_millis(1500), dlq_stream.next()).await {
+            Err(_) => return Err("cannot receive the dead letter".to_string()),
+            Ok(None) => return Err("dead-letter stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"2".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack dead letter error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(200), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages again".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test sending and receiving messages with a quorum queue and queue arguments.
+pub fn data_quorum(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = AmqpQueueOptions {
+        name: "quorum".to_string(),
+        reliable: true,
+        message_ttl_millis: Some(60000),
+        max_length: Some(10),
+        overflow: Some(AmqpOverflow::RejectPublish),
+        queue_type: AmqpQueueType::Quorum,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+            Err(_) => return Err("cannot receive 1".to_string()),
+            Ok(None) => return Err("stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"1".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test receiving messages with a message stream.
+pub fn data_stream(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = AmqpQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=3 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        let mut messages = vec![];
+        while messages.len() < 3 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err(format!("received {}/3 messages", messages.len())),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            if let Err(e) = msg.ack().await {
+                return Err(format!("ack error: {}", e));
+            }
+            messages.push(String::from_utf8_lossy(msg.payload()).to_string());
+        }
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string(), "3".to_string()])
+    })
+}
+
+/// Test `pause()` and `resume()`.
+pub fn data_pause(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = AmqpQueueOptions {
+        name: "pause".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].pause().await {
+            return Err(format!("pause() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(true)?;
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(500), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages after pause()".to_string());
+        }
+
+        if let Err(e) = resources.queues[1].resume().await {
+            return Err(format!("resume() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(false)?;
+        let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+            Err(_) => return Err("cannot receive messages after resume()".to_string()),
+            Ok(None) => return Err("stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"1".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test the `max_concurrency` option.
+pub fn data_max_concurrency(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = AmqpQueueOptions {
+        name: "max-concurrency".to_string(),
+        reliable: true,
+        prefetch: 10,
+        max_concurrency: 2,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = TestConcurrencyHandler {
+        running: Arc::new(Mutex::new(0)),
+        max_running: Arc::new(Mutex::new(0)),
+        count: Arc::new(Mutex::new(0)),
+    };
+    match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.set_msg_handler(Arc::new(handler.clone())),
+    }
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=10 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..RETRY_10MS * 3 {
+            if *handler.count.lock().unwrap() >= 10 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        expect(*handler.count.lock().unwrap()).to_equal(10)?;
+        expect(*handler.max_running.lock().unwrap()).to_equal(2)
+    })
+}
+
 /// Create connected (optional) connections/queues for testing connections.
 fn create_conn_rsc(
     state: &mut TestState,
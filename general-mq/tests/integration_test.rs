@@ -6,7 +6,9 @@ use tokio::{runtime::Runtime, task};
 use general_mq::{connection::GmqConnection, queue::GmqQueue};
 
 mod amqp;
+mod memory;
 mod mqtt;
+mod redis;
 
 pub struct TestState {
     pub runtime: Runtime,
@@ -22,6 +24,8 @@ async fn integration_test() -> LabResult {
         describe("function test", |context| {
             context.describe_import(amqp::suite());
             context.describe_import(mqtt::suite());
+            context.describe_import(memory::suite());
+            context.describe_import(redis::suite());
         })
         .run()
     });
@@ -0,0 +1,1858 @@
+use std::{
+    env,
+    error::Error as StdError,
+    fs,
+    io::Write,
+    str,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use flate2::{Compression as GzLevel, write::GzEncoder};
+use futures::StreamExt;
+use laboratory::{SpecContext, expect};
+use serde::{Deserialize, Serialize};
+use tokio::time;
+
+use general_mq::{
+    Compression, MemoryConnection, MemoryConnectionOptions, MemoryQueue, MemoryQueueOptions,
+    OutboxOptions, Queue, QueueOptions,
+    connection::{GmqConnection, Status as ConnStatus},
+    queue::{EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode, Status},
+    randomstring,
+    rpc::{self, RpcClient},
+    typed::{CONTENT_TYPE_JSON, TypedMessageHandler, TypedQueue},
+};
+
+use super::{STATE, TestState};
+
+#[derive(Default)]
+struct Resources {
+    pub conn: Vec<Box<MemoryConnection>>,
+    pub queues: Vec<Box<MemoryQueue>>,
+}
+
+struct TestDummyMsgHandler;
+
+struct TestConnectHandler {
+    pub recv_connected: Arc<Mutex<bool>>,
+    pub recv_queue_name: Arc<Mutex<String>>,
+}
+
+struct TestCloseHandler {
+    pub recv_closed: Arc<Mutex<bool>>,
+}
+
+struct TestReconnectHandler {
+    pub connected_count: Arc<Mutex<usize>>,
+    pub recv_connecting: Arc<Mutex<bool>>,
+}
+
+#[derive(Clone)]
+struct TestRecvMsgHandler {
+    pub recv_messages: Arc<Mutex<Vec<Vec<u8>>>>,
+    pub recv_props: Arc<Mutex<Vec<(Vec<u8>, MessageProperties)>>>,
+    pub use_nack: Arc<Mutex<bool>>,
+    pub nack_messages: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+#[derive(Clone)]
+struct TestSlowMsgHandler {
+    pub delay_millis: u64,
+    pub done_count: Arc<Mutex<usize>>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct TestTypedData {
+    pub id: u32,
+    pub name: String,
+}
+
+#[derive(Clone)]
+struct TestTypedMsgHandler {
+    pub recv_data: Arc<Mutex<Vec<(TestTypedData, Option<String>)>>>,
+    pub decode_errors: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+const RETRY_10MS: usize = 100;
+
+#[async_trait]
+impl MessageHandler for TestDummyMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, _msg: Box<dyn Message>) {}
+}
+
+#[async_trait]
+impl EventHandler for TestConnectHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Connected {
+            *self.recv_connected.lock().unwrap() = true;
+            *self.recv_queue_name.lock().unwrap() = queue.name().to_string();
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestCloseHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Closed {
+            *self.recv_closed.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestReconnectHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Connected {
+            let mut mutex = self.connected_count.lock().unwrap();
+            *mutex += 1;
+        } else if status == Status::Connecting {
+            *self.recv_connecting.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for TestRecvMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        let use_nack;
+        {
+            use_nack = *self.use_nack.lock().unwrap();
+        }
+        if use_nack {
+            {
+                *self.use_nack.lock().unwrap() = false;
+            }
+            let data = msg.payload().to_vec();
+            self.nack_messages.lock().unwrap().push(data);
+            let _ = msg.nack().await;
+        } else {
+            let data = msg.payload().to_vec();
+            self.recv_props
+                .lock()
+                .unwrap()
+                .push((data.clone(), msg.properties().clone()));
+            self.recv_messages.lock().unwrap().push(data);
+            let _ = msg.ack().await;
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for TestSlowMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        time::sleep(Duration::from_millis(self.delay_millis)).await;
+        let _ = msg.ack().await;
+        *self.done_count.lock().unwrap() += 1;
+    }
+}
+
+#[async_trait]
+impl TypedMessageHandler<TestTypedData> for TestTypedMsgHandler {
+    async fn on_message(
+        &self,
+        _queue: Arc<dyn GmqQueue>,
+        msg: Box<dyn Message>,
+        data: TestTypedData,
+    ) {
+        let content_type = msg.properties().content_type.clone();
+        self.recv_data.lock().unwrap().push((data, content_type));
+        let _ = msg.ack().await;
+    }
+
+    async fn on_decode_error(
+        &self,
+        _queue: Arc<dyn GmqQueue>,
+        msg: Box<dyn Message>,
+        _err: serde_json::Error,
+    ) {
+        self.decode_errors
+            .lock()
+            .unwrap()
+            .push(msg.payload().to_vec());
+    }
+}
+
+/// Test default options.
+pub fn new_default(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match MemoryConnection::new(MemoryConnectionOptions::default()) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        reconnect_millis: 0,
+        ..Default::default()
+    };
+    let queue = MemoryQueue::new(opts, &conn);
+    expect(queue.is_ok()).to_equal(true)?;
+
+    let opts = QueueOptions::Memory(
+        MemoryQueueOptions {
+            name: "name".to_string(),
+            ..Default::default()
+        },
+        &conn,
+    );
+    let queue = Queue::new(opts);
+    expect(queue.is_ok()).to_equal(true)
+}
+
+/// Test options with wrong values.
+pub fn new_wrong_opts(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match MemoryConnection::new(MemoryConnectionOptions::default()) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+
+    let queue = MemoryQueue::new(MemoryQueueOptions::default(), &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = MemoryQueueOptions {
+        name: "A@".to_string(),
+        ..Default::default()
+    };
+    let queue = MemoryQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        dead_letter: Some("A@".to_string()),
+        ..Default::default()
+    };
+    let queue = MemoryQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        outbox: Some(OutboxOptions::default()),
+        ..Default::default()
+    };
+    let queue = MemoryQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        outbox: Some(OutboxOptions {
+            path: outbox_path(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let queue = MemoryQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)
+}
+
+/// Test queue properties after `new()`.
+pub fn properties(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match MemoryConnection::new(MemoryConnectionOptions::default()) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+
+    let opts = MemoryQueueOptions {
+        name: "name-send".to_string(),
+        is_recv: false,
+        ..Default::default()
+    };
+    let queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.name()).to_equal("name-send")?;
+    expect(queue.is_recv()).to_equal(false)?;
+    expect(queue.status()).to_equal(Status::Closed)?;
+
+    let opts = MemoryQueueOptions {
+        name: "name-recv".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.name()).to_equal("name-recv")?;
+    expect(queue.is_recv()).to_equal(true)?;
+    expect(queue.status()).to_equal(Status::Closed)
+}
+
+/// Test `connect()` without handlers.
+pub fn connect_no_handler(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match MemoryConnection::new(MemoryConnectionOptions::default()) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let mut queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.connect().is_err()).to_equal(true)
+}
+
+/// Test `connect()` with a handler.
+pub fn connect_with_handler(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let handler = Arc::new(TestConnectHandler {
+        recv_connected: Arc::new(Mutex::new(false)),
+        recv_queue_name: Arc::new(Mutex::new("".to_string())),
+    });
+    let mut conn = match MemoryConnection::new(MemoryConnectionOptions::default()) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let mut queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    state.queues = vec![Box::new(queue.clone())];
+    queue.set_handler(handler.clone());
+    queue.set_msg_handler(Arc::new(TestDummyMsgHandler {}));
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    if let Err(e) = queue.connect() {
+        return Err(format!("GmqQueue::connect() error: {}", e));
+    }
+
+    state.runtime.block_on(async move {
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_connected.lock().unwrap() {
+                    return expect(handler.recv_queue_name.lock().unwrap().as_str())
+                        .to_equal("name");
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not connected".to_string())
+    })
+}
+
+/// Test `close()`.
+pub fn close(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = Arc::new(TestCloseHandler {
+        recv_closed: Arc::new(Mutex::new(false)),
+    });
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get_mut(1) {
+            None => return Err("should have a queue".to_string()),
+            Some(q) => q,
+        };
+        queue.set_handler(handler.clone());
+        wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() again error: {}", e));
+        }
+        expect(queue.status()).to_equal(Status::Closed)?;
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_closed.lock().unwrap() {
+                    return Ok(());
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not closed".to_string())
+    })
+}
+
+/// Test `send_msg()` with error conditions.
+pub fn send_error(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get(1) {
+            None => return Err("should have a receiver queue".to_string()),
+            Some(q) => q,
+        };
+        if queue.send_msg(vec![]).await.is_ok() {
+            return Err("receiver send_msg() should error".to_string());
+        }
+        let queue = match resources.queues.get_mut(0) {
+            None => return Err("should have a sender queue".to_string()),
+            Some(q) => q,
+        };
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        if queue.send_msg(vec![]).await.is_ok() {
+            return Err("closed send_msg() should error".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test reconnect by closing/connecting the associated connection.
+pub fn reconnect(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = Arc::new(TestReconnectHandler {
+        connected_count: Arc::new(Mutex::new(0)),
+        recv_connecting: Arc::new(Mutex::new(false)),
+    });
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get_mut(1) {
+            None => return Err("should have a queue".to_string()),
+            Some(q) => q,
+        };
+        queue.set_handler(handler.clone());
+        wait_connected(queue.as_ref(), RETRY_10MS).await?;
+
+        let conn = match resources.conn.get_mut(1) {
+            None => return Err("should have a connection".to_string()),
+            Some(conn) => conn,
+        };
+        if let Err(e) = conn.close().await {
+            return Err(format!("close connection error: {}", e));
+        }
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            if *handler.recv_connecting.lock().unwrap() {
+                break;
+            }
+            retry = retry - 1;
+        }
+        if retry == 0 {
+            return Err("no connecting status".to_string());
+        }
+        if let Err(e) = conn.connect() {
+            return Err(format!("connect connection error: {}", e));
+        }
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            if *handler.connected_count.lock().unwrap() > 0 {
+                return Ok(());
+            }
+            retry = retry - 1;
+        }
+        Err("not reconnected".to_string())
+    })
+}
+
+/// Send unicast data to one receiver.
+pub fn data_unicast_1to1(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let mut messages = wait_messages(&handlers, 2).await?;
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])
+    })
+}
+
+/// Send unicast data to 3 receivers.
+pub fn data_unicast_1to3(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 3)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2", "3", "4", "5", "6"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let mut messages = wait_messages(&handlers, 6).await?;
+        messages.sort();
+        expect(messages).to_equal(vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+            "5".to_string(),
+            "6".to_string(),
+        ])?;
+        for handler in handlers.iter() {
+            if handler.recv_messages.lock().unwrap().len() != 2 {
+                return Err("messages are not delivered in round-robin order".to_string());
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Send broadcast data to 3 receivers.
+pub fn data_broadcast_1to3(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        broadcast: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 3)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let messages = wait_messages(&handlers, 6).await?;
+        expect(messages.len()).to_equal(6)?;
+        for handler in handlers.iter() {
+            let messages = handler.recv_messages.lock().unwrap();
+            let mut received = vec![get_message(&messages, 0)?, get_message(&messages, 1)?];
+            received.sort();
+            expect(received).to_equal(vec!["1".to_string(), "2".to_string()])?;
+        }
+        Ok(())
+    })
+}
+
+/// Send reliable data to a closed receiver then it MUST receive after connecting.
+pub fn data_reliable(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        wait_messages(&handlers, 1).await?;
+
+        if let Err(e) = resources.queues[1].close().await {
+            return Err(format!("close recv error: {}", e));
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"2".to_vec()).await {
+            return Err(format!("send 2 error: {}", e));
+        }
+        if let Err(e) = resources.queues[1].connect() {
+            return Err(format!("connect recv error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 2).await?;
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])
+    })
+}
+
+/// Reject reliable data then it MUST be delivered again.
+pub fn data_nack(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    {
+        *handlers[0].use_nack.lock().unwrap() = true;
+    }
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 1).await?;
+        expect(messages).to_equal(vec!["1".to_string()])?;
+        let nack_messages = handlers[0].nack_messages.lock().unwrap();
+        expect(get_message(&nack_messages, 0)?).to_equal("1".to_string())
+    })
+}
+
+/// Send unreliable data to a broadcast queue without receivers then it MUST NOT be received.
+pub fn data_best_effort(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        broadcast: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].close().await {
+            return Err(format!("close recv error: {}", e));
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if let Err(e) = resources.queues[1].connect() {
+            return Err(format!("connect recv error: {}", e));
+        }
+        wait_connected(resources.queues[1].as_ref(), RETRY_10MS).await?;
+        if let Err(e) = resources.queues[0].send_msg(b"2".to_vec()).await {
+            return Err(format!("send 2 error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 1).await?;
+        expect(messages).to_equal(vec!["2".to_string()])
+    })
+}
+
+/// Send data with properties.
+pub fn data_properties(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    let mut props = MessageProperties {
+        content_type: Some("application/json".to_string()),
+        correlation_id: Some("correlation".to_string()),
+        timestamp: Some(1700000000),
+        ..Default::default()
+    };
+    props
+        .headers
+        .insert("dataId".to_string(), "data-id".to_string());
+    let expect_props = props.clone();
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if let Err(e) = resources.queues[0]
+            .send_msg_with_props(b"2".to_vec(), props)
+            .await
+        {
+            return Err(format!("send 2 error: {}", e));
+        }
+        let mut messages = wait_messages(&handlers, 2).await?;
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])?;
+        for (data, props) in handlers[0].recv_props.lock().unwrap().iter() {
+            match data.as_slice() {
+                b"1" => expect(props).to_equal(&MessageProperties::default())?,
+                _ => expect(props).to_equal(&expect_props)?,
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Test `nack_with_mode()` with a dead-letter queue.
+pub fn data_dead_letter(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "dead-letter".to_string(),
+        reliable: true,
+        dead_letter: Some("dead-letter.dlq".to_string()),
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    let opts = MemoryQueueOptions {
+        name: "dead-letter.dlq".to_string(),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let mut dlq = match MemoryQueue::new(opts, &resources.conn[0]) {
+        Err(e) => return Err(format!("MemoryQueue::new() dead-letter error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut dlq_stream = dlq.msg_stream(1);
+    if let Err(e) = dlq.connect() {
+        return Err(format!("GmqQueue::connect() dead-letter error: {}", e));
+    }
+    state.queues.push(Box::new(dlq.clone()));
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        wait_connected(&dlq, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..2 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err("cannot receive messages".to_string()),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            let mode = match msg.payload() {
+                b"1" => NackMode::Drop,
+                _ => NackMode::DeadLetter,
+            };
+            if let Err(e) = msg.nack_with_mode(mode).await {
+                return Err(format!("nack {:?} error: {}", mode, e));
+            }
+        }
+
+        let msg = match time::timeout(Duration::from_millis(1500), dlq_stream.next()).await {
+            Err(_) => return Err("cannot receive the dead letter".to_string()),
+            Ok(None) => return Err("dead-letter stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"2".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack dead letter error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(200), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages again".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test receiving messages with a message stream.
+pub fn data_stream(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=10 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+
+        // Deliveries wait for the stream to be read.
+        time::sleep(Duration::from_millis(100)).await;
+        let received = resources.queues[1].metrics().received;
+        if received > 2 {
+            return Err(format!("received {} messages before reading", received));
+        }
+
+        let mut messages = vec![];
+        while messages.len() < 10 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err(format!("received {}/10 messages", messages.len())),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            if let Err(e) = msg.ack().await {
+                return Err(format!("ack error: {}", e));
+            }
+            messages.push(String::from_utf8_lossy(msg.payload()).to_string());
+        }
+        let expected: Vec<String> = (1..=10).map(|i| i.to_string()).collect();
+        expect(messages).to_equal(expected)
+    })
+}
+
+/// Test `pause()` and `resume()`.
+pub fn data_pause(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "pause".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].pause().await {
+            return Err(format!("pause() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(true)?;
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(500), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages after pause()".to_string());
+        }
+
+        if let Err(e) = resources.queues[1].resume().await {
+            return Err(format!("resume() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(false)?;
+        let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+            Err(_) => return Err("cannot receive messages after resume()".to_string()),
+            Ok(None) => return Err("stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"1".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test sending messages through the outbox before connected.
+pub fn data_outbox(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let outbox = OutboxOptions {
+        path: outbox_path(),
+        ..Default::default()
+    };
+    let result = data_outbox_inner(state, &outbox);
+    let _ = fs::remove_dir_all(outbox.path.as_str());
+    result
+}
+
+/// Test the limits of the outbox.
+pub fn data_outbox_limit(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let outbox = OutboxOptions {
+        path: outbox_path(),
+        max_messages: 2,
+        max_age_millis: 500,
+        ..Default::default()
+    };
+    let result = data_outbox_limit_inner(state, &outbox);
+    let _ = fs::remove_dir_all(outbox.path.as_str());
+    result
+}
+
+/// Test RPC calls with a responder.
+pub fn data_rpc(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (mut client, conn) = create_rpc_client(state)?;
+    let opts = MemoryQueueOptions {
+        name: "rpc".to_string(),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let mut responder = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() responder error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut stream = responder.msg_stream(1);
+    if let Err(e) = responder.connect() {
+        return Err(format!("GmqQueue::connect() responder error: {}", e));
+    }
+    state.queues.push(Box::new(responder.clone()));
+    let opts = MemoryQueueOptions {
+        name: client.reply().name().to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut reply_queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() reply sender error: {}", e)),
+        Ok(q) => q,
+    };
+    if let Err(e) = reply_queue.connect() {
+        return Err(format!("GmqQueue::connect() reply sender error: {}", e));
+    }
+    state.queues.push(Box::new(reply_queue.clone()));
+    if let Err(e) = client.connect() {
+        return Err(format!("RpcClient::connect() error: {}", e));
+    }
+
+    let reply_name = client.reply().name().to_string();
+    state.runtime.block_on(async move {
+        wait_connected(&responder, RETRY_10MS).await?;
+        wait_connected(&reply_queue, RETRY_10MS).await?;
+        wait_connected(client.request(), RETRY_10MS).await?;
+        wait_connected(client.reply(), RETRY_10MS).await?;
+
+        let task = tokio::spawn(async move {
+            while let Some(msg) = stream.next().await {
+                let _ = msg.ack().await;
+                if rpc::reply_to(msg.properties()) != Some(reply_name.as_str()) {
+                    continue;
+                }
+                let mut payload = b"re:".to_vec();
+                payload.extend_from_slice(msg.payload());
+                let _ = rpc::reply(&reply_queue, msg.as_ref(), payload).await;
+            }
+        });
+
+        let result = tokio::join!(
+            client.call(b"1".to_vec(), Duration::from_millis(1500)),
+            client.call(b"2".to_vec(), Duration::from_millis(1500)),
+        );
+        task.abort();
+        match result.0 {
+            Err(e) => return Err(format!("call 1 error: {}", e)),
+            Ok(payload) => expect(payload).to_equal(b"re:1".to_vec())?,
+        }
+        match result.1 {
+            Err(e) => return Err(format!("call 2 error: {}", e)),
+            Ok(payload) => expect(payload).to_equal(b"re:2".to_vec())?,
+        }
+        expect(client.pending_len()).to_equal(0)?;
+        if let Err(e) = client.close().await {
+            return Err(format!("RpcClient::close() error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test RPC calls without responders.
+pub fn data_rpc_timeout(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (mut client, _conn) = create_rpc_client(state)?;
+    if let Err(e) = client.connect() {
+        return Err(format!("RpcClient::connect() error: {}", e));
+    }
+
+    state.runtime.block_on(async move {
+        wait_connected(client.request(), RETRY_10MS).await?;
+        wait_connected(client.reply(), RETRY_10MS).await?;
+        match client.call(b"1".to_vec(), Duration::from_millis(100)).await {
+            Err(e) => expect(e.to_string()).to_equal("timeout".to_string())?,
+            Ok(_) => return Err("call should time out".to_string()),
+        }
+        expect(client.pending_len()).to_equal(0)
+    })
+}
+
+/// Test metrics of senders and receivers.
+pub fn data_metrics(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "metrics".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    *handlers[0].use_nack.lock().unwrap() = true;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=2 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        let _ = wait_messages(&handlers, 2).await?;
+        time::sleep(Duration::from_millis(50)).await;
+
+        let metrics = resources.queues[0].metrics();
+        expect(metrics.sent).to_equal(2)?;
+        expect(metrics.send_errors).to_equal(0)?;
+        expect(metrics.connected).to_equal(true)?;
+        let metrics = resources.queues[1].metrics();
+        expect(metrics.received).to_equal(3)?;
+        expect(metrics.acked).to_equal(2)?;
+        expect(metrics.nacked).to_equal(1)?;
+        expect(metrics.handled).to_equal(3)?;
+        expect(metrics.reconnects).to_equal(0)?;
+        expect(metrics.connected).to_equal(true)?;
+
+        if let Err(e) = resources.conn[0].close().await {
+            return Err(format!("close connection error: {}", e));
+        }
+        if resources.queues[0].send_msg(b"3".to_vec()).await.is_ok() {
+            return Err("send should fail after closing the connection".to_string());
+        }
+        let metrics = resources.queues[0].metrics();
+        expect(metrics.send_errors).to_equal(1)?;
+        time::sleep(Duration::from_millis(50)).await;
+        expect(resources.queues[0].metrics().connected).to_equal(false)?;
+        if let Err(e) = resources.conn[0].connect() {
+            return Err(format!("connect connection error: {}", e));
+        }
+        time::sleep(Duration::from_millis(100)).await;
+        let metrics = resources.queues[0].metrics();
+        expect(metrics.connected).to_equal(true)?;
+        expect(metrics.reconnects).to_equal(1)?;
+        expect(metrics.disconnected_millis > 0).to_equal(true)
+    })
+}
+
+/// Test sending and receiving typed data and rejecting malformed payloads.
+pub fn data_typed(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let uri = format!("mem://{}", randomstring(8).to_lowercase());
+    let mut conn = match MemoryConnection::new(MemoryConnectionOptions { uri }) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    state.conn.push(Box::new(conn.clone()));
+
+    let opts = MemoryQueueOptions {
+        name: "typed".to_string(),
+        reliable: true,
+        dead_letter: Some("typed.dlq".to_string()),
+        ..Default::default()
+    };
+    let sender = match Queue::new(QueueOptions::Memory(opts.clone(), &conn)) {
+        Err(e) => return Err(format!("Queue::new() sender error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut sender = TypedQueue::<TestTypedData>::new(sender);
+    let mut recv_opts = opts;
+    recv_opts.is_recv = true;
+    let receiver = match Queue::new(QueueOptions::Memory(recv_opts, &conn)) {
+        Err(e) => return Err(format!("Queue::new() receiver error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut receiver = TypedQueue::<TestTypedData>::new(receiver);
+    let handler = TestTypedMsgHandler {
+        recv_data: Arc::new(Mutex::new(vec![])),
+        decode_errors: Arc::new(Mutex::new(vec![])),
+    };
+    receiver.set_msg_handler(Arc::new(handler.clone()));
+    let opts = MemoryQueueOptions {
+        name: "typed.dlq".to_string(),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let mut dlq = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() dead-letter error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut dlq_stream = dlq.msg_stream(1);
+    for queue in [&mut sender, &mut receiver] {
+        if let Err(e) = queue.connect() {
+            return Err(format!("TypedQueue::connect() error: {}", e));
+        }
+    }
+    if let Err(e) = dlq.connect() {
+        return Err(format!("GmqQueue::connect() dead-letter error: {}", e));
+    }
+    state.queues.push(Box::new(dlq.clone()));
+
+    state.runtime.block_on(async move {
+        wait_connected(sender.queue(), RETRY_10MS).await?;
+        wait_connected(receiver.queue(), RETRY_10MS).await?;
+        wait_connected(&dlq, RETRY_10MS).await?;
+
+        let data = TestTypedData {
+            id: 1,
+            name: "name".to_string(),
+        };
+        if let Err(e) = sender.send(&data).await {
+            return Err(format!("send data error: {}", e));
+        }
+        if let Err(e) = sender.queue().send_msg(b"{\"id\":\"2\"}".to_vec()).await {
+            return Err(format!("send malformed payload error: {}", e));
+        }
+
+        let msg = match time::timeout(Duration::from_millis(1500), dlq_stream.next()).await {
+            Err(_) => return Err("cannot receive the dead letter".to_string()),
+            Ok(None) => return Err("dead-letter stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"{\"id\":\"2\"}".as_slice())?;
+        let _ = msg.ack().await;
+        time::sleep(Duration::from_millis(50)).await;
+
+        {
+            let recv_data = handler.recv_data.lock().unwrap();
+            expect(recv_data.len()).to_equal(1)?;
+            expect(&recv_data[0].0).to_equal(&data)?;
+            expect(recv_data[0].1.as_deref()).to_equal(Some(CONTENT_TYPE_JSON))?;
+        }
+        let decode_errors = handler.decode_errors.lock().unwrap().clone();
+        expect(decode_errors).to_equal(vec![b"{\"id\":\"2\"}".to_vec()])?;
+
+        if let Err(e) = sender.close().await {
+            return Err(format!("close sender error: {}", e));
+        }
+        if let Err(e) = receiver.close().await {
+            return Err(format!("close receiver error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test compressed payloads from gzip, zstd and uncompressed senders.
+pub fn data_compression(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "compression".to_string(),
+        reliable: true,
+        compression: Compression::Gzip,
+        ..Default::default()
+    };
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let opts = MemoryQueueOptions {
+        name: "compression".to_string(),
+        reliable: true,
+        compression: Compression::Zstd,
+        ..Default::default()
+    };
+    let mut zstd_queue = match MemoryQueue::new(opts, &resources.conn[0]) {
+        Err(e) => return Err(format!("MemoryQueue::new() zstd error: {}", e)),
+        Ok(q) => q,
+    };
+    if let Err(e) = zstd_queue.connect() {
+        return Err(format!("GmqQueue::connect() zstd error: {}", e));
+    }
+    state.queues.push(Box::new(zstd_queue.clone()));
+
+    let long = "a".repeat(1000);
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        wait_connected(&zstd_queue, RETRY_10MS).await?;
+
+        let mut props = MessageProperties::default();
+        props.headers.insert("key".to_string(), "value".to_string());
+        let sender = &resources.queues[0];
+        if let Err(e) = sender
+            .send_msg_with_props(long.clone().into_bytes(), props.clone())
+            .await
+        {
+            return Err(format!("send gzip error: {}", e));
+        }
+        if let Err(e) = sender.send_msg(b"1".to_vec()).await {
+            return Err(format!("send short error: {}", e));
+        }
+        if let Err(e) = zstd_queue.send_msg(long.clone().into_bytes()).await {
+            return Err(format!("send zstd error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 3).await?;
+        expect(messages).to_equal(vec![long.clone(), "1".to_string(), long.clone()])?;
+        let recv_props = handlers[0].recv_props.lock().unwrap().clone();
+        expect(&recv_props[0].1).to_equal(&props)?;
+        expect(recv_props[1].1.headers.is_empty()).to_equal(true)?;
+        expect(recv_props[2].1.headers.is_empty()).to_equal(true)
+    })
+}
+
+/// Test rejecting payloads that are too large after decompression.
+pub fn data_compression_limit(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "compression-limit".to_string(),
+        reliable: true,
+        dead_letter: Some("compression-limit.dlq".to_string()),
+        ..Default::default()
+    };
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    let payload = match zstd::encode_all(vec![0u8; 64 * 1024 * 1024 + 1].as_slice(), 0) {
+        Err(e) => return Err(format!("compress error: {}", e)),
+        Ok(payload) => payload,
+    };
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+
+        let mut props = MessageProperties::default();
+        props
+            .headers
+            .insert("content-encoding".to_string(), "zstd".to_string());
+        if let Err(e) = resources.queues[0]
+            .send_msg_with_props(payload, props)
+            .await
+        {
+            return Err(format!("send error: {}", e));
+        }
+
+        for _ in 0..RETRY_10MS {
+            if resources.queues[1].metrics().nacked > 0 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        let metrics = resources.queues[1].metrics();
+        expect(metrics.nacked).to_equal(1)?;
+        expect(metrics.handled).to_equal(0)?;
+        expect(handlers[0].recv_messages.lock().unwrap().len()).to_equal(0)
+    })
+}
+
+/// Test closing a receiver gracefully after rejecting a payload that is too large after
+/// decompression.
+pub fn data_compression_limit_close(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MemoryQueueOptions {
+        name: "compression-limit-close".to_string(),
+        reliable: true,
+        dead_letter: Some("compression-limit-close.dlq".to_string()),
+        ..Default::default()
+    };
+    create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    let mut encoder = GzEncoder::new(vec![], GzLevel::fast());
+    if let Err(e) = encoder.write_all(vec![0u8; 64 * 1024 * 1024 + 1].as_slice()) {
+        return Err(format!("compress error: {}", e));
+    }
+    let payload = match encoder.finish() {
+        Err(e) => return Err(format!("compress finish error: {}", e)),
+        Ok(payload) => payload,
+    };
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+
+        let mut props = MessageProperties::default();
+        props
+            .headers
+            .insert("content-encoding".to_string(), "gzip".to_string());
+        if let Err(e) = resources.queues[0]
+            .send_msg_with_props(payload, props)
+            .await
+        {
+            return Err(format!("send error: {}", e));
+        }
+
+        for _ in 0..RETRY_10MS {
+            if resources.queues[1].metrics().nacked > 0 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        expect(resources.queues[1].metrics().nacked).to_equal(1)?;
+        if let Err(e) = resources.queues[1]
+            .close_graceful(Duration::from_secs(1))
+            .await
+        {
+            return Err(format!("close_graceful() error: {}", e));
+        }
+        expect(resources.queues[1].status()).to_equal(Status::Closed)
+    })
+}
+
+/// Test closing a receiver after the message handler returns.
+pub fn data_close_graceful(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (_conn, sender, mut receiver, handler) = create_slow_rsc(state, "close-graceful", 200)?;
+    state.runtime.block_on(async move {
+        wait_connected(&sender, RETRY_10MS).await?;
+        wait_connected(&receiver, RETRY_10MS).await?;
+        if let Err(e) = sender.send_msg(b"1".to_vec()).await {
+            return Err(format!("send error: {}", e));
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        expect(receiver.metrics().received).to_equal(1)?;
+        if let Err(e) = receiver.close_graceful(Duration::from_secs(1)).await {
+            return Err(format!("close_graceful() error: {}", e));
+        }
+        expect(*handler.done_count.lock().unwrap()).to_equal(1)?;
+        expect(receiver.status()).to_equal(Status::Closed)?;
+        expect(receiver.metrics().acked).to_equal(1)
+    })
+}
+
+/// Test closing a receiver that is not drained in time.
+pub fn data_close_graceful_timeout(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (_conn, sender, mut receiver, handler) = create_slow_rsc(state, "close-graceful", 500)?;
+    state.runtime.block_on(async move {
+        wait_connected(&sender, RETRY_10MS).await?;
+        wait_connected(&receiver, RETRY_10MS).await?;
+        if let Err(e) = sender.send_msg(b"1".to_vec()).await {
+            return Err(format!("send error: {}", e));
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        match receiver.close_graceful(Duration::from_millis(50)).await {
+            Err(e) => expect(e.to_string()).to_equal("timeout".to_string())?,
+            Ok(_) => return Err("close_graceful() should time out".to_string()),
+        }
+        expect(*handler.done_count.lock().unwrap()).to_equal(0)?;
+        expect(receiver.status()).to_equal(Status::Closed)
+    })
+}
+
+/// Test closing a connection with its queues.
+pub fn data_close_graceful_conn(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (mut conn, sender, receiver, handler) = create_slow_rsc(state, "close-graceful", 200)?;
+    state.runtime.block_on(async move {
+        wait_connected(&sender, RETRY_10MS).await?;
+        wait_connected(&receiver, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = sender.send_msg(i.to_string().into_bytes()).await {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        if let Err(e) = conn.close_graceful(Duration::from_secs(2)).await {
+            return Err(format!("close_graceful() error: {}", e));
+        }
+        expect(*handler.done_count.lock().unwrap()).to_equal(2)?;
+        expect(sender.status()).to_equal(Status::Closed)?;
+        expect(receiver.status()).to_equal(Status::Closed)?;
+        expect(conn.status()).to_equal(ConnStatus::Closed)
+    })
+}
+
+/// Test draining a connection without closing its queues.
+pub fn data_drain_conn(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let (conn, sender, receiver, handler) = create_slow_rsc(state, "drain", 200)?;
+    state.runtime.block_on(async move {
+        wait_connected(&sender, RETRY_10MS).await?;
+        wait_connected(&receiver, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = sender.send_msg(i.to_string().into_bytes()).await {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        if let Err(e) = conn.drain(Duration::from_secs(2)).await {
+            return Err(format!("drain() error: {}", e));
+        }
+        expect(*handler.done_count.lock().unwrap()).to_equal(2)?;
+        expect(receiver.is_paused()).to_equal(true)?;
+        expect(sender.status()).to_equal(Status::Connected)?;
+        expect(receiver.status()).to_equal(Status::Connected)?;
+        expect(conn.status()).to_equal(ConnStatus::Connected)?;
+        if let Err(e) = sender.send_msg(b"3".to_vec()).await {
+            return Err(format!("send 3 after drain() error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Create connected connections/queues for testing messages. The first queue is the sender.
+///
+/// All connections use a dedicated broker to isolate test cases.
+fn create_msg_rsc(
+    state: &mut TestState,
+    resources: &mut Resources,
+    opts: &MemoryQueueOptions,
+    receiver_count: usize,
+) -> Result<Vec<TestRecvMsgHandler>, String> {
+    let uri = format!("mem://{}", randomstring(8).to_lowercase());
+    let mut opts = opts.clone();
+    opts.reconnect_millis = 10;
+
+    let conn_opts = MemoryConnectionOptions { uri: uri.clone() };
+    let conn = match MemoryConnection::new(conn_opts) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    resources.conn = vec![Box::new(conn.clone())];
+    let mut send_opts = opts.clone();
+    send_opts.is_recv = receiver_count == 0;
+    let mut queue = match MemoryQueue::new(send_opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() send error: {}", e)),
+        Ok(q) => q,
+    };
+    queue.set_msg_handler(Arc::new(TestDummyMsgHandler {}));
+    state.queues = vec![Box::new(queue.clone())];
+    resources.queues = vec![Box::new(queue)];
+
+    let mut ret_handlers = vec![];
+    for _ in 0..receiver_count {
+        let conn_opts = MemoryConnectionOptions { uri: uri.clone() };
+        let conn = match MemoryConnection::new(conn_opts) {
+            Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+            Ok(conn) => conn,
+        };
+        state.conn.push(Box::new(conn.clone()));
+        resources.conn.push(Box::new(conn.clone()));
+
+        let mut recv_opts = opts.clone();
+        recv_opts.is_recv = true;
+        let mut queue = match MemoryQueue::new(recv_opts, &conn) {
+            Err(e) => return Err(format!("MemoryQueue::new() recv error: {}", e)),
+            Ok(q) => q,
+        };
+        state.queues.push(Box::new(queue.clone()));
+
+        let handler = TestRecvMsgHandler {
+            recv_messages: Arc::new(Mutex::new(vec![])),
+            recv_props: Arc::new(Mutex::new(vec![])),
+            use_nack: Arc::new(Mutex::new(false)),
+            nack_messages: Arc::new(Mutex::new(vec![])),
+        };
+        queue.set_msg_handler(Arc::new(handler.clone()));
+        resources.queues.push(Box::new(queue));
+        ret_handlers.push(handler);
+    }
+
+    for conn in resources.conn.iter_mut() {
+        if let Err(e) = conn.connect() {
+            return Err(format!("GmqConnection::connect() error: {}", e));
+        }
+    }
+    for queue in resources.queues.iter_mut() {
+        if let Err(e) = queue.connect() {
+            return Err(format!("GmqQueue::connect() error: {}", e));
+        }
+    }
+    Ok(ret_handlers)
+}
+
+fn data_outbox_inner(state: &mut TestState, outbox: &OutboxOptions) -> Result<(), String> {
+    let mut resources = Resources::default();
+
+    // Messages are kept in the directory for the next instance.
+    let queue = create_outbox_sender(&mut resources, outbox)?;
+    state.runtime.block_on(async {
+        for data in ["1", "2", "3"] {
+            if let Err(e) = queue.send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        expect(queue.outbox_len().await).to_equal(3)
+    })?;
+    let queue = create_outbox_sender(&mut resources, outbox)?;
+    state
+        .runtime
+        .block_on(async { expect(queue.outbox_len().await).to_equal(3) })?;
+
+    let handlers = connect_outbox_rsc(state, &mut resources)?;
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"4".to_vec()).await {
+            return Err(format!("send 4 error: {}", e));
+        }
+        let mut messages = wait_messages(&handlers, 4).await?;
+        messages.sort();
+        expect(messages).to_equal(vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+        ])?;
+        expect(resources.queues[0].outbox_len().await).to_equal(0)
+    })
+}
+
+fn data_outbox_limit_inner(state: &mut TestState, outbox: &OutboxOptions) -> Result<(), String> {
+    let mut resources = Resources::default();
+
+    let queue = create_outbox_sender(&mut resources, outbox)?;
+    state.runtime.block_on(async {
+        for data in ["1", "2", "3"] {
+            if let Err(e) = queue.send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        expect(queue.outbox_len().await).to_equal(2)
+    })?;
+
+    let handlers = connect_outbox_rsc(state, &mut resources)?;
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        let mut messages = wait_messages(&handlers, 2).await?;
+        messages.sort();
+        expect(messages).to_equal(vec!["2".to_string(), "3".to_string()])?;
+
+        // Expired messages are dropped.
+        if let Err(e) = resources.queues[0].close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"4".to_vec()).await {
+            return Err(format!("send 4 error: {}", e));
+        }
+        expect(resources.queues[0].outbox_len().await).to_equal(1)?;
+        time::sleep(Duration::from_millis(600)).await;
+        if let Err(e) = resources.queues[0].connect() {
+            return Err(format!("connect() error: {}", e));
+        }
+        wait_connected(resources.queues[0].as_ref(), RETRY_10MS).await?;
+        time::sleep(Duration::from_millis(100)).await;
+        expect(resources.queues[0].outbox_len().await).to_equal(0)?;
+        expect(handlers[0].recv_messages.lock().unwrap().len()).to_equal(2)
+    })
+}
+
+/// Create a connection with a connected sender and a receiver with [`TestSlowMsgHandler`].
+fn create_slow_rsc(
+    state: &mut TestState,
+    name: &str,
+    delay_millis: u64,
+) -> Result<
+    (
+        MemoryConnection,
+        MemoryQueue,
+        MemoryQueue,
+        TestSlowMsgHandler,
+    ),
+    String,
+> {
+    let uri = format!("mem://{}", randomstring(8).to_lowercase());
+    let mut conn = match MemoryConnection::new(MemoryConnectionOptions { uri }) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    state.conn.push(Box::new(conn.clone()));
+
+    let opts = MemoryQueueOptions {
+        name: name.to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut sender = match MemoryQueue::new(opts.clone(), &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() sender error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut recv_opts = opts;
+    recv_opts.is_recv = true;
+    let mut receiver = match MemoryQueue::new(recv_opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() receiver error: {}", e)),
+        Ok(q) => q,
+    };
+    let handler = TestSlowMsgHandler {
+        delay_millis,
+        done_count: Arc::new(Mutex::new(0)),
+    };
+    receiver.set_msg_handler(Arc::new(handler.clone()));
+    for queue in [&mut sender, &mut receiver] {
+        if let Err(e) = queue.connect() {
+            return Err(format!("GmqQueue::connect() error: {}", e));
+        }
+        state.queues.push(Box::new(queue.clone()));
+    }
+    Ok((conn, sender, receiver, handler))
+}
+
+/// Create an RPC client with the `rpc` request queue without connecting the client.
+fn create_rpc_client(state: &mut TestState) -> Result<(RpcClient, MemoryConnection), String> {
+    let uri = format!("mem://{}", randomstring(8).to_lowercase());
+    let mut conn = match MemoryConnection::new(MemoryConnectionOptions { uri }) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    state.conn.push(Box::new(conn.clone()));
+
+    let opts = MemoryQueueOptions {
+        name: "rpc".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let request = match Queue::new(QueueOptions::Memory(opts.clone(), &conn)) {
+        Err(e) => return Err(format!("Queue::new() request error: {}", e)),
+        Ok(q) => q,
+    };
+    let opts = MemoryQueueOptions {
+        name: format!("rpc.reply.{}", randomstring(8).to_lowercase()),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let reply = match Queue::new(QueueOptions::Memory(opts, &conn)) {
+        Err(e) => return Err(format!("Queue::new() reply error: {}", e)),
+        Ok(q) => q,
+    };
+    if RpcClient::new(reply.clone(), request.clone()).is_ok() {
+        return Err("RpcClient::new() with swapped queues should be error".to_string());
+    }
+    let client = match RpcClient::new(request, reply) {
+        Err(e) => return Err(format!("RpcClient::new() error: {}", e)),
+        Ok(client) => client,
+    };
+    state.queues.push(Box::new(client.request().clone()));
+    state.queues.push(Box::new(client.reply().clone()));
+    Ok((client, conn))
+}
+
+/// Create a sender with the outbox without connecting.
+fn create_outbox_sender(
+    resources: &mut Resources,
+    outbox: &OutboxOptions,
+) -> Result<MemoryQueue, String> {
+    let uri = format!("mem://{}", randomstring(8).to_lowercase());
+    let conn = match MemoryConnection::new(MemoryConnectionOptions { uri }) {
+        Err(e) => return Err(format!("MemoryConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    let opts = MemoryQueueOptions {
+        name: "outbox".to_string(),
+        reconnect_millis: 10,
+        outbox: Some(outbox.clone()),
+        ..Default::default()
+    };
+    let queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() send error: {}", e)),
+        Ok(q) => q,
+    };
+    resources.conn = vec![Box::new(conn)];
+    resources.queues = vec![Box::new(queue.clone())];
+    Ok(queue)
+}
+
+/// Create a receiver for the sender of [`create_outbox_sender`] and connect all of them.
+fn connect_outbox_rsc(
+    state: &mut TestState,
+    resources: &mut Resources,
+) -> Result<Vec<TestRecvMsgHandler>, String> {
+    let conn = match resources.conn.first() {
+        None => return Err("should have connection".to_string()),
+        Some(conn) => conn.as_ref().clone(),
+    };
+    let opts = MemoryQueueOptions {
+        name: "outbox".to_string(),
+        is_recv: true,
+        reconnect_millis: 10,
+        ..Default::default()
+    };
+    let mut queue = match MemoryQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("MemoryQueue::new() recv error: {}", e)),
+        Ok(q) => q,
+    };
+    let handler = TestRecvMsgHandler {
+        recv_messages: Arc::new(Mutex::new(vec![])),
+        recv_props: Arc::new(Mutex::new(vec![])),
+        use_nack: Arc::new(Mutex::new(false)),
+        nack_messages: Arc::new(Mutex::new(vec![])),
+    };
+    queue.set_msg_handler(Arc::new(handler.clone()));
+    resources.queues.push(Box::new(queue));
+
+    state.conn = vec![Box::new(conn)];
+    state.queues = vec![];
+    for conn in resources.conn.iter_mut() {
+        if let Err(e) = conn.connect() {
+            return Err(format!("GmqConnection::connect() error: {}", e));
+        }
+    }
+    for queue in resources.queues.iter_mut() {
+        if let Err(e) = queue.connect() {
+            return Err(format!("GmqQueue::connect() error: {}", e));
+        }
+        state.queues.push(Box::new(queue.as_ref().clone()));
+    }
+    Ok(vec![handler])
+}
+
+/// To get a random outbox directory in the temporary directory.
+fn outbox_path() -> String {
+    env::temp_dir()
+        .join(format!("general-mq-outbox-{}", randomstring(8)))
+        .to_string_lossy()
+        .to_string()
+}
+
+async fn wait_connected(queue: &dyn GmqQueue, mut retry: usize) -> Result<(), String> {
+    while retry > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+        if queue.status() == Status::Connected {
+            return Ok(());
+        }
+        retry = retry - 1;
+    }
+    Err("not connected".to_string())
+}
+
+/// To wait for `count` received messages of all handlers.
+async fn wait_messages(
+    handlers: &[TestRecvMsgHandler],
+    count: usize,
+) -> Result<Vec<String>, String> {
+    let mut retry = 150;
+    let mut len = 0;
+    while retry > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+        len = handlers
+            .iter()
+            .map(|h| h.recv_messages.lock().unwrap().len())
+            .sum();
+        if len >= count {
+            break;
+        }
+        retry = retry - 1;
+    }
+    if retry == 0 {
+        return Err(format!("received {}/{} messages", len, count));
+    }
+    let mut messages = vec![];
+    for handler in handlers.iter() {
+        let recv_messages = handler.recv_messages.lock().unwrap();
+        for i in 0..recv_messages.len() {
+            messages.push(get_message(&recv_messages, i)?);
+        }
+    }
+    Ok(messages)
+}
+
+fn get_message(messages: &[Vec<u8>], index: usize) -> Result<String, String> {
+    match messages.get(index) {
+        None => Err(format!("messages[{}] get none", index)),
+        Some(msg) => match str::from_utf8(msg) {
+            Err(e) => Err(format!("messages[{}] from UTF8 error: {}", index, e)),
+            Ok(msg) => Ok(msg.to_string()),
+        },
+    }
+}
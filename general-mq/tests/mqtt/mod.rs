@@ -11,15 +11,28 @@ pub fn suite() -> Suite<TestState> {
             context.it("new() with default", connection::new_default);
             context.it("new() with zero", connection::new_zero);
             context.it("new() with wrong opts", connection::new_wrong_opts);
+            context.it("new() with TLS", connection::new_tls);
+            context.it("new() with multiple hosts", connection::new_multi_host);
+            context.it("new() with MQTT 5", connection::new_v5);
 
             context.it("status()", connection::properties);
 
             context.it("connect() without handler", connection::connect_no_handler);
             context.it("connect() with handler", connection::connect_with_handler);
+            context.it("connect() with MQTT 5", connection::connect_v5);
             context.it(
                 "connect() after connect()",
                 connection::connect_after_connect,
             );
+            context.it(
+                "connect() with multiple hosts",
+                connection::connect_multi_host,
+            );
+            context.it(
+                "connect() with multiple hosts and a shared port",
+                connection::connect_multi_host_port,
+            );
+            context.it("connect() with reconnect limit", connection::connect_failed);
 
             context.it("remove_handler()", connection::remove_handler);
 
@@ -75,12 +88,19 @@ pub fn suite() -> Suite<TestState> {
             context.it("unicast 1 to 1", queue::data_unicast_1to1);
             context.it("unicast 1 to 3", queue::data_unicast_1to3);
 
+            context.it("unicast 1 to 3 with MQTT 5", queue::data_unicast_v5);
+
             context.it("broadcast 1 to 1", queue::data_broadcast_1to1);
             context.it("broadcast 1 to 3", queue::data_broadcast_1to3);
 
             context.it_skip("reliable", queue::data_reliable);
             context.it("best effort", queue::data_best_effort);
 
+            context.it("stream", queue::data_stream);
+            context.it("dead letter", queue::data_dead_letter);
+            context.it("pause", queue::data_pause);
+            context.it("max concurrency", queue::data_max_concurrency);
+
             context.after_each(clear_state);
         });
 
@@ -6,13 +6,15 @@ use std::{
 };
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use laboratory::{SpecContext, expect};
 use tokio::{task, time};
 
 use general_mq::{
-    MqttConnection, MqttConnectionOptions, MqttQueue, MqttQueueOptions, Queue, QueueOptions,
+    MqttConnection, MqttConnectionOptions, MqttQueue, MqttQueueOptions, MqttVersion, Queue,
+    QueueOptions,
     connection::GmqConnection,
-    queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
+    queue::{EventHandler, GmqQueue, Message, MessageHandler, NackMode, Status},
     randomstring,
 };
 
@@ -55,6 +57,13 @@ struct TestRecvMsgHandler {
     pub nack_errors: Arc<Mutex<Vec<String>>>,
 }
 
+#[derive(Clone)]
+struct TestConcurrencyHandler {
+    pub running: Arc<Mutex<usize>>,
+    pub max_running: Arc<Mutex<usize>>,
+    pub count: Arc<Mutex<usize>>,
+}
+
 const RETRY_10MS: usize = 100;
 
 #[async_trait]
@@ -137,6 +146,24 @@ impl MessageHandler for TestRecvMsgHandler {
     }
 }
 
+#[async_trait]
+impl MessageHandler for TestConcurrencyHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        {
+            let mut running = self.running.lock().unwrap();
+            *running += 1;
+            let mut max_running = self.max_running.lock().unwrap();
+            if *running > *max_running {
+                *max_running = *running;
+            }
+        }
+        time::sleep(Duration::from_millis(50)).await;
+        let _ = msg.ack().await;
+        *self.running.lock().unwrap() -= 1;
+        *self.count.lock().unwrap() += 1;
+    }
+}
+
 /// Test default options.
 pub fn new_default(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     let conn = match MqttConnection::new(MqttConnectionOptions::default()) {
@@ -1106,6 +1133,72 @@ pub fn data_unicast_1to3(context: &mut SpecContext<TestState>) -> Result<(), Str
     })
 }
 
+/// Send unicast data with MQTT 5 properties to 3 receivers with native shared subscriptions.
+pub fn data_unicast_v5(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MqttQueueOptions {
+        name: "name".to_string(),
+        message_expiry_secs: Some(60),
+        user_properties: vec![("key".to_string(), "value".to_string())],
+        ..Default::default()
+    };
+    let handlers = create_msg_rsc_with_version(state, &mut resources, &opts, 3, MqttVersion::V5)?;
+
+    for queue in resources.queues.iter() {
+        state
+            .runtime
+            .block_on(wait_connected(queue.as_ref(), RETRY_10MS))?;
+    }
+
+    let send_queue = match resources.queues.get_mut(0) {
+        None => return Err(format!("should have send queue")),
+        Some(q) => q,
+    };
+
+    let dataset = ["1", "2", "3", "4", "5", "6"];
+    for data in dataset {
+        let queue_clone = send_queue.clone();
+        task::spawn(async move {
+            let _ = queue_clone.send_msg(data.as_bytes().to_vec()).await;
+        });
+    }
+
+    state.runtime.block_on(async move {
+        let mut len = 0;
+        let mut retry = 150;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            len = 0;
+            for handler in handlers.iter() {
+                len += handler.recv_messages.lock().unwrap().len();
+            }
+            if len == 6 {
+                break;
+            }
+            retry = retry - 1;
+        }
+        if retry == 0 {
+            return Err(format!("received {}/6 messages", len));
+        }
+        let mut all_msg = vec![];
+        for handler in handlers.iter() {
+            let messages = handler.recv_messages.lock().unwrap();
+            let messages = messages.as_slice();
+            for i in 0..messages.len() {
+                let str = get_message(messages, i)?;
+                if all_msg.contains(&str) {
+                    return Err("duplicate message".to_string());
+                }
+                all_msg.push(str);
+            }
+        }
+        Ok(())
+    })
+}
+
 /// Send broadcast data to one receiver.
 pub fn data_broadcast_1to1(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let mut state = context.state.borrow_mut();
@@ -1473,6 +1566,233 @@ pub fn data_best_effort(context: &mut SpecContext<TestState>) -> Result<(), Stri
     })
 }
 
+/// Test `nack_with_mode()` with a dead-letter queue.
+pub fn data_dead_letter(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MqttQueueOptions {
+        name: "dead-letter".to_string(),
+        dead_letter: Some("dead-letter.dlq".to_string()),
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    let opts = MqttQueueOptions {
+        name: "dead-letter.dlq".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let mut dlq = match MqttQueue::new(opts, &resources.conn[0]) {
+        Err(e) => return Err(format!("MqttQueue::new() dead-letter error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut dlq_stream = dlq.msg_stream(1);
+    if let Err(e) = dlq.connect() {
+        return Err(format!("GmqQueue::connect() dead-letter error: {}", e));
+    }
+    state.queues.push(Box::new(dlq.clone()));
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        wait_connected(&dlq, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..2 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err("cannot receive messages".to_string()),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            let mode = match msg.payload() {
+                b"1" => NackMode::Drop,
+                _ => NackMode::DeadLetter,
+            };
+            if let Err(e) = msg.nack_with_mode(mode).await {
+                return Err(format!("nack {:?} error: {}", mode, e));
+            }
+        }
+
+        let msg = match time::timeout(Duration::from_millis(1500), dlq_stream.next()).await {
+            Err(_) => return Err("cannot receive the dead letter".to_string()),
+            Ok(None) => return Err("dead-letter stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"2".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack dead letter error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(200), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages again".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test receiving messages with a message stream.
+pub fn data_stream(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MqttQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=3 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        let mut messages = vec![];
+        while messages.len() < 3 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err(format!("received {}/3 messages", messages.len())),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            if let Err(e) = msg.ack().await {
+                return Err(format!("ack error: {}", e));
+            }
+            messages.push(String::from_utf8_lossy(msg.payload()).to_string());
+        }
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string(), "3".to_string()])
+    })
+}
+
+/// Test `pause()` and `resume()`.
+pub fn data_pause(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MqttQueueOptions {
+        name: "pause".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].pause().await {
+            return Err(format!("pause() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(true)?;
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(500), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages after pause()".to_string());
+        }
+
+        if let Err(e) = resources.queues[1].resume().await {
+            return Err(format!("resume() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(false)?;
+        // Messages are dropped by the broker without subscribers.
+        time::sleep(Duration::from_millis(500)).await;
+        if let Err(e) = resources.queues[0].send_msg(b"2".to_vec()).await {
+            return Err(format!("send 2 error: {}", e));
+        }
+        let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+            Err(_) => return Err("cannot receive messages after resume()".to_string()),
+            Ok(None) => return Err("stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"2".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test the `max_concurrency` option.
+pub fn data_max_concurrency(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = MqttQueueOptions {
+        name: "max-concurrency".to_string(),
+        reliable: true,
+        max_concurrency: 2,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = TestConcurrencyHandler {
+        running: Arc::new(Mutex::new(0)),
+        max_running: Arc::new(Mutex::new(0)),
+        count: Arc::new(Mutex::new(0)),
+    };
+    match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.set_msg_handler(Arc::new(handler.clone())),
+    }
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=10 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..RETRY_10MS * 3 {
+            if *handler.count.lock().unwrap() >= 10 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        expect(*handler.count.lock().unwrap()).to_equal(10)?;
+        expect(*handler.max_running.lock().unwrap()).to_equal(2)
+    })
+}
+
 /// Create connected (optional) connections/queues for testing connections.
 fn create_conn_rsc(
     state: &mut TestState,
@@ -1568,9 +1888,22 @@ fn create_msg_rsc(
     resources: &mut Resources,
     opts: &MqttQueueOptions,
     receiver_count: usize,
+) -> Result<Vec<TestRecvMsgHandler>, String> {
+    create_msg_rsc_with_version(state, resources, opts, receiver_count, MqttVersion::V3)
+}
+
+/// Create connected (optional) connections/queues of the specified MQTT version for testing
+/// messages.
+fn create_msg_rsc_with_version(
+    state: &mut TestState,
+    resources: &mut Resources,
+    opts: &MqttQueueOptions,
+    receiver_count: usize,
+    version: MqttVersion,
 ) -> Result<Vec<TestRecvMsgHandler>, String> {
     let conn_opts = MqttConnectionOptions {
         client_id: Some(format!("sender-{}", randomstring(8))),
+        version,
         ..Default::default()
     };
     let conn = match MqttConnection::new(conn_opts) {
@@ -1596,6 +1929,7 @@ fn create_msg_rsc(
                 true => Some(format!("receiver-{}", i)),
             },
             clean_session: !opts.reliable,
+            version,
             ..Default::default()
         };
         let conn = match MqttConnection::new(conn_opts) {
@@ -0,0 +1,323 @@
+use std::{
+    error::Error as StdError,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use laboratory::{SpecContext, expect};
+use tokio::time;
+
+use general_mq::{
+    ReconnectPolicy, RedisConnection, RedisConnectionOptions,
+    connection::{EventHandler, GmqConnection, Status},
+};
+
+use super::{STATE, TestState};
+
+struct TestConnectHandler {
+    pub recv_connected: Arc<Mutex<bool>>,
+}
+
+struct TestCloseHandler {
+    pub recv_closed: Arc<Mutex<bool>>,
+}
+
+struct TestFailedHandler {
+    pub recv_failed: Arc<Mutex<bool>>,
+}
+
+const RETRY_10MS: usize = 100;
+
+#[async_trait]
+impl EventHandler for TestConnectHandler {
+    async fn on_error(
+        &self,
+        _handler_id: String,
+        _conn: Arc<dyn GmqConnection>,
+        _err: Box<dyn StdError + Send + Sync>,
+    ) {
+    }
+
+    async fn on_status(&self, _handler_id: String, _conn: Arc<dyn GmqConnection>, status: Status) {
+        if status == Status::Connected {
+            *self.recv_connected.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestCloseHandler {
+    async fn on_error(
+        &self,
+        _handler_id: String,
+        _conn: Arc<dyn GmqConnection>,
+        _err: Box<dyn StdError + Send + Sync>,
+    ) {
+    }
+
+    async fn on_status(&self, _handler_id: String, _conn: Arc<dyn GmqConnection>, status: Status) {
+        if status == Status::Closed {
+            *self.recv_closed.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestFailedHandler {
+    async fn on_error(
+        &self,
+        _handler_id: String,
+        _conn: Arc<dyn GmqConnection>,
+        _err: Box<dyn StdError + Send + Sync>,
+    ) {
+    }
+
+    async fn on_status(&self, _handler_id: String, _conn: Arc<dyn GmqConnection>, status: Status) {
+        if status == Status::Failed {
+            *self.recv_failed.lock().unwrap() = true;
+        }
+    }
+}
+
+/// Test default options.
+pub fn new_default(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = RedisConnection::new(RedisConnectionOptions::default());
+    expect(conn.is_ok()).to_equal(true)?;
+
+    let opts = RedisConnectionOptions {
+        uri: "rediss://localhost".to_string(),
+        ..Default::default()
+    };
+    let conn = RedisConnection::new(opts);
+    expect(conn.is_ok()).to_equal(true)
+}
+
+/// Test options with wrong values.
+pub fn new_wrong_opts(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let opts = RedisConnectionOptions {
+        uri: "http://localhost".to_string(),
+        ..Default::default()
+    };
+    let conn = RedisConnection::new(opts);
+    expect(conn.is_err()).to_equal(true)?;
+
+    let opts = RedisConnectionOptions {
+        uri: "localhost".to_string(),
+        ..Default::default()
+    };
+    let conn = RedisConnection::new(opts);
+    expect(conn.is_err()).to_equal(true)
+}
+
+/// Test connection properties after `new()`.
+pub fn properties(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+
+    if conn.status() != Status::Closed {
+        return Err("connection status not Closed".to_string());
+    }
+
+    Ok(())
+}
+
+/// Test `connect()` with a handler.
+pub fn connect_with_handler(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let mut conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let conn: &mut dyn GmqConnection = &mut conn;
+
+    let handler = Arc::new(TestConnectHandler {
+        recv_connected: Arc::new(Mutex::new(false)),
+    });
+    let _ = conn.add_handler(handler.clone());
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+
+    state.runtime.block_on(async move {
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_connected.lock().unwrap() {
+                    return Ok(());
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not connected".to_string())
+    })
+}
+
+/// Test `connect()` for a conneted connection.
+pub fn connect_after_connect(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let mut conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let conn: &mut dyn GmqConnection = &mut conn;
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    expect(conn.connect().is_ok()).to_equal(true)
+}
+
+/// Test `connect()` with a reconnect policy that reaches the limit of attempts.
+pub fn connect_failed(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisConnectionOptions {
+        uri: "redis://localhost:1".to_string(),
+        reconnect_policy: Some(ReconnectPolicy {
+            initial_millis: 10,
+            max_millis: 20,
+            max_attempts: Some(3),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let mut conn = match RedisConnection::new(opts) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let conn: &mut dyn GmqConnection = &mut conn;
+
+    let handler = Arc::new(TestFailedHandler {
+        recv_failed: Arc::new(Mutex::new(false)),
+    });
+    let _ = conn.add_handler(handler.clone());
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+
+    state.runtime.block_on(async move {
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_failed.lock().unwrap() {
+                    break;
+                }
+            }
+            retry = retry - 1;
+        }
+        if retry == 0 {
+            return Err("not failed".to_string());
+        }
+        if conn.status() != Status::Failed {
+            return Err("status is not Failed".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test `close()`.
+pub fn close(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let mut conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let conn: &mut dyn GmqConnection = &mut conn;
+
+    let closed_handler = Arc::new(TestCloseHandler {
+        recv_closed: Arc::new(Mutex::new(false)),
+    });
+    let _ = conn.add_handler(closed_handler.clone());
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+
+    if let Err(e) = state.runtime.block_on(wait_connected(conn)) {
+        return Err(e.to_string());
+    }
+
+    state.runtime.block_on(async move {
+        if let Err(e) = conn.close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *closed_handler.recv_closed.lock().unwrap() {
+                    return Ok(());
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not closed".to_string())
+    })
+}
+
+/// Test `close()` for a closed connection.
+pub fn close_after_close(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let mut conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let conn: &mut dyn GmqConnection = &mut conn;
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+
+    if let Err(e) = state.runtime.block_on(wait_connected(conn)) {
+        return Err(e.to_string());
+    }
+
+    state.runtime.block_on(async move {
+        if let Err(e) = conn.close().await {
+            return Err(format!("close error: {}", e));
+        }
+        if conn.status() != Status::Closed {
+            return Err("status is not Closed".to_string());
+        }
+        if let Err(e) = conn.close().await {
+            return Err(format!("close again error: {}", e));
+        }
+        if conn.status() != Status::Closed {
+            return Err("status is not Closed".to_string());
+        }
+        Ok(())
+    })
+}
+
+async fn wait_connected(conn: &dyn GmqConnection) -> Result<(), String> {
+    let mut retry = RETRY_10MS;
+    while retry > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+        if conn.status() == Status::Connected {
+            return Ok(());
+        }
+        retry = retry - 1;
+    }
+    Err("not connected".to_string())
+}
@@ -0,0 +1,72 @@
+use laboratory::{Suite, describe};
+
+use crate::{STATE, TestState, clear_state, new_state};
+
+mod connection;
+mod queue;
+
+pub fn suite() -> Suite<TestState> {
+    describe("redis", |context| {
+        context.describe("RedisConnection", |context| {
+            context.it("new() with default", connection::new_default);
+            context.it("new() with wrong opts", connection::new_wrong_opts);
+
+            context.it("status()", connection::properties);
+
+            context.it("connect() with handler", connection::connect_with_handler);
+            context.it(
+                "connect() after connect()",
+                connection::connect_after_connect,
+            );
+            context.it("connect() with reconnect limit", connection::connect_failed);
+
+            context.it("close()", connection::close);
+            context.it("close() after close()", connection::close_after_close);
+
+            context.after_each(clear_state);
+        });
+
+        context.describe("RedisQueue", |context| {
+            context.it("new() with default", queue::new_default);
+            context.it("new() with wrong opts", queue::new_wrong_opts);
+
+            context.it("name(), is_recv(), status()", queue::properties);
+
+            context.it("connect() without handler", queue::connect_no_handler);
+            context.it("connect() with handler", queue::connect_with_handler);
+
+            context.it("close()", queue::close);
+
+            context.it("send_msg() with error conditions", queue::send_error);
+
+            context.after_each(clear_state);
+        });
+
+        context.describe("Scenarios", |context| {
+            context.it("reconnect", queue::reconnect);
+
+            context.it("unicast 1 to 1", queue::data_unicast_1to1);
+            context.it("unicast 1 to 3", queue::data_unicast_1to3);
+            context.it(
+                "unicast send before receiving",
+                queue::data_unicast_send_first,
+            );
+
+            context.it("broadcast 1 to 3", queue::data_broadcast_1to3);
+
+            context.it("reliable", queue::data_reliable);
+            context.it("nack", queue::data_nack);
+            context.it("best effort", queue::data_best_effort);
+            context.it("properties", queue::data_properties);
+            context.it("dead letter", queue::data_dead_letter);
+            context.it("pause", queue::data_pause);
+            context.it("prefetch", queue::data_prefetch);
+
+            context.after_each(clear_state);
+        });
+
+        context.before_all(|state| {
+            state.insert(STATE, new_state());
+        });
+    })
+}
@@ -0,0 +1,1042 @@
+use std::{
+    error::Error as StdError,
+    str,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use laboratory::{SpecContext, expect};
+use tokio::time;
+
+use general_mq::{
+    Queue, QueueOptions, RedisConnection, RedisConnectionOptions, RedisQueue, RedisQueueOptions,
+    connection::GmqConnection,
+    queue::{EventHandler, GmqQueue, Message, MessageHandler, MessageProperties, NackMode, Status},
+    randomstring,
+};
+
+use super::{STATE, TestState};
+
+#[derive(Default)]
+struct Resources {
+    pub conn: Vec<Box<RedisConnection>>,
+    pub queues: Vec<Box<RedisQueue>>,
+}
+
+struct TestDummyMsgHandler;
+
+struct TestConnectHandler {
+    pub recv_connected: Arc<Mutex<bool>>,
+    pub recv_queue_name: Arc<Mutex<String>>,
+}
+
+struct TestCloseHandler {
+    pub recv_closed: Arc<Mutex<bool>>,
+}
+
+struct TestReconnectHandler {
+    pub connected_count: Arc<Mutex<usize>>,
+    pub recv_connecting: Arc<Mutex<bool>>,
+}
+
+/// The handler that keeps messages in process until `release` is `true`.
+#[derive(Clone)]
+struct TestBlockMsgHandler {
+    pub recv_count: Arc<Mutex<usize>>,
+    pub release: Arc<Mutex<bool>>,
+}
+
+#[derive(Clone)]
+struct TestRecvMsgHandler {
+    pub recv_messages: Arc<Mutex<Vec<Vec<u8>>>>,
+    pub recv_props: Arc<Mutex<Vec<(Vec<u8>, MessageProperties)>>>,
+    pub use_nack: Arc<Mutex<bool>>,
+    pub nack_messages: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+const RETRY_10MS: usize = 100;
+
+#[async_trait]
+impl MessageHandler for TestDummyMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, _msg: Box<dyn Message>) {}
+}
+
+#[async_trait]
+impl EventHandler for TestConnectHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Connected {
+            *self.recv_connected.lock().unwrap() = true;
+            *self.recv_queue_name.lock().unwrap() = queue.name().to_string();
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestCloseHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Closed {
+            *self.recv_closed.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for TestReconnectHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, status: Status) {
+        if status == Status::Connected {
+            let mut mutex = self.connected_count.lock().unwrap();
+            *mutex += 1;
+        } else if status == Status::Connecting {
+            *self.recv_connecting.lock().unwrap() = true;
+        }
+    }
+}
+
+#[async_trait]
+impl MessageHandler for TestBlockMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        *self.recv_count.lock().unwrap() += 1;
+        while !*self.release.lock().unwrap() {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        let _ = msg.ack().await;
+    }
+}
+
+#[async_trait]
+impl MessageHandler for TestRecvMsgHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        let use_nack;
+        {
+            use_nack = *self.use_nack.lock().unwrap();
+        }
+        if use_nack {
+            {
+                *self.use_nack.lock().unwrap() = false;
+            }
+            let data = msg.payload().to_vec();
+            self.nack_messages.lock().unwrap().push(data);
+            let _ = msg.nack().await;
+        } else {
+            let data = msg.payload().to_vec();
+            self.recv_props
+                .lock()
+                .unwrap()
+                .push((data.clone(), msg.properties().clone()));
+            self.recv_messages.lock().unwrap().push(data);
+            let _ = msg.ack().await;
+        }
+    }
+}
+
+/// Test default options.
+pub fn new_default(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        reconnect_millis: 0,
+        ..Default::default()
+    };
+    let queue = RedisQueue::new(opts, &conn);
+    expect(queue.is_ok()).to_equal(true)?;
+
+    let opts = QueueOptions::Redis(
+        RedisQueueOptions {
+            name: "name".to_string(),
+            ..Default::default()
+        },
+        &conn,
+    );
+    let queue = Queue::new(opts);
+    expect(queue.is_ok()).to_equal(true)
+}
+
+/// Test options with wrong values.
+pub fn new_wrong_opts(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+
+    let queue = RedisQueue::new(RedisQueueOptions::default(), &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = RedisQueueOptions {
+        name: "A@".to_string(),
+        ..Default::default()
+    };
+    let queue = RedisQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)?;
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        prefetch: 0,
+        ..Default::default()
+    };
+    let queue = RedisQueue::new(opts, &conn);
+    expect(queue.is_err()).to_equal(true)
+}
+
+/// Test queue properties after `new()`.
+pub fn properties(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+
+    let opts = RedisQueueOptions {
+        name: "name-send".to_string(),
+        is_recv: false,
+        ..Default::default()
+    };
+    let queue = match RedisQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.name()).to_equal("name-send")?;
+    expect(queue.is_recv()).to_equal(false)?;
+    expect(queue.status()).to_equal(Status::Closed)?;
+
+    let opts = RedisQueueOptions {
+        name: "name-recv".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let queue = match RedisQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.name()).to_equal("name-recv")?;
+    expect(queue.is_recv()).to_equal(true)?;
+    expect(queue.status()).to_equal(Status::Closed)
+}
+
+/// Test `connect()` without handlers.
+pub fn connect_no_handler(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let mut queue = match RedisQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    expect(queue.connect().is_err()).to_equal(true)
+}
+
+/// Test `connect()` with a handler.
+pub fn connect_with_handler(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let handler = Arc::new(TestConnectHandler {
+        recv_connected: Arc::new(Mutex::new(false)),
+        recv_queue_name: Arc::new(Mutex::new("".to_string())),
+    });
+    let mut conn = match RedisConnection::new(RedisConnectionOptions::default()) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        is_recv: true,
+        ..Default::default()
+    };
+    let mut queue = match RedisQueue::new(opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() error: {}", e)),
+        Ok(q) => q,
+    };
+    state.queues = vec![Box::new(queue.clone())];
+    queue.set_handler(handler.clone());
+    queue.set_msg_handler(Arc::new(TestDummyMsgHandler {}));
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    if let Err(e) = queue.connect() {
+        return Err(format!("GmqQueue::connect() error: {}", e));
+    }
+
+    state.runtime.block_on(async move {
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_connected.lock().unwrap() {
+                    return expect(handler.recv_queue_name.lock().unwrap().as_str())
+                        .to_equal("name");
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not connected".to_string())
+    })
+}
+
+/// Test `close()`.
+pub fn close(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = Arc::new(TestCloseHandler {
+        recv_closed: Arc::new(Mutex::new(false)),
+    });
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get_mut(1) {
+            None => return Err("should have a queue".to_string()),
+            Some(q) => q,
+        };
+        queue.set_handler(handler.clone());
+        wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() again error: {}", e));
+        }
+        expect(queue.status()).to_equal(Status::Closed)?;
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            {
+                if *handler.recv_closed.lock().unwrap() {
+                    return Ok(());
+                }
+            }
+            retry = retry - 1;
+        }
+        Err("not closed".to_string())
+    })
+}
+
+/// Test `send_msg()` with error conditions.
+pub fn send_error(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get(1) {
+            None => return Err("should have a receiver queue".to_string()),
+            Some(q) => q,
+        };
+        if queue.send_msg(vec![]).await.is_ok() {
+            return Err("receiver send_msg() should error".to_string());
+        }
+        let queue = match resources.queues.get_mut(0) {
+            None => return Err("should have a sender queue".to_string()),
+            Some(q) => q,
+        };
+        if let Err(e) = queue.close().await {
+            return Err(format!("close() error: {}", e));
+        }
+        if queue.send_msg(vec![]).await.is_ok() {
+            return Err("closed send_msg() should error".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test reconnect by closing/connecting the associated connection.
+pub fn reconnect(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = Arc::new(TestReconnectHandler {
+        connected_count: Arc::new(Mutex::new(0)),
+        recv_connecting: Arc::new(Mutex::new(false)),
+    });
+
+    state.runtime.block_on(async move {
+        let queue = match resources.queues.get_mut(1) {
+            None => return Err("should have a queue".to_string()),
+            Some(q) => q,
+        };
+        queue.set_handler(handler.clone());
+        wait_connected(queue.as_ref(), RETRY_10MS).await?;
+
+        let conn = match resources.conn.get_mut(1) {
+            None => return Err("should have a connection".to_string()),
+            Some(conn) => conn,
+        };
+        if let Err(e) = conn.close().await {
+            return Err(format!("close connection error: {}", e));
+        }
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            if *handler.recv_connecting.lock().unwrap() {
+                break;
+            }
+            retry = retry - 1;
+        }
+        if retry == 0 {
+            return Err("no connecting status".to_string());
+        }
+        if let Err(e) = conn.connect() {
+            return Err(format!("connect connection error: {}", e));
+        }
+        let mut retry = RETRY_10MS;
+        while retry > 0 {
+            time::sleep(Duration::from_millis(10)).await;
+            if *handler.connected_count.lock().unwrap() > 0 {
+                return Ok(());
+            }
+            retry = retry - 1;
+        }
+        Err("not reconnected".to_string())
+    })
+}
+
+/// Send unicast data to one receiver.
+pub fn data_unicast_1to1(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let mut messages = wait_messages(&handlers, 2).await?;
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])
+    })
+}
+
+/// Send unicast data to 3 receivers.
+pub fn data_unicast_1to3(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 3)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2", "3", "4", "5", "6"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let mut messages = wait_messages(&handlers, 6).await?;
+        messages.sort();
+        expect(messages).to_equal(vec![
+            "1".to_string(),
+            "2".to_string(),
+            "3".to_string(),
+            "4".to_string(),
+            "5".to_string(),
+            "6".to_string(),
+        ])
+    })
+}
+
+/// Send broadcast data to 3 receivers.
+/// Test that the unicast receiver receives messages that are sent before it connects.
+pub fn data_unicast_send_first(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: format!("send-first-{}", randomstring(8).to_lowercase()),
+        reliable: true,
+        reconnect_millis: 10,
+        ..Default::default()
+    };
+    let conn_opts = RedisConnectionOptions {
+        reconnect_millis: 10,
+        ..Default::default()
+    };
+    let mut conn = match RedisConnection::new(conn_opts) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn.push(Box::new(conn.clone()));
+    let mut send_queue = match RedisQueue::new(opts.clone(), &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() send error: {}", e)),
+        Ok(q) => q,
+    };
+    state.queues.push(Box::new(send_queue.clone()));
+    let mut recv_opts = opts.clone();
+    recv_opts.is_recv = true;
+    let mut recv_queue = match RedisQueue::new(recv_opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() recv error: {}", e)),
+        Ok(q) => q,
+    };
+    state.queues.push(Box::new(recv_queue.clone()));
+    let handler = TestRecvMsgHandler {
+        recv_messages: Arc::new(Mutex::new(vec![])),
+        recv_props: Arc::new(Mutex::new(vec![])),
+        use_nack: Arc::new(Mutex::new(false)),
+        nack_messages: Arc::new(Mutex::new(vec![])),
+    };
+    recv_queue.set_msg_handler(Arc::new(handler.clone()));
+
+    if let Err(e) = conn.connect() {
+        return Err(format!("GmqConnection::connect() error: {}", e));
+    }
+    if let Err(e) = send_queue.connect() {
+        return Err(format!("GmqQueue::connect() send error: {}", e));
+    }
+    state.runtime.block_on(async move {
+        wait_connected(&send_queue, RETRY_10MS).await?;
+        if let Err(e) = send_queue.send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if let Err(e) = recv_queue.connect() {
+            return Err(format!("GmqQueue::connect() recv error: {}", e));
+        }
+        wait_connected(&recv_queue, RETRY_10MS).await?;
+        let messages = wait_messages(&[handler], 1).await?;
+        expect(messages).to_equal(vec!["1".to_string()])
+    })
+}
+
+pub fn data_broadcast_1to3(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        broadcast: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 3)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for data in ["1", "2"] {
+            if let Err(e) = resources.queues[0].send_msg(data.as_bytes().to_vec()).await {
+                return Err(format!("send {} error: {}", data, e));
+            }
+        }
+        let messages = wait_messages(&handlers, 6).await?;
+        expect(messages.len()).to_equal(6)?;
+        for handler in handlers.iter() {
+            let messages = handler.recv_messages.lock().unwrap();
+            let mut received = vec![get_message(&messages, 0)?, get_message(&messages, 1)?];
+            received.sort();
+            expect(received).to_equal(vec!["1".to_string(), "2".to_string()])?;
+        }
+        Ok(())
+    })
+}
+
+/// Send reliable data to a closed receiver then it MUST receive after connecting.
+pub fn data_reliable(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        wait_messages(&handlers, 1).await?;
+
+        if let Err(e) = resources.queues[1].close().await {
+            return Err(format!("close recv error: {}", e));
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"2".to_vec()).await {
+            return Err(format!("send 2 error: {}", e));
+        }
+        if let Err(e) = resources.queues[1].connect() {
+            return Err(format!("connect recv error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 2).await?;
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])
+    })
+}
+
+/// Reject reliable data then it MUST be claimed and delivered again.
+pub fn data_nack(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        reliable: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    {
+        *handlers[0].use_nack.lock().unwrap() = true;
+    }
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 1).await?;
+        expect(messages).to_equal(vec!["1".to_string()])?;
+        let nack_messages = handlers[0].nack_messages.lock().unwrap();
+        expect(get_message(&nack_messages, 0)?).to_equal("1".to_string())
+    })
+}
+
+/// Send unreliable data to a broadcast queue without receivers then it MUST NOT be received.
+pub fn data_best_effort(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        broadcast: true,
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].close().await {
+            return Err(format!("close recv error: {}", e));
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if let Err(e) = resources.queues[1].connect() {
+            return Err(format!("connect recv error: {}", e));
+        }
+        wait_connected(resources.queues[1].as_ref(), RETRY_10MS).await?;
+        if let Err(e) = resources.queues[0].send_msg(b"2".to_vec()).await {
+            return Err(format!("send 2 error: {}", e));
+        }
+        let messages = wait_messages(&handlers, 1).await?;
+        expect(messages).to_equal(vec!["2".to_string()])
+    })
+}
+
+/// Send data with properties.
+pub fn data_properties(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let opts = RedisQueueOptions {
+        name: "name".to_string(),
+        ..Default::default()
+    };
+    let mut resources = Resources::default();
+    let handlers = create_msg_rsc(state, &mut resources, &opts, 1)?;
+
+    let mut props = MessageProperties {
+        content_type: Some("application/json".to_string()),
+        correlation_id: Some("correlation".to_string()),
+        timestamp: Some(1700000000),
+        ..Default::default()
+    };
+    props
+        .headers
+        .insert("dataId".to_string(), "data-id".to_string());
+    let expect_props = props.clone();
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if let Err(e) = resources.queues[0]
+            .send_msg_with_props(b"2".to_vec(), props)
+            .await
+        {
+            return Err(format!("send 2 error: {}", e));
+        }
+        let mut messages = wait_messages(&handlers, 2).await?;
+        messages.sort();
+        expect(messages).to_equal(vec!["1".to_string(), "2".to_string()])?;
+        for (data, props) in handlers[0].recv_props.lock().unwrap().iter() {
+            match data.as_slice() {
+                b"1" => expect(props).to_equal(&MessageProperties::default())?,
+                _ => expect(props).to_equal(&expect_props)?,
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Test `nack_with_mode()` with a dead-letter queue.
+pub fn data_dead_letter(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = RedisQueueOptions {
+        name: "dead-letter".to_string(),
+        reliable: true,
+        dead_letter: Some("dead-letter.dlq".to_string()),
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    let opts = RedisQueueOptions {
+        name: "dead-letter.dlq".to_string(),
+        is_recv: true,
+        reliable: true,
+        ..Default::default()
+    };
+    let mut dlq = match RedisQueue::new(opts, &resources.conn[0]) {
+        Err(e) => return Err(format!("RedisQueue::new() dead-letter error: {}", e)),
+        Ok(q) => q,
+    };
+    let mut dlq_stream = dlq.msg_stream(1);
+    if let Err(e) = dlq.connect() {
+        return Err(format!("GmqQueue::connect() dead-letter error: {}", e));
+    }
+    state.queues.push(Box::new(dlq.clone()));
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        wait_connected(&dlq, RETRY_10MS).await?;
+        for i in 1..=2 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        for _ in 0..2 {
+            let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+                Err(_) => return Err("cannot receive messages".to_string()),
+                Ok(None) => return Err("stream ended".to_string()),
+                Ok(Some(msg)) => msg,
+            };
+            let mode = match msg.payload() {
+                b"1" => NackMode::Drop,
+                _ => NackMode::DeadLetter,
+            };
+            if let Err(e) = msg.nack_with_mode(mode).await {
+                return Err(format!("nack {:?} error: {}", mode, e));
+            }
+        }
+
+        let msg = match time::timeout(Duration::from_millis(1500), dlq_stream.next()).await {
+            Err(_) => return Err("cannot receive the dead letter".to_string()),
+            Ok(None) => return Err("dead-letter stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"2".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack dead letter error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(200), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages again".to_string());
+        }
+        Ok(())
+    })
+}
+
+/// Test `pause()` and `resume()`.
+pub fn data_pause(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = RedisQueueOptions {
+        name: "pause".to_string(),
+        reliable: true,
+        reconnect_millis: 100,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let mut stream = match resources.queues.get_mut(1) {
+        None => return Err("should have recv queue".to_string()),
+        Some(q) => q.msg_stream(1),
+    };
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        if let Err(e) = resources.queues[1].pause().await {
+            return Err(format!("pause() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(true)?;
+        // Wait for the current blocking read.
+        time::sleep(Duration::from_millis(300)).await;
+        if let Err(e) = resources.queues[0].send_msg(b"1".to_vec()).await {
+            return Err(format!("send 1 error: {}", e));
+        }
+        if time::timeout(Duration::from_millis(500), stream.next())
+            .await
+            .is_ok()
+        {
+            return Err("should not receive messages after pause()".to_string());
+        }
+
+        if let Err(e) = resources.queues[1].resume().await {
+            return Err(format!("resume() error: {}", e));
+        }
+        expect(resources.queues[1].is_paused()).to_equal(false)?;
+        let msg = match time::timeout(Duration::from_millis(1500), stream.next()).await {
+            Err(_) => return Err("cannot receive messages after resume()".to_string()),
+            Ok(None) => return Err("stream ended".to_string()),
+            Ok(Some(msg)) => msg,
+        };
+        expect(msg.payload()).to_equal(b"1".as_slice())?;
+        if let Err(e) = msg.ack().await {
+            return Err(format!("ack error: {}", e));
+        }
+        Ok(())
+    })
+}
+
+/// Test that receivers do not read more than `prefetch` messages in process.
+pub fn data_prefetch(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let mut resources = Resources::default();
+
+    let opts = RedisQueueOptions {
+        name: "prefetch".to_string(),
+        reliable: true,
+        prefetch: 2,
+        ..Default::default()
+    };
+    let _ = create_msg_rsc(state, &mut resources, &opts, 1)?;
+    let handler = TestBlockMsgHandler {
+        recv_count: Arc::new(Mutex::new(0)),
+        release: Arc::new(Mutex::new(false)),
+    };
+    resources.queues[1].set_msg_handler(Arc::new(handler.clone()));
+
+    state.runtime.block_on(async move {
+        for queue in resources.queues.iter() {
+            wait_connected(queue.as_ref(), RETRY_10MS).await?;
+        }
+        for i in 1..=5 {
+            if let Err(e) = resources.queues[0]
+                .send_msg(i.to_string().into_bytes())
+                .await
+            {
+                return Err(format!("send {} error: {}", i, e));
+            }
+        }
+        time::sleep(Duration::from_millis(300)).await;
+        expect(*handler.recv_count.lock().unwrap()).to_equal(2)?;
+
+        *handler.release.lock().unwrap() = true;
+        for _ in 0..RETRY_10MS {
+            if *handler.recv_count.lock().unwrap() >= 5 {
+                break;
+            }
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        expect(*handler.recv_count.lock().unwrap()).to_equal(5)
+    })
+}
+
+/// Create connected connections/queues for testing messages. The first queue is the sender.
+///
+/// All queues use a dedicated stream to isolate test cases.
+fn create_msg_rsc(
+    state: &mut TestState,
+    resources: &mut Resources,
+    opts: &RedisQueueOptions,
+    receiver_count: usize,
+) -> Result<Vec<TestRecvMsgHandler>, String> {
+    let mut opts = opts.clone();
+    opts.name = format!("{}-{}", opts.name, randomstring(8).to_lowercase());
+    opts.reconnect_millis = 10;
+
+    let conn_opts = RedisConnectionOptions {
+        reconnect_millis: 10,
+        ..Default::default()
+    };
+    let conn = match RedisConnection::new(conn_opts) {
+        Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+        Ok(conn) => conn,
+    };
+    state.conn = vec![Box::new(conn.clone())];
+    resources.conn = vec![Box::new(conn.clone())];
+    let mut send_opts = opts.clone();
+    send_opts.is_recv = receiver_count == 0;
+    let mut queue = match RedisQueue::new(send_opts, &conn) {
+        Err(e) => return Err(format!("RedisQueue::new() send error: {}", e)),
+        Ok(q) => q,
+    };
+    queue.set_msg_handler(Arc::new(TestDummyMsgHandler {}));
+    state.queues = vec![Box::new(queue.clone())];
+    resources.queues = vec![Box::new(queue)];
+
+    let mut ret_handlers = vec![];
+    for _ in 0..receiver_count {
+        let conn_opts = RedisConnectionOptions {
+            reconnect_millis: 10,
+            ..Default::default()
+        };
+        let conn = match RedisConnection::new(conn_opts) {
+            Err(e) => return Err(format!("RedisConnection::new() error: {}", e)),
+            Ok(conn) => conn,
+        };
+        state.conn.push(Box::new(conn.clone()));
+        resources.conn.push(Box::new(conn.clone()));
+
+        let mut recv_opts = opts.clone();
+        recv_opts.is_recv = true;
+        let mut queue = match RedisQueue::new(recv_opts, &conn) {
+            Err(e) => return Err(format!("RedisQueue::new() recv error: {}", e)),
+            Ok(q) => q,
+        };
+        state.queues.push(Box::new(queue.clone()));
+
+        let handler = TestRecvMsgHandler {
+            recv_messages: Arc::new(Mutex::new(vec![])),
+            recv_props: Arc::new(Mutex::new(vec![])),
+            use_nack: Arc::new(Mutex::new(false)),
+            nack_messages: Arc::new(Mutex::new(vec![])),
+        };
+        queue.set_msg_handler(Arc::new(handler.clone()));
+        resources.queues.push(Box::new(queue));
+        ret_handlers.push(handler);
+    }
+
+    for conn in resources.conn.iter_mut() {
+        if let Err(e) = conn.connect() {
+            return Err(format!("GmqConnection::connect() error: {}", e));
+        }
+    }
+    for queue in resources.queues.iter_mut() {
+        if let Err(e) = queue.connect() {
+            return Err(format!("GmqQueue::connect() error: {}", e));
+        }
+    }
+    Ok(ret_handlers)
+}
+
+async fn wait_connected(queue: &dyn GmqQueue, mut retry: usize) -> Result<(), String> {
+    while retry > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+        if queue.status() == Status::Connected {
+            return Ok(());
+        }
+        retry = retry - 1;
+    }
+    Err("not connected".to_string())
+}
+
+/// To wait for `count` received messages of all handlers.
+async fn wait_messages(
+    handlers: &[TestRecvMsgHandler],
+    count: usize,
+) -> Result<Vec<String>, String> {
+    let mut retry = 150;
+    let mut len = 0;
+    while retry > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+        len = handlers
+            .iter()
+            .map(|h| h.recv_messages.lock().unwrap().len())
+            .sum();
+        if len >= count {
+            break;
+        }
+        retry = retry - 1;
+    }
+    if retry == 0 {
+        return Err(format!("received {}/{} messages", len, count));
+    }
+    let mut messages = vec![];
+    for handler in handlers.iter() {
+        let recv_messages = handler.recv_messages.lock().unwrap();
+        for i in 0..recv_messages.len() {
+            messages.push(get_message(&recv_messages, i)?);
+        }
+    }
+    Ok(messages)
+}
+
+fn get_message(messages: &[Vec<u8>], index: usize) -> Result<String, String> {
+    match messages.get(index) {
+        None => Err(format!("messages[{}] get none", index)),
+        Some(msg) => match str::from_utf8(msg) {
+            Err(e) => Err(format!("messages[{}] from UTF8 error: {}", index, e)),
+            Ok(msg) => Ok(msg.to_string()),
+        },
+    }
+}
@@ -0,0 +1,523 @@
+//! Payload codecs of device profiles.
+//!
+//! A codec is a JSON object with the following optional fields:
+//!
+//! - `fields`: the binary layout. Each item has:
+//!     - `name`: the key of the decoded object.
+//!     - `offset`: the byte offset of the value. The maximum value is 1024.
+//!     - `type`: one of `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`, `i64`, `f32`, `f64`.
+//!     - `endian`: `big` (default) or `little`.
+//!     - `scale`: the decoded value is `raw * scale`. The encoded value is `round(value / scale)`.
+//! - `script`: a [Rhai](https://rhai.rs) script that may define the following functions:
+//!     - `decode(bytes, object)`: returns the decoded object. `object` is decoded by `fields`.
+//!     - `encode(object)`: returns a blob or an array of bytes. `fields` is not used if this
+//!       function is defined.
+//!
+//! Scripts run in a sandbox without module imports and with limited operations and sizes.
+//!
+//! For example:
+//! ```json
+//! {
+//!     "fields": [
+//!         { "name": "temperature", "offset": 0, "type": "i16", "scale": 0.01 },
+//!         { "name": "battery", "offset": 2, "type": "u8" }
+//!     ],
+//!     "script": "fn decode(bytes, object) { object.low = object.battery < 20; object }"
+//! }
+//! ```
+
+use std::{
+    error::Error as StdError,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use log::warn;
+use lru::LruCache;
+use rhai::{AST, Array, Blob, Dynamic, Engine, Scope, module_resolvers::DummyModuleResolver};
+use serde_json::{Map, Number, Value};
+
+use crate::models::{Model, profile::QueryCond};
+
+/// The parsed payload codec.
+pub struct Codec {
+    fields: Vec<Field>,
+    script: Option<Script>,
+}
+
+/// The cache of codecs and offline timeouts of profiles.
+///
+/// Items expire in a short time so that other broker instances apply profile changes without
+/// control channels.
+pub struct CodecCache {
+    model: Arc<dyn Model>,
+    items: Mutex<LruCache<String, CacheItem>>,
+}
+
+/// One field of the binary layout.
+struct Field {
+    name: String,
+    offset: usize,
+    field_type: FieldType,
+    little_endian: bool,
+    scale: Option<f64>,
+}
+
+#[derive(Clone, Copy)]
+enum FieldType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+}
+
+/// The raw value before scaling.
+enum RawValue {
+    Int(i128),
+    Float(f64),
+}
+
+/// The compiled script.
+struct Script {
+    engine: Engine,
+    ast: AST,
+    has_decode: bool,
+    has_encode: bool,
+}
+
+#[derive(Clone)]
+struct CacheItem {
+    codec: Option<Arc<Codec>>,
+    offline_timeout: u64,
+    expired_at: Instant,
+}
+
+const CACHE_SIZE: usize = 1_000;
+const FIELD_MAX_OFFSET: u64 = 1024;
+const CACHE_TTL: Duration = Duration::from_secs(60);
+const SCRIPT_MAX_OPERATIONS: u64 = 100_000;
+const SCRIPT_MAX_CALL_LEVELS: usize = 16;
+const SCRIPT_MAX_EXPR_DEPTH: usize = 64;
+const SCRIPT_MAX_FN_EXPR_DEPTH: usize = 32;
+const SCRIPT_MAX_STRING_SIZE: usize = 4096;
+const SCRIPT_MAX_ARRAY_SIZE: usize = 4096;
+const SCRIPT_MAX_MAP_SIZE: usize = 256;
+const SCRIPT_FN_DECODE: &'static str = "decode";
+const SCRIPT_FN_ENCODE: &'static str = "encode";
+
+impl Codec {
+    /// To parse and validate the codec of the profile.
+    pub fn new(codec: &Map<String, Value>) -> Result<Self, String> {
+        let fields = match codec.get("fields") {
+            None => vec![],
+            Some(Value::Array(fields)) => {
+                let mut list = vec![];
+                for (i, field) in fields.iter().enumerate() {
+                    list.push(Field::new(i, field)?);
+                }
+                list
+            }
+            Some(_) => return Err("invalid `codec.fields`".to_string()),
+        };
+        let script = match codec.get("script") {
+            None => None,
+            Some(Value::String(script)) => match script.len() {
+                0 => None,
+                _ => Some(Script::new(script.as_str())?),
+            },
+            Some(_) => return Err("invalid `codec.script`".to_string()),
+        };
+        Ok(Codec { fields, script })
+    }
+
+    /// To decode the hex string payload. Returns `None` if the codec does not decode payloads.
+    pub fn decode(&self, data: &str) -> Result<Option<Map<String, Value>>, String> {
+        let has_script = self.script.as_ref().is_some_and(|s| s.has_decode);
+        if self.fields.len() == 0 && !has_script {
+            return Ok(None);
+        }
+        let bytes = match hex::decode(data) {
+            Err(e) => return Err(format!("invalid data: {}", e)),
+            Ok(bytes) => bytes,
+        };
+        let mut object = Map::new();
+        for field in self.fields.iter() {
+            object.insert(field.name.clone(), field.read(bytes.as_slice())?);
+        }
+        match self.script.as_ref() {
+            Some(script) if script.has_decode => Ok(Some(script.decode(bytes, object)?)),
+            _ => Ok(Some(object)),
+        }
+    }
+
+    /// To encode the object into the hex string payload. Returns `None` if the codec does not
+    /// encode objects.
+    pub fn encode(&self, object: &Map<String, Value>) -> Result<Option<String>, String> {
+        if let Some(script) = self.script.as_ref() {
+            if script.has_encode {
+                return Ok(Some(hex::encode(script.encode(object)?)));
+            }
+        }
+        if self.fields.len() == 0 {
+            return Ok(None);
+        }
+        let size = self
+            .fields
+            .iter()
+            .map(|f| f.offset + f.field_type.size())
+            .max()
+            .unwrap_or(0);
+        let mut bytes = vec![0; size];
+        for field in self.fields.iter() {
+            match object.get(field.name.as_str()) {
+                None => return Err(format!("missing `{}`", field.name)),
+                Some(value) => field.write(bytes.as_mut_slice(), value)?,
+            }
+        }
+        Ok(Some(hex::encode(bytes)))
+    }
+}
+
+impl CodecCache {
+    /// To create the cache instance.
+    pub fn new(model: Arc<dyn Model>) -> Self {
+        let size = unsafe { NonZeroUsize::new_unchecked(CACHE_SIZE) };
+        CodecCache {
+            model,
+            items: Mutex::new(LruCache::new(size)),
+        }
+    }
+
+    /// To get the codec of the profile of the unit. Returns `None` if the profile does not exist
+    /// or it has an invalid codec.
+    pub async fn get(
+        &self,
+        unit_id: &str,
+        code: &str,
+    ) -> Result<Option<Arc<Codec>>, Box<dyn StdError>> {
+        match self.get_item(unit_id, code).await? {
+            None => Ok(None),
+            Some(item) => Ok(item.codec),
+        }
+    }
+
+    /// To get the offline timeout in seconds of the profile of the unit. Returns `0` if the
+    /// profile does not exist.
+    pub async fn get_offline_timeout(
+        &self,
+        unit_id: &str,
+        code: &str,
+    ) -> Result<u64, Box<dyn StdError>> {
+        match self.get_item(unit_id, code).await? {
+            None => Ok(0),
+            Some(item) => Ok(item.offline_timeout),
+        }
+    }
+
+    /// To remove the codec of the profile of the unit.
+    pub fn del(&self, unit_id: &str, code: &str) {
+        let key = format!("{}.{}", unit_id, code);
+        self.items.lock().unwrap().pop(&key);
+    }
+
+    /// To remove all codecs.
+    pub fn clear(&self) {
+        self.items.lock().unwrap().clear();
+    }
+
+    /// To get the cached item of the profile of the unit or to load it from the database.
+    async fn get_item(
+        &self,
+        unit_id: &str,
+        code: &str,
+    ) -> Result<Option<CacheItem>, Box<dyn StdError>> {
+        const FN_NAME: &'static str = "CodecCache::get_item";
+
+        if unit_id.len() == 0 || code.len() == 0 {
+            return Ok(None);
+        }
+        let key = format!("{}.{}", unit_id, code);
+        {
+            let mut lock = self.items.lock().unwrap();
+            if let Some(item) = lock.get(&key) {
+                if item.expired_at > Instant::now() {
+                    return Ok(Some(item.clone()));
+                }
+            }
+        }
+
+        let cond = QueryCond {
+            unit_id: Some(unit_id),
+            code: Some(code),
+            ..Default::default()
+        };
+        let item = match self.model.profile().get(&cond).await? {
+            None => CacheItem {
+                codec: None,
+                offline_timeout: 0,
+                expired_at: Instant::now() + CACHE_TTL,
+            },
+            Some(profile) => CacheItem {
+                codec: match Codec::new(&profile.codec) {
+                    Err(e) => {
+                        warn!("[{}] invalid codec of {}: {}", FN_NAME, key, e);
+                        None
+                    }
+                    Ok(codec) => Some(Arc::new(codec)),
+                },
+                offline_timeout: profile.offline_timeout,
+                expired_at: Instant::now() + CACHE_TTL,
+            },
+        };
+        self.items.lock().unwrap().put(key, item.clone());
+        Ok(Some(item))
+    }
+}
+
+impl Field {
+    fn new(index: usize, value: &Value) -> Result<Self, String> {
+        let field = match value.as_object() {
+            None => return Err(format!("invalid `codec.fields[{}]`", index)),
+            Some(field) => field,
+        };
+        let name = match field.get("name").and_then(|v| v.as_str()) {
+            Some(name) if name.len() > 0 => name.to_string(),
+            _ => return Err(format!("invalid `codec.fields[{}].name`", index)),
+        };
+        let offset = match field.get("offset").and_then(|v| v.as_u64()) {
+            Some(offset) if offset <= FIELD_MAX_OFFSET => offset as usize,
+            _ => return Err(format!("invalid `codec.fields[{}].offset`", index)),
+        };
+        let field_type = match field.get("type").and_then(|v| v.as_str()) {
+            Some("u8") => FieldType::U8,
+            Some("i8") => FieldType::I8,
+            Some("u16") => FieldType::U16,
+            Some("i16") => FieldType::I16,
+            Some("u32") => FieldType::U32,
+            Some("i32") => FieldType::I32,
+            Some("u64") => FieldType::U64,
+            Some("i64") => FieldType::I64,
+            Some("f32") => FieldType::F32,
+            Some("f64") => FieldType::F64,
+            _ => return Err(format!("invalid `codec.fields[{}].type`", index)),
+        };
+        let little_endian = match field.get("endian") {
+            None => false,
+            Some(Value::String(endian)) => match endian.as_str() {
+                "big" => false,
+                "little" => true,
+                _ => return Err(format!("invalid `codec.fields[{}].endian`", index)),
+            },
+            Some(_) => return Err(format!("invalid `codec.fields[{}].endian`", index)),
+        };
+        let scale = match field.get("scale") {
+            None => None,
+            Some(value) => match value.as_f64() {
+                Some(scale) if scale != 0.0 && scale.is_finite() => Some(scale),
+                _ => return Err(format!("invalid `codec.fields[{}].scale`", index)),
+            },
+        };
+        Ok(Field {
+            name,
+            offset,
+            field_type,
+            little_endian,
+            scale,
+        })
+    }
+
+    /// To read the value from the payload.
+    fn read(&self, bytes: &[u8]) -> Result<Value, String> {
+        let size = self.field_type.size();
+        let end = match self.offset.checked_add(size) {
+            Some(end) if end <= bytes.len() => end,
+            _ => return Err(format!("`{}` out of range", self.name)),
+        };
+        let mut buf = [0u8; 8];
+        buf[..size].copy_from_slice(&bytes[self.offset..end]);
+        if !self.little_endian {
+            buf[..size].reverse();
+        }
+        let value = match self.field_type {
+            FieldType::U8 => RawValue::Int(buf[0] as i128),
+            FieldType::I8 => RawValue::Int(buf[0] as i8 as i128),
+            FieldType::U16 => RawValue::Int(u16::from_le_bytes([buf[0], buf[1]]) as i128),
+            FieldType::I16 => RawValue::Int(i16::from_le_bytes([buf[0], buf[1]]) as i128),
+            FieldType::U32 => {
+                RawValue::Int(u32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as i128)
+            }
+            FieldType::I32 => {
+                RawValue::Int(i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as i128)
+            }
+            FieldType::U64 => RawValue::Int(u64::from_le_bytes(buf) as i128),
+            FieldType::I64 => RawValue::Int(i64::from_le_bytes(buf) as i128),
+            FieldType::F32 => {
+                RawValue::Float(f32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]) as f64)
+            }
+            FieldType::F64 => RawValue::Float(f64::from_le_bytes(buf)),
+        };
+        let value = match (value, self.scale) {
+            (RawValue::Int(v), None) => match i64::try_from(v) {
+                Err(_) => return Ok(Value::Number(Number::from(v as u64))),
+                Ok(v) => return Ok(Value::Number(Number::from(v))),
+            },
+            (RawValue::Int(v), Some(scale)) => v as f64 * scale,
+            (RawValue::Float(v), None) => v,
+            (RawValue::Float(v), Some(scale)) => v * scale,
+        };
+        match Number::from_f64(value) {
+            None => Ok(Value::Null),
+            Some(v) => Ok(Value::Number(v)),
+        }
+    }
+
+    /// To write the value into the payload.
+    fn write(&self, bytes: &mut [u8], value: &Value) -> Result<(), String> {
+        let value = match value.as_f64() {
+            None => return Err(format!("invalid `{}`", self.name)),
+            Some(value) => match self.scale {
+                None => value,
+                Some(scale) => value / scale,
+            },
+        };
+        let size = self.field_type.size();
+        let mut buf = [0u8; 8];
+        match self.field_type {
+            FieldType::F32 => buf[..4].copy_from_slice(&(value as f32).to_le_bytes()),
+            FieldType::F64 => buf.copy_from_slice(&value.to_le_bytes()),
+            _ => {
+                let value = value.round();
+                let (min, max) = self.field_type.int_range();
+                if !value.is_finite() || value < min || value > max {
+                    return Err(format!("`{}` out of range", self.name));
+                }
+                match self.field_type {
+                    FieldType::U64 => buf.copy_from_slice(&(value as u64).to_le_bytes()),
+                    _ => buf.copy_from_slice(&(value as i64).to_le_bytes()),
+                }
+            }
+        }
+        if !self.little_endian {
+            buf[..size].reverse();
+        }
+        bytes[self.offset..self.offset + size].copy_from_slice(&buf[..size]);
+        Ok(())
+    }
+}
+
+impl FieldType {
+    fn size(&self) -> usize {
+        match self {
+            FieldType::U8 | FieldType::I8 => 1,
+            FieldType::U16 | FieldType::I16 => 2,
+            FieldType::U32 | FieldType::I32 | FieldType::F32 => 4,
+            FieldType::U64 | FieldType::I64 | FieldType::F64 => 8,
+        }
+    }
+
+    /// The range of integer types.
+    fn int_range(&self) -> (f64, f64) {
+        match self {
+            FieldType::U8 => (0.0, u8::MAX as f64),
+            FieldType::I8 => (i8::MIN as f64, i8::MAX as f64),
+            FieldType::U16 => (0.0, u16::MAX as f64),
+            FieldType::I16 => (i16::MIN as f64, i16::MAX as f64),
+            FieldType::U32 => (0.0, u32::MAX as f64),
+            FieldType::I32 => (i32::MIN as f64, i32::MAX as f64),
+            FieldType::U64 => (0.0, u64::MAX as f64),
+            _ => (i64::MIN as f64, i64::MAX as f64),
+        }
+    }
+}
+
+impl Script {
+    fn new(script: &str) -> Result<Self, String> {
+        let mut engine = Engine::new();
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .set_max_operations(SCRIPT_MAX_OPERATIONS)
+            .set_max_call_levels(SCRIPT_MAX_CALL_LEVELS)
+            .set_max_expr_depths(SCRIPT_MAX_EXPR_DEPTH, SCRIPT_MAX_FN_EXPR_DEPTH)
+            .set_max_string_size(SCRIPT_MAX_STRING_SIZE)
+            .set_max_array_size(SCRIPT_MAX_ARRAY_SIZE)
+            .set_max_map_size(SCRIPT_MAX_MAP_SIZE)
+            .on_print(|_| {})
+            .on_debug(|_, _, _| {});
+        engine.disable_symbol("eval");
+        let ast = match engine.compile(script) {
+            Err(e) => return Err(format!("invalid `codec.script`: {}", e)),
+            Ok(ast) => ast,
+        };
+        let has_decode = ast
+            .iter_functions()
+            .any(|f| f.name == SCRIPT_FN_DECODE && f.params.len() == 2);
+        let has_encode = ast
+            .iter_functions()
+            .any(|f| f.name == SCRIPT_FN_ENCODE && f.params.len() == 1);
+        Ok(Script {
+            engine,
+            ast,
+            has_decode,
+            has_encode,
+        })
+    }
+
+    fn decode(
+        &self,
+        bytes: Blob,
+        object: Map<String, Value>,
+    ) -> Result<Map<String, Value>, String> {
+        let object = match rhai::serde::to_dynamic(object) {
+            Err(e) => return Err(format!("script decode error: {}", e)),
+            Ok(object) => object,
+        };
+        let result: Dynamic = match self.engine.call_fn(
+            &mut Scope::new(),
+            &self.ast,
+            SCRIPT_FN_DECODE,
+            (bytes, object),
+        ) {
+            Err(e) => return Err(format!("script decode error: {}", e)),
+            Ok(result) => result,
+        };
+        match rhai::serde::from_dynamic::<Value>(&result) {
+            Ok(Value::Object(object)) => Ok(object),
+            _ => Err("script decode error: not an object".to_string()),
+        }
+    }
+
+    fn encode(&self, object: &Map<String, Value>) -> Result<Vec<u8>, String> {
+        let object = match rhai::serde::to_dynamic(object) {
+            Err(e) => return Err(format!("script encode error: {}", e)),
+            Ok(object) => object,
+        };
+        let result: Dynamic =
+            match self
+                .engine
+                .call_fn(&mut Scope::new(), &self.ast, SCRIPT_FN_ENCODE, (object,))
+            {
+                Err(e) => return Err(format!("script encode error: {}", e)),
+                Ok(result) => result,
+            };
+        if result.is_blob() {
+            return Ok(result.cast::<Blob>());
+        } else if result.is_array() {
+            let mut bytes = vec![];
+            for item in result.cast::<Array>() {
+                match item.as_int() {
+                    Ok(v) if v >= 0 && v <= u8::MAX as i64 => bytes.push(v as u8),
+                    _ => return Err("script encode error: not a byte array".to_string()),
+                }
+            }
+            return Ok(bytes);
+        }
+        Err("script encode error: not a blob".to_string())
+    }
+}
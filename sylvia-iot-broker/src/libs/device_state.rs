@@ -0,0 +1,108 @@
+//! Buffered runtime states of devices.
+//!
+//! The latest uplink/downlink times and the latest uplink `extension` are kept in memory and are
+//! written to the database periodically by [`StateBuffer::flush`]. This prevents one database
+//! write for each data.
+
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use log::error;
+use serde_json::{Map, Value};
+
+use crate::models::{
+    Model,
+    device::{UpdateQueryCond, Updates},
+};
+
+/// The buffer of device states that are not written to the database.
+pub struct StateBuffer {
+    model: Arc<dyn Model>,
+    items: Mutex<HashMap<String, Item>>,
+}
+
+/// The buffered state of one device.
+#[derive(Default)]
+struct Item {
+    last_uplink_at: Option<DateTime<Utc>>,
+    last_downlink_at: Option<DateTime<Utc>>,
+    last_extension: Option<Map<String, Value>>,
+    /// `Some(None)` to clear the offline time and the online state.
+    offline_at: Option<Option<DateTime<Utc>>>,
+}
+
+impl StateBuffer {
+    pub fn new(model: Arc<dyn Model>) -> Self {
+        StateBuffer {
+            model,
+            items: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// To record one uplink data of the device.
+    ///
+    /// The device will be offline at `time + offline_timeout` seconds. Zero `offline_timeout`
+    /// means no offline detection.
+    pub fn add_uplink(
+        &self,
+        device_id: &str,
+        time: DateTime<Utc>,
+        extension: Option<&Map<String, Value>>,
+        offline_timeout: u64,
+    ) {
+        let offline_at = match offline_timeout {
+            0 => None,
+            timeout => Some(time + TimeDelta::seconds(timeout as i64)),
+        };
+        let mut items = self.items.lock().unwrap();
+        let item = items.entry(device_id.to_string()).or_default();
+        item.last_uplink_at = Some(time);
+        if let Some(extension) = extension {
+            item.last_extension = Some(extension.clone());
+        }
+        item.offline_at = Some(offline_at);
+    }
+
+    /// To record one downlink data that is sent to the network of the device.
+    pub fn add_downlink(&self, device_id: &str, time: DateTime<Utc>) {
+        let mut items = self.items.lock().unwrap();
+        let item = items.entry(device_id.to_string()).or_default();
+        item.last_downlink_at = Some(time);
+    }
+
+    /// To write all buffered states to the database.
+    pub async fn flush(&self) {
+        const FN_NAME: &'static str = "flush";
+
+        let items = {
+            let mut items = self.items.lock().unwrap();
+            mem::take(&mut *items)
+        };
+        for (device_id, item) in items.iter() {
+            let cond = UpdateQueryCond {
+                device_id: device_id.as_str(),
+            };
+            let updates = Updates {
+                last_uplink_at: item.last_uplink_at,
+                last_downlink_at: item.last_downlink_at,
+                last_extension: item.last_extension.as_ref(),
+                offline_at: item.offline_at,
+                online: match item.offline_at {
+                    None => None,
+                    Some(offline_at) => Some(offline_at.map(|_| true)),
+                },
+                ..Default::default()
+            };
+            if let Err(e) = self.model.device().update(&cond, &updates).await {
+                error!(
+                    "[{}] update device {} state error: {}",
+                    FN_NAME, device_id, e
+                );
+            }
+        }
+    }
+}
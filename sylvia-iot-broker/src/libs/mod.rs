@@ -1,2 +1,4 @@
+pub mod codec;
 pub mod config;
+pub mod device_state;
 pub mod mq;
@@ -10,7 +10,6 @@ use hex;
 use log::{error, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Map, Value};
-use tokio::task;
 use url::Url;
 
 use general_mq::{
@@ -22,11 +21,12 @@ use general_mq::{
 use sylvia_iot_corelib::{err, strings};
 
 use super::{
-    Connection, MgrMqStatus, MgrStatus, Options, get_connection, new_data_queues, remove_connection,
+    Connection, MgrMqStatus, MgrStatus, Options, get_connection, nack_mode, new_data_queues,
+    remove_connection,
 };
 
 /// Uplink data from broker to application.
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct UlData {
     #[serde(rename = "dataId")]
     pub data_id: String,
@@ -45,6 +45,9 @@ pub struct UlData {
     pub is_public: bool,
     pub profile: String,
     pub data: String,
+    /// The object decoded by the codec of the device profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object: Option<Map<String, Value>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extension: Option<Map<String, Value>>,
 }
@@ -60,7 +63,13 @@ pub struct DlData {
     pub network_code: Option<String>,
     #[serde(rename = "networkAddr")]
     pub network_addr: Option<String>,
+    /// Expiration in seconds. None to use the default value of the application or the broker.
+    #[serde(rename = "expiresIn")]
+    pub expires_in: Option<i64>,
+    #[serde(default)]
     pub data: String,
+    /// The object to be encoded by the codec of the device profile if `data` is empty.
+    pub object: Option<Map<String, Value>>,
     pub extension: Option<Map<String, Value>>,
 }
 
@@ -87,6 +96,27 @@ pub struct DlDataResult {
     pub message: Option<String>,
 }
 
+/// Device status event from broker to application.
+#[derive(Clone, Serialize)]
+pub struct DeviceStatus {
+    #[serde(rename = "deviceId")]
+    pub device_id: String,
+    #[serde(rename = "networkId")]
+    pub network_id: String,
+    #[serde(rename = "networkCode")]
+    pub network_code: String,
+    #[serde(rename = "networkAddr")]
+    pub network_addr: String,
+    #[serde(rename = "isPublic")]
+    pub is_public: bool,
+    pub profile: String,
+    /// Device status. Now only `offline`.
+    pub status: String,
+    pub time: String,
+    #[serde(rename = "lastUplinkAt", skip_serializing_if = "Option::is_none")]
+    pub last_uplink_at: Option<String>,
+}
+
 /// The manager for application queues.
 #[derive(Clone)]
 pub struct ApplicationMgr {
@@ -100,6 +130,7 @@ pub struct ApplicationMgr {
     dldata: Arc<Mutex<Queue>>,
     dldata_resp: Arc<Mutex<Queue>>,
     dldata_result: Arc<Mutex<Queue>>,
+    device_status: Option<Arc<Mutex<Queue>>>,
 
     status: Arc<Mutex<MgrStatus>>,
     handler: Arc<Mutex<Arc<dyn EventHandler>>>,
@@ -136,9 +167,14 @@ impl ApplicationMgr {
             return Err("`unit_id` cannot be empty for application".to_string());
         }
 
-        let conn = get_connection(&conn_pool, host_uri)?;
+        let conn = get_connection(
+            &conn_pool,
+            host_uri,
+            opts.tls.as_ref(),
+            opts.reconnect_policy.as_ref(),
+        )?;
 
-        let (uldata, dldata, dldata_resp, dldata_result) =
+        let (uldata, dldata, dldata_resp, dldata_result, device_status) =
             new_data_queues(&conn, &opts, QUEUE_PREFIX, false)?;
 
         let mgr = ApplicationMgr {
@@ -149,6 +185,7 @@ impl ApplicationMgr {
             dldata,
             dldata_resp: dldata_resp.unwrap(),
             dldata_result,
+            device_status,
             status: Arc::new(Mutex::new(MgrStatus::NotReady)),
             handler: Arc::new(Mutex::new(handler)),
         };
@@ -174,12 +211,26 @@ impl ApplicationMgr {
         if let Err(e) = q.connect() {
             return Err(e.to_string());
         }
+        if let Some(q) = mgr.device_status.as_ref() {
+            let mut q = { q.lock().unwrap().clone() };
+            q.set_handler(mq_handler.clone());
+            if let Err(e) = q.connect() {
+                return Err(e.to_string());
+            }
+        }
+        let count = mgr.queue_count();
         match conn {
             Connection::Amqp(_, counter) => {
-                *counter.lock().unwrap() += 4;
+                *counter.lock().unwrap() += count;
             }
             Connection::Mqtt(_, counter) => {
-                *counter.lock().unwrap() += 4;
+                *counter.lock().unwrap() += count;
+            }
+            Connection::Memory(_, counter) => {
+                *counter.lock().unwrap() += count;
+            }
+            Connection::Redis(_, counter) => {
+                *counter.lock().unwrap() += count;
             }
         }
         Ok(mgr)
@@ -218,6 +269,10 @@ impl ApplicationMgr {
             dldata_resp: { self.dldata_resp.lock().unwrap().status() },
             dldata_result: { self.dldata_result.lock().unwrap().status() },
             ctrl: QueueStatus::Closed,
+            device_status: match self.device_status.as_ref() {
+                None => QueueStatus::Closed,
+                Some(q) => q.lock().unwrap().status(),
+            },
         }
     }
 
@@ -231,18 +286,19 @@ impl ApplicationMgr {
         q.close().await?;
         let mut q = { self.dldata_result.lock().unwrap().clone() };
         q.close().await?;
+        if let Some(q) = self.device_status.as_ref() {
+            let mut q = { q.lock().unwrap().clone() };
+            q.close().await?;
+        }
 
-        remove_connection(&self.conn_pool, &self.host_uri, 4).await
+        remove_connection(&self.conn_pool, &self.host_uri, self.queue_count()).await
     }
 
     /// Send uplink data to the application.
-    pub fn send_uldata(&self, data: &UlData) -> Result<(), Box<dyn StdError>> {
+    pub async fn send_uldata(&self, data: &UlData) -> Result<(), Box<dyn StdError + Send + Sync>> {
         let payload = serde_json::to_vec(data)?;
         let queue = { (*self.uldata.lock().unwrap()).clone() };
-        task::spawn(async move {
-            let _ = queue.send_msg(payload).await;
-        });
-        Ok(())
+        queue.send_msg(payload).await
     }
 
     /// Send downlink response for a downlink data to the application.
@@ -264,6 +320,28 @@ impl ApplicationMgr {
         let queue = { (*self.dldata_result.lock().unwrap()).clone() };
         queue.send_msg(payload).await
     }
+
+    /// Send the device status event to the application. Events are ignored if the application
+    /// does not enable the `device-status` queue.
+    pub async fn send_device_status(
+        &self,
+        data: &DeviceStatus,
+    ) -> Result<(), Box<dyn StdError + Send + Sync>> {
+        let queue = match self.device_status.as_ref() {
+            None => return Ok(()),
+            Some(queue) => queue.lock().unwrap().clone(),
+        };
+        let payload = serde_json::to_vec(data)?;
+        queue.send_msg(payload).await
+    }
+
+    /// The number of queues of the manager that use the connection.
+    fn queue_count(&self) -> isize {
+        match self.device_status {
+            None => 4,
+            Some(_) => 5,
+        }
+    }
 }
 
 #[async_trait]
@@ -275,6 +353,11 @@ impl QueueEventHandler for MgrMqEventHandler {
             && { self.mgr.dldata.lock().unwrap().status() } == QueueStatus::Connected
             && { self.mgr.dldata_resp.lock().unwrap().status() } == QueueStatus::Connected
             && { self.mgr.dldata_result.lock().unwrap().status() } == QueueStatus::Connected
+            && self
+                .mgr
+                .device_status
+                .as_ref()
+                .is_none_or(|q| q.lock().unwrap().status() == QueueStatus::Connected)
         {
             false => MgrStatus::NotReady,
             true => MgrStatus::Ready,
@@ -319,7 +402,7 @@ impl MessageHandler for MgrMqEventHandler {
             let handler = { self.mgr.handler.lock().unwrap().clone() };
             match handler.on_dldata(&self.mgr, Box::new(data)).await {
                 Err(_) => {
-                    if let Err(e) = msg.nack().await {
+                    if let Err(e) = msg.nack_with_mode(nack_mode(&self.mgr.opts)).await {
                         error!("[{}] NACK message error: {}", FN_NAME, e);
                     }
                 }
@@ -424,6 +507,16 @@ fn parse_dldata_msg(msg: &[u8]) -> Result<DlData, DlDataResp> {
             _ => (),
         },
     }
+    if let Some(expires_in) = data.expires_in {
+        if expires_in <= 0 {
+            return Err(DlDataResp {
+                correlation_id: data.correlation_id.clone(),
+                error: Some(err::E_PARAM.to_string()),
+                message: Some("invalid `expiresIn`".to_string()),
+                ..Default::default()
+            });
+        }
+    }
     if data.data.len() > 0 {
         if let Err(_) = hex::decode(data.data.as_str()) {
             return Err(DlDataResp {
@@ -6,7 +6,8 @@ use std::{
 use url::Url;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, ReconnectPolicy,
+    RedisQueueOptions, TlsOptions,
     queue::{EventHandler, GmqQueue, MessageHandler},
 };
 
@@ -22,6 +23,8 @@ pub fn new(
     conn_pool: Arc<Mutex<HashMap<String, Connection>>>,
     host_uri: &Url,
     prefetch: Option<u16>,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     func_name: &str,
     is_recv: bool,
     handler: Arc<dyn EventHandler>,
@@ -31,7 +34,7 @@ pub fn new(
         return Err("`func_name` cannot be empty for control queue".to_string());
     }
 
-    let conn = get_connection(&conn_pool, host_uri)?;
+    let conn = get_connection(&conn_pool, host_uri, tls, reconnect)?;
     let mut queue = match conn {
         Connection::Amqp(conn, counter) => {
             let prefetch = match prefetch {
@@ -46,6 +49,7 @@ pub fn new(
                     name: format!("{}.{}", QUEUE_PREFIX, func_name),
                     is_recv,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     broadcast: true,
                     prefetch,
                     ..Default::default()
@@ -63,6 +67,7 @@ pub fn new(
                     name: format!("{}.{}", QUEUE_PREFIX, func_name),
                     is_recv,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     broadcast: true,
                     ..Default::default()
                 },
@@ -73,6 +78,47 @@ pub fn new(
             }
             Queue::new(opts)?
         }
+        Connection::Memory(conn, counter) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}", QUEUE_PREFIX, func_name),
+                    is_recv,
+                    reliable: true,
+                    broadcast: true,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
+        Connection::Redis(conn, counter) => {
+            let prefetch = match prefetch {
+                None => DEF_PREFETCH,
+                Some(prefetch) => match prefetch {
+                    0 => DEF_PREFETCH,
+                    _ => prefetch,
+                },
+            };
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}", QUEUE_PREFIX, func_name),
+                    is_recv,
+                    reliable: true,
+                    reconnect_policy: reconnect.cloned(),
+                    broadcast: true,
+                    prefetch,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
     };
     queue.set_handler(handler);
     if is_recv {
@@ -5,6 +5,7 @@
 //! - dldata: downlink data from the application to the broker.
 //! - dldata-resp: the response of downlink data.
 //! - dldata-result: the data process result from the network.
+//! - device-status: the device status events such as offline.
 //!
 //! For networks, the [`network::NetworkMgr`] manages the following kind of queues:
 //! - uldata: device uplink data from the network to the broker.
@@ -14,15 +15,22 @@
 use std::{
     collections::HashMap,
     error::Error as StdError,
+    path::Path,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
+use futures::future;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MqttConnection, MqttConnectionOptions,
-    MqttQueueOptions, Queue, QueueOptions, connection::GmqConnection, queue::Status,
+    AmqpConnection, AmqpConnectionOptions, AmqpOverflow, AmqpQueueOptions, AmqpQueueType,
+    Compression, MemoryConnection, MemoryConnectionOptions, MemoryQueueOptions, MqttConnection,
+    MqttConnectionOptions, MqttQueueOptions, OutboxOptions, Queue, QueueOptions, ReconnectPolicy,
+    RedisConnection, RedisConnectionOptions, RedisQueueOptions, TlsOptions,
+    connection::GmqConnection,
+    queue::{NackMode, Status},
 };
 
 pub mod application;
@@ -35,6 +43,8 @@ pub mod network;
 pub enum Connection {
     Amqp(AmqpConnection, Arc<Mutex<isize>>),
     Mqtt(MqttConnection, Arc<Mutex<isize>>),
+    Memory(MemoryConnection, Arc<Mutex<isize>>),
+    Redis(RedisConnection, Arc<Mutex<isize>>),
 }
 
 /// Manager status.
@@ -58,6 +68,8 @@ pub struct MgrMqStatus {
     pub dldata_result: Status,
     /// For `ctrl`.
     pub ctrl: Status,
+    /// For `device-status`.
+    pub device_status: Status,
 }
 
 /// The options of the application/network manager.
@@ -80,10 +92,51 @@ pub struct Options {
     /// MQTT shared queue prefix option.
     #[serde(rename = "sharedPrefix", skip_serializing_if = "Option::is_none")]
     pub shared_prefix: Option<String>,
+    /// Move failed data of received queues to `[queue].dead-letter` queues.
+    #[serde(rename = "deadLetter", default)]
+    pub dead_letter: bool,
+    /// TLS options of `amqps` and `mqtts` connections.
+    #[serde(skip)]
+    pub tls: Option<TlsOptions>,
+    /// The reconnect policy of connections and queues.
+    #[serde(skip)]
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// The outbox of queues that are sent by the broker. Each queue uses the `[path]/[queue]`
+    /// directory.
+    #[serde(skip)]
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of data of queues that are sent by the broker.
+    #[serde(skip)]
+    pub compression: Compression,
+    /// AMQP arguments of data queues.
+    #[serde(rename = "amqpArgs", default)]
+    pub amqp_args: AmqpQueueArgs,
+    /// Declare the `device-status` queue for applications.
+    #[serde(rename = "deviceStatus", default)]
+    pub device_status: bool,
+}
+
+/// AMQP arguments for declaring data queues.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct AmqpQueueArgs {
+    /// Queue type: `classic` or `quorum`. None for `classic`.
+    #[serde(rename = "queueType", skip_serializing_if = "Option::is_none")]
+    pub queue_type: Option<String>,
+    /// Message TTL in milliseconds.
+    #[serde(rename = "messageTtl", skip_serializing_if = "Option::is_none")]
+    pub message_ttl: Option<u32>,
+    /// Maximum number of ready messages.
+    #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+    /// The behavior when a queue reaches `maxLength`: `drop-head`, `reject-publish` or
+    /// `reject-publish-dlx`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<String>,
 }
 
 /// Support application/network host schemes.
-pub const SUPPORT_SCHEMES: &'static [&'static str] = &["amqp", "amqps", "mqtt", "mqtts"];
+pub const SUPPORT_SCHEMES: &'static [&'static str] =
+    &["amqp", "amqps", "mqtt", "mqtts", "mem", "redis", "rediss"];
 
 /// The default prefetch value for AMQP.
 const DEF_PREFETCH: u16 = 100;
@@ -97,10 +150,12 @@ impl Clone for MgrStatus {
 }
 
 /// Utility function to get the message queue connection instance. A new connection will be created
-/// if the host does not exist.
+/// with `tls` and `reconnect` options if the host does not exist.
 fn get_connection(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     host_uri: &Url,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Connection, String> {
     let uri = host_uri.to_string();
     let mut mutex = conn_pool.lock().unwrap();
@@ -112,6 +167,8 @@ fn get_connection(
         "amqp" | "amqps" => {
             let opts = AmqpConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = AmqpConnection::new(opts)?;
@@ -123,6 +180,8 @@ fn get_connection(
         "mqtt" | "mqtts" => {
             let opts = MqttConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = MqttConnection::new(opts)?;
@@ -131,6 +190,29 @@ fn get_connection(
             mutex.insert(uri, conn.clone());
             Ok(conn)
         }
+        "mem" => {
+            let opts = MemoryConnectionOptions {
+                uri: host_uri.to_string(),
+                ..Default::default()
+            };
+            let mut conn = MemoryConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Memory(conn, Arc::new(Mutex::new(0)));
+            mutex.insert(uri, conn.clone());
+            Ok(conn)
+        }
+        "redis" | "rediss" => {
+            let opts = RedisConnectionOptions {
+                uri: host_uri.to_string(),
+                reconnect_policy: reconnect.cloned(),
+                ..Default::default()
+            };
+            let mut conn = RedisConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Redis(conn, Arc::new(Mutex::new(0)));
+            mutex.insert(uri, conn.clone());
+            Ok(conn)
+        }
         s => Err(format!("unsupport scheme {}", s)),
     }
 }
@@ -160,6 +242,20 @@ async fn remove_connection(
                         return Ok(());
                     }
                 }
+                Connection::Memory(_, counter) => {
+                    let mut mutex = counter.lock().unwrap();
+                    *mutex -= count;
+                    if *mutex > 0 {
+                        return Ok(());
+                    }
+                }
+                Connection::Redis(_, counter) => {
+                    let mut mutex = counter.lock().unwrap();
+                    *mutex -= count;
+                    if *mutex > 0 {
+                        return Ok(());
+                    }
+                }
             },
         }
         mutex.remove(host_uri)
@@ -172,11 +268,58 @@ async fn remove_connection(
             Connection::Mqtt(mut conn, _) => {
                 conn.close().await?;
             }
+            Connection::Memory(mut conn, _) => {
+                conn.close().await?;
+            }
+            Connection::Redis(mut conn, _) => {
+                conn.close().await?;
+            }
         }
     }
     Ok(())
 }
 
+/// To close all connections of the pool gracefully. All receivers stop receiving and wait for
+/// in-process messages first, and then queues wait for publisher confirms and close. The `timeout`
+/// is shared by all connections.
+pub async fn close_graceful(
+    conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
+    timeout: Duration,
+) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    let conns: Vec<Connection> = {
+        let mut mutex = conn_pool.lock().unwrap();
+        mutex.drain().map(|(_, conn)| conn).collect()
+    };
+    let deadline = Instant::now() + timeout;
+
+    // Drain all receivers before closing any connection because message handlers of one connection
+    // send messages with queues of other connections.
+    let futures = conns.iter().map(|conn| async move {
+        match conn {
+            Connection::Amqp(conn, _) => conn.drain(timeout).await,
+            Connection::Mqtt(conn, _) => conn.drain(timeout).await,
+            Connection::Memory(conn, _) => conn.drain(timeout).await,
+            Connection::Redis(conn, _) => conn.drain(timeout).await,
+        }
+    });
+    let drained = future::join_all(futures).await;
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    let futures = conns.into_iter().map(|conn| async move {
+        match conn {
+            Connection::Amqp(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Mqtt(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Memory(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Redis(mut conn, _) => conn.close_graceful(timeout).await,
+        }
+    });
+    let closed = future::join_all(futures).await;
+    for result in drained.into_iter().chain(closed) {
+        result?;
+    }
+    Ok(())
+}
+
 /// The utility function for creating application/network control queue with the following name:
 /// - `[prefix].[unit].[code].ctrl`
 fn new_ctrl_queues(
@@ -222,6 +365,7 @@ fn new_ctrl_queues(
                     name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
                     is_recv: false,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     prefetch,
                     ..Default::default()
@@ -236,6 +380,7 @@ fn new_ctrl_queues(
                     name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
                     is_recv: false,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -244,6 +389,42 @@ fn new_ctrl_queues(
             );
             ctrl = Arc::new(Mutex::new(Queue::new(ctrl_opts)?));
         }
+        Connection::Memory(conn, _) => {
+            let ctrl_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            ctrl = Arc::new(Mutex::new(Queue::new(ctrl_opts)?));
+        }
+        Connection::Redis(conn, _) => {
+            let prefetch = match opts.prefetch {
+                None => DEF_PREFETCH,
+                Some(prefetch) => match prefetch {
+                    0 => DEF_PREFETCH,
+                    _ => prefetch,
+                },
+            };
+
+            let ctrl_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
+                    is_recv: false,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            ctrl = Arc::new(Mutex::new(Queue::new(ctrl_opts)?));
+        }
     }
 
     Ok(ctrl)
@@ -254,6 +435,8 @@ fn new_ctrl_queues(
 /// - `[prefix].[unit].[code].dldata`
 /// - `[prefix].[unit].[code].dldata-resp`: `Some` for applications and `None` for networks.
 /// - `[prefix].[unit].[code].dldata-result`
+/// - `[prefix].[unit].[code].device-status`: `Some` for applications with the `device_status`
+///   option and `None` for others.
 fn new_data_queues(
     conn: &Connection,
     opts: &Options,
@@ -265,6 +448,7 @@ fn new_data_queues(
         Arc<Mutex<Queue>>,
         Option<Arc<Mutex<Queue>>>,
         Arc<Mutex<Queue>>,
+        Option<Arc<Mutex<Queue>>>,
     ),
     String,
 > {
@@ -272,6 +456,7 @@ fn new_data_queues(
     let dldata: Arc<Mutex<Queue>>;
     let dldata_resp: Option<Arc<Mutex<Queue>>>;
     let dldata_result: Arc<Mutex<Queue>>;
+    let device_status: Option<Arc<Mutex<Queue>>>;
 
     if opts.unit_id.len() == 0 {
         if opts.unit_code.len() != 0 {
@@ -293,6 +478,11 @@ fn new_data_queues(
         0 => "_",
         _ => opts.unit_code.as_str(),
     };
+    let uldata_name = format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str());
+    let dldata_name = format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str());
+    let dldata_resp_name = format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str());
+    let dldata_result_name = format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str());
+    let device_status_name = format!("{}.{}.{}.device-status", prefix, unit, opts.name.as_str());
 
     match conn {
         Connection::Amqp(conn, _) => {
@@ -303,52 +493,85 @@ fn new_data_queues(
                     _ => prefetch,
                 },
             };
+            let args = amqp_queue_args(&opts.amqp_args)?;
 
             let uldata_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    name: uldata_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, uldata_name.as_str(), is_network),
+                    outbox: outbox_options(opts, uldata_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent: opts.persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
             let dldata_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    name: dldata_name.clone(),
                     is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, dldata_name.as_str(), !is_network),
+                    outbox: outbox_options(opts, dldata_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent: opts.persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
             let dldata_resp_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    name: dldata_resp_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_resp_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_resp_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent: opts.persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
             let dldata_result_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    name: dldata_result_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_result_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent: opts.persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Amqp(
+                AmqpQueueOptions {
+                    name: device_status_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, device_status_name.as_str(), is_network),
+                    outbox: outbox_options(opts, device_status_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    persistent: opts.persistent,
+                    broadcast: false,
+                    prefetch,
+                    ..args.clone()
                 },
                 conn,
             );
@@ -359,13 +582,21 @@ fn new_data_queues(
                 true => None,
             };
             dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            device_status = match !is_network && opts.device_status {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
         }
         Connection::Mqtt(conn, _) => {
             let uldata_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    name: uldata_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, uldata_name.as_str(), is_network),
+                    outbox: outbox_options(opts, uldata_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -374,9 +605,13 @@ fn new_data_queues(
             );
             let dldata_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    name: dldata_name.clone(),
                     is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, dldata_name.as_str(), !is_network),
+                    outbox: outbox_options(opts, dldata_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -385,9 +620,13 @@ fn new_data_queues(
             );
             let dldata_resp_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    name: dldata_resp_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_resp_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_resp_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -396,9 +635,28 @@ fn new_data_queues(
             );
             let dldata_result_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    name: dldata_result_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_result_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    shared_prefix: opts.shared_prefix.clone(),
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Mqtt(
+                MqttQueueOptions {
+                    name: device_status_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, device_status_name.as_str(), is_network),
+                    outbox: outbox_options(opts, device_status_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -412,8 +670,244 @@ fn new_data_queues(
                 true => None,
             };
             dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            device_status = match !is_network && opts.device_status {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
+        }
+        Connection::Memory(conn, _) => {
+            let uldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: uldata_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, uldata_name.as_str(), is_network),
+                    outbox: outbox_options(opts, uldata_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: dldata_name.clone(),
+                    is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, dldata_name.as_str(), !is_network),
+                    outbox: outbox_options(opts, dldata_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: dldata_resp_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_resp_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_resp_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: dldata_result_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_result_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: device_status_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, device_status_name.as_str(), is_network),
+                    outbox: outbox_options(opts, device_status_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Arc::new(Mutex::new(Queue::new(uldata_opts)?));
+            dldata = Arc::new(Mutex::new(Queue::new(dldata_opts)?));
+            dldata_resp = match is_network {
+                false => Some(Arc::new(Mutex::new(Queue::new(dldata_resp_opts)?))),
+                true => None,
+            };
+            dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            device_status = match !is_network && opts.device_status {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
+        }
+        Connection::Redis(conn, _) => {
+            let prefetch = match opts.prefetch {
+                None => DEF_PREFETCH,
+                Some(prefetch) => match prefetch {
+                    0 => DEF_PREFETCH,
+                    _ => prefetch,
+                },
+            };
+
+            let uldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: uldata_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, uldata_name.as_str(), is_network),
+                    outbox: outbox_options(opts, uldata_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: dldata_name.clone(),
+                    is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, dldata_name.as_str(), !is_network),
+                    outbox: outbox_options(opts, dldata_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: dldata_resp_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_resp_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_resp_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: dldata_result_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_result_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: device_status_name.clone(),
+                    is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, device_status_name.as_str(), is_network),
+                    outbox: outbox_options(opts, device_status_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Arc::new(Mutex::new(Queue::new(uldata_opts)?));
+            dldata = Arc::new(Mutex::new(Queue::new(dldata_opts)?));
+            dldata_resp = match is_network {
+                false => Some(Arc::new(Mutex::new(Queue::new(dldata_resp_opts)?))),
+                true => None,
+            };
+            dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            device_status = match !is_network && opts.device_status {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
         }
     }
 
-    Ok((uldata, dldata, dldata_resp, dldata_result))
+    Ok((uldata, dldata, dldata_resp, dldata_result, device_status))
+}
+
+/// To get the dead-letter queue name `[queue].dead-letter` of the queue that is received by the
+/// broker if the dead-letter option is enabled.
+fn dead_letter_name(opts: &Options, queue: &str, is_recv: bool) -> Option<String> {
+    match opts.dead_letter && is_recv {
+        false => None,
+        true => Some(format!("{}.dead-letter", queue)),
+    }
+}
+
+/// To get AMQP queue options with arguments of data queues.
+fn amqp_queue_args(args: &AmqpQueueArgs) -> Result<AmqpQueueOptions, String> {
+    let queue_type = match args.queue_type.as_deref() {
+        None | Some("classic") => AmqpQueueType::Classic,
+        Some("quorum") => AmqpQueueType::Quorum,
+        Some(v) => return Err(format!("invalid AMQP queue type: {}", v)),
+    };
+    let overflow = match args.overflow.as_deref() {
+        None => None,
+        Some("drop-head") => Some(AmqpOverflow::DropHead),
+        Some("reject-publish") => Some(AmqpOverflow::RejectPublish),
+        Some("reject-publish-dlx") => Some(AmqpOverflow::RejectPublishDlx),
+        Some(v) => return Err(format!("invalid AMQP overflow: {}", v)),
+    };
+    Ok(AmqpQueueOptions {
+        queue_type,
+        message_ttl_millis: args.message_ttl,
+        max_length: args.max_length,
+        overflow,
+        ..Default::default()
+    })
+}
+
+/// To get the outbox options with the `[path]/[queue]` directory of the queue that is sent by the
+/// broker if the outbox option is enabled.
+fn outbox_options(opts: &Options, queue: &str, is_recv: bool) -> Option<OutboxOptions> {
+    match opts.outbox.as_ref() {
+        None => None,
+        Some(outbox) => match is_recv {
+            true => None,
+            false => Some(OutboxOptions {
+                path: Path::new(outbox.path.as_str())
+                    .join(queue)
+                    .to_string_lossy()
+                    .to_string(),
+                ..outbox.clone()
+            }),
+        },
+    }
+}
+
+/// To get the NACK mode of failed data.
+fn nack_mode(opts: &Options) -> NackMode {
+    match opts.dead_letter {
+        false => NackMode::Requeue,
+        true => NackMode::DeadLetter,
+    }
 }
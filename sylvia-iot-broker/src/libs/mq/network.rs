@@ -23,8 +23,8 @@ use general_mq::{
 use sylvia_iot_corelib::strings;
 
 use super::{
-    Connection, MgrMqStatus, MgrStatus, Options, get_connection, new_ctrl_queues, new_data_queues,
-    remove_connection,
+    Connection, MgrMqStatus, MgrStatus, Options, get_connection, nack_mode, new_ctrl_queues,
+    new_data_queues, remove_connection,
 };
 
 /// Uplink data from network to broker.
@@ -104,9 +104,15 @@ impl NetworkMgr {
         opts: Options,
         handler: Arc<dyn EventHandler>,
     ) -> Result<Self, String> {
-        let conn = get_connection(&conn_pool, host_uri)?;
-
-        let (uldata, dldata, _, dldata_result) = new_data_queues(&conn, &opts, QUEUE_PREFIX, true)?;
+        let conn = get_connection(
+            &conn_pool,
+            host_uri,
+            opts.tls.as_ref(),
+            opts.reconnect_policy.as_ref(),
+        )?;
+
+        let (uldata, dldata, _, dldata_result, _) =
+            new_data_queues(&conn, &opts, QUEUE_PREFIX, true)?;
         let ctrl = new_ctrl_queues(&conn, &opts, QUEUE_PREFIX)?;
 
         let mgr = NetworkMgr {
@@ -150,6 +156,12 @@ impl NetworkMgr {
             Connection::Mqtt(_, counter) => {
                 *counter.lock().unwrap() += 4;
             }
+            Connection::Memory(_, counter) => {
+                *counter.lock().unwrap() += 4;
+            }
+            Connection::Redis(_, counter) => {
+                *counter.lock().unwrap() += 4;
+            }
         }
         Ok(mgr)
     }
@@ -179,7 +191,7 @@ impl NetworkMgr {
         *self.status.lock().unwrap()
     }
 
-    /// Detail status of each message queue. Please ignore `dldata_resp`.
+    /// Detail status of each message queue. Please ignore `dldata_resp` and `device_status`.
     pub fn mq_status(&self) -> MgrMqStatus {
         MgrMqStatus {
             uldata: { self.uldata.lock().unwrap().status() },
@@ -187,6 +199,7 @@ impl NetworkMgr {
             dldata_resp: QueueStatus::Closed,
             dldata_result: { self.dldata_result.lock().unwrap().status() },
             ctrl: { self.ctrl.lock().unwrap().status() },
+            device_status: QueueStatus::Closed,
         }
     }
 
@@ -313,7 +326,7 @@ impl MessageHandler for MgrMqEventHandler {
             let handler = { self.mgr.handler.lock().unwrap().clone() };
             match handler.on_uldata(&self.mgr, Box::new(data)).await {
                 Err(_) => {
-                    if let Err(e) = msg.nack().await {
+                    if let Err(e) = msg.nack_with_mode(nack_mode(&self.mgr.opts)).await {
                         error!("[{}] NACK message error: {}", FN_NAME, e);
                     }
                 }
@@ -355,7 +368,7 @@ impl MessageHandler for MgrMqEventHandler {
             let handler = { self.mgr.handler.lock().unwrap().clone() };
             match handler.on_dldata_result(&self.mgr, Box::new(data)).await {
                 Err(_) => {
-                    if let Err(e) = msg.nack().await {
+                    if let Err(e) = msg.nack_with_mode(nack_mode(&self.mgr.opts)).await {
                         error!("[{}] NACK message error: {}", FN_NAME, e);
                     }
                 }
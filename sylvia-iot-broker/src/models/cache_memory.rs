@@ -10,8 +10,9 @@ use super::{
         device::{Cache as DeviceCache, Options as DeviceOptions},
         device_route::{Cache as DeviceRouteCache, Options as DeviceRouteOptions},
         network_route::{Cache as NetworkRouteCache, Options as NetworkRouteOptions},
+        uldata_dedup::Cache as UlDataDedupCache,
     },
-    network_route,
+    network_route, uldata_dedup,
 };
 
 /// Pure memory cache.
@@ -20,6 +21,7 @@ pub struct Cache {
     device: Arc<DeviceCache>,
     device_route: Arc<DeviceRouteCache>,
     network_route: Arc<NetworkRouteCache>,
+    uldata_dedup: Arc<UlDataDedupCache>,
 }
 
 pub struct Options<'a> {
@@ -35,6 +37,7 @@ impl Cache {
             device: Arc::new(DeviceCache::new(opts.device, model.clone())),
             device_route: Arc::new(DeviceRouteCache::new(opts.device_route, model.clone())),
             network_route: Arc::new(NetworkRouteCache::new(opts.network_route, model.clone())),
+            uldata_dedup: Arc::new(UlDataDedupCache::new()),
         }
     }
 }
@@ -44,6 +47,7 @@ impl super::Cache for Cache {
     async fn close(&self) -> Result<(), Box<dyn StdError>> {
         let _ = self.device_route().clear().await;
         let _ = self.network_route().clear().await;
+        let _ = self.uldata_dedup().clear().await;
         Ok(())
     }
 
@@ -58,4 +62,8 @@ impl super::Cache for Cache {
     fn network_route(&self) -> &dyn network_route::NetworkRouteCache {
         self.network_route.as_ref()
     }
+
+    fn uldata_dedup(&self) -> &dyn uldata_dedup::UlDataDedupCache {
+        self.uldata_dedup.as_ref()
+    }
 }
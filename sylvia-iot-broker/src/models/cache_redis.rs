@@ -0,0 +1,79 @@
+//! Redis cache that is shared by all broker instances.
+
+use std::{error::Error as StdError, sync::Arc};
+
+use async_trait::async_trait;
+
+use super::{
+    Model, device, device_route, network_route,
+    redis::{
+        conn::{self, Options as RedisOptions},
+        device::Cache as DeviceCache,
+        device_route::Cache as DeviceRouteCache,
+        network_route::Cache as NetworkRouteCache,
+        uldata_dedup::Cache as UlDataDedupCache,
+    },
+    uldata_dedup,
+};
+
+/// Redis cache.
+#[derive(Clone)]
+pub struct Cache {
+    device: Arc<DeviceCache>,
+    device_route: Arc<DeviceRouteCache>,
+    network_route: Arc<NetworkRouteCache>,
+    uldata_dedup: Arc<UlDataDedupCache>,
+}
+
+pub struct Options<'a> {
+    pub conn: &'a RedisOptions,
+    /// Expiration in seconds of items. Zero means no expiration.
+    pub ttl: u64,
+}
+
+impl Cache {
+    /// Create an instance.
+    pub async fn new(
+        opts: &Options<'_>,
+        model: &Arc<dyn Model>,
+    ) -> Result<Self, Box<dyn StdError>> {
+        let conn = conn::connect(opts.conn).await?;
+        Ok(Cache {
+            device: Arc::new(DeviceCache::new(conn.clone(), opts.ttl, model.clone())),
+            device_route: Arc::new(DeviceRouteCache::new(conn.clone(), opts.ttl, model.clone())),
+            network_route: Arc::new(NetworkRouteCache::new(
+                conn.clone(),
+                opts.ttl,
+                model.clone(),
+            )),
+            uldata_dedup: Arc::new(UlDataDedupCache::new(conn)),
+        })
+    }
+}
+
+#[async_trait]
+impl super::Cache for Cache {
+    async fn close(&self) -> Result<(), Box<dyn StdError>> {
+        Ok(())
+    }
+
+    fn is_shared(&self) -> bool {
+        true
+    }
+
+    fn device(&self) -> &dyn device::DeviceCache {
+        self.device.as_ref()
+    }
+
+    fn device_route(&self) -> &dyn device_route::DeviceRouteCache {
+        self.device_route.as_ref()
+    }
+
+    fn network_route(&self) -> &dyn network_route::NetworkRouteCache {
+        self.network_route.as_ref()
+    }
+
+    fn uldata_dedup(&self) -> &dyn uldata_dedup::UlDataDedupCache {
+        self.uldata_dedup.as_ref()
+    }
+}
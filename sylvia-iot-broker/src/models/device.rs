@@ -20,12 +20,23 @@ pub struct Device {
     pub profile: String,
     pub name: String,
     pub info: Map<String, Value>,
+    /// The time of the latest uplink data.
+    pub last_uplink_at: Option<DateTime<Utc>>,
+    /// The time of the latest downlink data that is sent to the network.
+    pub last_downlink_at: Option<DateTime<Utc>>,
+    /// The `extension` of the latest uplink data that has one.
+    pub last_extension: Option<Map<String, Value>>,
+    /// The device becomes offline if there is no uplink data before this time.
+    pub offline_at: Option<DateTime<Utc>>,
+    /// `None` if the profile of the device has no offline timeout or there is no uplink data.
+    pub online: Option<bool>,
 }
 
 // The device cache item.
 #[derive(Clone)]
 pub struct DeviceCacheItem {
     pub device_id: String,
+    pub unit_id: String,
     pub profile: String,
 }
 
@@ -114,6 +125,10 @@ pub struct ListQueryCond<'a> {
     pub profile: Option<&'a str>,
     /// To get unit that their **name** contains the specified (partial) word.
     pub name_contains: Option<&'a str>,
+    /// To get online or offline devices.
+    pub online: Option<bool>,
+    /// To get devices that become offline before and at the specified time.
+    pub offline_at_lte: Option<DateTime<Utc>>,
 }
 
 /// The query condition for the update operation.
@@ -132,6 +147,11 @@ pub struct Updates<'a> {
     pub profile: Option<&'a str>,
     pub name: Option<&'a str>,
     pub info: Option<&'a Map<String, Value>>,
+    pub last_uplink_at: Option<DateTime<Utc>>,
+    pub last_downlink_at: Option<DateTime<Utc>>,
+    pub last_extension: Option<&'a Map<String, Value>>,
+    pub offline_at: Option<Option<DateTime<Utc>>>,
+    pub online: Option<Option<bool>>,
 }
 
 /// Model operations.
@@ -176,6 +196,17 @@ pub trait DeviceModel: Sync {
         cond: &UpdateQueryCond,
         updates: &Updates,
     ) -> Result<(), Box<dyn StdError>>;
+
+    /// To mark an online device offline if it has the specified `offline_at` and to report
+    /// whether the device is changed by this operation.
+    ///
+    /// Only one caller gets `true` for the same state, so processes sharing one database can use
+    /// this to claim devices.
+    async fn set_offline(
+        &self,
+        device_id: &str,
+        offline_at: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn StdError>>;
 }
 
 /// The operations for cursors.
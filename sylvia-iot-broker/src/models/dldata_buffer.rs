@@ -71,6 +71,8 @@ pub struct ListQueryCond<'a> {
     pub network_id: Option<&'a str>,
     /// To get device data of the specified device.
     pub device_id: Option<&'a str>,
+    /// To get downlink data buffers that expired before and at the specified time.
+    pub expired_at_lte: Option<DateTime<Utc>>,
 }
 
 /// Model operations.
@@ -105,6 +107,12 @@ pub trait DlDataBufferModel: Sync {
 
     /// To delete one or more items.
     async fn del(&self, cond: &QueryCond) -> Result<(), Box<dyn StdError>>;
+
+    /// To delete an item and to report whether the item is deleted by this operation.
+    ///
+    /// Only one caller gets `true` for the same item, so processes sharing one database can use
+    /// this to claim items.
+    async fn del_one(&self, data_id: &str) -> Result<bool, Box<dyn StdError>>;
 }
 
 /// The operations for cursors.
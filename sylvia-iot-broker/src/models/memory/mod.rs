@@ -3,3 +3,4 @@
 pub mod device;
 pub mod device_route;
 pub mod network_route;
+pub mod uldata_dedup;
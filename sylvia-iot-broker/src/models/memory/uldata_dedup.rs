@@ -0,0 +1,79 @@
+use std::{
+    error::Error as StdError,
+    num::NonZeroUsize,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use lru::LruCache;
+use serde_json::{Map, Value};
+use tokio::sync::RwLock;
+
+use super::super::uldata_dedup::UlDataDedupCache;
+
+pub struct Cache {
+    items: Arc<RwLock<LruCache<String, Item>>>,
+}
+
+struct Item {
+    expires_at: Instant,
+    extensions: Vec<Option<Map<String, Value>>>,
+}
+
+const DEF_SIZE: usize = 10_000;
+
+impl Cache {
+    pub fn new() -> Self {
+        let size = unsafe { NonZeroUsize::new_unchecked(DEF_SIZE) };
+        Cache {
+            items: Arc::new(RwLock::new(LruCache::new(size))),
+        }
+    }
+}
+
+#[async_trait]
+impl UlDataDedupCache for Cache {
+    async fn clear(&self) -> Result<(), Box<dyn StdError>> {
+        let mut lock = self.items.write().await;
+        lock.clear();
+        Ok(())
+    }
+
+    async fn add(
+        &self,
+        key: &str,
+        extension: Option<&Map<String, Value>>,
+        ttl: u64,
+    ) -> Result<bool, Box<dyn StdError>> {
+        let now = Instant::now();
+        let expires_at = now + Duration::from_millis(ttl);
+        let mut lock = self.items.write().await;
+        if let Some(item) = lock.get_mut(key) {
+            if item.expires_at > now {
+                item.extensions.push(extension.cloned());
+                return Ok(false);
+            }
+        }
+        let item = Item {
+            expires_at,
+            extensions: vec![extension.cloned()],
+        };
+        let _ = lock.push(key.to_string(), item);
+        Ok(true)
+    }
+
+    async fn take(&self, key: &str) -> Result<Vec<Option<Map<String, Value>>>, Box<dyn StdError>> {
+        let mut lock = self.items.write().await;
+        match lock.pop(key) {
+            Some(item) if item.expires_at > Instant::now() => Ok(item.extensions),
+            _ => Ok(vec![]),
+        }
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache::new()
+    }
+}
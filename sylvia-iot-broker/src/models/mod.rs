@@ -1,8 +1,9 @@
 //! Traits and implementations for accessing databases and caches.
 //!
-//! Currently we only provide pure MongoDB/SQLite implementation. Mixing implementation is
-//! possible. For example, put units/devices in MongoDB and put routes in Redis. Then use a
-//! model struct and impl to mix both databases.
+//! Currently we only provide pure MongoDB/PostgreSQL/SQLite implementation for models and
+//! memory/Redis implementation for caches. Mixing implementation is possible. For example, put
+//! units/devices in MongoDB and put routes in Redis. Then use a model struct and impl to mix both
+//! databases.
 
 use std::{error::Error as StdError, sync::Arc};
 
@@ -14,31 +15,43 @@ pub mod device_route;
 pub mod dldata_buffer;
 pub mod network;
 pub mod network_route;
+pub mod profile;
+pub mod uldata_dedup;
 pub mod unit;
 
 mod cache_memory;
+mod cache_redis;
 mod memory;
 mod model_mongodb;
+mod model_postgres;
 mod model_sqlite;
 mod mongodb;
+mod postgres;
+mod redis;
 mod sqlite;
 
 pub use self::{
     cache_memory::{Cache as MemoryCache, Options as MemoryOptions},
+    cache_redis::{Cache as RedisCache, Options as RedisCacheOptions},
     memory::{
         device::Options as DeviceOptions, device_route::Options as DeviceRouteOptions,
         network_route::Options as NetworkRouteOptions,
     },
     mongodb::conn::{self as mongodb_conn, Options as MongoDbOptions},
+    postgres::conn::{self as postgres_conn, Options as PostgresOptions},
+    redis::conn::{self as redis_conn, Options as RedisOptions},
     sqlite::conn::{self as sqlite_conn, Options as SqliteOptions},
 };
 pub use model_mongodb::Model as MongoDbModel;
+pub use model_postgres::Model as PostgresModel;
 pub use model_sqlite::Model as SqliteModel;
 
 /// Database connection options for model implementation.
 pub enum ConnOptions {
     // Pure MongoDB model implementation.
     MongoDB(MongoDbOptions),
+    /// Pure PostgreSQL model implementation.
+    Postgres(PostgresOptions),
     /// Pure SQLite model implementation.
     Sqlite(SqliteOptions),
 }
@@ -50,6 +63,12 @@ pub enum CacheConnOptions {
         device_route: DeviceRouteOptions,
         network_route: NetworkRouteOptions,
     },
+    /// Redis cache that is shared by all broker instances.
+    Redis {
+        conn: RedisOptions,
+        /// Expiration in seconds of items. Zero means no expiration.
+        ttl: u64,
+    },
 }
 
 /// The top level trait to get all models (tables/collections).
@@ -78,6 +97,9 @@ pub trait Model: Send + Sync {
 
     /// To get the downlink data buffer model.
     fn dldata_buffer(&self) -> &dyn dldata_buffer::DlDataBufferModel;
+
+    /// To get the device profile model.
+    fn profile(&self) -> &dyn profile::ProfileModel;
 }
 
 /// The top level trait to get all caches.
@@ -86,6 +108,15 @@ pub trait Cache: Send + Sync {
     /// Close database connection.
     async fn close(&self) -> Result<(), Box<dyn StdError>>;
 
+    /// Whether the cache is shared by all broker instances.
+    ///
+    /// Shared caches are not cleared when queues or managers change status, because clearing one
+    /// instance's view would cause all instances to query databases again. Their items expire by
+    /// TTL instead.
+    fn is_shared(&self) -> bool {
+        false
+    }
+
     /// To get the device cache.
     fn device(&self) -> &dyn device::DeviceCache;
 
@@ -94,12 +125,16 @@ pub trait Cache: Send + Sync {
 
     /// To get the network route cache.
     fn network_route(&self) -> &dyn network_route::NetworkRouteCache;
+
+    /// To get the uplink data deduplication cache.
+    fn uldata_dedup(&self) -> &dyn uldata_dedup::UlDataDedupCache;
 }
 
 /// To create the database model with the specified database implementation.
 pub async fn new(opts: &ConnOptions) -> Result<Arc<dyn Model>, Box<dyn StdError>> {
     let model: Arc<dyn Model> = match opts {
         ConnOptions::MongoDB(opts) => Arc::new(MongoDbModel::new(opts).await?),
+        ConnOptions::Postgres(opts) => Arc::new(PostgresModel::new(opts).await?),
         ConnOptions::Sqlite(opts) => Arc::new(SqliteModel::new(opts).await?),
     };
     model.unit().init().await?;
@@ -109,6 +144,7 @@ pub async fn new(opts: &ConnOptions) -> Result<Arc<dyn Model>, Box<dyn StdError>
     model.device_route().init().await?;
     model.network_route().init().await?;
     model.dldata_buffer().init().await?;
+    model.profile().init().await?;
     Ok(model)
 }
 
@@ -130,6 +166,10 @@ pub async fn new_cache(
             };
             Arc::new(MemoryCache::new(&opts, model))
         }
+        CacheConnOptions::Redis { conn, ttl } => {
+            let opts = RedisCacheOptions { conn, ttl: *ttl };
+            Arc::new(RedisCache::new(&opts, model).await?)
+        }
     };
     Ok(cache)
 }
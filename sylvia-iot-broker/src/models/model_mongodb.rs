@@ -15,9 +15,10 @@ use super::{
         dldata_buffer::Model as DlDataBufferModel,
         network::Model as NetworkModel,
         network_route::Model as NetworkRouteModel,
+        profile::Model as ProfileModel,
         unit::Model as UnitModel,
     },
-    network, network_route, unit,
+    network, network_route, profile, unit,
 };
 
 /// Pure MongoDB model.
@@ -31,6 +32,7 @@ pub struct Model {
     device_route: Arc<DeviceRouteModel>,
     network_route: Arc<NetworkRouteModel>,
     dldata_buffer: Arc<DlDataBufferModel>,
+    profile: Arc<ProfileModel>,
 }
 
 impl Model {
@@ -46,6 +48,7 @@ impl Model {
             device_route: Arc::new(DeviceRouteModel::new(conn.clone()).await?),
             network_route: Arc::new(NetworkRouteModel::new(conn.clone()).await?),
             dldata_buffer: Arc::new(DlDataBufferModel::new(conn.clone()).await?),
+            profile: Arc::new(ProfileModel::new(conn.clone()).await?),
         })
     }
 
@@ -88,4 +91,8 @@ impl super::Model for Model {
     fn dldata_buffer(&self) -> &dyn dldata_buffer::DlDataBufferModel {
         self.dldata_buffer.as_ref()
     }
+
+    fn profile(&self) -> &dyn profile::ProfileModel {
+        self.profile.as_ref()
+    }
 }
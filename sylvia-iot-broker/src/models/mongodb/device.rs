@@ -1,6 +1,7 @@
 use std::{error::Error as StdError, sync::Arc};
 
 use async_trait::async_trait;
+use chrono::Utc;
 use futures::TryStreamExt;
 use mongodb::{
     Cursor as MongoDbCursor, Database,
@@ -53,6 +54,15 @@ struct Schema {
     profile: String,
     name: String,
     info: Document,
+    #[serde(rename = "lastUplinkAt")]
+    last_uplink_at: Option<DateTime>,
+    #[serde(rename = "lastDownlinkAt")]
+    last_downlink_at: Option<DateTime>,
+    #[serde(rename = "lastExtension")]
+    last_extension: Option<Document>,
+    #[serde(rename = "offlineAt")]
+    offline_at: Option<DateTime>,
+    online: Option<bool>,
 }
 
 const COL_NAME: &'static str = "device";
@@ -85,6 +95,7 @@ impl DeviceModel for Model {
             doc! {"name": "modifiedAt_1", "key": {"modifiedAt": 1}},
             doc! {"name": "profile_1", "key": {"profile": 1}},
             doc! {"name": "name_1", "key": {"name": 1}},
+            doc! {"name": "online_1_offlineAt_1", "key": {"online": 1, "offlineAt": 1}},
         ];
         let command = doc! {
             "createIndexes": COL_NAME,
@@ -154,6 +165,23 @@ impl DeviceModel for Model {
                 profile: item.profile,
                 name: item.name,
                 info: bson::deserialize_from_document(item.info)?,
+                last_uplink_at: match item.last_uplink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_downlink_at: match item.last_downlink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_extension: match item.last_extension {
+                    None => None,
+                    Some(value) => Some(bson::deserialize_from_document(value)?),
+                },
+                offline_at: match item.offline_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                online: item.online,
             }));
         }
         Ok(None)
@@ -172,6 +200,23 @@ impl DeviceModel for Model {
             profile: device.profile.clone(),
             name: device.name.clone(),
             info: bson::serialize_to_document(&device.info)?,
+            last_uplink_at: match device.last_uplink_at {
+                None => None,
+                Some(value) => Some(value.into()),
+            },
+            last_downlink_at: match device.last_downlink_at {
+                None => None,
+                Some(value) => Some(value.into()),
+            },
+            last_extension: match device.last_extension.as_ref() {
+                None => None,
+                Some(value) => Some(bson::serialize_to_document(value)?),
+            },
+            offline_at: match device.offline_at {
+                None => None,
+                Some(value) => Some(value.into()),
+            },
+            online: device.online,
         };
         self.conn
             .collection::<Schema>(COL_NAME)
@@ -195,6 +240,23 @@ impl DeviceModel for Model {
                 profile: device.profile.clone(),
                 name: device.name.clone(),
                 info: bson::serialize_to_document(&device.info)?,
+                last_uplink_at: match device.last_uplink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_downlink_at: match device.last_downlink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_extension: match device.last_extension.as_ref() {
+                    None => None,
+                    Some(value) => Some(bson::serialize_to_document(value)?),
+                },
+                offline_at: match device.offline_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                online: device.online,
             });
         }
         if let Err(e) = self
@@ -246,6 +308,21 @@ impl DeviceModel for Model {
         }
         return Ok(());
     }
+
+    async fn set_offline(
+        &self,
+        device_id: &str,
+        offline_at: chrono::DateTime<Utc>,
+    ) -> Result<bool, Box<dyn StdError>> {
+        let offline_at: DateTime = offline_at.into();
+        let filter = doc! {"deviceId": device_id, "online": true, "offlineAt": offline_at};
+        let result = self
+            .conn
+            .collection::<Schema>(COL_NAME)
+            .update_one(filter, doc! {"$set": {"online": false}})
+            .await?;
+        Ok(result.modified_count > 0)
+    }
 }
 
 impl DbCursor {
@@ -272,6 +349,23 @@ impl Cursor for DbCursor {
                 profile: item.profile,
                 name: item.name,
                 info: bson::deserialize_from_document(item.info)?,
+                last_uplink_at: match item.last_uplink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_downlink_at: match item.last_downlink_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                last_extension: match item.last_extension {
+                    None => None,
+                    Some(value) => Some(bson::deserialize_from_document(value)?),
+                },
+                offline_at: match item.offline_at {
+                    None => None,
+                    Some(value) => Some(value.into()),
+                },
+                online: item.online,
             }));
         }
         Ok(None)
@@ -344,6 +438,13 @@ fn get_list_query_filter(cond: &ListQueryCond) -> Document {
             }
         }
     }
+    if let Some(value) = cond.online {
+        filter.insert("online", value);
+    }
+    if let Some(value) = cond.offline_at_lte {
+        let offline_at: DateTime = value.into();
+        filter.insert("offlineAt", doc! {"$lte": offline_at});
+    }
     filter
 }
 
@@ -427,6 +528,52 @@ fn get_update_doc(updates: &Updates) -> Option<Document> {
         );
         count += 1;
     }
+    if let Some(value) = updates.last_uplink_at.as_ref() {
+        document.insert(
+            "lastUplinkAt",
+            DateTime::from_millis(value.timestamp_millis()),
+        );
+        count += 1;
+    }
+    if let Some(value) = updates.last_downlink_at.as_ref() {
+        document.insert(
+            "lastDownlinkAt",
+            DateTime::from_millis(value.timestamp_millis()),
+        );
+        count += 1;
+    }
+    if let Some(value) = updates.last_extension {
+        document.insert(
+            "lastExtension",
+            match bson::serialize_to_document(value) {
+                Err(_) => return None,
+                Ok(doc) => doc,
+            },
+        );
+        count += 1;
+    }
+    if let Some(value) = updates.offline_at.as_ref() {
+        match value {
+            None => {
+                document.insert("offlineAt", Bson::Null);
+            }
+            Some(value) => {
+                document.insert("offlineAt", DateTime::from_millis(value.timestamp_millis()));
+            }
+        }
+        count += 1;
+    }
+    if let Some(value) = updates.online.as_ref() {
+        match value {
+            None => {
+                document.insert("online", Bson::Null);
+            }
+            Some(value) => {
+                document.insert("online", *value);
+            }
+        }
+        count += 1;
+    }
     if count == 0 {
         return None;
     }
@@ -177,6 +177,16 @@ impl DlDataBufferModel for Model {
             .await?;
         Ok(())
     }
+
+    async fn del_one(&self, data_id: &str) -> Result<bool, Box<dyn StdError>> {
+        let filter = doc! {"dataId": data_id};
+        let result = self
+            .conn
+            .collection::<Schema>(COL_NAME)
+            .delete_one(filter)
+            .await?;
+        Ok(result.deleted_count > 0)
+    }
 }
 
 impl DbCursor {
@@ -253,6 +263,10 @@ fn get_list_query_filter(cond: &ListQueryCond) -> Document {
     if let Some(value) = cond.device_id {
         filter.insert("deviceId", value);
     }
+    if let Some(value) = cond.expired_at_lte {
+        let expired_at: DateTime = value.into();
+        filter.insert("expiredAt", doc! {"$lte": expired_at});
+    }
     filter
 }
 
@@ -7,4 +7,5 @@ pub mod device_route;
 pub mod dldata_buffer;
 pub mod network;
 pub mod network_route;
+pub mod profile;
 pub mod unit;
@@ -0,0 +1,64 @@
+use std::error::Error as StdError;
+
+use redis::{AsyncCommands, Client, aio::MultiplexedConnection};
+
+/// Redis connection options.
+pub struct Options {
+    /// Redis URL. Use `redis://:password@host:port` format.
+    pub url: String,
+}
+
+/// Connect to Redis.
+pub async fn connect(options: &Options) -> Result<MultiplexedConnection, Box<dyn StdError>> {
+    let conn = Client::open(options.url.as_str())?
+        .get_multiplexed_async_connection()
+        .await?;
+    Ok(conn)
+}
+
+/// To set the value with expiration in seconds. Zero `ttl` means no expiration.
+pub(crate) async fn set(
+    conn: &MultiplexedConnection,
+    key: String,
+    value: String,
+    ttl: u64,
+) -> Result<(), Box<dyn StdError>> {
+    let mut conn = conn.clone();
+    let _: () = match ttl {
+        0 => conn.set(key, value).await?,
+        _ => conn.set_ex(key, value, ttl).await?,
+    };
+    Ok(())
+}
+
+/// To delete all keys that start with the specified prefix.
+pub(crate) async fn del_prefix(
+    conn: &MultiplexedConnection,
+    prefix: &str,
+) -> Result<(), Box<dyn StdError>> {
+    let mut conn = conn.clone();
+    let pattern = format!("{}*", escape_pattern(prefix));
+    let mut keys: Vec<String> = vec![];
+    {
+        let mut iter = conn.scan_match::<_, String>(pattern).await?;
+        while let Some(key) = iter.next_item().await {
+            keys.push(key?);
+        }
+    }
+    for chunk in keys.chunks(1000) {
+        let _: () = conn.del(chunk).await?;
+    }
+    Ok(())
+}
+
+/// To escape glob-style characters of the `SCAN MATCH` pattern.
+fn escape_pattern(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(c, '*' | '?' | '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
@@ -0,0 +1,154 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, aio::MultiplexedConnection};
+use serde::{Deserialize, Serialize};
+use serde_json;
+
+use super::{
+    super::{
+        Model,
+        device::{
+            DelCacheQueryCond, DeviceCache, DeviceCacheItem, GetCacheQueryCond, QueryCond,
+            QueryOneCond,
+        },
+    },
+    conn,
+};
+
+pub struct Cache {
+    model: Arc<dyn Model>,
+    conn: MultiplexedConnection,
+    /// Expiration in seconds of items.
+    ttl: u64,
+}
+
+/// Redis schema. Use JSON string as the value and `null` for non-exist devices.
+#[derive(Deserialize, Serialize)]
+struct Schema {
+    #[serde(rename = "deviceId")]
+    device_id: String,
+    #[serde(rename = "unitId", default)]
+    unit_id: String,
+    profile: String,
+}
+
+const PREFIX: &'static str = "broker:device:uldata:";
+
+impl Cache {
+    pub fn new(conn: MultiplexedConnection, ttl: u64, model: Arc<dyn Model>) -> Self {
+        Cache { model, conn, ttl }
+    }
+}
+
+#[async_trait]
+impl DeviceCache for Cache {
+    async fn clear(&self) -> Result<(), Box<dyn StdError>> {
+        conn::del_prefix(&self.conn, PREFIX).await
+    }
+
+    async fn get(
+        &self,
+        cond: &GetCacheQueryCond,
+    ) -> Result<Option<DeviceCacheItem>, Box<dyn StdError>> {
+        // Try to hit cache first, or returns a model query condition.
+        let model_cond = match cond {
+            GetCacheQueryCond::CodeAddr(cond) => {
+                let key = match cond.unit_code {
+                    None => format!(".{}.{}", cond.network_code, cond.network_addr),
+                    Some(unit) => format!("{}.{}.{}", unit, cond.network_code, cond.network_addr),
+                };
+                let mut conn = self.conn.clone();
+                let result: Option<String> = conn.get(PREFIX.to_string() + key.as_str()).await?;
+                if let Some(value) = result {
+                    let item: Option<Schema> = serde_json::from_str(value.as_str())?;
+                    return match item {
+                        None => Ok(None),
+                        Some(item) => Ok(Some(DeviceCacheItem {
+                            device_id: item.device_id,
+                            unit_id: item.unit_id,
+                            profile: item.profile,
+                        })),
+                    };
+                }
+                QueryCond {
+                    device: Some(QueryOneCond {
+                        unit_code: cond.unit_code,
+                        network_code: cond.network_code,
+                        network_addr: cond.network_addr,
+                    }),
+                    ..Default::default()
+                }
+            }
+        };
+
+        let item = match self.model.device().get(&model_cond).await? {
+            None => None,
+            Some(device) => Some(DeviceCacheItem {
+                device_id: device.device_id,
+                unit_id: device.unit_id,
+                profile: device.profile,
+            }),
+        };
+        let _ = self.set(cond, item.as_ref()).await;
+        Ok(item)
+    }
+
+    async fn set(
+        &self,
+        cond: &GetCacheQueryCond,
+        value: Option<&DeviceCacheItem>,
+    ) -> Result<(), Box<dyn StdError>> {
+        match cond {
+            GetCacheQueryCond::CodeAddr(cond) => {
+                let key = match cond.unit_code {
+                    None => format!(".{}.{}", cond.network_code, cond.network_addr),
+                    Some(unit) => format!("{}.{}.{}", unit, cond.network_code, cond.network_addr),
+                };
+                let item = match value {
+                    None => None,
+                    Some(value) => Some(Schema {
+                        device_id: value.device_id.clone(),
+                        unit_id: value.unit_id.clone(),
+                        profile: value.profile.clone(),
+                    }),
+                };
+                let item_str = serde_json::to_string(&item)?;
+                conn::set(
+                    &self.conn,
+                    PREFIX.to_string() + key.as_str(),
+                    item_str,
+                    self.ttl,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn del(&self, cond: &DelCacheQueryCond) -> Result<(), Box<dyn StdError>> {
+        let key = match cond.network_code {
+            None => {
+                // Disallow deleting all devices of public networks.
+                if cond.unit_code.len() == 0 {
+                    return Ok(());
+                }
+
+                // Remove all routes of the unit.
+                format!("{}.", cond.unit_code)
+            }
+            Some(code) => match cond.network_addr {
+                None => {
+                    // Remove all routes of the network.
+                    format!("{}.{}.", cond.unit_code, code)
+                }
+                Some(addr) => {
+                    let key = format!("{}{}.{}.{}", PREFIX, cond.unit_code, code, addr);
+                    let mut conn = self.conn.clone();
+                    let _: () = conn.del(key).await?;
+                    return Ok(());
+                }
+            },
+        };
+        conn::del_prefix(&self.conn, format!("{}{}", PREFIX, key).as_str()).await
+    }
+}
@@ -0,0 +1,313 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, aio::MultiplexedConnection};
+use serde::{Deserialize, Serialize};
+use serde_json;
+
+use super::{
+    super::{
+        Model,
+        device::{QueryCond as DeviceQueryCond, QueryOneCond as DeviceQueryOneCond},
+        device_route::{
+            DelCachePubQueryCond, DelCacheQueryCond, DeviceRouteCache, DeviceRouteCacheDlData,
+            DeviceRouteCacheUlData, GetCachePubQueryCond, GetCacheQueryCond, ListOptions,
+            ListQueryCond,
+        },
+    },
+    conn,
+};
+
+pub struct Cache {
+    model: Arc<dyn Model>,
+    conn: MultiplexedConnection,
+    /// Expiration in seconds of items.
+    ttl: u64,
+}
+
+/// Redis schema of uplink data. Use JSON string as the value and `null` for no routes.
+#[derive(Deserialize, Serialize)]
+struct UlDataSchema {
+    #[serde(rename = "appMgrKeys")]
+    app_mgr_keys: Vec<String>,
+}
+
+/// Redis schema of downlink data. Use JSON string as the value and `null` for non-exist devices.
+#[derive(Deserialize, Serialize)]
+struct DlDataSchema {
+    #[serde(rename = "netMgrKey")]
+    net_mgr_key: String,
+    #[serde(rename = "networkId")]
+    network_id: String,
+    #[serde(rename = "networkAddr")]
+    network_addr: String,
+    #[serde(rename = "deviceId")]
+    device_id: String,
+    profile: String,
+}
+
+const PREFIX_ULDATA: &'static str = "broker:deviceRoute:uldata:";
+const PREFIX_DLDATA: &'static str = "broker:deviceRoute:dldata:";
+const PREFIX_DLDATA_PUB: &'static str = "broker:deviceRoute:dldataPub:";
+
+impl Cache {
+    pub fn new(conn: MultiplexedConnection, ttl: u64, model: Arc<dyn Model>) -> Self {
+        Cache { model, conn, ttl }
+    }
+
+    /// To get the downlink data item of the specified key.
+    async fn get_dldata_item(
+        &self,
+        key: String,
+    ) -> Result<Option<Option<DeviceRouteCacheDlData>>, Box<dyn StdError>> {
+        let mut conn = self.conn.clone();
+        let result: Option<String> = conn.get(key).await?;
+        let value = match result {
+            None => return Ok(None),
+            Some(value) => value,
+        };
+        let item: Option<DlDataSchema> = serde_json::from_str(value.as_str())?;
+        match item {
+            None => Ok(Some(None)),
+            Some(item) => Ok(Some(Some(DeviceRouteCacheDlData {
+                net_mgr_key: item.net_mgr_key,
+                network_id: item.network_id,
+                network_addr: item.network_addr,
+                device_id: item.device_id,
+                profile: item.profile,
+            }))),
+        }
+    }
+
+    /// To set the downlink data item of the specified key.
+    async fn set_dldata_item(
+        &self,
+        key: String,
+        value: Option<&DeviceRouteCacheDlData>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let item = match value {
+            None => None,
+            Some(value) => Some(DlDataSchema {
+                net_mgr_key: value.net_mgr_key.clone(),
+                network_id: value.network_id.clone(),
+                network_addr: value.network_addr.clone(),
+                device_id: value.device_id.clone(),
+                profile: value.profile.clone(),
+            }),
+        };
+        let item_str = serde_json::to_string(&item)?;
+        conn::set(&self.conn, key, item_str, self.ttl).await
+    }
+}
+
+#[async_trait]
+impl DeviceRouteCache for Cache {
+    async fn clear(&self) -> Result<(), Box<dyn StdError>> {
+        conn::del_prefix(&self.conn, PREFIX_ULDATA).await?;
+        conn::del_prefix(&self.conn, PREFIX_DLDATA).await?;
+        conn::del_prefix(&self.conn, PREFIX_DLDATA_PUB).await
+    }
+
+    async fn get_uldata(
+        &self,
+        device_id: &str,
+    ) -> Result<Option<DeviceRouteCacheUlData>, Box<dyn StdError>> {
+        {
+            let mut conn = self.conn.clone();
+            let result: Option<String> = conn.get(PREFIX_ULDATA.to_string() + device_id).await?;
+            if let Some(value) = result {
+                let item: Option<UlDataSchema> = serde_json::from_str(value.as_str())?;
+                return match item {
+                    None => Ok(None),
+                    Some(item) => Ok(Some(DeviceRouteCacheUlData {
+                        app_mgr_keys: item.app_mgr_keys,
+                    })),
+                };
+            }
+        }
+
+        let opts = ListOptions {
+            cond: &ListQueryCond {
+                device_id: Some(device_id),
+                ..Default::default()
+            },
+            offset: None,
+            limit: None,
+            sort: None,
+            cursor_max: None,
+        };
+        let (routes, _) = self.model.device_route().list(&opts, None).await?;
+        let data: Option<DeviceRouteCacheUlData> = match routes.len() {
+            0 => None,
+            _ => {
+                let mut routes_data = vec![];
+                for r in routes.iter() {
+                    routes_data.push(format!("{}.{}", r.unit_code, r.application_code))
+                }
+                Some(DeviceRouteCacheUlData {
+                    app_mgr_keys: routes_data,
+                })
+            }
+        };
+        let _ = self.set_uldata(device_id, data.as_ref()).await;
+        Ok(data)
+    }
+
+    async fn set_uldata(
+        &self,
+        device_id: &str,
+        value: Option<&DeviceRouteCacheUlData>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let item = match value {
+            None => None,
+            Some(value) => Some(UlDataSchema {
+                app_mgr_keys: value.app_mgr_keys.clone(),
+            }),
+        };
+        let item_str = serde_json::to_string(&item)?;
+        let key = PREFIX_ULDATA.to_string() + device_id;
+        conn::set(&self.conn, key, item_str, self.ttl).await
+    }
+
+    async fn del_uldata(&self, device_id: &str) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.conn.clone();
+        let _: () = conn.del(PREFIX_ULDATA.to_string() + device_id).await?;
+        Ok(())
+    }
+
+    async fn get_dldata(
+        &self,
+        cond: &GetCacheQueryCond,
+    ) -> Result<Option<DeviceRouteCacheDlData>, Box<dyn StdError>> {
+        let key = format!(
+            "{}{}.{}.{}",
+            PREFIX_DLDATA, cond.unit_code, cond.network_code, cond.network_addr
+        );
+        if let Some(value) = self.get_dldata_item(key).await? {
+            return Ok(value);
+        }
+
+        let dev_cond = DeviceQueryCond {
+            device: Some(DeviceQueryOneCond {
+                unit_code: Some(cond.unit_code),
+                network_code: cond.network_code,
+                network_addr: cond.network_addr,
+            }),
+            ..Default::default()
+        };
+        let device = self.model.device().get(&dev_cond).await?;
+        let data = match device {
+            None => None,
+            Some(device) => match device.unit_code.as_ref() {
+                // This should not occur!
+                None => None,
+                Some(unit_code) => Some(DeviceRouteCacheDlData {
+                    net_mgr_key: format!("{}.{}", unit_code, cond.network_code),
+                    network_id: device.network_id,
+                    network_addr: device.network_addr,
+                    device_id: device.device_id,
+                    profile: device.profile,
+                }),
+            },
+        };
+        let _ = self.set_dldata(cond, data.as_ref()).await;
+        Ok(data)
+    }
+
+    async fn set_dldata(
+        &self,
+        cond: &GetCacheQueryCond,
+        value: Option<&DeviceRouteCacheDlData>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let key = format!(
+            "{}{}.{}.{}",
+            PREFIX_DLDATA, cond.unit_code, cond.network_code, cond.network_addr
+        );
+        self.set_dldata_item(key, value).await
+    }
+
+    async fn del_dldata(&self, cond: &DelCacheQueryCond) -> Result<(), Box<dyn StdError>> {
+        let key = match cond.network_code {
+            None => {
+                // Remove all routes of the unit.
+                format!("{}.", cond.unit_code)
+            }
+            Some(code) => match cond.network_addr {
+                None => {
+                    // Remove all routes of the network.
+                    format!("{}.{}.", cond.unit_code, code)
+                }
+                Some(addr) => {
+                    let key = format!("{}{}.{}.{}", PREFIX_DLDATA, cond.unit_code, code, addr);
+                    let mut conn = self.conn.clone();
+                    let _: () = conn.del(key).await?;
+                    return Ok(());
+                }
+            },
+        };
+        conn::del_prefix(&self.conn, format!("{}{}", PREFIX_DLDATA, key).as_str()).await
+    }
+
+    async fn get_dldata_pub(
+        &self,
+        cond: &GetCachePubQueryCond,
+    ) -> Result<Option<DeviceRouteCacheDlData>, Box<dyn StdError>> {
+        let key = format!("{}{}.{}", PREFIX_DLDATA_PUB, cond.unit_id, cond.device_id);
+        if let Some(value) = self.get_dldata_item(key).await? {
+            return Ok(value);
+        }
+
+        let dev_cond = DeviceQueryCond {
+            unit_id: Some(cond.unit_id),
+            device_id: Some(cond.device_id),
+            ..Default::default()
+        };
+        let device = self.model.device().get(&dev_cond).await?;
+        let data = match device {
+            None => None,
+            Some(device) => match device.unit_code.as_ref() {
+                None => Some(DeviceRouteCacheDlData {
+                    net_mgr_key: format!(".{}", device.network_code),
+                    network_id: device.network_id,
+                    network_addr: device.network_addr,
+                    device_id: device.device_id,
+                    profile: device.profile,
+                }),
+                Some(unit_code) => Some(DeviceRouteCacheDlData {
+                    net_mgr_key: format!("{}.{}", unit_code, device.network_code),
+                    network_id: device.network_id,
+                    network_addr: device.network_addr,
+                    device_id: device.device_id,
+                    profile: device.profile,
+                }),
+            },
+        };
+        let _ = self.set_dldata_pub(cond, data.as_ref()).await;
+        Ok(data)
+    }
+
+    async fn set_dldata_pub(
+        &self,
+        cond: &GetCachePubQueryCond,
+        value: Option<&DeviceRouteCacheDlData>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let key = format!("{}{}.{}", PREFIX_DLDATA_PUB, cond.unit_id, cond.device_id);
+        self.set_dldata_item(key, value).await
+    }
+
+    async fn del_dldata_pub(&self, cond: &DelCachePubQueryCond) -> Result<(), Box<dyn StdError>> {
+        match cond.device_id {
+            None => {
+                // Remove all routes of the unit.
+                let prefix = format!("{}{}.", PREFIX_DLDATA_PUB, cond.unit_id);
+                conn::del_prefix(&self.conn, prefix.as_str()).await
+            }
+            Some(id) => {
+                let key = format!("{}{}.{}", PREFIX_DLDATA_PUB, cond.unit_id, id);
+                let mut conn = self.conn.clone();
+                let _: () = conn.del(key).await?;
+                Ok(())
+            }
+        }
+    }
+}
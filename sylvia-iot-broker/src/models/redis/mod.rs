@@ -0,0 +1,7 @@
+//! Redis model implementation.
+
+pub mod conn;
+pub mod device;
+pub mod device_route;
+pub mod network_route;
+pub mod uldata_dedup;
@@ -0,0 +1,115 @@
+use std::{error::Error as StdError, sync::Arc};
+
+use async_trait::async_trait;
+use redis::{AsyncCommands, aio::MultiplexedConnection};
+use serde::{Deserialize, Serialize};
+use serde_json;
+
+use super::{
+    super::{
+        Model,
+        network_route::{ListOptions, ListQueryCond, NetworkRouteCache, NetworkRouteCacheUlData},
+    },
+    conn,
+};
+
+pub struct Cache {
+    model: Arc<dyn Model>,
+    conn: MultiplexedConnection,
+    /// Expiration in seconds of items.
+    ttl: u64,
+}
+
+/// Redis schema. Use JSON string as the value and `null` for no routes.
+#[derive(Deserialize, Serialize)]
+struct Schema {
+    #[serde(rename = "appMgrKeys")]
+    app_mgr_keys: Vec<String>,
+}
+
+const PREFIX: &'static str = "broker:networkRoute:uldata:";
+
+impl Cache {
+    pub fn new(conn: MultiplexedConnection, ttl: u64, model: Arc<dyn Model>) -> Self {
+        Cache { model, conn, ttl }
+    }
+}
+
+#[async_trait]
+impl NetworkRouteCache for Cache {
+    async fn clear(&self) -> Result<(), Box<dyn StdError>> {
+        conn::del_prefix(&self.conn, PREFIX).await
+    }
+
+    async fn get_uldata(
+        &self,
+        network_id: &str,
+    ) -> Result<Option<NetworkRouteCacheUlData>, Box<dyn StdError>> {
+        {
+            let mut conn = self.conn.clone();
+            let result: Option<String> = conn.get(PREFIX.to_string() + network_id).await?;
+            if let Some(value) = result {
+                let item: Option<Schema> = serde_json::from_str(value.as_str())?;
+                return match item {
+                    None => Ok(None),
+                    Some(item) => Ok(Some(NetworkRouteCacheUlData {
+                        app_mgr_keys: item.app_mgr_keys,
+                    })),
+                };
+            }
+        }
+
+        let opts = ListOptions {
+            cond: &ListQueryCond {
+                network_id: Some(network_id),
+                ..Default::default()
+            },
+            offset: None,
+            limit: None,
+            sort: None,
+            cursor_max: None,
+        };
+        let (routes, _) = self.model.network_route().list(&opts, None).await?;
+        let data = match routes.len() {
+            0 => None,
+            _ => {
+                let mut routes_data = vec![];
+                for r in routes.iter() {
+                    routes_data.push(format!("{}.{}", r.unit_code, r.application_code))
+                }
+                Some(NetworkRouteCacheUlData {
+                    app_mgr_keys: routes_data,
+                })
+            }
+        };
+        let _ = self.set_uldata(network_id, data.as_ref()).await;
+        Ok(data)
+    }
+
+    async fn set_uldata(
+        &self,
+        network_id: &str,
+        value: Option<&NetworkRouteCacheUlData>,
+    ) -> Result<(), Box<dyn StdError>> {
+        let item = match value {
+            None => None,
+            Some(value) => Some(Schema {
+                app_mgr_keys: value.app_mgr_keys.clone(),
+            }),
+        };
+        let item_str = serde_json::to_string(&item)?;
+        conn::set(
+            &self.conn,
+            PREFIX.to_string() + network_id,
+            item_str,
+            self.ttl,
+        )
+        .await
+    }
+
+    async fn del_uldata(&self, network_id: &str) -> Result<(), Box<dyn StdError>> {
+        let mut conn = self.conn.clone();
+        let _: () = conn.del(PREFIX.to_string() + network_id).await?;
+        Ok(())
+    }
+}
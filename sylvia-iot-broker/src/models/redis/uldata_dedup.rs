@@ -0,0 +1,72 @@
+use std::error::Error as StdError;
+
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use serde_json::{self, Map, Value};
+
+use super::{super::uldata_dedup::UlDataDedupCache, conn};
+
+pub struct Cache {
+    conn: MultiplexedConnection,
+}
+
+/// Redis schema. Use a list of JSON strings of extensions as the value and `null` for no
+/// extension.
+const PREFIX: &'static str = "broker:uldataDedup:";
+
+/// Push the extension and set the expiration only for the first copy, so duplicates do not extend
+/// the window.
+const ADD_SCRIPT: &'static str = r#"
+local count = redis.call('RPUSH', KEYS[1], ARGV[1])
+if count == 1 then
+    redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return count
+"#;
+
+impl Cache {
+    pub fn new(conn: MultiplexedConnection) -> Self {
+        Cache { conn }
+    }
+}
+
+#[async_trait]
+impl UlDataDedupCache for Cache {
+    async fn clear(&self) -> Result<(), Box<dyn StdError>> {
+        conn::del_prefix(&self.conn, PREFIX).await
+    }
+
+    async fn add(
+        &self,
+        key: &str,
+        extension: Option<&Map<String, Value>>,
+        ttl: u64,
+    ) -> Result<bool, Box<dyn StdError>> {
+        let key = PREFIX.to_string() + key;
+        let item_str = serde_json::to_string(&extension)?;
+        let mut conn = self.conn.clone();
+        let count: u64 = redis::Script::new(ADD_SCRIPT)
+            .key(key.as_str())
+            .arg(item_str)
+            .arg(ttl)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(count == 1)
+    }
+
+    async fn take(&self, key: &str) -> Result<Vec<Option<Map<String, Value>>>, Box<dyn StdError>> {
+        let key = PREFIX.to_string() + key;
+        let mut conn = self.conn.clone();
+        let (items, _): (Vec<String>, u64) = redis::pipe()
+            .atomic()
+            .lrange(key.as_str(), 0, -1)
+            .del(key.as_str())
+            .query_async(&mut conn)
+            .await?;
+        let mut extensions = vec![];
+        for item in items.iter() {
+            extensions.push(serde_json::from_str(item.as_str())?);
+        }
+        Ok(extensions)
+    }
+}
@@ -1,7 +1,7 @@
 use std::{error::Error as StdError, sync::Arc};
 
 use async_trait::async_trait;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use futures::TryStreamExt;
 use sql_builder::{SqlBuilder, quote};
 use sqlx::{AssertSqlSafe, SqlitePool};
@@ -45,6 +45,20 @@ struct Schema {
     profile: String,
     name: String,
     info: String,
+    /// i64 as time tick from Epoch in milliseconds.
+    last_uplink_at: Option<i64>,
+    /// i64 as time tick from Epoch in milliseconds.
+    last_downlink_at: Option<i64>,
+    last_extension: Option<String>,
+    /// i64 as time tick from Epoch in milliseconds.
+    offline_at: Option<i64>,
+    online: Option<bool>,
+}
+
+/// The column of `PRAGMA table_info`.
+#[derive(sqlx::FromRow)]
+struct ColumnSchema {
+    name: String,
 }
 
 /// Use "COUNT(*)" instead of "COUNT(fields...)" to simplify the implementation.
@@ -67,6 +81,11 @@ const FIELDS: &'static [&'static str] = &[
     "profile",
     "name",
     "info",
+    "last_uplink_at",
+    "last_downlink_at",
+    "last_extension",
+    "offline_at",
+    "online",
 ];
 const TABLE_INIT_SQL: &'static str = "\
     CREATE TABLE IF NOT EXISTS device (\
@@ -81,9 +100,23 @@ const TABLE_INIT_SQL: &'static str = "\
     profile TEXT NOT NULL,\
     name TEXT NOT NULL,\
     info TEXT,\
+    last_uplink_at INTEGER,\
+    last_downlink_at INTEGER,\
+    last_extension TEXT,\
+    offline_at INTEGER,\
+    online INTEGER,\
     UNIQUE (unit_code,network_code,network_addr),\
     PRIMARY KEY (device_id))";
 
+/// Columns of device states that `init()` adds to tables created by previous versions.
+const TABLE_STATE_COLUMNS: &'static [(&'static str, &'static str)] = &[
+    ("last_uplink_at", "INTEGER"),
+    ("last_downlink_at", "INTEGER"),
+    ("last_extension", "TEXT"),
+    ("offline_at", "INTEGER"),
+    ("online", "INTEGER"),
+];
+
 impl Model {
     /// To create the model instance with a database connection.
     pub async fn new(conn: Arc<SqlitePool>) -> Result<Self, Box<dyn StdError>> {
@@ -99,6 +132,22 @@ impl DeviceModel for Model {
         let _ = sqlx::query(TABLE_INIT_SQL)
             .execute(self.conn.as_ref())
             .await?;
+        let columns: Vec<ColumnSchema> =
+            sqlx::query_as("SELECT name FROM pragma_table_info('device')")
+                .fetch_all(self.conn.as_ref())
+                .await?;
+        for (name, column_type) in TABLE_STATE_COLUMNS.iter() {
+            if columns.iter().any(|column| column.name.as_str() == *name) {
+                continue;
+            }
+            let sql = format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                TABLE_NAME, name, column_type
+            );
+            let _ = sqlx::query(AssertSqlSafe(sql))
+                .execute(self.conn.as_ref())
+                .await?;
+        }
         Ok(())
     }
 
@@ -167,6 +216,23 @@ impl DeviceModel for Model {
                 profile: row.profile,
                 name: row.name,
                 info: serde_json::from_str(row.info.as_str())?,
+                last_uplink_at: match row.last_uplink_at {
+                    None => None,
+                    Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+                },
+                last_downlink_at: match row.last_downlink_at {
+                    None => None,
+                    Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+                },
+                last_extension: match row.last_extension {
+                    None => None,
+                    Some(value) => Some(serde_json::from_str(value.as_str())?),
+                },
+                offline_at: match row.offline_at {
+                    None => None,
+                    Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+                },
+                online: row.online,
             });
             if let Some(limit) = opts_limit {
                 if limit > 0 && cursor.offset() >= limit {
@@ -218,6 +284,23 @@ impl DeviceModel for Model {
             profile: row.profile,
             name: row.name,
             info: serde_json::from_str(row.info.as_str())?,
+            last_uplink_at: match row.last_uplink_at {
+                None => None,
+                Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+            },
+            last_downlink_at: match row.last_downlink_at {
+                None => None,
+                Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+            },
+            last_extension: match row.last_extension {
+                None => None,
+                Some(value) => Some(serde_json::from_str(value.as_str())?),
+            },
+            offline_at: match row.offline_at {
+                None => None,
+                Some(value) => Some(Utc.timestamp_nanos(value * 1000000)),
+            },
+            online: row.online,
         }))
     }
 
@@ -242,6 +325,30 @@ impl DeviceModel for Model {
             quote(device.profile.as_str()),
             quote(device.name.as_str()),
             info,
+            match device.last_uplink_at {
+                None => "NULL".to_string(),
+                Some(value) => value.timestamp_millis().to_string(),
+            },
+            match device.last_downlink_at {
+                None => "NULL".to_string(),
+                Some(value) => value.timestamp_millis().to_string(),
+            },
+            match device.last_extension.as_ref() {
+                None => "NULL".to_string(),
+                Some(value) => match serde_json::to_string(value) {
+                    Err(_) => quote("{}"),
+                    Ok(value) => quote(value.as_str()),
+                },
+            },
+            match device.offline_at {
+                None => "NULL".to_string(),
+                Some(value) => value.timestamp_millis().to_string(),
+            },
+            match device.online {
+                None => "NULL".to_string(),
+                Some(false) => "0".to_string(),
+                Some(true) => "1".to_string(),
+            },
         ];
         let sql = SqlBuilder::insert_into(TABLE_NAME)
             .fields(FIELDS)
@@ -279,6 +386,30 @@ impl DeviceModel for Model {
                 quote(device.profile.as_str()),
                 quote(device.name.as_str()),
                 info,
+                match device.last_uplink_at {
+                    None => "NULL".to_string(),
+                    Some(value) => value.timestamp_millis().to_string(),
+                },
+                match device.last_downlink_at {
+                    None => "NULL".to_string(),
+                    Some(value) => value.timestamp_millis().to_string(),
+                },
+                match device.last_extension.as_ref() {
+                    None => "NULL".to_string(),
+                    Some(value) => match serde_json::to_string(value) {
+                        Err(_) => quote("{}"),
+                        Ok(value) => quote(value.as_str()),
+                    },
+                },
+                match device.offline_at {
+                    None => "NULL".to_string(),
+                    Some(value) => value.timestamp_millis().to_string(),
+                },
+                match device.online {
+                    None => "NULL".to_string(),
+                    Some(false) => "0".to_string(),
+                    Some(true) => "1".to_string(),
+                },
             ]);
         }
         let sql = builder.sql()?.replace(");", ") ON CONFLICT DO NOTHING;");
@@ -311,6 +442,23 @@ impl DeviceModel for Model {
             .await?;
         Ok(())
     }
+
+    async fn set_offline(
+        &self,
+        device_id: &str,
+        offline_at: DateTime<Utc>,
+    ) -> Result<bool, Box<dyn StdError>> {
+        let sql = SqlBuilder::update_table(TABLE_NAME)
+            .set("online", "0")
+            .and_where_eq("device_id", quote(device_id))
+            .and_where_eq("online", "1")
+            .and_where_eq("offline_at", offline_at.timestamp_millis())
+            .sql()?;
+        let result = sqlx::query(AssertSqlSafe(sql))
+            .execute(self.conn.as_ref())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 impl DbCursor {
@@ -388,6 +536,15 @@ fn build_list_where<'a>(
     if let Some(value) = cond.name_contains {
         build_where_like(builder, "name", value.to_lowercase().as_str());
     }
+    if let Some(value) = cond.online {
+        match value {
+            false => builder.and_where_eq("online", "0"),
+            true => builder.and_where_eq("online", "1"),
+        };
+    }
+    if let Some(value) = cond.offline_at_lte {
+        builder.and_where_le("offline_at", value.timestamp_millis());
+    }
     builder
 }
 
@@ -465,6 +622,50 @@ fn build_update_where<'a>(
         }
         count += 1;
     }
+    if let Some(value) = updates.last_uplink_at.as_ref() {
+        builder.set("last_uplink_at", value.timestamp_millis());
+        count += 1;
+    }
+    if let Some(value) = updates.last_downlink_at.as_ref() {
+        builder.set("last_downlink_at", value.timestamp_millis());
+        count += 1;
+    }
+    if let Some(value) = updates.last_extension {
+        match serde_json::to_string(value) {
+            Err(_) => {
+                builder.set("last_extension", quote("{}"));
+            }
+            Ok(value) => {
+                builder.set("last_extension", quote(value));
+            }
+        }
+        count += 1;
+    }
+    if let Some(value) = updates.offline_at.as_ref() {
+        match value {
+            None => {
+                builder.set("offline_at", "NULL");
+            }
+            Some(value) => {
+                builder.set("offline_at", value.timestamp_millis());
+            }
+        }
+        count += 1;
+    }
+    if let Some(value) = updates.online.as_ref() {
+        match value {
+            None => {
+                builder.set("online", "NULL");
+            }
+            Some(false) => {
+                builder.set("online", "0");
+            }
+            Some(true) => {
+                builder.set("online", "1");
+            }
+        }
+        count += 1;
+    }
     if count == 0 {
         return None;
     }
@@ -236,6 +236,16 @@ impl DlDataBufferModel for Model {
             .await?;
         Ok(())
     }
+
+    async fn del_one(&self, data_id: &str) -> Result<bool, Box<dyn StdError>> {
+        let sql = SqlBuilder::delete_from(TABLE_NAME)
+            .and_where_eq("data_id", quote(data_id))
+            .sql()?;
+        let result = sqlx::query(AssertSqlSafe(sql))
+            .execute(self.conn.as_ref())
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 impl DbCursor {
@@ -298,6 +308,9 @@ fn build_list_where<'a>(
     if let Some(value) = cond.device_id {
         builder.and_where_eq("device_id", quote(value));
     }
+    if let Some(value) = cond.expired_at_lte {
+        builder.and_where_le("expired_at", value.timestamp_millis());
+    }
     builder
 }
 
@@ -14,6 +14,7 @@ pub mod device_route;
 pub mod dldata_buffer;
 pub mod network;
 pub mod network_route;
+pub mod profile;
 pub mod unit;
 
 fn build_where_like<S, T>(builder: &mut SqlBuilder, field: S, mask: T) -> &mut SqlBuilder
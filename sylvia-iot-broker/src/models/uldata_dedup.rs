@@ -0,0 +1,30 @@
+//! Traits and structs for uplink data deduplication.
+
+use std::error::Error as StdError;
+
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+
+/// Cache operations.
+///
+/// Each item collects the `extension` of all copies of one uplink data that arrive in the
+/// deduplication window.
+#[async_trait]
+pub trait UlDataDedupCache: Sync {
+    /// To clear all items.
+    async fn clear(&self) -> Result<(), Box<dyn StdError>>;
+
+    /// To add the `extension` of one copy. The item expires `ttl` milliseconds after the first copy.
+    ///
+    /// Returns `true` for the first copy. Other copies will be merged into the first one.
+    async fn add(
+        &self,
+        key: &str,
+        extension: Option<&Map<String, Value>>,
+        ttl: u64,
+    ) -> Result<bool, Box<dyn StdError>>;
+
+    /// To get the `extension` of all copies in the arrival order and remove the item. Copies that
+    /// arrive later will be added as a new item.
+    async fn take(&self, key: &str) -> Result<Vec<Option<Map<String, Value>>>, Box<dyn StdError>>;
+}
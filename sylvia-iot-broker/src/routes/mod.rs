@@ -6,26 +6,37 @@ use std::{
 };
 
 use axum::Router;
+use chrono::{DateTime, Utc};
 use reqwest;
 use url::Url;
 
 use async_trait::async_trait;
 use log::{error, info, warn};
+use tokio::{
+    sync::{mpsc, watch},
+    task::{self, JoinHandle},
+};
 
 use general_mq::{
-    Queue,
+    Compression, OutboxOptions, Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Status},
 };
 use sylvia_iot_corelib::constants::{CacheEngine, DbEngine};
 
 use crate::{
     libs::{
+        codec::CodecCache,
         config::{self, Config},
-        mq::{self, Connection, application::ApplicationMgr, network::NetworkMgr},
+        device_state::StateBuffer,
+        mq::{
+            self, Connection,
+            application::{ApplicationMgr, UlData as ApplicationUlData},
+            network::NetworkMgr,
+        },
     },
     models::{
         self, Cache, CacheConnOptions, ConnOptions, DeviceOptions, DeviceRouteOptions, Model,
-        MongoDbOptions, NetworkRouteOptions, SqliteOptions,
+        MongoDbOptions, NetworkRouteOptions, PostgresOptions, RedisOptions, SqliteOptions,
     },
 };
 
@@ -47,6 +58,10 @@ pub struct State {
     pub model: Arc<dyn Model>,
     /// The database cache.
     pub cache: Option<Arc<dyn Cache>>,
+    /// The codec cache of device profiles.
+    pub codecs: Arc<CodecCache>,
+    /// The buffer of device states that are written to the database periodically.
+    pub device_states: Arc<StateBuffer>,
     /// The sylvia-iot-auth base API path with host.
     ///
     /// For example, `http://localhost:1080/auth`.
@@ -54,6 +69,36 @@ pub struct State {
     pub amqp_prefetch: u16,
     pub amqp_persistent: bool,
     pub mqtt_shared_prefix: String,
+    /// Move failed data to dead-letter queues.
+    pub mq_dead_letter: bool,
+    /// AMQP arguments of data queues.
+    pub amqp_args: mq::AmqpQueueArgs,
+    /// TLS options of `amqps` and `mqtts` connections.
+    pub mq_tls: Option<TlsOptions>,
+    /// The reconnect policy of connections and queues.
+    pub mq_reconnect: Option<ReconnectPolicy>,
+    /// The outbox of queues that are sent by application/network managers.
+    pub mq_outbox: Option<OutboxOptions>,
+    /// The compression algorithm of data that are sent by application/network managers.
+    pub mq_compression: Compression,
+    /// Default expiration in seconds of downlink data.
+    pub dldata_expires_in: i64,
+    /// Maximum expiration in seconds of downlink data.
+    pub dldata_max_expires_in: i64,
+    /// Interval in seconds to sweep expired downlink data buffers.
+    pub dldata_sweep_interval: u64,
+    /// Maximum number of retries to send uplink data to applications.
+    pub uldata_retry_limit: u64,
+    /// Delay in milliseconds before the first uplink data retry.
+    pub uldata_retry_delay: u64,
+    /// Queues of uplink data to retry sending to applications.
+    pub uldata_retry_queues: UlDataRetryQueues,
+    /// Window in milliseconds to deduplicate uplink data. Zero disables deduplication.
+    pub uldata_dedup_window: u64,
+    /// Interval in seconds to write buffered device states.
+    pub device_flush_interval: u64,
+    /// Interval in seconds to sweep devices that become offline.
+    pub device_sweep_interval: u64,
     /// The client for internal HTTP requests.
     pub client: reqwest::Client,
     /// Queue connections. Key is uri.
@@ -68,6 +113,35 @@ pub struct State {
     pub ctrl_senders: CtrlSenders,
     /// Data channel sender.
     pub data_sender: Option<Queue>,
+    /// Background tasks such as sweepers.
+    pub tasks: Tasks,
+}
+
+/// Background tasks that are stopped before the service shuts down.
+#[derive(Clone)]
+pub struct Tasks {
+    stop: Arc<watch::Sender<bool>>,
+    handles: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+/// Bounded queues of uplink data to retry sending to applications. Each application has one queue
+/// and one task to retry data in order. Key is `[unit-code].[application-code]`.
+#[derive(Clone, Default)]
+pub struct UlDataRetryQueues {
+    pub(crate) queues: Arc<Mutex<HashMap<String, mpsc::Sender<UlDataRetry>>>>,
+}
+
+/// Uplink data that failed to send to an application.
+pub(crate) struct UlDataRetry {
+    /// The time when the broker received the data.
+    pub proc: DateTime<Utc>,
+    /// The unit code of the network manager.
+    pub netmgr_unit_code: Option<String>,
+    /// The unit ID of the application manager.
+    pub app_unit_id: String,
+    pub data: ApplicationUlData,
+    /// The last error of sending the data.
+    pub error: String,
 }
 
 /// Control channel senders.
@@ -97,6 +171,7 @@ impl ErrReq {
     pub const NETWORK_EXIST: (u16, &'static str) = (400, "err_broker_network_exist");
     pub const NETWORK_NOT_EXIST: (u16, &'static str) = (400, "err_broker_network_not_exist");
     pub const OWNER_NOT_EXIST: (u16, &'static str) = (400, "err_broker_owner_not_exist");
+    pub const PROFILE_EXIST: (u16, &'static str) = (400, "err_broker_profile_exist");
     pub const ROUTE_EXIST: (u16, &'static str) = (400, "err_broker_route_exist");
     pub const UNIT_EXIST: (u16, &'static str) = (400, "err_broker_unit_exist");
     pub const UNIT_NOT_EXIST: (u16, &'static str) = (400, "err_broker_unit_not_exist");
@@ -136,6 +211,13 @@ pub async fn new_state(
                 pool_size: conf.pool_size,
             })
         }
+        DbEngine::POSTGRESQL => {
+            let conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+            ConnOptions::Postgres(PostgresOptions {
+                url: conf.url.as_ref().unwrap().to_string(),
+                pool_size: conf.pool_size,
+            })
+        }
         _ => {
             let conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
             ConnOptions::Sqlite(SqliteOptions {
@@ -162,24 +244,54 @@ pub async fn new_state(
                     },
                 })
             }
+            CacheEngine::REDIS => {
+                let conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+                Some(CacheConnOptions::Redis {
+                    conn: RedisOptions {
+                        url: conf.url.as_ref().unwrap().to_string(),
+                    },
+                    ttl: conf.ttl.unwrap(),
+                })
+            }
             _ => None,
         },
     };
     let mq_conf = conf.mq.as_ref().unwrap();
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    let device_conf = conf.device.as_ref().unwrap();
     let model = models::new(&db_opts).await?;
     let cache = match cache_opts {
         None => None,
         Some(opts) => Some(models::new_cache(&opts, &model).await?),
     };
+    if uldata_conf.dedup_window.unwrap() > 0 && cache.is_none() {
+        warn!("[new_state] uplink data deduplication is disabled without the cache");
+    }
+    let codecs = Arc::new(CodecCache::new(model.clone()));
+    let device_states = Arc::new(StateBuffer::new(model.clone()));
     let auth_base = conf.auth.as_ref().unwrap().clone();
     let mq_conns = Arc::new(Mutex::new(HashMap::new()));
     let ch_conf = conf.mq_channels.as_ref().unwrap();
-    let ctrl_senders = new_ctrl_senders(&mq_conns, &ch_conf, cache.clone())?;
+    let mq_tls = new_mq_tls(mq_conf);
+    let mq_reconnect = new_mq_reconnect(mq_conf);
+    let ctrl_senders = new_ctrl_senders(
+        &mq_conns,
+        &ch_conf,
+        mq_tls.as_ref(),
+        mq_reconnect.as_ref(),
+        cache.clone(),
+    )?;
     let data_sender = match ch_conf.data.as_ref() {
         None => None,
         Some(conf) => match conf.url.as_ref() {
             None => None,
-            Some(_) => Some(new_data_sender(&mq_conns, conf)?),
+            Some(_) => Some(new_data_sender(
+                &mq_conns,
+                conf,
+                mq_tls.as_ref(),
+                mq_reconnect.as_ref(),
+            )?),
         },
     };
     let state = State {
@@ -190,10 +302,27 @@ pub async fn new_state(
         api_scopes: conf.api_scopes.as_ref().unwrap().clone(),
         model,
         cache,
+        codecs,
+        device_states,
         auth_base,
         amqp_prefetch: mq_conf.prefetch.unwrap(),
         amqp_persistent: mq_conf.persistent.unwrap(),
         mqtt_shared_prefix: mq_conf.shared_prefix.as_ref().unwrap().to_string(),
+        mq_dead_letter: mq_conf.dead_letter.unwrap(),
+        amqp_args: new_amqp_args(mq_conf),
+        mq_tls,
+        mq_reconnect,
+        mq_outbox: new_mq_outbox(mq_conf),
+        mq_compression: new_mq_compression(mq_conf),
+        dldata_expires_in: dldata_conf.expires_in.unwrap() as i64,
+        dldata_max_expires_in: dldata_conf.max_expires_in.unwrap() as i64,
+        dldata_sweep_interval: dldata_conf.sweep_interval.unwrap(),
+        uldata_retry_limit: uldata_conf.retry_limit.unwrap(),
+        uldata_retry_delay: uldata_conf.retry_delay.unwrap(),
+        uldata_retry_queues: UlDataRetryQueues::default(),
+        uldata_dedup_window: uldata_conf.dedup_window.unwrap(),
+        device_flush_interval: device_conf.flush_interval.unwrap(),
+        device_sweep_interval: device_conf.sweep_interval.unwrap(),
         client: reqwest::Client::new(),
         mq_conns,
         application_mgrs: Arc::new(Mutex::new(HashMap::new())),
@@ -201,6 +330,7 @@ pub async fn new_state(
         ctrl_receivers: Arc::new(Mutex::new(HashMap::new())),
         ctrl_senders,
         data_sender,
+        tasks: Tasks::default(),
     };
     let (r1, r2, r3, r4, r5, r6) = tokio::join!(
         v1::unit::init(&state, &ch_conf.unit.as_ref().unwrap()),
@@ -216,9 +346,44 @@ pub async fn new_state(
     r4?;
     r5?;
     r6?;
+    v1::dldata_buffer::init(&state);
+    v1::device::init_state(&state);
     Ok(state)
 }
 
+impl Default for Tasks {
+    fn default() -> Self {
+        Tasks {
+            stop: Arc::new(watch::channel(false).0),
+            handles: Arc::new(Mutex::new(vec![])),
+        }
+    }
+}
+
+impl Tasks {
+    /// To spawn a task with the receiver that is changed when tasks are stopped. Tasks should
+    /// return after the current work is done. Handles of finished tasks are removed.
+    pub fn spawn<F, Fut>(&self, f: F)
+    where
+        F: FnOnce(watch::Receiver<bool>) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = task::spawn(f(self.stop.subscribe()));
+        let mut handles = self.handles.lock().unwrap();
+        handles.retain(|handle| !handle.is_finished());
+        handles.push(handle);
+    }
+
+    /// To stop all tasks and wait for them.
+    pub async fn stop(&self) {
+        let _ = self.stop.send(true);
+        let handles = { self.handles.lock().unwrap().drain(..).collect::<Vec<_>>() };
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+}
+
 /// To register service URIs in the specified root path.
 pub fn new_service(state: &State) -> Router {
     Router::new().nest(
@@ -236,13 +401,16 @@ pub fn new_service(state: &State) -> Router {
             .merge(v1::dldata_buffer::new_service(
                 "/api/v1/dldata-buffer",
                 state,
-            )),
+            ))
+            .merge(v1::profile::new_service("/api/v1/profile", state)),
     )
 }
 
 pub fn new_ctrl_senders(
     mq_conns: &Arc<Mutex<HashMap<String, Connection>>>,
     ch_conf: &config::MqChannels,
+    mq_tls: Option<&TlsOptions>,
+    mq_reconnect: Option<&ReconnectPolicy>,
     cache: Option<Arc<dyn Cache>>,
 ) -> Result<CtrlSenders, Box<dyn StdError>> {
     let unit_ctrl_cfg = ch_conf.unit.as_ref().unwrap();
@@ -253,12 +421,109 @@ pub fn new_ctrl_senders(
     let netr_ctrl_cfg = ch_conf.network_route.as_ref().unwrap();
 
     Ok(CtrlSenders {
-        unit: v1::unit::new_ctrl_sender(mq_conns, unit_ctrl_cfg)?,
-        application: v1::application::new_ctrl_sender(mq_conns, app_ctrl_cfg)?,
-        network: v1::network::new_ctrl_sender(mq_conns, net_ctrl_cfg, cache.clone())?,
-        device: v1::device::new_ctrl_sender(mq_conns, dev_ctrl_cfg, cache.clone())?,
-        device_route: v1::device_route::new_ctrl_sender(mq_conns, devr_ctrl_cfg, cache.clone())?,
-        network_route: v1::network_route::new_ctrl_sender(mq_conns, netr_ctrl_cfg, cache.clone())?,
+        unit: v1::unit::new_ctrl_sender(mq_conns, unit_ctrl_cfg, mq_tls, mq_reconnect)?,
+        application: v1::application::new_ctrl_sender(
+            mq_conns,
+            app_ctrl_cfg,
+            mq_tls,
+            mq_reconnect,
+        )?,
+        network: v1::network::new_ctrl_sender(
+            mq_conns,
+            net_ctrl_cfg,
+            mq_tls,
+            mq_reconnect,
+            cache.clone(),
+        )?,
+        device: v1::device::new_ctrl_sender(
+            mq_conns,
+            dev_ctrl_cfg,
+            mq_tls,
+            mq_reconnect,
+            cache.clone(),
+        )?,
+        device_route: v1::device_route::new_ctrl_sender(
+            mq_conns,
+            devr_ctrl_cfg,
+            mq_tls,
+            mq_reconnect,
+            cache.clone(),
+        )?,
+        network_route: v1::network_route::new_ctrl_sender(
+            mq_conns,
+            netr_ctrl_cfg,
+            mq_tls,
+            mq_reconnect,
+            cache.clone(),
+        )?,
+    })
+}
+
+/// To get TLS options of message queue connections. Returns `None` to use the default options if
+/// no TLS option is configured.
+pub fn new_mq_tls(mq_conf: &config::Mq) -> Option<TlsOptions> {
+    let tls = mq_conf.tls.as_ref()?;
+    TlsOptions::from_config(
+        tls.cacert_file.clone(),
+        tls.cert_file.clone(),
+        tls.key_file.clone(),
+        tls.server_name.clone(),
+        tls.insecure.unwrap_or(config::DEF_MQ_TLS_INSECURE),
+    )
+}
+
+/// To get the reconnect policy with jitter of message queue connections and queues. Returns `None`
+/// to reconnect with fixed delays if no reconnect option is configured.
+pub fn new_mq_reconnect(mq_conf: &config::Mq) -> Option<ReconnectPolicy> {
+    let reconnect = mq_conf.reconnect.as_ref()?;
+    Some(ReconnectPolicy {
+        initial_millis: reconnect
+            .initial_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_INITIAL_MILLIS),
+        max_millis: reconnect
+            .max_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_MAX_MILLIS),
+        ..Default::default()
+    })
+}
+
+/// To get AMQP arguments of data queues of application/network managers.
+pub fn new_amqp_args(mq_conf: &config::Mq) -> mq::AmqpQueueArgs {
+    mq::AmqpQueueArgs {
+        queue_type: mq_conf.queue_type.clone(),
+        message_ttl: mq_conf.message_ttl.filter(|v| *v > 0),
+        max_length: mq_conf.max_length.filter(|v| *v > 0),
+        overflow: mq_conf.overflow.clone(),
+    }
+}
+
+/// To get the compression algorithm of application/network managers.
+pub fn new_mq_compression(mq_conf: &config::Mq) -> Compression {
+    match mq_conf.compression.as_deref() {
+        Some("gzip") => Compression::Gzip,
+        Some("zstd") => Compression::Zstd,
+        _ => Compression::None,
+    }
+}
+
+/// To get outbox options of application/network managers. Returns `None` if the outbox path is
+/// not configured.
+pub fn new_mq_outbox(mq_conf: &config::Mq) -> Option<OutboxOptions> {
+    let outbox = mq_conf.outbox.as_ref()?;
+    let path = match outbox.path.as_ref() {
+        None => return None,
+        Some(path) => match path.len() {
+            0 => return None,
+            _ => path.clone(),
+        },
+    };
+    Some(OutboxOptions {
+        path,
+        max_messages: outbox
+            .max_messages
+            .unwrap_or(config::DEF_MQ_OUTBOX_MAX_MESSAGES),
+        max_bytes: outbox.max_bytes.unwrap_or(config::DEF_MQ_OUTBOX_MAX_BYTES),
+        max_age_millis: outbox.max_age.unwrap_or(config::DEF_MQ_OUTBOX_MAX_AGE),
     })
 }
 
@@ -266,6 +531,8 @@ pub fn new_ctrl_senders(
 pub fn new_data_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &config::BrokerData,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Queue, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
         None => {
@@ -284,7 +551,14 @@ pub fn new_data_sender(
         Some(persistent) => persistent,
     };
 
-    match mq::data::new(conn_pool, &url, persistent, Arc::new(DataSenderHandler {})) {
+    match mq::data::new(
+        conn_pool,
+        &url,
+        persistent,
+        tls,
+        reconnect,
+        Arc::new(DataSenderHandler {}),
+    ) {
         Err(e) => Err(Box::new(IoError::new(ErrorKind::InvalidInput, e))),
         Ok(q) => Ok(q),
     }
@@ -14,7 +14,7 @@ use axum::{
     http::{StatusCode, header},
     response::IntoResponse,
 };
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Map, Value};
@@ -22,7 +22,7 @@ use tokio::time;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Compression, OutboxOptions, Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::{
@@ -42,7 +42,9 @@ use super::{
 };
 use crate::{
     libs::{
+        codec::CodecCache,
         config::BrokerCtrl as CfgCtrl,
+        device_state::StateBuffer,
         mq::{
             self, Connection, MgrStatus, Options as MgrOptions,
             application::{ApplicationMgr, DlData, DlDataResp, EventHandler},
@@ -62,8 +64,12 @@ use crate::{
 struct MgrHandler {
     model: Arc<dyn Model>,
     cache: Option<Arc<dyn Cache>>,
+    codecs: Arc<CodecCache>,
+    device_states: Arc<StateBuffer>,
     network_mgrs: Arc<Mutex<HashMap<String, NetworkMgr>>>,
     data_sender: Option<Queue>,
+    dldata_expires_in: i64,
+    dldata_max_expires_in: i64,
 }
 
 #[derive(Deserialize)]
@@ -173,10 +179,18 @@ struct CtrlSenderHandler;
 struct CtrlReceiverHandler {
     model: Arc<dyn Model>,
     cache: Option<Arc<dyn Cache>>,
+    codecs: Arc<CodecCache>,
+    device_states: Arc<StateBuffer>,
     mq_conns: Arc<Mutex<HashMap<String, Connection>>>,
+    mq_tls: Option<TlsOptions>,
+    mq_reconnect: Option<ReconnectPolicy>,
+    mq_outbox: Option<OutboxOptions>,
+    mq_compression: Compression,
     application_mgrs: Arc<Mutex<HashMap<String, ApplicationMgr>>>,
     network_mgrs: Arc<Mutex<HashMap<String, NetworkMgr>>>,
     data_sender: Option<Queue>,
+    dldata_expires_in: i64,
+    dldata_max_expires_in: i64,
 }
 
 impl CtrlMsgOp {
@@ -194,7 +208,10 @@ const LIST_LIMIT_DEFAULT: u64 = 100;
 const LIST_CURSOR_MAX: u64 = 100;
 const ID_RAND_LEN: usize = 8;
 const DATA_ID_RAND_LEN: usize = 12;
-const DATA_EXPIRES_IN: i64 = 86400; // in seconds
+/// The `info` key of the default downlink data expiration in seconds of the application.
+const INFO_DLDATA_EXPIRES_IN: &'static str = "dldataExpiresIn";
+/// The `info` key to enable the `device-status` queue of the application.
+const INFO_DEVICE_STATUS: &'static str = "deviceStatus";
 const CTRL_QUEUE_NAME: &'static str = "application";
 const DEF_DLDATA_STATUS: i32 = -2;
 
@@ -265,12 +282,23 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
                 prefetch: Some(state.amqp_prefetch),
                 persistent: state.amqp_persistent,
                 shared_prefix: Some(state.mqtt_shared_prefix.clone()),
+                dead_letter: state.mq_dead_letter,
+                tls: state.mq_tls.clone(),
+                reconnect_policy: state.mq_reconnect.clone(),
+                outbox: state.mq_outbox.clone(),
+                compression: state.mq_compression,
+                amqp_args: state.amqp_args.clone(),
+                device_status: device_status_enabled(&item.info),
             };
             let handler = MgrHandler {
                 model: state.model.clone(),
                 cache: state.cache.clone(),
+                codecs: state.codecs.clone(),
+                device_states: state.device_states.clone(),
                 network_mgrs: state.network_mgrs.clone(),
                 data_sender: state.data_sender.clone(),
+                dldata_expires_in: state.dldata_expires_in,
+                dldata_max_expires_in: state.dldata_max_expires_in,
             };
             let mgr =
                 match ApplicationMgr::new(state.mq_conns.clone(), &url, opts, Arc::new(handler)) {
@@ -300,6 +328,8 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
 pub fn new_ctrl_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &CfgCtrl,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Arc<Mutex<Queue>>, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
         None => {
@@ -318,6 +348,8 @@ pub fn new_ctrl_sender(
         conn_pool.clone(),
         &url,
         config.prefetch,
+        tls,
+        reconnect,
         CTRL_QUEUE_NAME,
         false,
         Arc::new(CtrlSenderHandler {}),
@@ -345,15 +377,25 @@ pub fn new_ctrl_receiver(state: &AppState, config: &CfgCtrl) -> Result<Queue, Bo
     let handler = Arc::new(CtrlReceiverHandler {
         model: state.model.clone(),
         cache: state.cache.clone(),
+        codecs: state.codecs.clone(),
+        device_states: state.device_states.clone(),
         mq_conns: state.mq_conns.clone(),
+        mq_tls: state.mq_tls.clone(),
+        mq_reconnect: state.mq_reconnect.clone(),
+        mq_outbox: state.mq_outbox.clone(),
+        mq_compression: state.mq_compression,
         application_mgrs: state.application_mgrs.clone(),
         network_mgrs: state.network_mgrs.clone(),
         data_sender: state.data_sender.clone(),
+        dldata_expires_in: state.dldata_expires_in,
+        dldata_max_expires_in: state.dldata_max_expires_in,
     });
     match mq::control::new(
         state.mq_conns.clone(),
         &url,
         config.prefetch,
+        state.mq_tls.as_ref(),
+        state.mq_reconnect.as_ref(),
         CTRL_QUEUE_NAME,
         true,
         handler.clone(),
@@ -394,13 +436,7 @@ pub async fn post_application(
         },
     };
     if let Some(info) = body.data.info.as_ref() {
-        for (k, _) in info.iter() {
-            if k.len() == 0 {
-                return Err(ErrResp::ErrParam(Some(
-                    "`info` key must not be empty".to_string(),
-                )));
-            }
-        }
+        check_info(info)?;
     }
     let unit_id = body.data.unit_id.as_str();
     if unit_id.len() == 0 {
@@ -452,10 +488,8 @@ pub async fn post_application(
         FN_NAME,
         &state,
         &host_uri,
-        unit_id,
-        unit_code.as_str(),
-        application.application_id.as_str(),
-        application.code.as_str(),
+        &application,
+        device_status_enabled(&application.info),
     )
     .await?;
     Ok(Json(response::PostApplication {
@@ -725,15 +759,24 @@ pub async fn patch_application(
 
     let updates = get_updates(&mut body.data).await?;
     let mut should_add_mgr = false;
+    let device_status = match updates.info {
+        None => device_status_enabled(&application.info),
+        Some(info) => device_status_enabled(info),
+    };
 
     // Remove old manager.
     if let Some(host_uri) = updates.host_uri {
         let uri = Url::parse(host_uri).unwrap();
         if !uri.as_str().eq(application.host_uri.as_str()) {
-            delete_manager(FN_NAME, &state, &application).await?;
             should_add_mgr = true;
         }
     }
+    if device_status != device_status_enabled(&application.info) {
+        should_add_mgr = true;
+    }
+    if should_add_mgr {
+        delete_manager(FN_NAME, &state, &application).await?;
+    }
 
     // Update database.
     let cond = UpdateQueryCond { application_id };
@@ -744,19 +787,12 @@ pub async fn patch_application(
 
     // Add new manager.
     if should_add_mgr {
-        if let Some(host_uri) = updates.host_uri {
-            let uri = Url::parse(host_uri).unwrap();
-            add_manager(
-                FN_NAME,
-                &state,
-                &uri,
-                application.unit_id.as_str(),
-                application.unit_code.as_str(),
-                application.application_id.as_str(),
-                application.code.as_str(),
-            )
-            .await?;
-        }
+        let host_uri = match updates.host_uri {
+            None => application.host_uri.as_str(),
+            Some(host_uri) => host_uri,
+        };
+        let uri = Url::parse(host_uri).unwrap();
+        add_manager(FN_NAME, &state, &uri, &application, device_status).await?;
     }
     Ok(StatusCode::NO_CONTENT)
 }
@@ -870,13 +906,7 @@ async fn get_updates<'a>(
         count += 1;
     }
     if let Some(info) = body.info.as_ref() {
-        for (k, _) in info.iter() {
-            if k.len() == 0 {
-                return Err(ErrResp::ErrParam(Some(
-                    "`info` key must not be empty".to_string(),
-                )));
-            }
-        }
+        check_info(info)?;
         updates.info = Some(info);
         count += 1;
     }
@@ -890,6 +920,44 @@ async fn get_updates<'a>(
     Ok(updates)
 }
 
+/// To check `info` keys and the reserved `dldataExpiresIn` and `deviceStatus` values.
+fn check_info(info: &Map<String, Value>) -> Result<(), ErrResp> {
+    for (k, _) in info.iter() {
+        if k.len() == 0 {
+            return Err(ErrResp::ErrParam(Some(
+                "`info` key must not be empty".to_string(),
+            )));
+        }
+    }
+    if let Some(expires_in) = info.get(INFO_DLDATA_EXPIRES_IN) {
+        match expires_in.as_i64() {
+            Some(v) if v > 0 => (),
+            _ => {
+                return Err(ErrResp::ErrParam(Some(format!(
+                    "`info.{}` must be a positive integer",
+                    INFO_DLDATA_EXPIRES_IN
+                ))));
+            }
+        }
+    }
+    if let Some(device_status) = info.get(INFO_DEVICE_STATUS) {
+        if !device_status.is_boolean() {
+            return Err(ErrResp::ErrParam(Some(format!(
+                "`info.{}` must be a boolean",
+                INFO_DEVICE_STATUS
+            ))));
+        }
+    }
+    Ok(())
+}
+
+/// To check if the application enables the `device-status` queue with `info.deviceStatus`.
+fn device_status_enabled(info: &Map<String, Value>) -> bool {
+    info.get(INFO_DEVICE_STATUS)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
 /// To check if the application code is used by the unit.
 ///
 /// # Errors
@@ -1077,19 +1145,25 @@ async fn add_manager(
     fn_name: &str,
     state: &AppState,
     host_uri: &Url,
-    unit_id: &str,
-    unit_code: &str,
-    id: &str,
-    name: &str,
+    application: &Application,
+    device_status: bool,
 ) -> Result<(), ErrResp> {
+    let name = application.code.as_str();
     let opts = MgrOptions {
-        unit_id: unit_id.to_string(),
-        unit_code: unit_code.to_string(),
-        id: id.to_string(),
-        name: name.to_string(),
+        unit_id: application.unit_id.clone(),
+        unit_code: application.unit_code.clone(),
+        id: application.application_id.clone(),
+        name: application.code.clone(),
         prefetch: Some(state.amqp_prefetch),
         persistent: state.amqp_persistent,
         shared_prefix: Some(state.mqtt_shared_prefix.clone()),
+        dead_letter: state.mq_dead_letter,
+        tls: state.mq_tls.clone(),
+        reconnect_policy: state.mq_reconnect.clone(),
+        outbox: state.mq_outbox.clone(),
+        compression: state.mq_compression,
+        amqp_args: state.amqp_args.clone(),
+        device_status,
     };
     let msg = SendCtrlMsg::AddManager {
         operation: CtrlMsgOp::ADD_MANAGER.to_string(),
@@ -1149,6 +1223,83 @@ async fn delete_manager(
 }
 
 impl MgrHandler {
+    /// Get the downlink data expiration in seconds by the following order and limit it with the
+    /// maximum value:
+    /// - `expiresIn` of the data.
+    /// - `info.dldataExpiresIn` of the application.
+    /// - the default value of the broker.
+    async fn get_expires_in(&self, mgr: &ApplicationMgr, data: &Box<DlData>) -> i64 {
+        const FN_NAME: &'static str = "get_expires_in";
+
+        let expires_in = match data.expires_in {
+            Some(expires_in) => expires_in,
+            None => {
+                let cond = QueryCond {
+                    application_id: Some(mgr.id()),
+                    ..Default::default()
+                };
+                match self.model.application().get(&cond).await {
+                    Err(e) => {
+                        warn!("[{}] get application {} error: {}", FN_NAME, mgr.id(), e);
+                        self.dldata_expires_in
+                    }
+                    Ok(application) => match application
+                        .as_ref()
+                        .and_then(|a| a.info.get(INFO_DLDATA_EXPIRES_IN))
+                        .and_then(|v| v.as_i64())
+                    {
+                        Some(expires_in) if expires_in > 0 => expires_in,
+                        _ => self.dldata_expires_in,
+                    },
+                }
+            }
+        };
+        expires_in.min(self.dldata_max_expires_in)
+    }
+
+    /// To encode the object with the codec of the device profile.
+    async fn encode_dldata(
+        &self,
+        mgr: &ApplicationMgr,
+        profile: &str,
+        data: &Box<DlData>,
+        object: &Map<String, Value>,
+    ) -> Result<String, Box<DlDataResp>> {
+        const FN_NAME: &'static str = "encode_dldata";
+
+        let codec = match self.codecs.get(mgr.unit_id(), profile).await {
+            Err(e) => {
+                error!("[{}] get codec of {} error: {}", FN_NAME, profile, e);
+                return Err(Box::new(DlDataResp {
+                    correlation_id: data.correlation_id.clone(),
+                    error: Some(err::E_DB.to_string()),
+                    message: Some(format!("{}", e)),
+                    ..Default::default()
+                }));
+            }
+            Ok(codec) => codec,
+        };
+        let result = match codec {
+            None => Ok(None),
+            Some(codec) => codec.encode(object),
+        };
+        match result {
+            Err(e) => Err(Box::new(DlDataResp {
+                correlation_id: data.correlation_id.clone(),
+                error: Some(err::E_PARAM.to_string()),
+                message: Some(format!("invalid `object`: {}", e)),
+                ..Default::default()
+            })),
+            Ok(None) => Err(Box::new(DlDataResp {
+                correlation_id: data.correlation_id.clone(),
+                error: Some(err::E_PARAM.to_string()),
+                message: Some(format!("no codec for profile `{}`", profile)),
+                ..Default::default()
+            })),
+            Ok(Some(hex)) => Ok(hex),
+        }
+    }
+
     /// Get device route information from cache or database. This function handles two cases:
     /// - with `network_code` and `network_addr` for private network devices.
     /// - with `device_id` for both private and public network devices.
@@ -1378,8 +1529,8 @@ impl MgrHandler {
 #[async_trait]
 impl EventHandler for MgrHandler {
     async fn on_status_change(&self, mgr: &ApplicationMgr, status: MgrStatus) {
-        // Clear cache when manager status changed.
-        if let Some(cache) = self.cache.as_ref() {
+        // Clear cache when manager status changed. Shared caches expire by TTL instead.
+        if let Some(cache) = self.cache.as_ref().filter(|c| !c.is_shared()) {
             if let Err(e) = cache.device().clear().await {
                 error!(
                     "[on_status_change] {}.{} clear device cache error: {}",
@@ -1441,6 +1592,20 @@ impl EventHandler for MgrHandler {
             Ok(route) => route,
         };
 
+        // Encode the object if the application does not send raw data.
+        let mut data = data;
+        if data.data.len() == 0 {
+            if let Some(object) = data.object.take() {
+                data.data = match self
+                    .encode_dldata(mgr, dldata_route.profile.as_str(), &data, &object)
+                    .await
+                {
+                    Err(e) => return Ok(e),
+                    Ok(hex) => hex,
+                };
+            }
+        }
+
         let now = Utc::now();
         let data_id = strings::random_id(&now, DATA_ID_RAND_LEN);
 
@@ -1472,19 +1637,24 @@ impl EventHandler for MgrHandler {
             }
         };
 
-        let ts_nanos = match now.timestamp_nanos_opt() {
+        let expires_in = self.get_expires_in(mgr, &data).await;
+        let expired_at = match TimeDelta::try_seconds(expires_in)
+            .and_then(|expires_in| now.checked_add_signed(expires_in))
+        {
             None => {
-                error!("[{}] cannot generate valid nanoseconds", FN_NAME);
+                error!(
+                    "[{}] cannot generate valid expiration with {} seconds",
+                    FN_NAME, expires_in
+                );
                 return Ok(Box::new(DlDataResp {
                     correlation_id: data.correlation_id.clone(),
                     error: Some(err::E_RSC.to_string()),
-                    message: Some(format!("cannot generate valid nanoseconds")),
+                    message: Some(format!("cannot generate valid expiration")),
                     ..Default::default()
                 }));
             }
-            Some(ts) => ts,
+            Some(expired_at) => expired_at,
         };
-        let expired_at = Utc.timestamp_nanos(ts_nanos + DATA_EXPIRES_IN * 1_000_000_000);
         let dldata = dldata_buffer::DlDataBuffer {
             data_id: data_id.clone(),
             unit_id: mgr.unit_id().to_string(),
@@ -1513,7 +1683,7 @@ impl EventHandler for MgrHandler {
         let net_data = NetworkDlData {
             data_id,
             publish: time_str(&now),
-            expires_in: DATA_EXPIRES_IN,
+            expires_in,
             network_addr: dldata_route.network_addr,
             data: data.data,
             extension: data.extension,
@@ -1535,6 +1705,8 @@ impl EventHandler for MgrHandler {
                 ..Default::default()
             }));
         }
+        self.device_states
+            .add_downlink(dldata.device_id.as_str(), now);
 
         Ok(Box::new(DlDataResp {
             correlation_id: data.correlation_id,
@@ -1621,15 +1793,24 @@ impl MessageHandler for CtrlReceiverHandler {
                 let handler = MgrHandler {
                     model: self.model.clone(),
                     cache: self.cache.clone(),
+                    codecs: self.codecs.clone(),
+                    device_states: self.device_states.clone(),
                     network_mgrs: self.network_mgrs.clone(),
                     data_sender: self.data_sender.clone(),
+                    dldata_expires_in: self.dldata_expires_in,
+                    dldata_max_expires_in: self.dldata_max_expires_in,
                 };
                 let unit_code = new.mgr_options.unit_code.clone();
                 let name = new.mgr_options.name.clone();
+                let mut mgr_options = new.mgr_options;
+                mgr_options.tls = self.mq_tls.clone();
+                mgr_options.reconnect_policy = self.mq_reconnect.clone();
+                mgr_options.outbox = self.mq_outbox.clone();
+                mgr_options.compression = self.mq_compression;
                 let mgr = match ApplicationMgr::new(
                     self.mq_conns.clone(),
                     &host_uri,
-                    new.mgr_options,
+                    mgr_options,
                     Arc::new(handler),
                 ) {
                     Err(e) => {
@@ -14,7 +14,7 @@ use axum::{
     http::{StatusCode, header},
     response::IntoResponse,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Map};
@@ -22,7 +22,7 @@ use tokio::time;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::{
@@ -43,7 +43,7 @@ use super::{
 use crate::{
     libs::{
         config::BrokerCtrl as CfgCtrl,
-        mq::{self, Connection},
+        mq::{self, Connection, application::DeviceStatus},
     },
     models::{
         Cache,
@@ -53,6 +53,7 @@ use crate::{
         },
         device_route, dldata_buffer,
         network::{Network, QueryCond as NetworkQueryCond},
+        network_route,
     },
 };
 
@@ -201,6 +202,9 @@ const LIST_LIMIT_DEFAULT: u64 = 100;
 const LIST_CURSOR_MAX: u64 = 100;
 const ID_RAND_LEN: usize = 8;
 const CTRL_QUEUE_NAME: &'static str = "device";
+/// The maximum number of offline devices to handle in one sweep round.
+const SWEEP_LIMIT: u64 = 100;
+const DEVICE_STATUS_OFFLINE: &'static str = "offline";
 
 /// Initialize channels.
 pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn StdError>> {
@@ -247,10 +251,45 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
     Ok(())
 }
 
+/// To start the tasks that write buffered device states and mark devices offline periodically.
+///
+/// Applications receive `offline` status of devices that are routed to them. Devices are claimed
+/// by marking them offline before sending status, so multiple brokers that share the database
+/// report one status for one device.
+///
+/// The tasks are stopped by [`Tasks::stop`](crate::routes::Tasks::stop) after the current work.
+pub fn init_state(state: &AppState) {
+    let tasks = state.tasks.clone();
+    let device_states = state.device_states.clone();
+    let flush_interval = state.device_flush_interval;
+    tasks.spawn(|mut stop| async move {
+        let mut interval = time::interval(Duration::from_secs(flush_interval));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => device_states.flush().await,
+                _ = stop.changed() => break,
+            }
+        }
+    });
+
+    let state = state.clone();
+    tasks.spawn(|mut stop| async move {
+        let mut interval = time::interval(Duration::from_secs(state.device_sweep_interval));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => sweep_offline(&state).await,
+                _ = stop.changed() => break,
+            }
+        }
+    });
+}
+
 /// Create control channel sender queue.
 pub fn new_ctrl_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &CfgCtrl,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     cache: Option<Arc<dyn Cache>>,
 ) -> Result<Arc<Mutex<Queue>>, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
@@ -270,6 +309,8 @@ pub fn new_ctrl_sender(
         conn_pool.clone(),
         &url,
         config.prefetch,
+        tls,
+        reconnect,
         CTRL_QUEUE_NAME,
         false,
         Arc::new(CtrlSenderHandler {
@@ -303,6 +344,8 @@ pub fn new_ctrl_receiver(state: &AppState, config: &CfgCtrl) -> Result<Queue, Bo
         state.mq_conns.clone(),
         &url,
         config.prefetch,
+        state.mq_tls.as_ref(),
+        state.mq_reconnect.as_ref(),
         CTRL_QUEUE_NAME,
         true,
         handler.clone(),
@@ -406,6 +449,11 @@ pub async fn post_device(
             None => Map::new(),
             Some(info) => info.clone(),
         },
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = state.model.device().add(&device).await {
         error!("[{}] add error: {}", FN_NAME, e);
@@ -540,6 +588,11 @@ pub async fn post_device_bulk(
             },
             name: network_addr.clone(),
             info: Map::new(),
+            last_uplink_at: None,
+            last_downlink_at: None,
+            last_extension: None,
+            offline_at: None,
+            online: None,
         };
         devices.push(device);
     }
@@ -768,6 +821,11 @@ pub async fn post_device_range(
             },
             name: network_addr,
             info: Map::new(),
+            last_uplink_at: None,
+            last_downlink_at: None,
+            last_extension: None,
+            offline_at: None,
+            online: None,
         };
         devices.push(device);
     }
@@ -990,6 +1048,7 @@ pub async fn get_device_count(
             },
         },
         name_contains: name_contains_cond,
+        online: query.online,
         ..Default::default()
     };
     match state.model.device().count(&cond).await {
@@ -1078,6 +1137,7 @@ pub async fn get_device_list(
             },
         },
         name_contains: name_contains_cond,
+        online: query.online,
         ..Default::default()
     };
     let sort_cond = get_sort_cond(&query.sort)?;
@@ -1147,6 +1207,7 @@ pub async fn get_device_list(
                 },
             },
             name_contains: name_contains_cond,
+            online: query.online,
             ..Default::default()
         };
         let opts = ListOptions {
@@ -1540,6 +1601,156 @@ async fn get_updates<'a>(body: &'a mut request::PatchDeviceData) -> Result<Updat
     Ok(updates)
 }
 
+/// To mark online devices that reach their offline time offline and to report status to
+/// applications.
+async fn sweep_offline(state: &AppState) {
+    const FN_NAME: &'static str = "sweep_offline";
+
+    let now = Utc::now();
+    let cond = ListQueryCond {
+        online: Some(true),
+        offline_at_lte: Some(now),
+        ..Default::default()
+    };
+    let opts = ListOptions {
+        cond: &cond,
+        offset: None,
+        limit: Some(SWEEP_LIMIT),
+        sort: None,
+        cursor_max: None,
+    };
+    // Devices are marked offline while sweeping, so query the first page again until no more
+    // data.
+    loop {
+        let list = match state.model.device().list(&opts, None).await {
+            Err(e) => {
+                error!("[{}] list error: {}", FN_NAME, e);
+                return;
+            }
+            Ok((list, _)) => list,
+        };
+        for item in list.iter() {
+            let offline_at = match item.offline_at {
+                None => continue,
+                Some(offline_at) => offline_at,
+            };
+            match state
+                .model
+                .device()
+                .set_offline(item.device_id.as_str(), offline_at)
+                .await
+            {
+                Err(e) => {
+                    error!("[{}] set {} offline error: {}", FN_NAME, item.device_id, e);
+                    return;
+                }
+                Ok(false) => continue, // Claimed by other brokers or received new data.
+                Ok(true) => (),
+            }
+            send_offline_status(state, &offline_at, item).await;
+        }
+        if (list.len() as u64) < SWEEP_LIMIT {
+            return;
+        }
+    }
+}
+
+/// To send the offline status to applications that the device is routed to by device routes or
+/// network routes.
+async fn send_offline_status(state: &AppState, offline_at: &DateTime<Utc>, device: &Device) {
+    const FN_NAME: &'static str = "send_offline_status";
+
+    let mut keys: Vec<String> = vec![];
+    let cond = device_route::ListQueryCond {
+        device_id: Some(device.device_id.as_str()),
+        ..Default::default()
+    };
+    let opts = device_route::ListOptions {
+        cond: &cond,
+        offset: None,
+        limit: None,
+        sort: None,
+        cursor_max: Some(LIST_CURSOR_MAX),
+    };
+    let mut cursor: Option<Box<dyn device_route::Cursor>> = None;
+    loop {
+        let (list, _cursor) = match state.model.device_route().list(&opts, cursor).await {
+            Err(e) => {
+                error!("[{}] get device route error: {}", FN_NAME, e);
+                return;
+            }
+            Ok((list, cursor)) => (list, cursor),
+        };
+        for route in list.iter() {
+            let key = gen_mgr_key(route.unit_code.as_str(), route.application_code.as_str());
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        if _cursor.is_none() {
+            break;
+        }
+        cursor = _cursor;
+    }
+    let cond = network_route::ListQueryCond {
+        network_id: Some(device.network_id.as_str()),
+        ..Default::default()
+    };
+    let opts = network_route::ListOptions {
+        cond: &cond,
+        offset: None,
+        limit: None,
+        sort: None,
+        cursor_max: Some(LIST_CURSOR_MAX),
+    };
+    let mut cursor: Option<Box<dyn network_route::Cursor>> = None;
+    loop {
+        let (list, _cursor) = match state.model.network_route().list(&opts, cursor).await {
+            Err(e) => {
+                error!("[{}] get network route error: {}", FN_NAME, e);
+                return;
+            }
+            Ok((list, cursor)) => (list, cursor),
+        };
+        for route in list.iter() {
+            let key = gen_mgr_key(route.unit_code.as_str(), route.application_code.as_str());
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        if _cursor.is_none() {
+            break;
+        }
+        cursor = _cursor;
+    }
+
+    let data = DeviceStatus {
+        device_id: device.device_id.clone(),
+        network_id: device.network_id.clone(),
+        network_code: device.network_code.clone(),
+        network_addr: device.network_addr.clone(),
+        is_public: device.unit_code.is_none(),
+        profile: device.profile.clone(),
+        status: DEVICE_STATUS_OFFLINE.to_string(),
+        time: time_str(offline_at),
+        last_uplink_at: match device.last_uplink_at.as_ref() {
+            None => None,
+            Some(time) => Some(time_str(time)),
+        },
+    };
+    for key in keys.iter() {
+        let mgr = {
+            match state.application_mgrs.lock().unwrap().get(key) {
+                None => continue,
+                Some(mgr) => mgr.clone(),
+            }
+        };
+        if let Err(e) = mgr.send_device_status(&data).await {
+            error!("[{}] send status to {} error: {}", FN_NAME, key, e);
+        }
+    }
+}
+
 /// To check if the network is exists for the unit. Public network can be matched for admin or
 /// manager roles.
 ///
@@ -1669,6 +1880,20 @@ fn device_transform(device: &Device) -> response::GetDeviceData {
         profile: device.profile.clone(),
         name: device.name.clone(),
         info: device.info.clone(),
+        last_uplink_at: match device.last_uplink_at.as_ref() {
+            None => None,
+            Some(time) => Some(time_str(time)),
+        },
+        last_downlink_at: match device.last_downlink_at.as_ref() {
+            None => None,
+            Some(time) => Some(time_str(time)),
+        },
+        last_extension: device.last_extension.clone(),
+        offline_at: match device.offline_at.as_ref() {
+            None => None,
+            Some(time) => Some(time_str(time)),
+        },
+        online: device.online,
     }
 }
 
@@ -1855,6 +2080,10 @@ async fn send_net_ctrl_message(
 
 /// Clear the device and relative cache.
 async fn clear_cache(fn_name: &str, queue_name: &str, cache: &Arc<dyn Cache>) {
+    // Clearing shared caches makes all instances query databases again. They expire by TTL.
+    if cache.is_shared() {
+        return;
+    }
     if let Err(e) = cache.device().clear().await {
         error!(
             "[{}] {} clear device cache error: {}",
@@ -10,7 +10,7 @@ use super::super::{
 mod api;
 mod request;
 mod response;
-pub use api::{init, new_ctrl_receiver, new_ctrl_sender};
+pub use api::{init, init_state, new_ctrl_receiver, new_ctrl_sender};
 
 pub fn new_service(scope_path: &str, state: &State) -> Router {
     let mut role_scopes_root: HashMap<Method, RoleScopeType> = HashMap::new();
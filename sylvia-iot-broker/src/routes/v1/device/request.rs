@@ -65,6 +65,7 @@ pub struct GetDeviceCountQuery {
     pub addr: Option<String>,
     pub profile: Option<String>,
     pub contains: Option<String>,
+    pub online: Option<bool>,
 }
 
 #[derive(Clone, Deserialize)]
@@ -74,6 +75,7 @@ pub struct GetDeviceListQuery {
     pub addr: Option<String>,
     pub profile: Option<String>,
     pub contains: Option<String>,
+    pub online: Option<bool>,
     pub offset: Option<u64>,
     pub limit: Option<u64>,
     pub sort: Option<String>,
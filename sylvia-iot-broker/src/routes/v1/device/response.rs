@@ -53,4 +53,13 @@ pub struct GetDeviceData {
     pub profile: String,
     pub name: String,
     pub info: Map<String, Value>,
+    #[serde(rename = "lastUplinkAt")]
+    pub last_uplink_at: Option<String>,
+    #[serde(rename = "lastDownlinkAt")]
+    pub last_downlink_at: Option<String>,
+    #[serde(rename = "lastExtension")]
+    pub last_extension: Option<Map<String, Value>>,
+    #[serde(rename = "offlineAt")]
+    pub offline_at: Option<String>,
+    pub online: Option<bool>,
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, error::Error as StdError};
+use std::{collections::HashMap, error::Error as StdError, time::Duration};
 
 use axum::{
     Extension,
@@ -7,9 +7,13 @@ use axum::{
     http::{StatusCode, header},
     response::IntoResponse,
 };
-use log::error;
+use chrono::{DateTime, Utc};
+use log::{error, warn};
+use serde::Serialize;
 use serde_json;
+use tokio::time;
 
+use general_mq::queue::GmqQueue;
 use sylvia_iot_corelib::{
     constants::ContentType,
     err::ErrResp,
@@ -21,16 +25,69 @@ use sylvia_iot_corelib::{
 use super::{
     super::{
         super::{ErrReq, State as AppState, middleware::GetTokenInfoData},
-        lib::check_unit,
+        lib::{check_unit, gen_mgr_key},
     },
     request, response,
 };
-use crate::models::dldata_buffer::{
-    DlDataBuffer, ListOptions, ListQueryCond, QueryCond, SortCond, SortKey,
+use crate::{
+    libs::mq::application::DlDataResult,
+    models::dldata_buffer::{
+        DlDataBuffer, ListOptions, ListQueryCond, QueryCond, SortCond, SortKey,
+    },
 };
 
+/// Data channel.
+#[derive(Serialize)]
+struct SendDataMsg {
+    kind: String,
+    data: SendDataKind,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SendDataKind {
+    AppDlDataResult {
+        #[serde(rename = "dataId")]
+        data_id: String,
+        resp: String,
+        status: i32,
+    },
+}
+
+struct DataMsgKind;
+
+impl DataMsgKind {
+    const APP_DLDATA_RES: &'static str = "application-dldata-result";
+}
+
 const LIST_LIMIT_DEFAULT: u64 = 100;
 const LIST_CURSOR_MAX: u64 = 100;
+/// The maximum number of expired buffers to handle in one sweep round.
+const SWEEP_LIMIT: u64 = 100;
+/// The downlink data result status for buffers that expired without any result from networks.
+const DLDATA_STATUS_EXPIRED: i32 = 408;
+const DLDATA_EXPIRED_MESSAGE: &'static str = "expired";
+
+/// To start the task that removes expired downlink data buffers periodically.
+///
+/// Applications receive results with status **408** for removed buffers. Buffers are claimed
+/// by deleting them before sending results, so multiple brokers that share the database report
+/// one result for one buffer.
+///
+/// The task is stopped by [`Tasks::stop`](crate::routes::Tasks::stop) after the current sweep.
+pub fn init(state: &AppState) {
+    let tasks = state.tasks.clone();
+    let state = state.clone();
+    tasks.spawn(|mut stop| async move {
+        let mut interval = time::interval(Duration::from_secs(state.dldata_sweep_interval));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => sweep_expired(&state).await,
+                _ = stop.changed() => break,
+            }
+        }
+    });
+}
 
 /// `GET /{base}/api/v1/dldata-buffer/count`
 pub async fn get_dldata_buffer_count(
@@ -304,6 +361,104 @@ pub async fn delete_dldata_buffer(
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// To remove all expired downlink data buffers and to report results to applications.
+async fn sweep_expired(state: &AppState) {
+    const FN_NAME: &'static str = "sweep_expired";
+
+    let now = Utc::now();
+    let cond = ListQueryCond {
+        expired_at_lte: Some(now),
+        ..Default::default()
+    };
+    let sort_cond = vec![SortCond {
+        key: SortKey::ExpiredAt,
+        asc: true,
+    }];
+    let opts = ListOptions {
+        cond: &cond,
+        offset: None,
+        limit: Some(SWEEP_LIMIT),
+        sort: Some(sort_cond.as_slice()),
+        cursor_max: None,
+    };
+    // Buffers are deleted while sweeping, so query the first page again until no more data.
+    loop {
+        let list = match state.model.dldata_buffer().list(&opts, None).await {
+            Err(e) => {
+                error!("[{}] list error: {}", FN_NAME, e);
+                return;
+            }
+            Ok((list, _)) => list,
+        };
+        for item in list.iter() {
+            match state
+                .model
+                .dldata_buffer()
+                .del_one(item.data_id.as_str())
+                .await
+            {
+                Err(e) => {
+                    error!("[{}] del {} error: {}", FN_NAME, item.data_id, e);
+                    return;
+                }
+                Ok(false) => continue, // Claimed by other brokers or deleted by results.
+                Ok(true) => (),
+            }
+            send_expired_result(state, &now, item).await;
+        }
+        if (list.len() as u64) < SWEEP_LIMIT {
+            return;
+        }
+    }
+}
+
+/// To send the expired result to the application and the data channel.
+async fn send_expired_result(state: &AppState, now: &DateTime<Utc>, dldata: &DlDataBuffer) {
+    const FN_NAME: &'static str = "send_expired_result";
+
+    let key = gen_mgr_key(dldata.unit_code.as_str(), dldata.application_code.as_str());
+    let mgr = {
+        match state.application_mgrs.lock().unwrap().get(&key) {
+            None => None,
+            Some(mgr) => Some(mgr.clone()),
+        }
+    };
+    match mgr {
+        None => warn!("[{}] no manager {} for {}", FN_NAME, key, dldata.data_id),
+        Some(mgr) => {
+            let result_data = DlDataResult {
+                data_id: dldata.data_id.clone(),
+                status: DLDATA_STATUS_EXPIRED,
+                message: Some(DLDATA_EXPIRED_MESSAGE.to_string()),
+            };
+            if let Err(e) = mgr.send_dldata_result(&result_data).await {
+                error!("[{}] send data to {} error: {}", FN_NAME, key, e);
+            }
+        }
+    }
+
+    if let Some(sender) = state.data_sender.as_ref() {
+        let msg = SendDataMsg {
+            kind: DataMsgKind::APP_DLDATA_RES.to_string(),
+            data: SendDataKind::AppDlDataResult {
+                data_id: dldata.data_id.clone(),
+                resp: time_str(now),
+                status: DLDATA_STATUS_EXPIRED,
+            },
+        };
+        let payload = match serde_json::to_vec(&msg) {
+            Err(e) => {
+                error!("[{}] marshal JSON error: {}", FN_NAME, e);
+                return;
+            }
+            Ok(payload) => payload,
+        };
+        if let Err(e) = sender.send_msg(payload).await {
+            error!("[{}] send data to {} error: {}", FN_NAME, sender.name(), e);
+        }
+    }
+}
+
 fn get_sort_cond(sort_args: &Option<String>) -> Result<Vec<SortCond>, ErrResp> {
     match sort_args.as_ref() {
         None => Ok(vec![
@@ -10,6 +10,7 @@ use super::super::{
 mod api;
 mod request;
 mod response;
+pub use api::init;
 
 pub fn new_service(scope_path: &str, state: &State) -> Router {
     let mut role_scopes_count: HashMap<Method, RoleScopeType> = HashMap::new();
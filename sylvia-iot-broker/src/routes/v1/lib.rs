@@ -9,6 +9,7 @@ use crate::models::{
     application::{Application, QueryCond as ApplicationQueryCond},
     device::{Device, QueryCond as DeviceQueryCond},
     network::{Network, QueryCond as NetworkQueryCond},
+    profile::{Profile, QueryCond as ProfileQueryCond},
     unit::{QueryCond as UnitQueryCond, Unit},
 };
 
@@ -160,6 +161,44 @@ pub async fn check_device(
     }
 }
 
+/// To check if the user ID can access the profile. Choose `only_owner` to check if the user is the
+/// unit owner or one of unit members.
+///
+/// # Errors
+///
+/// Returns OK if the profile is found or not. Otherwise errors will be returned.
+pub async fn check_profile(
+    fn_name: &str,
+    profile_id: &str,
+    user_id: &str,
+    only_owner: bool, // to check if this `user_id` is the owner.
+    roles: &HashMap<String, bool>,
+    state: &AppState,
+) -> Result<Option<Profile>, ErrResp> {
+    let cond = ProfileQueryCond {
+        profile_id: Some(profile_id),
+        ..Default::default()
+    };
+    let profile = match state.model.profile().get(&cond).await {
+        Err(e) => {
+            error!("[{}] get error: {}", fn_name, e);
+            return Err(ErrResp::ErrDb(Some(e.to_string())));
+        }
+        Ok(profile) => match profile {
+            None => return Ok(None),
+            Some(profile) => profile,
+        },
+    };
+    if Role::is_role(roles, Role::ADMIN) || Role::is_role(roles, Role::MANAGER) {
+        return Ok(Some(profile));
+    }
+    let unit_id = profile.unit_id.as_str();
+    match check_unit(fn_name, user_id, roles, unit_id, only_owner, state).await? {
+        None => Ok(None),
+        Some(_) => Ok(Some(profile)),
+    }
+}
+
 /// To generate a key for managing managers. Empty unit for public networks.
 pub fn gen_mgr_key(unit: &str, name: &str) -> String {
     format!("{}.{}", unit, name)
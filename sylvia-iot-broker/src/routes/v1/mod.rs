@@ -5,4 +5,5 @@ pub mod dldata_buffer;
 mod lib;
 pub mod network;
 pub mod network_route;
+pub mod profile;
 pub mod unit;
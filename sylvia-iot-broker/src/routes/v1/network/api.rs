@@ -18,11 +18,18 @@ use chrono::{DateTime, Utc};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{self, Map, Value};
-use tokio::time;
+use sha2::{Digest, Sha256};
+use tokio::{
+    sync::{
+        mpsc::{self, error::TrySendError},
+        watch,
+    },
+    time,
+};
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Compression, OutboxOptions, Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::{
@@ -35,14 +42,19 @@ use sylvia_iot_corelib::{
 
 use super::{
     super::{
-        super::{ErrReq, State as AppState, middleware::GetTokenInfoData},
+        super::{
+            ErrReq, State as AppState, Tasks, UlDataRetry, UlDataRetryQueues,
+            middleware::GetTokenInfoData,
+        },
         lib::{check_network, check_unit, gen_mgr_key},
     },
     request, response,
 };
 use crate::{
     libs::{
+        codec::CodecCache,
         config::BrokerCtrl as CfgCtrl,
+        device_state::StateBuffer,
         mq::{
             self, Connection, MgrStatus, Options as MgrOptions,
             application::{
@@ -64,11 +76,29 @@ use crate::{
     },
 };
 
+#[derive(Clone)]
 struct MgrHandler {
     model: Arc<dyn Model>,
     cache: Option<Arc<dyn Cache>>,
+    codecs: Arc<CodecCache>,
+    device_states: Arc<StateBuffer>,
     application_mgrs: Arc<Mutex<HashMap<String, ApplicationMgr>>>,
     data_sender: Option<Queue>,
+    uldata_retry_limit: u64,
+    uldata_retry_delay: u64,
+    uldata_retry_queues: UlDataRetryQueues,
+    uldata_dedup_window: u64,
+    tasks: Tasks,
+}
+
+/// The result of adding uplink data into the deduplication window.
+enum Dedup {
+    /// Deduplication is disabled or unavailable. The data is sent directly.
+    None,
+    /// The first copy with the key. The data is sent after the window with merged duplicates.
+    First(String),
+    /// A duplicate that is merged into the first copy.
+    Duplicate,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -132,6 +162,30 @@ enum SendDataKind {
         #[serde(skip_serializing_if = "Option::is_none")]
         extension: Option<Map<String, Value>>,
     },
+    AppUlDataFailed {
+        #[serde(rename = "dataId")]
+        data_id: String,
+        proc: String,
+        #[serde(rename = "pub")]
+        publish: String,
+        #[serde(rename = "unitCode")]
+        unit_code: Option<String>,
+        #[serde(rename = "networkCode")]
+        network_code: String,
+        #[serde(rename = "networkAddr")]
+        network_addr: String,
+        #[serde(rename = "unitId")]
+        unit_id: String,
+        #[serde(rename = "deviceId")]
+        device_id: String,
+        time: String,
+        profile: String,
+        data: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        extension: Option<Map<String, Value>>,
+        retries: u64,
+        error: String,
+    },
     AppDlDataResult {
         #[serde(rename = "dataId")]
         data_id: String,
@@ -196,10 +250,21 @@ struct CtrlSenderHandler {
 struct CtrlReceiverHandler {
     model: Arc<dyn Model>,
     cache: Option<Arc<dyn Cache>>,
+    codecs: Arc<CodecCache>,
+    device_states: Arc<StateBuffer>,
     mq_conns: Arc<Mutex<HashMap<String, Connection>>>,
+    mq_tls: Option<TlsOptions>,
+    mq_reconnect: Option<ReconnectPolicy>,
+    mq_outbox: Option<OutboxOptions>,
+    mq_compression: Compression,
     application_mgrs: Arc<Mutex<HashMap<String, ApplicationMgr>>>,
     network_mgrs: Arc<Mutex<HashMap<String, NetworkMgr>>>,
     data_sender: Option<Queue>,
+    uldata_retry_limit: u64,
+    uldata_retry_delay: u64,
+    uldata_retry_queues: UlDataRetryQueues,
+    uldata_dedup_window: u64,
+    tasks: Tasks,
 }
 
 impl CtrlMsgOp {
@@ -210,6 +275,7 @@ impl CtrlMsgOp {
 
 impl DataMsgKind {
     const APP_ULDATA: &'static str = "application-uldata";
+    const APP_ULDATA_FAILED: &'static str = "application-uldata-failed";
     const APP_DLDATA_RES: &'static str = "application-dldata-result";
     const NET_ULDATA: &'static str = "network-uldata";
     const NET_DLDATA_RES: &'static str = "network-dldata-result";
@@ -220,6 +286,12 @@ const LIST_CURSOR_MAX: u64 = 100;
 const ID_RAND_LEN: usize = 8;
 const DATA_ID_RAND_LEN: usize = 12;
 const CTRL_QUEUE_NAME: &'static str = "network";
+/// The maximum delay between two uplink data retries.
+const ULDATA_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+/// The maximum number of uplink data in the retry queue of one application.
+const ULDATA_RETRY_QUEUE_SIZE: usize = 1000;
+/// The `extension` key of merged duplicates of uplink data.
+const ULDATA_DUPLICATES_KEY: &'static str = "duplicates";
 
 /// Initialize network managers and channels.
 pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn StdError>> {
@@ -296,12 +368,26 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
                 prefetch: Some(state.amqp_prefetch),
                 persistent: state.amqp_persistent,
                 shared_prefix: Some(state.mqtt_shared_prefix.clone()),
+                dead_letter: state.mq_dead_letter,
+                tls: state.mq_tls.clone(),
+                reconnect_policy: state.mq_reconnect.clone(),
+                outbox: state.mq_outbox.clone(),
+                compression: state.mq_compression,
+                amqp_args: state.amqp_args.clone(),
+                device_status: false,
             };
             let handler = MgrHandler {
                 model: state.model.clone(),
                 cache: state.cache.clone(),
+                codecs: state.codecs.clone(),
+                device_states: state.device_states.clone(),
                 application_mgrs: state.application_mgrs.clone(),
                 data_sender: state.data_sender.clone(),
+                uldata_retry_limit: state.uldata_retry_limit,
+                uldata_retry_delay: state.uldata_retry_delay,
+                uldata_retry_queues: state.uldata_retry_queues.clone(),
+                uldata_dedup_window: state.uldata_dedup_window,
+                tasks: state.tasks.clone(),
             };
             let mgr = match NetworkMgr::new(state.mq_conns.clone(), &url, opts, Arc::new(handler)) {
                 Err(e) => {
@@ -326,6 +412,8 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
 pub fn new_ctrl_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &CfgCtrl,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     cache: Option<Arc<dyn Cache>>,
 ) -> Result<Arc<Mutex<Queue>>, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
@@ -345,6 +433,8 @@ pub fn new_ctrl_sender(
         conn_pool.clone(),
         &url,
         config.prefetch,
+        tls,
+        reconnect,
         CTRL_QUEUE_NAME,
         false,
         Arc::new(CtrlSenderHandler {
@@ -374,15 +464,28 @@ pub fn new_ctrl_receiver(state: &AppState, config: &CfgCtrl) -> Result<Queue, Bo
     let handler = Arc::new(CtrlReceiverHandler {
         model: state.model.clone(),
         cache: state.cache.clone(),
+        codecs: state.codecs.clone(),
+        device_states: state.device_states.clone(),
         mq_conns: state.mq_conns.clone(),
+        mq_tls: state.mq_tls.clone(),
+        mq_reconnect: state.mq_reconnect.clone(),
+        mq_outbox: state.mq_outbox.clone(),
+        mq_compression: state.mq_compression,
         application_mgrs: state.application_mgrs.clone(),
         network_mgrs: state.network_mgrs.clone(),
         data_sender: state.data_sender.clone(),
+        uldata_retry_limit: state.uldata_retry_limit,
+        uldata_retry_delay: state.uldata_retry_delay,
+        uldata_retry_queues: state.uldata_retry_queues.clone(),
+        uldata_dedup_window: state.uldata_dedup_window,
+        tasks: state.tasks.clone(),
     });
     match mq::control::new(
         state.mq_conns.clone(),
         &url,
         config.prefetch,
+        state.mq_tls.as_ref(),
+        state.mq_reconnect.as_ref(),
         CTRL_QUEUE_NAME,
         true,
         handler.clone(),
@@ -1152,6 +1255,13 @@ async fn add_manager(
         prefetch: Some(state.amqp_prefetch),
         persistent: state.amqp_persistent,
         shared_prefix: Some(state.mqtt_shared_prefix.clone()),
+        dead_letter: state.mq_dead_letter,
+        tls: state.mq_tls.clone(),
+        reconnect_policy: state.mq_reconnect.clone(),
+        outbox: state.mq_outbox.clone(),
+        compression: state.mq_compression,
+        amqp_args: state.amqp_args.clone(),
+        device_status: false,
     };
     let msg = SendCtrlMsg::AddManager {
         operation: CtrlMsgOp::ADD_MANAGER.to_string(),
@@ -1256,6 +1366,7 @@ impl MgrHandler {
                         }
                         Some(device) => Ok(Some(DeviceCacheItem {
                             device_id: device.device_id,
+                            unit_id: device.unit_id,
                             profile: device.profile,
                         })),
                     },
@@ -1286,6 +1397,65 @@ impl MgrHandler {
         }
     }
 
+    /// To decode the data with the codec of the device profile. Returns `None` if the profile has
+    /// no codec or the data cannot be decoded.
+    async fn decode_uldata(
+        &self,
+        device: &DeviceCacheItem,
+        data: &str,
+    ) -> Option<Map<String, Value>> {
+        const FN_NAME: &'static str = "decode_uldata";
+
+        let codec = match self
+            .codecs
+            .get(device.unit_id.as_str(), device.profile.as_str())
+            .await
+        {
+            Err(e) => {
+                error!("[{}] get codec of {} error: {}", FN_NAME, device.profile, e);
+                return None;
+            }
+            Ok(codec) => codec?,
+        };
+        match codec.decode(data) {
+            Err(e) => {
+                warn!(
+                    "[{}] decode data of device {} error: {}",
+                    FN_NAME, device.device_id, e
+                );
+                None
+            }
+            Ok(object) => object,
+        }
+    }
+
+    /// To record the uplink time and the extension of the device for tracking its state.
+    async fn record_uldata(
+        &self,
+        time: &DateTime<Utc>,
+        device: &DeviceCacheItem,
+        extension: Option<&Map<String, Value>>,
+    ) {
+        const FN_NAME: &'static str = "record_uldata";
+
+        let offline_timeout = match self
+            .codecs
+            .get_offline_timeout(device.unit_id.as_str(), device.profile.as_str())
+            .await
+        {
+            Err(e) => {
+                error!(
+                    "[{}] get offline timeout of {} error: {}",
+                    FN_NAME, device.profile, e
+                );
+                0
+            }
+            Ok(timeout) => timeout,
+        };
+        self.device_states
+            .add_uplink(device.device_id.as_str(), *time, extension, offline_timeout);
+    }
+
     async fn send_by_device_route(
         &self,
         netmgr_unit_code: Option<String>,
@@ -1321,18 +1491,8 @@ impl MgrHandler {
                 };
                 let now = Utc::now();
                 app_data.data_id = strings::random_id(&now, DATA_ID_RAND_LEN);
-                if let Err(e) = mgr.send_uldata(&app_data) {
-                    // TODO: retry internally because one or more routes may sent successfully.
-                    error!("[{}] send data to {} error: {}", FN_NAME, key, e);
-                    continue;
-                }
-                self.send_application_uldata_msg(
-                    &now,
-                    netmgr_unit_code.clone(),
-                    mgr.unit_id().to_string(),
-                    &app_data,
-                )
-                .await?;
+                self.send_uldata(key, &mgr, &now, netmgr_unit_code.clone(), app_data)
+                    .await;
                 sent_mgrs.push(key.clone());
             }
 
@@ -1372,18 +1532,8 @@ impl MgrHandler {
                 };
                 let now = Utc::now();
                 app_data.data_id = strings::random_id(&now, DATA_ID_RAND_LEN);
-                if let Err(e) = mgr.send_uldata(&app_data) {
-                    // TODO: retry internally because one or more routes may sent successfully.
-                    error!("[{}] send data to {} error: {}", FN_NAME, key, e);
-                    continue;
-                }
-                self.send_application_uldata_msg(
-                    &now,
-                    netmgr_unit_code.clone(),
-                    mgr.unit_id().to_string(),
-                    &app_data,
-                )
-                .await?;
+                self.send_uldata(&key, &mgr, &now, netmgr_unit_code.clone(), app_data)
+                    .await;
                 sent_mgrs.push(key);
             }
             if _cursor.is_none() {
@@ -1430,18 +1580,8 @@ impl MgrHandler {
                 };
                 let now = Utc::now();
                 app_data.data_id = strings::random_id(&now, DATA_ID_RAND_LEN);
-                if let Err(e) = mgr.send_uldata(&app_data) {
-                    // TODO: retry internally because one or more routes may sent successfully.
-                    error!("[{}] send data to {} error: {}", FN_NAME, key, e);
-                    continue;
-                }
-                self.send_application_uldata_msg(
-                    &now,
-                    netmgr_unit_code.clone(),
-                    mgr.unit_id().to_string(),
-                    &app_data,
-                )
-                .await?;
+                self.send_uldata(key, &mgr, &now, netmgr_unit_code.clone(), app_data)
+                    .await;
                 sent_mgrs.push(key.clone());
             }
 
@@ -1481,18 +1621,8 @@ impl MgrHandler {
                 };
                 let now = Utc::now();
                 app_data.data_id = strings::random_id(&now, DATA_ID_RAND_LEN);
-                if let Err(e) = mgr.send_uldata(&app_data) {
-                    // TODO: retry internally because one or more routes may sent successfully.
-                    error!("[{}] send data to {} error: {}", FN_NAME, key, e);
-                    continue;
-                }
-                self.send_application_uldata_msg(
-                    &now,
-                    netmgr_unit_code.clone(),
-                    mgr.unit_id().to_string(),
-                    &app_data,
-                )
-                .await?;
+                self.send_uldata(&key, &mgr, &now, netmgr_unit_code.clone(), app_data)
+                    .await;
                 sent_mgrs.push(key);
             }
             if _cursor.is_none() {
@@ -1503,46 +1633,301 @@ impl MgrHandler {
         Ok(())
     }
 
-    async fn send_application_uldata_msg(
+    /// To add the uplink data into the deduplication window. The item is taken by the first copy
+    /// after the window and expires two windows after the first copy if it is not taken.
+    async fn dedup_uldata(&self, network_id: &str, data: &UlData) -> Dedup {
+        const FN_NAME: &'static str = "dedup_uldata";
+
+        if self.uldata_dedup_window == 0 {
+            return Dedup::None;
+        }
+        let cache = match self.cache.as_ref() {
+            None => return Dedup::None,
+            Some(cache) => cache,
+        };
+        let key = gen_dedup_key(network_id, data);
+        let ttl = self.uldata_dedup_window * 2;
+        match cache
+            .uldata_dedup()
+            .add(key.as_str(), data.extension.as_ref(), ttl)
+            .await
+        {
+            Err(e) => {
+                // Send the data without deduplication.
+                error!("[{}] add {} error: {}", FN_NAME, key, e);
+                Dedup::None
+            }
+            Ok(true) => Dedup::First(key),
+            Ok(false) => {
+                debug!(
+                    "[{}] merge duplicate data of {}",
+                    FN_NAME, data.network_addr
+                );
+                Dedup::Duplicate
+            }
+        }
+    }
+
+    /// To merge `extension` of duplicates into the `duplicates` array of the first copy.
+    async fn merge_uldata(&self, key: &str, app_data: &mut ApplicationUlData) {
+        const FN_NAME: &'static str = "merge_uldata";
+
+        app_data.publish = time_str(&Utc::now());
+        let cache = match self.cache.as_ref() {
+            None => return,
+            Some(cache) => cache,
+        };
+        let extensions = match cache.uldata_dedup().take(key).await {
+            Err(e) => {
+                error!("[{}] take {} error: {}", FN_NAME, key, e);
+                return;
+            }
+            Ok(extensions) => extensions,
+        };
+        if extensions.len() <= 1 {
+            return;
+        }
+        let duplicates = extensions
+            .into_iter()
+            .skip(1)
+            .map(|extension| Value::Object(extension.unwrap_or_default()))
+            .collect();
+        let mut extension = app_data.extension.take().unwrap_or_default();
+        extension.insert(ULDATA_DUPLICATES_KEY.to_string(), Value::Array(duplicates));
+        app_data.extension = Some(extension);
+    }
+
+    /// To send uplink data to applications by device routes and network routes. Applications in
+    /// `sent_mgrs` will not receive the data again.
+    async fn route_uldata(
         &self,
+        network_id: String,
+        unit_code: Option<String>,
+        app_data: &mut ApplicationUlData,
+        sent_mgrs: &mut Vec<String>,
+    ) -> Result<(), ()> {
+        // Get device routes to pass data.
+        self.send_by_device_route(unit_code.clone(), app_data, sent_mgrs)
+            .await?;
+
+        // Get network routes to pass data.
+        self.send_by_network_route(network_id, unit_code, app_data, sent_mgrs)
+            .await
+    }
+
+    /// To send the deduplicated uplink data after the window. The network message has been
+    /// acknowledged, so routing is retried with exponential backoff and the data is sent
+    /// immediately when the service stops.
+    async fn route_dedup_uldata(
+        self,
+        key: String,
+        network_id: String,
+        unit_code: Option<String>,
+        mut app_data: ApplicationUlData,
+        mut stop: watch::Receiver<bool>,
+    ) {
+        const FN_NAME: &'static str = "route_dedup_uldata";
+
+        tokio::select! {
+            _ = time::sleep(Duration::from_millis(self.uldata_dedup_window)) => {}
+            _ = wait_stop(&mut stop) => {}
+        }
+        self.merge_uldata(key.as_str(), &mut app_data).await;
+
+        let mut sent_mgrs = vec![];
+        let mut delay = Duration::from_millis(self.uldata_retry_delay);
+        let mut retry = 0;
+        loop {
+            if self
+                .route_uldata(
+                    network_id.clone(),
+                    unit_code.clone(),
+                    &mut app_data,
+                    &mut sent_mgrs,
+                )
+                .await
+                .is_ok()
+            {
+                return;
+            }
+            if retry >= self.uldata_retry_limit || *stop.borrow() {
+                break;
+            }
+            tokio::select! {
+                _ = time::sleep(delay) => {}
+                _ = wait_stop(&mut stop) => {}
+            }
+            retry += 1;
+            delay = (delay * 2).min(ULDATA_RETRY_MAX_DELAY);
+        }
+        error!(
+            "[{}] route data {} failed after {} retries",
+            FN_NAME, app_data.data_id, retry
+        );
+    }
+
+    /// To send uplink data to the application. Failed data is put into the retry queue of the
+    /// application, so the data of other routes will not be sent again.
+    async fn send_uldata(
+        &self,
+        key: &str,
+        mgr: &ApplicationMgr,
         proc: &DateTime<Utc>,
         netmgr_unit_code: Option<String>,
-        app_unit_id: String,
         app_data: &ApplicationUlData,
-    ) -> Result<(), ()> {
-        const FN_NAME: &'static str = "send_application_uldata_msg";
+    ) {
+        const FN_NAME: &'static str = "send_uldata";
+
+        let app_unit_id = mgr.unit_id().to_string();
+        if let Err(e) = mgr.send_uldata(app_data).await {
+            warn!("[{}] send data to {} error: {}", FN_NAME, key, e);
+            let item = UlDataRetry {
+                proc: *proc,
+                netmgr_unit_code,
+                app_unit_id,
+                data: app_data.clone(),
+                error: e.to_string(),
+            };
+            self.retry_uldata(key, item).await;
+            return;
+        }
+        let _ = send_application_uldata_msg(
+            self.data_sender.as_ref(),
+            proc,
+            netmgr_unit_code,
+            app_unit_id,
+            app_data,
+        )
+        .await;
+    }
 
-        if let Some(sender) = self.data_sender.as_ref() {
-            let msg = SendDataMsg {
-                kind: DataMsgKind::APP_ULDATA.to_string(),
-                data: SendDataKind::AppUlData {
-                    data_id: app_data.data_id.clone(),
-                    proc: time_str(proc),
-                    publish: app_data.publish.clone(),
-                    unit_code: netmgr_unit_code,
-                    network_code: app_data.network_code.clone(),
-                    network_addr: app_data.network_addr.clone(),
-                    unit_id: app_unit_id,
-                    device_id: app_data.device_id.clone(),
-                    time: app_data.time.clone(),
-                    profile: app_data.profile.clone(),
-                    data: app_data.data.clone(),
-                    extension: app_data.extension.clone(),
+    /// To put the uplink data into the retry queue of the application. The task of the queue is
+    /// created for the first data. The failure is recorded immediately if the queue is full.
+    async fn retry_uldata(&self, key: &str, item: UlDataRetry) {
+        const FN_NAME: &'static str = "retry_uldata";
+
+        let sender = {
+            let mut queues = self.uldata_retry_queues.queues.lock().unwrap();
+            match queues.get(key) {
+                Some(sender) if !sender.is_closed() => sender.clone(),
+                _ => {
+                    let (sender, receiver) = mpsc::channel(ULDATA_RETRY_QUEUE_SIZE);
+                    let handler = self.clone();
+                    let key = key.to_string();
+                    queues.insert(key.clone(), sender.clone());
+                    self.tasks
+                        .spawn(move |stop| handler.retry_uldata_loop(key, receiver, stop));
+                    sender
+                }
+            }
+        };
+        if let Err(e) = sender.try_send(item) {
+            let item = match e {
+                TrySendError::Full(item) => item,
+                TrySendError::Closed(item) => item,
+            };
+            warn!(
+                "[{}] retry queue of {} is unavailable, drop data {}",
+                FN_NAME, key, item.data.data_id
+            );
+            self.record_uldata_failed(key, item, 0).await;
+        }
+    }
+
+    /// To retry sending uplink data in the queue of one application in order with exponential
+    /// backoff. Data that reaches the retry limit or remains in the queue when the service stops is
+    /// recorded in the data channel.
+    async fn retry_uldata_loop(
+        self,
+        key: String,
+        mut receiver: mpsc::Receiver<UlDataRetry>,
+        mut stop: watch::Receiver<bool>,
+    ) {
+        const FN_NAME: &'static str = "retry_uldata_loop";
+
+        let limit = self.uldata_retry_limit;
+        'queue: loop {
+            let mut item = tokio::select! {
+                item = receiver.recv() => match item {
+                    None => return,
+                    Some(item) => item,
                 },
+                _ = wait_stop(&mut stop) => break 'queue,
             };
-            let payload = match serde_json::to_vec(&msg) {
-                Err(e) => {
-                    error!("[{}] marshal JSON error: {}", FN_NAME, e);
-                    return Err(());
+
+            let mut delay = Duration::from_millis(self.uldata_retry_delay);
+            let mut retry = 0;
+            while retry < limit {
+                tokio::select! {
+                    _ = time::sleep(delay) => {}
+                    _ = wait_stop(&mut stop) => {
+                        self.record_uldata_failed(key.as_str(), item, retry).await;
+                        break 'queue;
+                    }
+                }
+                retry += 1;
+                delay = (delay * 2).min(ULDATA_RETRY_MAX_DELAY);
+
+                let mgr = { self.application_mgrs.lock().unwrap().get(&key).cloned() };
+                let mgr = match mgr {
+                    None => {
+                        warn!(
+                            "[{}] manager {} removed, drop data {} and the retry queue",
+                            FN_NAME, key, item.data.data_id
+                        );
+                        self.uldata_retry_queues.queues.lock().unwrap().remove(&key);
+                        return;
+                    }
+                    Some(mgr) => mgr,
+                };
+                match mgr.send_uldata(&item.data).await {
+                    Err(e) => {
+                        warn!(
+                            "[{}] retry {}/{} send data {} to {} error: {}",
+                            FN_NAME, retry, limit, item.data.data_id, key, e
+                        );
+                        item.error = e.to_string();
+                    }
+                    Ok(_) => {
+                        let _ = send_application_uldata_msg(
+                            self.data_sender.as_ref(),
+                            &item.proc,
+                            item.netmgr_unit_code,
+                            item.app_unit_id,
+                            &item.data,
+                        )
+                        .await;
+                        continue 'queue;
+                    }
                 }
-                Ok(payload) => payload,
-            };
-            if let Err(e) = sender.send_msg(payload).await {
-                error!("[{}] send data to {} error: {}", FN_NAME, sender.name(), e);
-                return Err(());
             }
+            self.record_uldata_failed(key.as_str(), item, retry).await;
         }
-        Ok(())
+
+        receiver.close();
+        while let Some(item) = receiver.recv().await {
+            self.record_uldata_failed(key.as_str(), item, 0).await;
+        }
+    }
+
+    /// To record the uplink data that failed to send to the application in the data channel.
+    async fn record_uldata_failed(&self, key: &str, item: UlDataRetry, retries: u64) {
+        const FN_NAME: &'static str = "record_uldata_failed";
+
+        error!(
+            "[{}] send data {} to {} failed after {} retries: {}",
+            FN_NAME, item.data.data_id, key, retries, item.error
+        );
+        let _ = send_application_uldata_failed_msg(
+            self.data_sender.as_ref(),
+            &item.proc,
+            item.netmgr_unit_code,
+            item.app_unit_id,
+            &item.data,
+            retries,
+            item.error,
+        )
+        .await;
     }
 
     async fn send_application_dldata_result_msg(
@@ -1664,8 +2049,8 @@ impl MgrHandler {
 #[async_trait]
 impl EventHandler for MgrHandler {
     async fn on_status_change(&self, mgr: &NetworkMgr, status: MgrStatus) {
-        // Clear cache when manager status changed.
-        if let Some(cache) = self.cache.as_ref() {
+        // Clear cache when manager status changed. Shared caches expire by TTL instead.
+        if let Some(cache) = self.cache.as_ref().filter(|c| !c.is_shared()) {
             if let Err(e) = cache.device().clear().await {
                 error!(
                     "[on_status_change] {}.{} clear device cache error: {}",
@@ -1712,6 +2097,7 @@ impl EventHandler for MgrHandler {
 
     // Do the following jobs:
     // - check if the source device is valid for the unit.
+    // - merge duplicate data in the deduplication window.
     // - lookup device route to send the data.
     // - lookup network route to send the data.
     // The manager does not send duplicate data to one application if the data matches both routes.
@@ -1728,7 +2114,14 @@ impl EventHandler for MgrHandler {
             None => return Ok(()),
             Some(device) => device,
         };
+        self.record_uldata(&proc, &device, data.extension.as_ref())
+            .await;
+        let dedup = self.dedup_uldata(mgr.id(), &data).await;
+        if let Dedup::Duplicate = dedup {
+            return Ok(());
+        }
 
+        let object = self.decode_uldata(&device, data.data.as_str()).await;
         let mut app_data = {
             let now = Utc::now();
             ApplicationUlData {
@@ -1742,32 +2135,34 @@ impl EventHandler for MgrHandler {
                 is_public: mgr.unit_id().is_empty(),
                 profile: device.profile,
                 data: data.data,
+                object,
                 extension: data.extension,
             }
         };
 
-        let mut sent_mgrs = vec![];
+        let network_id = mgr.id().to_string();
         let unit_code = match mgr.unit_code().len() {
             0 => None,
             _ => Some(mgr.unit_code().to_string()),
         };
-
-        // Get device routes to pass data.
-        self.send_by_device_route(unit_code.clone(), &mut app_data, &mut sent_mgrs)
-            .await?;
-
-        // Get network routes to pass data.
-        self.send_by_network_route(
-            mgr.id().to_string(),
-            unit_code,
-            &mut app_data,
-            &mut sent_mgrs,
-        )
-        .await
+        match dedup {
+            Dedup::First(key) => {
+                let handler = self.clone();
+                self.tasks.spawn(move |stop| {
+                    handler.route_dedup_uldata(key, network_id, unit_code, app_data, stop)
+                });
+                Ok(())
+            }
+            _ => {
+                self.route_uldata(network_id, unit_code, &mut app_data, &mut vec![])
+                    .await
+            }
+        }
     }
 
     // Do the following jobs:
-    // - check if the associated dldata buffer exists.
+    // - check if the associated dldata buffer exists and is not expired.
+    // - claim the dldata buffer by removing it for the final result.
     // - send the result to the source application.
     async fn on_dldata_result(&self, _mgr: &NetworkMgr, data: Box<DlDataResult>) -> Result<(), ()> {
         const FN_NAME: &'static str = "on_dldata_result";
@@ -1791,6 +2186,32 @@ impl EventHandler for MgrHandler {
                 Some(dldata) => dldata,
             },
         };
+        if data.status < 0 {
+            // The sweep task reports the expired result.
+            if dldata.expired_at <= now {
+                warn!("[{}] data ID {} is expired", FN_NAME, data.data_id);
+                return Ok(());
+            }
+        } else {
+            // Claim the buffer so that the sweep task or other brokers will not report results
+            // again.
+            match self
+                .model
+                .dldata_buffer()
+                .del_one(data.data_id.as_str())
+                .await
+            {
+                Err(e) => {
+                    error!("[{}] delete dldata {} error: {}", FN_NAME, data.data_id, e);
+                    return Err(());
+                }
+                Ok(false) => {
+                    warn!("[{}] data ID {} is claimed", FN_NAME, data.data_id);
+                    return Ok(());
+                }
+                Ok(true) => (),
+            }
+        }
 
         let key = gen_mgr_key(dldata.unit_code.as_str(), dldata.application_code.as_str());
         let mgr = {
@@ -1811,26 +2232,16 @@ impl EventHandler for MgrHandler {
             }
             self.send_application_dldata_result_msg(&now, &data).await?;
         }
-        if data.status < 0 {
-            return Ok(());
-        }
-
-        // Remove dldata buffer after completing data processing.
-        let cond = dldata_buffer::QueryCond {
-            data_id: Some(data.data_id.as_str()),
-            ..Default::default()
-        };
-        if let Err(e) = self.model.dldata_buffer().del(&cond).await {
-            // TODO: retry delete internally.
-            error!("[{}] delete dldata {} error: {}", FN_NAME, data.data_id, e);
-            return Err(());
-        }
         Ok(())
     }
 }
 
 /// Clear the network relative cache.
 async fn clear_cache(fn_name: &str, queue_name: &str, cache: &Arc<dyn Cache>) {
+    // Clearing shared caches makes all instances query databases again. They expire by TTL.
+    if cache.is_shared() {
+        return;
+    }
     if let Err(e) = cache.device().clear().await {
         error!(
             "[{}] {} clear device cache error: {}",
@@ -1845,6 +2256,116 @@ async fn clear_cache(fn_name: &str, queue_name: &str, cache: &Arc<dyn Cache>) {
     }
 }
 
+/// To generate the deduplication key with the network, address, time and payload of uplink data.
+fn gen_dedup_key(network_id: &str, data: &UlData) -> String {
+    let mut hasher = Sha256::new();
+    for field in [
+        network_id,
+        data.network_addr.as_str(),
+        data.time.as_str(),
+        data.data.as_str(),
+    ] {
+        hasher.update(field.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// To wait until tasks are stopped. This also returns for tasks that are spawned after stopping.
+async fn wait_stop(stop: &mut watch::Receiver<bool>) {
+    let _ = stop.wait_for(|stop| *stop).await;
+}
+
+/// To send the `application-uldata` message to the data channel.
+async fn send_application_uldata_msg(
+    data_sender: Option<&Queue>,
+    proc: &DateTime<Utc>,
+    netmgr_unit_code: Option<String>,
+    app_unit_id: String,
+    app_data: &ApplicationUlData,
+) -> Result<(), ()> {
+    const FN_NAME: &'static str = "send_application_uldata_msg";
+
+    if let Some(sender) = data_sender {
+        let msg = SendDataMsg {
+            kind: DataMsgKind::APP_ULDATA.to_string(),
+            data: SendDataKind::AppUlData {
+                data_id: app_data.data_id.clone(),
+                proc: time_str(proc),
+                publish: app_data.publish.clone(),
+                unit_code: netmgr_unit_code,
+                network_code: app_data.network_code.clone(),
+                network_addr: app_data.network_addr.clone(),
+                unit_id: app_unit_id,
+                device_id: app_data.device_id.clone(),
+                time: app_data.time.clone(),
+                profile: app_data.profile.clone(),
+                data: app_data.data.clone(),
+                extension: app_data.extension.clone(),
+            },
+        };
+        let payload = match serde_json::to_vec(&msg) {
+            Err(e) => {
+                error!("[{}] marshal JSON error: {}", FN_NAME, e);
+                return Err(());
+            }
+            Ok(payload) => payload,
+        };
+        if let Err(e) = sender.send_msg(payload).await {
+            error!("[{}] send data to {} error: {}", FN_NAME, sender.name(), e);
+            return Err(());
+        }
+    }
+    Ok(())
+}
+
+/// To send the `application-uldata-failed` message to the data channel.
+async fn send_application_uldata_failed_msg(
+    data_sender: Option<&Queue>,
+    proc: &DateTime<Utc>,
+    netmgr_unit_code: Option<String>,
+    app_unit_id: String,
+    app_data: &ApplicationUlData,
+    retries: u64,
+    err: String,
+) -> Result<(), ()> {
+    const FN_NAME: &'static str = "send_application_uldata_failed_msg";
+
+    if let Some(sender) = data_sender {
+        let msg = SendDataMsg {
+            kind: DataMsgKind::APP_ULDATA_FAILED.to_string(),
+            data: SendDataKind::AppUlDataFailed {
+                data_id: app_data.data_id.clone(),
+                proc: time_str(proc),
+                publish: app_data.publish.clone(),
+                unit_code: netmgr_unit_code,
+                network_code: app_data.network_code.clone(),
+                network_addr: app_data.network_addr.clone(),
+                unit_id: app_unit_id,
+                device_id: app_data.device_id.clone(),
+                time: app_data.time.clone(),
+                profile: app_data.profile.clone(),
+                data: app_data.data.clone(),
+                extension: app_data.extension.clone(),
+                retries,
+                error: err,
+            },
+        };
+        let payload = match serde_json::to_vec(&msg) {
+            Err(e) => {
+                error!("[{}] marshal JSON error: {}", FN_NAME, e);
+                return Err(());
+            }
+            Ok(payload) => payload,
+        };
+        if let Err(e) = sender.send_msg(payload).await {
+            error!("[{}] send data to {} error: {}", FN_NAME, sender.name(), e);
+            return Err(());
+        }
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl QueueEventHandler for CtrlSenderHandler {
     async fn on_error(&self, queue: Arc<dyn GmqQueue>, err: Box<dyn StdError + Send + Sync>) {
@@ -1980,15 +2501,27 @@ impl MessageHandler for CtrlReceiverHandler {
                 let handler = MgrHandler {
                     model: self.model.clone(),
                     cache: self.cache.clone(),
+                    codecs: self.codecs.clone(),
+                    device_states: self.device_states.clone(),
                     application_mgrs: self.application_mgrs.clone(),
                     data_sender: self.data_sender.clone(),
+                    uldata_retry_limit: self.uldata_retry_limit,
+                    uldata_retry_delay: self.uldata_retry_delay,
+                    uldata_retry_queues: self.uldata_retry_queues.clone(),
+                    uldata_dedup_window: self.uldata_dedup_window,
+                    tasks: self.tasks.clone(),
                 };
                 let unit_code = new.mgr_options.unit_code.clone();
                 let name = new.mgr_options.name.clone();
+                let mut mgr_options = new.mgr_options;
+                mgr_options.tls = self.mq_tls.clone();
+                mgr_options.reconnect_policy = self.mq_reconnect.clone();
+                mgr_options.outbox = self.mq_outbox.clone();
+                mgr_options.compression = self.mq_compression;
                 let mgr = match NetworkMgr::new(
                     self.mq_conns.clone(),
                     &host_uri,
-                    new.mgr_options,
+                    mgr_options,
                     Arc::new(handler),
                 ) {
                     Err(e) => {
@@ -23,7 +23,7 @@ use tokio::time;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::{
@@ -148,6 +148,8 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
 pub fn new_ctrl_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &CfgCtrl,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     cache: Option<Arc<dyn Cache>>,
 ) -> Result<Arc<Mutex<Queue>>, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
@@ -167,6 +169,8 @@ pub fn new_ctrl_sender(
         conn_pool.clone(),
         &url,
         config.prefetch,
+        tls,
+        reconnect,
         CTRL_QUEUE_NAME,
         false,
         Arc::new(CtrlSenderHandler {
@@ -200,6 +204,8 @@ pub fn new_ctrl_receiver(state: &AppState, config: &CfgCtrl) -> Result<Queue, Bo
         state.mq_conns.clone(),
         &url,
         config.prefetch,
+        state.mq_tls.as_ref(),
+        state.mq_reconnect.as_ref(),
         CTRL_QUEUE_NAME,
         true,
         handler.clone(),
@@ -764,6 +770,10 @@ async fn send_del_ctrl_message(
 
 /// Clear the network relative cache.
 async fn clear_cache(fn_name: &str, queue_name: &str, cache: &Arc<dyn Cache>) {
+    // Clearing shared caches makes all instances query databases again. They expire by TTL.
+    if cache.is_shared() {
+        return;
+    }
     if let Err(e) = cache.network_route().clear().await {
         error!(
             "[{}] {} clear network route cache error: {}",
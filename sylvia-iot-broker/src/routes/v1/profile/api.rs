@@ -0,0 +1,623 @@
+use std::error::Error as StdError;
+
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use chrono::Utc;
+use log::error;
+use serde_json::{self, Map, Value};
+
+use sylvia_iot_corelib::{
+    constants::ContentType,
+    err::ErrResp,
+    http::{Json, Path, Query},
+    role::Role,
+    strings::{self, time_str},
+};
+
+use super::{
+    super::{
+        super::{ErrReq, State as AppState, middleware::GetTokenInfoData},
+        lib::{check_profile, check_unit},
+    },
+    request, response,
+};
+use crate::{
+    libs::codec::Codec,
+    models::profile::{
+        ListOptions, ListQueryCond, Profile, QueryCond, SortCond, SortKey, UpdateQueryCond, Updates,
+    },
+};
+
+const LIST_LIMIT_DEFAULT: u64 = 100;
+const LIST_CURSOR_MAX: u64 = 100;
+const ID_RAND_LEN: usize = 8;
+
+/// `POST /{base}/api/v1/profile`
+pub async fn post_profile(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Json(body): Json<request::PostProfileBody>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "post_profile";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+
+    let code = body.data.code.to_lowercase();
+    if !strings::is_code(code.as_str()) {
+        return Err(ErrResp::ErrParam(Some(
+            "`code` must be [A-Za-z0-9]{1}[A-Za-z0-9-_]*".to_string(),
+        )));
+    }
+    if let Some(codec) = body.data.codec.as_ref() {
+        check_codec(codec)?;
+    }
+    if let Some(info) = body.data.info.as_ref() {
+        check_info(info)?;
+    }
+    let unit_id = body.data.unit_id.as_str();
+    if unit_id.len() == 0 {
+        return Err(ErrResp::ErrParam(Some(
+            "`unitId` must with at least one character".to_string(),
+        )));
+    }
+    let unit_code = match check_unit(FN_NAME, user_id, roles, unit_id, true, &state).await? {
+        None => {
+            return Err(ErrResp::Custom(
+                ErrReq::UNIT_NOT_EXIST.0,
+                ErrReq::UNIT_NOT_EXIST.1,
+                None,
+            ));
+        }
+        Some(unit) => unit.code,
+    };
+    if check_code(FN_NAME, unit_id, code.as_str(), &state).await? {
+        return Err(ErrResp::Custom(
+            ErrReq::PROFILE_EXIST.0,
+            ErrReq::PROFILE_EXIST.1,
+            None,
+        ));
+    }
+
+    let now = Utc::now();
+    let profile = Profile {
+        profile_id: strings::random_id(&now, ID_RAND_LEN),
+        code,
+        unit_id: unit_id.to_string(),
+        unit_code,
+        created_at: now,
+        modified_at: now,
+        name: match body.data.name.as_ref() {
+            None => "".to_string(),
+            Some(name) => name.clone(),
+        },
+        codec: match body.data.codec.as_ref() {
+            None => Map::new(),
+            Some(codec) => codec.clone(),
+        },
+        offline_timeout: match body.data.offline_timeout {
+            None => 0,
+            Some(timeout) => timeout,
+        },
+        info: match body.data.info.as_ref() {
+            None => Map::new(),
+            Some(info) => info.clone(),
+        },
+    };
+    if let Err(e) = state.model.profile().add(&profile).await {
+        error!("[{}] add error: {}", FN_NAME, e);
+        return Err(ErrResp::ErrDb(Some(e.to_string())));
+    }
+    // Remove the cached result of devices that were using this profile before it was created.
+    state
+        .codecs
+        .del(profile.unit_id.as_str(), profile.code.as_str());
+    Ok(Json(response::PostProfile {
+        data: response::PostProfileData {
+            profile_id: profile.profile_id,
+        },
+    }))
+}
+
+/// `GET /{base}/api/v1/profile/count`
+pub async fn get_profile_count(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Query(query): Query<request::GetProfileCountQuery>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile_count";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+
+    if !Role::is_role(roles, Role::ADMIN) && !Role::is_role(roles, Role::MANAGER) {
+        match query.unit.as_ref() {
+            None => return Err(ErrResp::ErrParam(Some("missing `unit`".to_string()))),
+            Some(unit_id) => {
+                if unit_id.len() == 0 {
+                    return Err(ErrResp::ErrParam(Some("missing `unit`".to_string())));
+                }
+            }
+        }
+    }
+    let unit_cond = match query.unit.as_ref() {
+        None => None,
+        Some(unit_id) => match unit_id.len() {
+            0 => None,
+            _ => {
+                match check_unit(FN_NAME, user_id, roles, unit_id.as_str(), false, &state).await? {
+                    None => {
+                        return Err(ErrResp::Custom(
+                            ErrReq::UNIT_NOT_EXIST.0,
+                            ErrReq::UNIT_NOT_EXIST.1,
+                            None,
+                        ));
+                    }
+                    Some(_) => Some(unit_id.as_str()),
+                }
+            }
+        },
+    };
+    let mut code_cond = None;
+    let mut code_contains_cond = None;
+    if let Some(code) = query.code.as_ref() {
+        if code.len() > 0 {
+            code_cond = Some(code.as_str());
+        }
+    }
+    if code_cond.is_none() {
+        if let Some(contains) = query.contains.as_ref() {
+            if contains.len() > 0 {
+                code_contains_cond = Some(contains.as_str());
+            }
+        }
+    }
+    let cond = ListQueryCond {
+        unit_id: unit_cond,
+        code: code_cond,
+        code_contains: code_contains_cond,
+        ..Default::default()
+    };
+    match state.model.profile().count(&cond).await {
+        Err(e) => {
+            error!("[{}] count error: {}", FN_NAME, e);
+            Err(ErrResp::ErrDb(Some(e.to_string())))
+        }
+        Ok(count) => Ok(Json(response::GetProfileCount {
+            data: response::GetCountData { count },
+        })),
+    }
+}
+
+/// `GET /{base}/api/v1/profile/list`
+pub async fn get_profile_list(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Query(query): Query<request::GetProfileListQuery>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile_list";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+
+    if !Role::is_role(roles, Role::ADMIN) && !Role::is_role(roles, Role::MANAGER) {
+        match query.unit.as_ref() {
+            None => return Err(ErrResp::ErrParam(Some("missing `unit`".to_string()))),
+            Some(unit_id) => {
+                if unit_id.len() == 0 {
+                    return Err(ErrResp::ErrParam(Some("missing `unit`".to_string())));
+                }
+            }
+        }
+    }
+    let unit_cond = match query.unit.as_ref() {
+        None => None,
+        Some(unit_id) => match unit_id.len() {
+            0 => None,
+            _ => {
+                match check_unit(FN_NAME, user_id, roles, unit_id.as_str(), false, &state).await? {
+                    None => {
+                        return Err(ErrResp::Custom(
+                            ErrReq::UNIT_NOT_EXIST.0,
+                            ErrReq::UNIT_NOT_EXIST.1,
+                            None,
+                        ));
+                    }
+                    Some(_) => Some(unit_id.as_str()),
+                }
+            }
+        },
+    };
+    let mut code_cond = None;
+    let mut code_contains_cond = None;
+    if let Some(code) = query.code.as_ref() {
+        if code.len() > 0 {
+            code_cond = Some(code.as_str());
+        }
+    }
+    if code_cond.is_none() {
+        if let Some(contains) = query.contains.as_ref() {
+            if contains.len() > 0 {
+                code_contains_cond = Some(contains.as_str());
+            }
+        }
+    }
+    let cond = ListQueryCond {
+        unit_id: unit_cond,
+        code: code_cond,
+        code_contains: code_contains_cond,
+        ..Default::default()
+    };
+    let sort_cond = get_sort_cond(&query.sort)?;
+    let opts = ListOptions {
+        cond: &cond,
+        offset: query.offset,
+        limit: match query.limit {
+            None => Some(LIST_LIMIT_DEFAULT),
+            Some(limit) => match limit {
+                0 => None,
+                _ => Some(limit),
+            },
+        },
+        sort: Some(sort_cond.as_slice()),
+        cursor_max: Some(LIST_CURSOR_MAX),
+    };
+
+    let (list, cursor) = match state.model.profile().list(&opts, None).await {
+        Err(e) => {
+            error!("[{}] list error: {}", FN_NAME, e);
+            return Err(ErrResp::ErrDb(Some(e.to_string())));
+        }
+        Ok((list, cursor)) => match cursor {
+            None => match query.format {
+                Some(request::ListFormat::Array) => {
+                    return Ok(Json(profile_list_transform(&list)).into_response());
+                }
+                _ => {
+                    return Ok(Json(response::GetProfileList {
+                        data: profile_list_transform(&list),
+                    })
+                    .into_response());
+                }
+            },
+            Some(_) => (list, cursor),
+        },
+    };
+
+    let body = Body::from_stream(async_stream::stream! {
+        let unit_cond = match query.unit.as_ref() {
+            None => None,
+            Some(unit_id) => match unit_id.len() {
+                0 => None,
+                _ => Some(unit_id.as_str()),
+            },
+        };
+        let mut code_contains_cond = None;
+        if let Some(contains) = query.contains.as_ref() {
+            if contains.len() > 0 {
+                code_contains_cond = Some(contains.as_str());
+            }
+        }
+        let cond = ListQueryCond {
+            unit_id: unit_cond,
+            code_contains: code_contains_cond,
+            ..Default::default()
+        };
+        let opts = ListOptions {
+            cond: &cond,
+            offset: query.offset,
+            limit: match query.limit {
+                None => Some(LIST_LIMIT_DEFAULT),
+                Some(limit) => match limit {
+                    0 => None,
+                    _ => Some(limit),
+                },
+            },
+            sort: Some(sort_cond.as_slice()),
+            cursor_max: Some(LIST_CURSOR_MAX),
+        };
+
+        let mut list = list;
+        let mut cursor = cursor;
+        let mut is_first = true;
+        loop {
+            yield profile_list_transform_bytes(&list, is_first, cursor.is_none(), query.format.as_ref());
+            is_first = false;
+            if cursor.is_none() {
+                break;
+            }
+            let (_list, _cursor) = match state.model.profile().list(&opts, cursor).await {
+                Err(_) => break,
+                Ok((list, cursor)) => (list, cursor),
+            };
+            list = _list;
+            cursor = _cursor;
+        }
+    });
+    Ok(([(header::CONTENT_TYPE, ContentType::JSON)], body).into_response())
+}
+
+/// `GET /{base}/api/v1/profile/{profileId}`
+pub async fn get_profile(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Path(param): Path<request::ProfileIdPath>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+    let profile_id = param.profile_id.as_str();
+
+    match check_profile(FN_NAME, profile_id, user_id, false, roles, &state).await? {
+        None => Err(ErrResp::ErrNotFound(None)),
+        Some(profile) => Ok(Json(response::GetProfile {
+            data: profile_transform(&profile),
+        })),
+    }
+}
+
+/// `PATCH /{base}/api/v1/profile/{profileId}`
+pub async fn patch_profile(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Path(param): Path<request::ProfileIdPath>,
+    Json(body): Json<request::PatchProfileBody>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "patch_profile";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+    let profile_id = param.profile_id.as_str();
+
+    // To check if the profile is for the user.
+    let profile = match check_profile(FN_NAME, profile_id, user_id, true, roles, &state).await? {
+        None => return Err(ErrResp::ErrNotFound(None)),
+        Some(profile) => profile,
+    };
+
+    let updates = get_updates(&body.data)?;
+    let cond = UpdateQueryCond { profile_id };
+    if let Err(e) = state.model.profile().update(&cond, &updates).await {
+        error!("[{}] update error: {}", FN_NAME, e);
+        return Err(ErrResp::ErrDb(Some(e.to_string())));
+    }
+    if updates.codec.is_some() || updates.offline_timeout.is_some() {
+        state
+            .codecs
+            .del(profile.unit_id.as_str(), profile.code.as_str());
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// `DELETE /{base}/api/v1/profile/{profileId}`
+pub async fn delete_profile(
+    State(state): State<AppState>,
+    Extension(token_info): Extension<GetTokenInfoData>,
+    Path(param): Path<request::ProfileIdPath>,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "delete_profile";
+
+    let user_id = token_info.user_id.as_str();
+    let roles = &token_info.roles;
+    let profile_id = param.profile_id.as_str();
+
+    // To check if the profile is for the user.
+    let profile = match check_profile(FN_NAME, profile_id, user_id, true, roles, &state).await {
+        Err(e) => return Err(e), // XXX: not use "?" to solve E0282 error.
+        Ok(profile) => match profile {
+            None => return Ok(StatusCode::NO_CONTENT),
+            Some(profile) => profile,
+        },
+    };
+
+    let cond = QueryCond {
+        profile_id: Some(profile_id),
+        ..Default::default()
+    };
+    if let Err(e) = state.model.profile().del(&cond).await {
+        error!("[{}] del error: {}", FN_NAME, e);
+        return Err(ErrResp::ErrDb(Some(e.to_string())));
+    }
+    state
+        .codecs
+        .del(profile.unit_id.as_str(), profile.code.as_str());
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn get_sort_cond(sort_args: &Option<String>) -> Result<Vec<SortCond>, ErrResp> {
+    match sort_args.as_ref() {
+        None => Ok(vec![SortCond {
+            key: SortKey::Code,
+            asc: true,
+        }]),
+        Some(args) => {
+            let mut args = args.split(",");
+            let mut sort_cond = vec![];
+            while let Some(arg) = args.next() {
+                let mut cond = arg.split(":");
+                let key = match cond.next() {
+                    None => return Err(ErrResp::ErrParam(Some("wrong sort argument".to_string()))),
+                    Some(field) => match field {
+                        "code" => SortKey::Code,
+                        "created" => SortKey::CreatedAt,
+                        "modified" => SortKey::ModifiedAt,
+                        "name" => SortKey::Name,
+                        _ => {
+                            return Err(ErrResp::ErrParam(Some(format!(
+                                "invalid sort key {}",
+                                field
+                            ))));
+                        }
+                    },
+                };
+                let asc = match cond.next() {
+                    None => return Err(ErrResp::ErrParam(Some("wrong sort argument".to_string()))),
+                    Some(asc) => match asc {
+                        "asc" => true,
+                        "desc" => false,
+                        _ => {
+                            return Err(ErrResp::ErrParam(Some(format!(
+                                "invalid sort asc {}",
+                                asc
+                            ))));
+                        }
+                    },
+                };
+                if cond.next().is_some() {
+                    return Err(ErrResp::ErrParam(Some(
+                        "invalid sort condition".to_string(),
+                    )));
+                }
+                sort_cond.push(SortCond { key, asc });
+            }
+            Ok(sort_cond)
+        }
+    }
+}
+
+fn get_updates<'a>(body: &'a request::PatchProfileData) -> Result<Updates<'a>, ErrResp> {
+    let mut updates = Updates {
+        ..Default::default()
+    };
+    let mut count = 0;
+    if let Some(name) = body.name.as_ref() {
+        updates.name = Some(name.as_str());
+        count += 1;
+    }
+    if let Some(codec) = body.codec.as_ref() {
+        check_codec(codec)?;
+        updates.codec = Some(codec);
+        count += 1;
+    }
+    if let Some(timeout) = body.offline_timeout {
+        updates.offline_timeout = Some(timeout);
+        count += 1;
+    }
+    if let Some(info) = body.info.as_ref() {
+        check_info(info)?;
+        updates.info = Some(info);
+        count += 1;
+    }
+
+    if count == 0 {
+        return Err(ErrResp::ErrParam(Some(
+            "at least one parameter".to_string(),
+        )));
+    }
+    updates.modified_at = Some(Utc::now());
+    Ok(updates)
+}
+
+/// To check if the codec layout and script are valid.
+fn check_codec(codec: &Map<String, Value>) -> Result<(), ErrResp> {
+    match Codec::new(codec) {
+        Err(e) => Err(ErrResp::ErrParam(Some(e))),
+        Ok(_) => Ok(()),
+    }
+}
+
+fn check_info(info: &Map<String, Value>) -> Result<(), ErrResp> {
+    for (k, _) in info.iter() {
+        if k.len() == 0 {
+            return Err(ErrResp::ErrParam(Some(
+                "`info` key must not be empty".to_string(),
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// To check if the profile code is used by the unit.
+///
+/// # Errors
+///
+/// Returns OK if the code is found or not. Otherwise errors will be returned.
+async fn check_code(
+    fn_name: &str,
+    unit_id: &str,
+    code: &str,
+    state: &AppState,
+) -> Result<bool, ErrResp> {
+    let cond = QueryCond {
+        unit_id: Some(unit_id),
+        code: Some(code),
+        ..Default::default()
+    };
+    match state.model.profile().get(&cond).await {
+        Err(e) => {
+            error!("[{}] check code error: {}", fn_name, e);
+            return Err(ErrResp::ErrDb(Some(format!("check code error: {}", e))));
+        }
+        Ok(profile) => match profile {
+            None => Ok(false),
+            Some(_) => Ok(true),
+        },
+    }
+}
+
+fn profile_list_transform(list: &Vec<Profile>) -> Vec<response::GetProfileData> {
+    let mut ret = vec![];
+    for profile in list.iter() {
+        ret.push(profile_transform(&profile));
+    }
+    ret
+}
+
+fn profile_list_transform_bytes(
+    list: &Vec<Profile>,
+    with_start: bool,
+    with_end: bool,
+    format: Option<&request::ListFormat>,
+) -> Result<Bytes, Box<dyn StdError + Send + Sync>> {
+    let mut build_str = match with_start {
+        false => "".to_string(),
+        true => match format {
+            Some(request::ListFormat::Array) => "[".to_string(),
+            _ => "{\"data\":[".to_string(),
+        },
+    };
+    let mut is_first = with_start;
+
+    for item in list {
+        if is_first {
+            is_first = false;
+        } else {
+            build_str.push(',');
+        }
+        let json_str = match serde_json::to_string(&profile_transform(item)) {
+            Err(e) => return Err(Box::new(e)),
+            Ok(str) => str,
+        };
+        build_str += json_str.as_str();
+    }
+
+    if with_end {
+        build_str += match format {
+            Some(request::ListFormat::Array) => "]",
+            _ => "]}",
+        }
+    }
+    Ok(Bytes::copy_from_slice(build_str.as_str().as_bytes()))
+}
+
+fn profile_transform(profile: &Profile) -> response::GetProfileData {
+    response::GetProfileData {
+        profile_id: profile.profile_id.clone(),
+        code: profile.code.clone(),
+        unit_id: profile.unit_id.clone(),
+        unit_code: profile.unit_code.clone(),
+        created_at: time_str(&profile.created_at),
+        modified_at: time_str(&profile.modified_at),
+        name: profile.name.clone(),
+        codec: profile.codec.clone(),
+        offline_timeout: profile.offline_timeout,
+        info: profile.info.clone(),
+    }
+}
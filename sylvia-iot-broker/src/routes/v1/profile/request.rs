@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Deserialize)]
+pub struct ProfileIdPath {
+    pub profile_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct PostProfileBody {
+    pub data: PostProfileData,
+}
+
+#[derive(Deserialize)]
+pub struct PostProfileData {
+    pub code: String,
+    #[serde(rename = "unitId")]
+    pub unit_id: String,
+    pub name: Option<String>,
+    pub codec: Option<Map<String, Value>>,
+    #[serde(rename = "offlineTimeout")]
+    pub offline_timeout: Option<u64>,
+    pub info: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+pub struct GetProfileCountQuery {
+    pub unit: Option<String>,
+    pub code: Option<String>,
+    pub contains: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct GetProfileListQuery {
+    pub unit: Option<String>,
+    pub code: Option<String>,
+    pub contains: Option<String>,
+    pub offset: Option<u64>,
+    pub limit: Option<u64>,
+    pub sort: Option<String>,
+    pub format: Option<ListFormat>,
+}
+
+#[derive(Deserialize)]
+pub struct PatchProfileBody {
+    pub data: PatchProfileData,
+}
+
+#[derive(Deserialize)]
+pub struct PatchProfileData {
+    pub name: Option<String>,
+    pub codec: Option<Map<String, Value>>,
+    #[serde(rename = "offlineTimeout")]
+    pub offline_timeout: Option<u64>,
+    pub info: Option<Map<String, Value>>,
+}
+
+#[derive(Clone, Deserialize, PartialEq)]
+pub enum ListFormat {
+    #[serde(rename = "array")]
+    Array,
+    #[serde(rename = "data")]
+    Data,
+}
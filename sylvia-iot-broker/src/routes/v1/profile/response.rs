@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Serialize)]
+pub struct PostProfile {
+    pub data: PostProfileData,
+}
+
+#[derive(Serialize)]
+pub struct PostProfileData {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+}
+
+#[derive(Serialize)]
+pub struct GetProfileCount {
+    pub data: GetCountData,
+}
+
+#[derive(Serialize)]
+pub struct GetCountData {
+    pub count: u64,
+}
+
+#[derive(Serialize)]
+pub struct GetProfileList {
+    pub data: Vec<GetProfileData>,
+}
+
+#[derive(Serialize)]
+pub struct GetProfile {
+    pub data: GetProfileData,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct GetProfileData {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+    pub code: String,
+    #[serde(rename = "unitId")]
+    pub unit_id: String,
+    #[serde(rename = "unitCode")]
+    pub unit_code: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: String,
+    pub name: String,
+    pub codec: Map<String, Value>,
+    #[serde(rename = "offlineTimeout")]
+    pub offline_timeout: u64,
+    pub info: Map<String, Value>,
+}
@@ -23,7 +23,7 @@ use tokio::time;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::{
@@ -57,7 +57,7 @@ use crate::{
             self, Cursor as NetworkCursor, ListOptions as NetworkListOpts,
             ListQueryCond as NetworkCond,
         },
-        network_route,
+        network_route, profile,
         unit::{
             Cursor, ListOptions, ListQueryCond, QueryCond, SortCond, SortKey, Unit,
             UpdateQueryCond, Updates,
@@ -152,6 +152,8 @@ pub async fn init(state: &AppState, ctrl_conf: &CfgCtrl) -> Result<(), Box<dyn S
 pub fn new_ctrl_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &CfgCtrl,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Arc<Mutex<Queue>>, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
         None => {
@@ -170,6 +172,8 @@ pub fn new_ctrl_sender(
         conn_pool.clone(),
         &url,
         config.prefetch,
+        tls,
+        reconnect,
         CTRL_QUEUE_NAME,
         false,
         Arc::new(CtrlSenderHandler {}),
@@ -201,6 +205,8 @@ pub fn new_ctrl_receiver(state: &AppState, config: &CfgCtrl) -> Result<Queue, Bo
         state.mq_conns.clone(),
         &url,
         config.prefetch,
+        state.mq_tls.as_ref(),
+        state.mq_reconnect.as_ref(),
         CTRL_QUEUE_NAME,
         true,
         handler.clone(),
@@ -1027,6 +1033,16 @@ async fn del_unit_rsc(
         return Err(ErrResp::ErrDb(Some(e.to_string())));
     }
 
+    let cond = profile::QueryCond {
+        unit_id: Some(unit_id),
+        ..Default::default()
+    };
+    if let Err(e) = state.model.profile().del(&cond).await {
+        error!("[{}] del profile error: {}", fn_name, e);
+        return Err(ErrResp::ErrDb(Some(e.to_string())));
+    }
+    state.codecs.clear();
+
     let cond = QueryCond {
         unit_id: Some(unit_id),
         ..Default::default()
@@ -11,7 +11,7 @@ use general_mq::{Queue, connection::GmqConnection, queue::GmqQueue};
 use sylvia_iot_auth::models::SqliteModel as AuthDbModel;
 use sylvia_iot_broker::{
     libs::mq::{Connection, application::ApplicationMgr, network::NetworkMgr},
-    models::{Cache, Model, MongoDbModel, SqliteModel},
+    models::{Cache, Model, MongoDbModel, PostgresModel, SqliteModel},
     routes::State,
 };
 use sylvia_iot_corelib::constants::{CacheEngine, DbEngine, MqEngine};
@@ -27,6 +27,7 @@ pub struct TestState {
     pub auth_svc: Option<JoinHandle<()>>, // sylvia-iot-auth service.
     pub auth_uri: Option<String>, // the /tokeninfo URI.
     pub mongodb: Option<MongoDbModel>,
+    pub postgres: Option<PostgresModel>,
     pub sqlite: Option<SqliteModel>,
     pub cache: Option<Arc<dyn Cache>>,
     pub cache_model: Option<Arc<dyn Model>>,
@@ -53,6 +54,7 @@ pub const WAIT_COUNT: isize = 100;
 pub const WAIT_TICK: u64 = 100;
 pub const TEST_MONGODB_URL: &'static str = "mongodb://localhost:27017";
 pub const TEST_MONGODB_DB: &'static str = "test";
+pub const TEST_POSTGRES_URL: &'static str = "postgresql://localhost:5432/test";
 pub const TEST_SQLITE_PATH: &'static str = "test.db";
 pub const TEST_CACHE_SIZE: usize = 10000;
 pub const TEST_REDIRECT_URI: &'static str = "http://localhost:1080/auth/oauth2/redirect";
@@ -67,8 +69,10 @@ async fn integration_test() -> LabResult {
             context.describe_import(libs::mq::suite(MqEngine::RABBITMQ));
             context.describe_import(libs::mq::suite(MqEngine::EMQX));
             context.describe_import(models::mongodb::suite());
+            context.describe_import(models::postgres::suite());
             context.describe_import(models::sqlite::suite());
             context.describe_import(models::memory::suite());
+            context.describe_import(models::redis::suite());
             context.describe_import(routes::suite());
             context.describe_import(routes::middleware::suite(DbEngine::MONGODB));
             context.describe_import(routes::middleware::suite(DbEngine::SQLITE));
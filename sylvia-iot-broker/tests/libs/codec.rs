@@ -0,0 +1,201 @@
+use laboratory::{SpecContext, expect};
+use serde_json::{Map, Value, json};
+
+use sylvia_iot_broker::libs::codec::Codec;
+
+use crate::TestState;
+
+/// Test [`Codec::new`] with valid and invalid codecs.
+pub fn new(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let codecs = vec![
+        json!({}),
+        json!({"fields": []}),
+        json!({"fields": [{"name": "a", "offset": 0, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": 1, "type": "f32", "endian": "little", "scale": 0.1}]}),
+        json!({"fields": [{"name": "a", "offset": 1024, "type": "u64"}]}),
+        json!({"script": ""}),
+        json!({"script": "fn decode(bytes, object) { object }"}),
+    ];
+    for codec in codecs.iter() {
+        if let Err(e) = Codec::new(codec.as_object().unwrap()) {
+            return Err(format!("{} should be valid: {}", codec, e));
+        }
+    }
+
+    let codecs = vec![
+        json!({"fields": {}}),
+        json!({"fields": [1]}),
+        json!({"fields": [{"offset": 0, "type": "u8"}]}),
+        json!({"fields": [{"name": "", "offset": 0, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": -1, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": 1025, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": 18446744073709551615u64, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": 1000000000000u64, "type": "u8"}]}),
+        json!({"fields": [{"name": "a", "offset": 0, "type": "u128"}]}),
+        json!({"fields": [{"name": "a", "offset": 0, "type": "u8", "endian": "middle"}]}),
+        json!({"fields": [{"name": "a", "offset": 0, "type": "u8", "scale": 0}]}),
+        json!({"fields": [{"name": "a", "offset": 0, "type": "u8", "scale": "1"}]}),
+        json!({"script": 1}),
+        json!({"script": "fn decode(bytes, object) {"}),
+        json!({"script": "fn decode(bytes, object) { eval(\"object\") }"}),
+    ];
+    for codec in codecs.iter() {
+        if Codec::new(codec.as_object().unwrap()).is_ok() {
+            return Err(format!("{} should be invalid", codec));
+        }
+    }
+    Ok(())
+}
+
+/// Test [`Codec::decode`] with the binary layout.
+pub fn decode_fields(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let codec = json!({
+        "fields": [
+            {"name": "u8", "offset": 0, "type": "u8"},
+            {"name": "i8", "offset": 1, "type": "i8"},
+            {"name": "u16", "offset": 2, "type": "u16"},
+            {"name": "i16le", "offset": 4, "type": "i16", "endian": "little"},
+            {"name": "u32", "offset": 6, "type": "u32"},
+            {"name": "temperature", "offset": 10, "type": "i16", "scale": 0.5},
+            {"name": "f32", "offset": 12, "type": "f32"},
+        ]
+    });
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+
+    let data = "01ff0102feff00010000f63c3fc00000";
+    let object = match codec.decode(data) {
+        Err(e) => return Err(format!("decode error: {}", e)),
+        Ok(object) => match object {
+            None => return Err("should decode an object".to_string()),
+            Some(object) => object,
+        },
+    };
+    let expected = json!({
+        "u8": 1,
+        "i8": -1,
+        "u16": 258,
+        "i16le": -2,
+        "u32": 65536,
+        "temperature": -1250.0,
+        "f32": 1.5,
+    });
+    expect(Value::Object(object)).to_equal(expected)?;
+
+    if codec.decode("01ff").is_ok() {
+        return Err("decode short data should error".to_string());
+    }
+    if codec.decode("zz").is_ok() {
+        return Err("decode invalid hex should error".to_string());
+    }
+
+    let codec = json!({"fields": [{"name": "a", "offset": 1024, "type": "u64"}]});
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new max offset codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+    if codec.decode("01ff").is_ok() {
+        return Err("decode data shorter than the max offset should error".to_string());
+    }
+    match codec.encode(json!({"a": 1}).as_object().unwrap()) {
+        Err(e) => return Err(format!("encode with the max offset error: {}", e)),
+        Ok(data) => expect(data.map(|d| d.len())).to_equal(Some(2 * (1024 + 8)))?,
+    }
+
+    let codec = match Codec::new(&Map::new()) {
+        Err(e) => return Err(format!("new empty codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+    match codec.decode(data) {
+        Err(e) => Err(format!("decode with empty codec error: {}", e)),
+        Ok(object) => expect(object.is_none()).to_equal(true),
+    }
+}
+
+/// Test [`Codec::encode`] with the binary layout.
+pub fn encode_fields(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let codec = json!({
+        "fields": [
+            {"name": "cmd", "offset": 0, "type": "u8"},
+            {"name": "interval", "offset": 1, "type": "u16", "endian": "little"},
+            {"name": "threshold", "offset": 3, "type": "i16", "scale": 0.1},
+        ]
+    });
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+
+    let object = json!({"cmd": 2, "interval": 300, "threshold": -1.5});
+    match codec.encode(object.as_object().unwrap()) {
+        Err(e) => return Err(format!("encode error: {}", e)),
+        Ok(data) => expect(data).to_equal(Some("022c01fff1".to_string()))?,
+    }
+
+    let object = json!({"cmd": 2, "interval": 300});
+    if codec.encode(object.as_object().unwrap()).is_ok() {
+        return Err("encode without a field should error".to_string());
+    }
+    let object = json!({"cmd": 256, "interval": 300, "threshold": 0});
+    if codec.encode(object.as_object().unwrap()).is_ok() {
+        return Err("encode out of range should error".to_string());
+    }
+    let object = json!({"cmd": "2", "interval": 300, "threshold": 0});
+    if codec.encode(object.as_object().unwrap()).is_ok() {
+        return Err("encode string should error".to_string());
+    }
+    Ok(())
+}
+
+/// Test [`Codec::decode`] and [`Codec::encode`] with the script.
+pub fn script(_context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let codec = json!({
+        "fields": [{"name": "battery", "offset": 0, "type": "u8"}],
+        "script": "\
+            fn decode(bytes, object) { object.low = object.battery < 20; object.len = bytes.len(); object }\
+            fn encode(object) { let b = blob(); b.push(object.cmd); b.push(0xff); b }",
+    });
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+    match codec.decode("0a00") {
+        Err(e) => return Err(format!("decode error: {}", e)),
+        Ok(object) => expect(object.map(Value::Object))
+            .to_equal(Some(json!({"battery": 10, "low": true, "len": 2})))?,
+    }
+    let object = json!({"cmd": 1});
+    match codec.encode(object.as_object().unwrap()) {
+        Err(e) => return Err(format!("encode error: {}", e)),
+        Ok(data) => expect(data).to_equal(Some("01ff".to_string()))?,
+    }
+
+    let codec = json!({
+        "script": "fn decode(bytes, object) { 1 } fn encode(object) { [1, 256] }",
+    });
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+    if codec.decode("00").is_ok() {
+        return Err("decode not an object should error".to_string());
+    }
+    if codec.encode(&Map::new()).is_ok() {
+        return Err("encode not a byte array should error".to_string());
+    }
+
+    let codec = json!({
+        "script": "fn decode(bytes, object) { loop {} }",
+    });
+    let codec = match Codec::new(codec.as_object().unwrap()) {
+        Err(e) => return Err(format!("new codec error: {}", e)),
+        Ok(codec) => codec,
+    };
+    if codec.decode("00").is_ok() {
+        return Err("decode with an infinite loop should error".to_string());
+    }
+    Ok(())
+}
@@ -25,11 +25,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(config::DEF_ENGINE)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_URL)?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_DB)?;
     expect(db_conf.pool_size).to_equal(None)?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_POSTGRES_URL)?;
+    expect(db_conf.pool_size).to_equal(None)?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal(config::DEF_SQLITE_PATH)?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -40,6 +44,9 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(cache_conf.device).to_equal(Some(config::DEF_MEMORY_DEVICE))?;
     expect(cache_conf.device_route).to_equal(Some(config::DEF_MEMORY_DEVICE_ROUTE))?;
     expect(cache_conf.network_route).to_equal(Some(config::DEF_MEMORY_NETWORK_ROUTE))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_REDIS_URL)?;
+    expect(cache_conf.ttl).to_equal(Some(config::DEF_REDIS_TTL))?;
     expect(conf.mq.is_some()).to_equal(true)?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
@@ -49,6 +56,27 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_conf.dead_letter).to_equal(Some(config::DEF_MQ_DEADLETTER))?;
+    expect(mq_conf.queue_type.as_ref().unwrap().as_str()).to_equal(config::DEF_MQ_QUEUETYPE)?;
+    expect(mq_conf.message_ttl).to_equal(None)?;
+    expect(mq_conf.max_length).to_equal(None)?;
+    expect(mq_conf.overflow.is_none()).to_equal(true)?;
+    let tls_conf = mq_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.cert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.key_file.is_none()).to_equal(true)?;
+    expect(tls_conf.server_name.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let outbox_conf = mq_conf.outbox.as_ref().unwrap();
+    expect(outbox_conf.path.is_none()).to_equal(true)?;
+    expect(outbox_conf.max_messages).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_MESSAGES))?;
+    expect(outbox_conf.max_bytes).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_BYTES))?;
+    expect(outbox_conf.max_age).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_AGE))?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some(config::DEF_MQ_COMPRESSION))?;
     expect(conf.mq_channels.is_some()).to_equal(true)?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
@@ -92,6 +120,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.is_none()).to_equal(true)?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(config::DEF_DLDATA_EXPIRES_IN))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(config::DEF_DLDATA_MAX_EXPIRES_IN))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(config::DEF_DLDATA_SWEEP_INTERVAL))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(config::DEF_ULDATA_RETRY_LIMIT))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(config::DEF_ULDATA_RETRY_DELAY))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(config::DEF_ULDATA_DEDUP_WINDOW))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(config::DEF_DEVICE_FLUSH_INTERVAL))?;
+    expect(device_conf.sweep_interval).to_equal(Some(config::DEF_DEVICE_SWEEP_INTERVAL))?;
     expect(conf.api_scopes.as_ref()).to_equal(Some(&HashMap::new()))?;
 
     // Modified default by command-line arguments.
@@ -107,6 +149,10 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "db1",
         "--broker.db.mongodb.poolsize",
         "11",
+        "--broker.db.postgres.url",
+        "url12",
+        "--broker.db.postgres.poolsize",
+        "12",
         "--broker.db.sqlite.path",
         "path1",
         "--broker.cache.engine",
@@ -117,12 +163,50 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "112",
         "--broker.cache.memory.network-route",
         "113",
+        "--broker.cache.redis.url",
+        "redis1",
+        "--broker.cache.redis.ttl",
+        "114",
         "--broker.mq.prefetch",
         "12",
         "--broker.mq.persistent",
         "true",
         "--broker.mq.sharedprefix",
         "prefix1",
+        "--broker.mq.deadletter",
+        "true",
+        "--broker.mq.queuetype",
+        "quorum",
+        "--broker.mq.messagettl",
+        "1000",
+        "--broker.mq.maxlength",
+        "100",
+        "--broker.mq.overflow",
+        "reject-publish",
+        "--broker.mq.tls.cacertfile",
+        "cacert1",
+        "--broker.mq.tls.certfile",
+        "cert1",
+        "--broker.mq.tls.keyfile",
+        "key1",
+        "--broker.mq.tls.servername",
+        "server1",
+        "--broker.mq.tls.insecure",
+        "true",
+        "--broker.mq.outbox.path",
+        "outbox1",
+        "--broker.mq.outbox.maxmessages",
+        "11",
+        "--broker.mq.outbox.maxbytes",
+        "1024",
+        "--broker.mq.outbox.maxage",
+        "10000",
+        "--broker.mq.reconnect.initialmillis",
+        "100",
+        "--broker.mq.reconnect.maxmillis",
+        "10000",
+        "--broker.mq.compression",
+        "gzip",
         "--broker.mq-channels.unit.url",
         "url13",
         "--broker.mq-channels.unit.prefetch",
@@ -151,6 +235,22 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "url19",
         "--broker.mq-channels.data.persistent",
         "false",
+        "--broker.dldata.expires-in",
+        "60",
+        "--broker.dldata.max-expires-in",
+        "120",
+        "--broker.dldata.sweep-interval",
+        "30",
+        "--broker.uldata.retry-limit",
+        "3",
+        "--broker.uldata.retry-delay",
+        "300",
+        "--broker.uldata.dedup-window",
+        "200",
+        "--broker.device.flush-interval",
+        "20",
+        "--broker.device.sweep-interval",
+        "30",
         "--broker.api-scopes",
         "{\"key11\":[\"value11\"]}",
     ];
@@ -162,11 +262,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(DbEngine::MONGODB)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url11")?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal("db1")?;
     expect(db_conf.pool_size).to_equal(Some(11))?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url12")?;
+    expect(db_conf.pool_size).to_equal(Some(12))?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal("path1")?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -177,6 +281,9 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(cache_conf.device).to_equal(Some(111))?;
     expect(cache_conf.device_route).to_equal(Some(112))?;
     expect(cache_conf.network_route).to_equal(Some(113))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal("redis1")?;
+    expect(cache_conf.ttl).to_equal(Some(114))?;
     expect(conf.mq.is_some()).to_equal(true)?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
@@ -185,6 +292,26 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_conf.persistent.unwrap()).to_equal(true)?;
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("prefix1")?;
+    expect(mq_conf.dead_letter).to_equal(Some(true))?;
+    expect(mq_conf.queue_type.as_ref().unwrap().as_str()).to_equal("quorum")?;
+    expect(mq_conf.message_ttl).to_equal(Some(1000))?;
+    expect(mq_conf.max_length).to_equal(Some(100))?;
+    expect(mq_conf.overflow.as_ref().unwrap().as_str()).to_equal("reject-publish")?;
+    let tls_conf = mq_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_ref().unwrap().as_str()).to_equal("cacert1")?;
+    expect(tls_conf.cert_file.as_ref().unwrap().as_str()).to_equal("cert1")?;
+    expect(tls_conf.key_file.as_ref().unwrap().as_str()).to_equal("key1")?;
+    expect(tls_conf.server_name.as_ref().unwrap().as_str()).to_equal("server1")?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let outbox_conf = mq_conf.outbox.as_ref().unwrap();
+    expect(outbox_conf.path.as_ref().unwrap().as_str()).to_equal("outbox1")?;
+    expect(outbox_conf.max_messages).to_equal(Some(11))?;
+    expect(outbox_conf.max_bytes).to_equal(Some(1024))?;
+    expect(outbox_conf.max_age).to_equal(Some(10000))?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(100))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(10000))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some("gzip"))?;
     expect(conf.mq_channels.is_some()).to_equal(true)?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
@@ -229,6 +356,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url19")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(60))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(120))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(30))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(3))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(300))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(200))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(20))?;
+    expect(device_conf.sweep_interval).to_equal(Some(30))?;
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     map.insert("key11".to_string(), vec!["value11".to_string()]);
     expect(conf.api_scopes.as_ref()).to_equal(Some(&map))?;
@@ -280,14 +421,35 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_DB_MONGODB_URL", "url21");
     set_env_var("BROKER_DB_MONGODB_DATABASE", "db2");
     set_env_var("BROKER_DB_MONGODB_POOLSIZE", "21");
+    set_env_var("BROKER_DB_POSTGRES_URL", "url22");
+    set_env_var("BROKER_DB_POSTGRES_POOLSIZE", "22");
     set_env_var("BROKER_DB_SQLITE_PATH", "path2");
     set_env_var("BROKER_CACHE_ENGINE", "memory");
     set_env_var("BROKER_CACHE_MEMORY_DEVICE", "121");
     set_env_var("BROKER_CACHE_MEMORY_DEVICE_ROUTE", "122");
     set_env_var("BROKER_CACHE_MEMORY_NETWORK_ROUTE", "123");
+    set_env_var("BROKER_CACHE_REDIS_URL", "redis2");
+    set_env_var("BROKER_CACHE_REDIS_TTL", "124");
     set_env_var("BROKER_MQ_PREFETCH", "22");
     set_env_var("BROKER_MQ_PERSISTENT", "true");
     set_env_var("BROKER_MQ_SHAREDPREFIX", "prefix2");
+    set_env_var("BROKER_MQ_DEADLETTER", "true");
+    set_env_var("BROKER_MQ_QUEUETYPE", "quorum");
+    set_env_var("BROKER_MQ_MESSAGETTL", "2000");
+    set_env_var("BROKER_MQ_MAXLENGTH", "200");
+    set_env_var("BROKER_MQ_OVERFLOW", "reject-publish-dlx");
+    set_env_var("BROKER_MQ_TLS_CACERTFILE", "cacert2");
+    set_env_var("BROKER_MQ_TLS_CERTFILE", "cert2");
+    set_env_var("BROKER_MQ_TLS_KEYFILE", "key2");
+    set_env_var("BROKER_MQ_TLS_SERVERNAME", "server2");
+    set_env_var("BROKER_MQ_TLS_INSECURE", "true");
+    set_env_var("BROKER_MQ_OUTBOX_PATH", "outbox2");
+    set_env_var("BROKER_MQ_OUTBOX_MAXMESSAGES", "21");
+    set_env_var("BROKER_MQ_OUTBOX_MAXBYTES", "2024");
+    set_env_var("BROKER_MQ_OUTBOX_MAXAGE", "20000");
+    set_env_var("BROKER_MQ_RECONNECT_INITIALMILLIS", "200");
+    set_env_var("BROKER_MQ_RECONNECT_MAXMILLIS", "20000");
+    set_env_var("BROKER_MQ_COMPRESSION", "zstd");
     set_env_var("BROKER_MQCHANNELS_UNIT_URL", "url23");
     set_env_var("BROKER_MQCHANNELS_UNIT_PREFETCH", "23");
     set_env_var("BROKER_MQCHANNELS_APPLICATION_URL", "url24");
@@ -302,6 +464,14 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_MQCHANNELS_NETWORK_ROUTE_PREFETCH", "28");
     set_env_var("BROKER_MQCHANNELS_DATA_URL", "url29");
     set_env_var("BROKER_MQCHANNELS_DATA_PERSISTENT", "false");
+    set_env_var("BROKER_DLDATA_EXPIRES_IN", "70");
+    set_env_var("BROKER_DLDATA_MAX_EXPIRES_IN", "140");
+    set_env_var("BROKER_DLDATA_SWEEP_INTERVAL", "40");
+    set_env_var("BROKER_ULDATA_RETRY_LIMIT", "6");
+    set_env_var("BROKER_ULDATA_RETRY_DELAY", "600");
+    set_env_var("BROKER_ULDATA_DEDUP_WINDOW", "400");
+    set_env_var("BROKER_DEVICE_FLUSH_INTERVAL", "40");
+    set_env_var("BROKER_DEVICE_SWEEP_INTERVAL", "50");
     set_env_var("BROKER_API_SCOPES", "{\"key21\":[\"value21\"]}");
     let conf = config::read_args(&args);
     expect(conf.auth.is_some()).to_equal(true)?;
@@ -310,11 +480,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(DbEngine::MONGODB)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url21")?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal("db2")?;
     expect(db_conf.pool_size).to_equal(Some(21))?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url22")?;
+    expect(db_conf.pool_size).to_equal(Some(22))?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal("path2")?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -325,6 +499,9 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(cache_conf.device).to_equal(Some(121))?;
     expect(cache_conf.device_route).to_equal(Some(122))?;
     expect(cache_conf.network_route).to_equal(Some(123))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal("redis2")?;
+    expect(cache_conf.ttl).to_equal(Some(124))?;
     expect(conf.mq.is_some()).to_equal(true)?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
@@ -333,6 +510,26 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_conf.persistent.unwrap()).to_equal(true)?;
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("prefix2")?;
+    expect(mq_conf.dead_letter).to_equal(Some(true))?;
+    expect(mq_conf.queue_type.as_ref().unwrap().as_str()).to_equal("quorum")?;
+    expect(mq_conf.message_ttl).to_equal(Some(2000))?;
+    expect(mq_conf.max_length).to_equal(Some(200))?;
+    expect(mq_conf.overflow.as_ref().unwrap().as_str()).to_equal("reject-publish-dlx")?;
+    let tls_conf = mq_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_ref().unwrap().as_str()).to_equal("cacert2")?;
+    expect(tls_conf.cert_file.as_ref().unwrap().as_str()).to_equal("cert2")?;
+    expect(tls_conf.key_file.as_ref().unwrap().as_str()).to_equal("key2")?;
+    expect(tls_conf.server_name.as_ref().unwrap().as_str()).to_equal("server2")?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let outbox_conf = mq_conf.outbox.as_ref().unwrap();
+    expect(outbox_conf.path.as_ref().unwrap().as_str()).to_equal("outbox2")?;
+    expect(outbox_conf.max_messages).to_equal(Some(21))?;
+    expect(outbox_conf.max_bytes).to_equal(Some(2024))?;
+    expect(outbox_conf.max_age).to_equal(Some(20000))?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(200))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(20000))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some("zstd"))?;
     expect(conf.mq_channels.is_some()).to_equal(true)?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
@@ -377,6 +574,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url29")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(70))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(140))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(40))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(6))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(600))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(400))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(40))?;
+    expect(device_conf.sweep_interval).to_equal(Some(50))?;
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     map.insert("key21".to_string(), vec!["value21".to_string()]);
     expect(conf.api_scopes.as_ref()).to_equal(Some(&map))?;
@@ -397,9 +608,11 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_DB_ENGINE", "test2");
     set_env_var("BROKER_CACHE_ENGINE", "test3");
     set_env_var("BROKER_DB_MONGODB_POOLSIZE", "12_000");
+    set_env_var("BROKER_DB_POSTGRES_POOLSIZE", "12_000");
     set_env_var("BROKER_CACHE_MEMORY_DEVICE", "12_000");
     set_env_var("BROKER_CACHE_MEMORY_DEVICE_ROUTE", "12_000");
     set_env_var("BROKER_CACHE_MEMORY_NETWORK_ROUTE", "12_000");
+    set_env_var("BROKER_CACHE_REDIS_TTL", "12_000");
     set_env_var("BROKER_MQ_PREFETCH", "12_000");
     set_env_var("BROKER_MQ_PERSISTENT", "1");
     set_env_var("BROKER_MQCHANNELS_UNIT_PREFETCH", "12_000");
@@ -409,6 +622,14 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_MQCHANNELS_DEVICE_ROUTE_PREFETCH", "12_000");
     set_env_var("BROKER_MQCHANNELS_NETWORK_ROUTE_PREFETCH", "12_000");
     set_env_var("BROKER_MQCHANNELS_DATA_PERSISTENT", "0");
+    set_env_var("BROKER_DLDATA_EXPIRES_IN", "12_000");
+    set_env_var("BROKER_DLDATA_MAX_EXPIRES_IN", "12_000");
+    set_env_var("BROKER_DLDATA_SWEEP_INTERVAL", "12_000");
+    set_env_var("BROKER_ULDATA_RETRY_LIMIT", "12_000");
+    set_env_var("BROKER_ULDATA_RETRY_DELAY", "12_000");
+    set_env_var("BROKER_ULDATA_DEDUP_WINDOW", "12_000");
+    set_env_var("BROKER_DEVICE_FLUSH_INTERVAL", "12_000");
+    set_env_var("BROKER_DEVICE_SWEEP_INTERVAL", "12_000");
     set_env_var("BROKER_API_SCOPES", "}");
     let args = config::reg_args(Command::new("test")).get_matches_from(vec!["test"]);
     let conf = config::read_args(&args);
@@ -416,6 +637,8 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(config::DEF_ENGINE)?;
     expect(db_conf.pool_size).to_equal(None)?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.pool_size).to_equal(None)?;
     expect(conf.cache.is_some()).to_equal(true)?;
     let cache_conf = conf.cache.as_ref().unwrap();
     expect(cache_conf.engine.as_ref().unwrap().as_str()).to_equal(CacheEngine::NONE)?;
@@ -424,11 +647,27 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(cache_conf.device).to_equal(Some(config::DEF_MEMORY_DEVICE))?;
     expect(cache_conf.device_route).to_equal(Some(config::DEF_MEMORY_DEVICE_ROUTE))?;
     expect(cache_conf.network_route).to_equal(Some(config::DEF_MEMORY_NETWORK_ROUTE))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.ttl).to_equal(Some(config::DEF_REDIS_TTL))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.data.is_some()).to_equal(true)?;
     let data_conf = mq_channels_conf.data.as_ref().unwrap();
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(config::DEF_DLDATA_EXPIRES_IN))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(config::DEF_DLDATA_MAX_EXPIRES_IN))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(config::DEF_DLDATA_SWEEP_INTERVAL))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(config::DEF_ULDATA_RETRY_LIMIT))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(config::DEF_ULDATA_RETRY_DELAY))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(config::DEF_ULDATA_DEDUP_WINDOW))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(config::DEF_DEVICE_FLUSH_INTERVAL))?;
+    expect(device_conf.sweep_interval).to_equal(Some(config::DEF_DEVICE_SWEEP_INTERVAL))?;
     expect(conf.api_scopes.as_ref()).to_equal(Some(&HashMap::new()))?;
 
     // Test command-line arguments overwrite environment variables.
@@ -444,6 +683,10 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "db3",
         "--broker.db.mongodb.poolsize",
         "31",
+        "--broker.db.postgres.url",
+        "url32",
+        "--broker.db.postgres.poolsize",
+        "32",
         "--broker.db.sqlite.path",
         "path3",
         "--broker.cache.engine",
@@ -460,6 +703,32 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "true",
         "--broker.mq.sharedprefix",
         "prefix3",
+        "--broker.mq.deadletter",
+        "true",
+        "--broker.mq.tls.cacertfile",
+        "cacert3",
+        "--broker.mq.tls.certfile",
+        "cert3",
+        "--broker.mq.tls.keyfile",
+        "key3",
+        "--broker.mq.tls.servername",
+        "server3",
+        "--broker.mq.tls.insecure",
+        "true",
+        "--broker.mq.outbox.path",
+        "outbox3",
+        "--broker.mq.outbox.maxmessages",
+        "31",
+        "--broker.mq.outbox.maxbytes",
+        "3024",
+        "--broker.mq.outbox.maxage",
+        "30000",
+        "--broker.mq.reconnect.initialmillis",
+        "300",
+        "--broker.mq.reconnect.maxmillis",
+        "30000",
+        "--broker.mq.compression",
+        "gzip",
         "--broker.mq-channels.unit.url",
         "url33",
         "--broker.mq-channels.unit.prefetch",
@@ -488,6 +757,22 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "url39",
         "--broker.mq-channels.data.persistent",
         "false",
+        "--broker.dldata.expires-in",
+        "80",
+        "--broker.dldata.max-expires-in",
+        "160",
+        "--broker.dldata.sweep-interval",
+        "50",
+        "--broker.uldata.retry-limit",
+        "4",
+        "--broker.uldata.retry-delay",
+        "500",
+        "--broker.uldata.dedup-window",
+        "100",
+        "--broker.device.flush-interval",
+        "15",
+        "--broker.device.sweep-interval",
+        "25",
         "--broker.api-scopes",
         "{\"key31\":[\"value31\"]}",
     ];
@@ -496,6 +781,8 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_DB_MONGODB_URL", "url41");
     set_env_var("BROKER_DB_MONGODB_DATABASE", "db4");
     set_env_var("BROKER_DB_MONGODB_POOLSIZE", "41");
+    set_env_var("BROKER_DB_POSTGRES_URL", "url42");
+    set_env_var("BROKER_DB_POSTGRES_POOLSIZE", "42");
     set_env_var("BROKER_DB_SQLITE_PATH", "path4");
     set_env_var("BROKER_CACHE_ENGINE", "memory");
     set_env_var("BROKER_CACHE_MEMORY_DEVICE", "141");
@@ -504,6 +791,19 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_MQ_PREFETCH", "42");
     set_env_var("BROKER_MQ_PERSISTENT", "false");
     set_env_var("BROKER_MQ_SHAREDPREFIX", "prefix4");
+    set_env_var("BROKER_MQ_DEADLETTER", "false");
+    set_env_var("BROKER_MQ_TLS_CACERTFILE", "cacert4");
+    set_env_var("BROKER_MQ_TLS_CERTFILE", "cert4");
+    set_env_var("BROKER_MQ_TLS_KEYFILE", "key4");
+    set_env_var("BROKER_MQ_TLS_SERVERNAME", "server4");
+    set_env_var("BROKER_MQ_TLS_INSECURE", "false");
+    set_env_var("BROKER_MQ_OUTBOX_PATH", "outbox4");
+    set_env_var("BROKER_MQ_OUTBOX_MAXMESSAGES", "41");
+    set_env_var("BROKER_MQ_OUTBOX_MAXBYTES", "4024");
+    set_env_var("BROKER_MQ_OUTBOX_MAXAGE", "40000");
+    set_env_var("BROKER_MQ_RECONNECT_INITIALMILLIS", "400");
+    set_env_var("BROKER_MQ_RECONNECT_MAXMILLIS", "40000");
+    set_env_var("BROKER_MQ_COMPRESSION", "zstd");
     set_env_var("BROKER_MQCHANNELS_UNIT_URL", "url43");
     set_env_var("BROKER_MQCHANNELS_UNIT_PREFETCH", "43");
     set_env_var("BROKER_MQCHANNELS_APPLICATION_URL", "url44");
@@ -518,6 +818,14 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("BROKER_MQCHANNELS_NETWORK_ROUTE_PREFETCH", "48");
     set_env_var("BROKER_MQCHANNELS_DATA_URL", "url49");
     set_env_var("BROKER_MQCHANNELS_DATA_PERSISTENT", "true");
+    set_env_var("BROKER_DLDATA_EXPIRES_IN", "90");
+    set_env_var("BROKER_DLDATA_MAX_EXPIRES_IN", "180");
+    set_env_var("BROKER_DLDATA_SWEEP_INTERVAL", "60");
+    set_env_var("BROKER_ULDATA_RETRY_LIMIT", "7");
+    set_env_var("BROKER_ULDATA_RETRY_DELAY", "700");
+    set_env_var("BROKER_ULDATA_DEDUP_WINDOW", "300");
+    set_env_var("BROKER_DEVICE_FLUSH_INTERVAL", "35");
+    set_env_var("BROKER_DEVICE_SWEEP_INTERVAL", "45");
     set_env_var("BROKER_API_SCOPES", "{\"key41\":[\"value41\"]}");
     let args = config::reg_args(Command::new("test")).get_matches_from(args);
     let conf = config::read_args(&args);
@@ -527,11 +835,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(DbEngine::MONGODB)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url31")?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal("db3")?;
     expect(db_conf.pool_size).to_equal(Some(31))?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url32")?;
+    expect(db_conf.pool_size).to_equal(Some(32))?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal("path3")?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -550,6 +862,22 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_conf.persistent.unwrap()).to_equal(true)?;
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("prefix3")?;
+    expect(mq_conf.dead_letter).to_equal(Some(true))?;
+    let tls_conf = mq_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_ref().unwrap().as_str()).to_equal("cacert3")?;
+    expect(tls_conf.cert_file.as_ref().unwrap().as_str()).to_equal("cert3")?;
+    expect(tls_conf.key_file.as_ref().unwrap().as_str()).to_equal("key3")?;
+    expect(tls_conf.server_name.as_ref().unwrap().as_str()).to_equal("server3")?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let outbox_conf = mq_conf.outbox.as_ref().unwrap();
+    expect(outbox_conf.path.as_ref().unwrap().as_str()).to_equal("outbox3")?;
+    expect(outbox_conf.max_messages).to_equal(Some(31))?;
+    expect(outbox_conf.max_bytes).to_equal(Some(3024))?;
+    expect(outbox_conf.max_age).to_equal(Some(30000))?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(300))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(30000))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some("gzip"))?;
     expect(conf.mq_channels.is_some()).to_equal(true)?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
@@ -594,6 +922,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url39")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(80))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(160))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(50))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(4))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(500))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(100))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(15))?;
+    expect(device_conf.sweep_interval).to_equal(Some(25))?;
     let mut map: HashMap<String, Vec<String>> = HashMap::new();
     map.insert("key31".to_string(), vec!["value31".to_string()]);
     expect(conf.api_scopes.as_ref()).to_equal(Some(&map))
@@ -611,11 +953,15 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(config::DEF_ENGINE)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_URL)?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_DB)?;
     expect(db_conf.pool_size).to_equal(None)?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_POSTGRES_URL)?;
+    expect(db_conf.pool_size).to_equal(None)?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal(config::DEF_SQLITE_PATH)?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -626,6 +972,9 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(cache_conf.device).to_equal(Some(config::DEF_MEMORY_DEVICE))?;
     expect(cache_conf.device_route).to_equal(Some(config::DEF_MEMORY_DEVICE_ROUTE))?;
     expect(cache_conf.network_route).to_equal(Some(config::DEF_MEMORY_NETWORK_ROUTE))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_REDIS_URL)?;
+    expect(cache_conf.ttl).to_equal(Some(config::DEF_REDIS_TTL))?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
     expect(mq_conf.prefetch.unwrap()).to_equal(config::DEF_MQ_PREFETCH)?;
@@ -634,6 +983,14 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_conf.dead_letter).to_equal(Some(config::DEF_MQ_DEADLETTER))?;
+    expect(mq_conf.tls.is_none()).to_equal(true)?;
+    expect(mq_conf.outbox.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some(config::DEF_MQ_COMPRESSION))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
     let ctrl_conf = mq_channels_conf.unit.as_ref().unwrap();
@@ -695,11 +1052,15 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(config::DEF_ENGINE)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_URL)?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal(config::DEF_MONGODB_DB)?;
     expect(db_conf.pool_size).to_equal(None)?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_POSTGRES_URL)?;
+    expect(db_conf.pool_size).to_equal(None)?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal(config::DEF_SQLITE_PATH)?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -710,6 +1071,9 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(cache_conf.device).to_equal(Some(config::DEF_MEMORY_DEVICE))?;
     expect(cache_conf.device_route).to_equal(Some(config::DEF_MEMORY_DEVICE_ROUTE))?;
     expect(cache_conf.network_route).to_equal(Some(config::DEF_MEMORY_NETWORK_ROUTE))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal(config::DEF_REDIS_URL)?;
+    expect(cache_conf.ttl).to_equal(Some(config::DEF_REDIS_TTL))?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
     expect(mq_conf.prefetch.unwrap()).to_equal(config::DEF_MQ_PREFETCH)?;
@@ -718,6 +1082,14 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_conf.dead_letter).to_equal(Some(config::DEF_MQ_DEADLETTER))?;
+    expect(mq_conf.tls.is_none()).to_equal(true)?;
+    expect(mq_conf.outbox.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some(config::DEF_MQ_COMPRESSION))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
     let ctrl_conf = mq_channels_conf.unit.as_ref().unwrap();
@@ -765,6 +1137,10 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
         }),
         cache: Some(config::Cache {
             engine: Some("test3".to_string()),
+            redis: Some(config::RedisCache {
+                ttl: Some(0),
+                ..Default::default()
+            }),
             ..Default::default()
         }),
         mq: Some(config::Mq {
@@ -794,6 +1170,20 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 ..Default::default()
             }),
         }),
+        dldata: Some(config::DlData {
+            expires_in: Some(0),
+            max_expires_in: Some(0),
+            sweep_interval: Some(0),
+        }),
+        uldata: Some(config::UlData {
+            retry_limit: None,
+            retry_delay: Some(0),
+            dedup_window: None,
+        }),
+        device: Some(config::Device {
+            flush_interval: Some(0),
+            sweep_interval: None,
+        }),
         ..Default::default()
     };
     let conf = config::apply_default(&conf);
@@ -805,6 +1195,8 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(conf.cache.is_some()).to_equal(true)?;
     let cache_conf = conf.cache.as_ref().unwrap();
     expect(cache_conf.engine.as_ref().unwrap().as_str()).to_equal(config::DEF_CACHE_ENGINE)?;
+    let cache_conf = cache_conf.redis.as_ref().unwrap();
+    expect(cache_conf.ttl).to_equal(Some(config::DEF_REDIS_TTL))?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
     expect(mq_conf.prefetch.unwrap()).to_equal(config::DEF_MQ_PREFETCH)?;
@@ -813,6 +1205,14 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_conf.dead_letter).to_equal(Some(config::DEF_MQ_DEADLETTER))?;
+    expect(mq_conf.tls.is_none()).to_equal(true)?;
+    expect(mq_conf.outbox.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some(config::DEF_MQ_COMPRESSION))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
     let ctrl_conf = mq_channels_conf.unit.as_ref().unwrap();
@@ -854,6 +1254,20 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.url.is_none()).to_equal(true)?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(config::DEF_DLDATA_EXPIRES_IN))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(config::DEF_DLDATA_MAX_EXPIRES_IN))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(config::DEF_DLDATA_SWEEP_INTERVAL))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(config::DEF_ULDATA_RETRY_LIMIT))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(config::DEF_ULDATA_RETRY_DELAY))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(config::DEF_ULDATA_DEDUP_WINDOW))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(config::DEF_DEVICE_FLUSH_INTERVAL))?;
+    expect(device_conf.sweep_interval).to_equal(Some(config::DEF_DEVICE_SWEEP_INTERVAL))?;
     expect(conf.api_scopes.as_ref()).to_equal(Some(&HashMap::new()))?;
 
     let mut api_scopes: HashMap<String, Vec<String>> = HashMap::new();
@@ -867,6 +1281,10 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 database: Some("db".to_string()),
                 pool_size: Some(11),
             }),
+            postgres: Some(config::Postgres {
+                url: Some("url".to_string()),
+                pool_size: Some(12),
+            }),
             sqlite: Some(config::Sqlite {
                 path: Some("path".to_string()),
             }),
@@ -878,11 +1296,34 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 device_route: Some(101),
                 network_route: Some(102),
             }),
+            redis: Some(config::RedisCache {
+                url: Some("redis://redis".to_string()),
+                ttl: Some(60),
+            }),
         }),
         mq: Some(config::Mq {
             prefetch: Some(10),
             persistent: Some(true),
             shared_prefix: Some("$shared/group/".to_string()),
+            dead_letter: Some(true),
+            queue_type: Some("quorum".to_string()),
+            message_ttl: Some(60000),
+            max_length: Some(0),
+            overflow: Some("reject-publish".to_string()),
+            tls: Some(config::MqTls {
+                cacert_file: Some("cacert".to_string()),
+                ..Default::default()
+            }),
+            outbox: Some(config::MqOutbox {
+                path: Some("outbox".to_string()),
+                max_messages: Some(0),
+                ..Default::default()
+            }),
+            reconnect: Some(config::MqReconnect {
+                initial_millis: Some(0),
+                max_millis: Some(60000),
+            }),
+            compression: Some("gzip".to_string()),
         }),
         mq_channels: Some(config::MqChannels {
             unit: Some(config::BrokerCtrl {
@@ -914,6 +1355,20 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 persistent: Some(false),
             }),
         }),
+        dldata: Some(config::DlData {
+            expires_in: Some(200),
+            max_expires_in: Some(100),
+            sweep_interval: Some(10),
+        }),
+        uldata: Some(config::UlData {
+            retry_limit: Some(0),
+            retry_delay: Some(100),
+            dedup_window: Some(500),
+        }),
+        device: Some(config::Device {
+            flush_interval: Some(5),
+            sweep_interval: Some(30),
+        }),
         api_scopes: Some(api_scopes.clone()),
     };
     let conf = config::apply_default(&conf);
@@ -923,11 +1378,15 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
         .to_equal(config::DEF_ENGINE)?;
     expect(conf.db.as_ref().unwrap().mongodb.is_some()).to_equal(true)?;
+    expect(conf.db.as_ref().unwrap().postgres.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().sqlite.is_some()).to_equal(true)?;
     let db_conf = conf.db.as_ref().unwrap().mongodb.as_ref().unwrap();
     expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url")?;
     expect(db_conf.database.as_ref().unwrap().as_str()).to_equal("db")?;
     expect(db_conf.pool_size).to_equal(Some(11))?;
+    let db_conf = conf.db.as_ref().unwrap().postgres.as_ref().unwrap();
+    expect(db_conf.url.as_ref().unwrap().as_str()).to_equal("url")?;
+    expect(db_conf.pool_size).to_equal(Some(12))?;
     let db_conf = conf.db.as_ref().unwrap().sqlite.as_ref().unwrap();
     expect(db_conf.path.as_ref().unwrap().as_str()).to_equal("path")?;
     expect(conf.cache.is_some()).to_equal(true)?;
@@ -938,6 +1397,9 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(cache_conf.device).to_equal(Some(100))?;
     expect(cache_conf.device_route).to_equal(Some(101))?;
     expect(cache_conf.network_route).to_equal(Some(102))?;
+    let cache_conf = conf.cache.as_ref().unwrap().redis.as_ref().unwrap();
+    expect(cache_conf.url.as_ref().unwrap().as_str()).to_equal("redis://redis")?;
+    expect(cache_conf.ttl).to_equal(Some(60))?;
     let mq_conf = conf.mq.as_ref().unwrap();
     expect(mq_conf.prefetch.is_some()).to_equal(true)?;
     expect(mq_conf.prefetch.unwrap()).to_equal(10)?;
@@ -945,6 +1407,27 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(mq_conf.persistent.unwrap()).to_equal(true)?;
     expect(mq_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(mq_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("$shared/group/")?;
+    expect(mq_conf.dead_letter).to_equal(Some(true))?;
+    expect(mq_conf.queue_type.as_ref().unwrap().as_str()).to_equal("quorum")?;
+    expect(mq_conf.message_ttl).to_equal(Some(60000))?;
+    expect(mq_conf.max_length).to_equal(None)?;
+    expect(mq_conf.overflow.as_ref().unwrap().as_str()).to_equal("reject-publish")?;
+    let tls_conf = mq_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_ref().unwrap().as_str()).to_equal("cacert")?;
+    expect(tls_conf.cert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.key_file.is_none()).to_equal(true)?;
+    expect(tls_conf.server_name.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let outbox_conf = mq_conf.outbox.as_ref().unwrap();
+    expect(outbox_conf.path.as_ref().unwrap().as_str()).to_equal("outbox")?;
+    expect(outbox_conf.max_messages).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_MESSAGES))?;
+    expect(outbox_conf.max_bytes).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_BYTES))?;
+    expect(outbox_conf.max_age).to_equal(Some(config::DEF_MQ_OUTBOX_MAX_AGE))?;
+    let reconnect_conf = mq_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(60000))?;
+    expect(mq_conf.compression.as_deref()).to_equal(Some("gzip"))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.unit.is_some()).to_equal(true)?;
     let ctrl_conf = mq_channels_conf.unit.as_ref().unwrap();
@@ -988,7 +1471,34 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url9")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
-    expect(conf.api_scopes.as_ref()).to_equal(Some(&api_scopes))
+    expect(conf.dldata.is_some()).to_equal(true)?;
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(100))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(100))?;
+    expect(dldata_conf.sweep_interval).to_equal(Some(10))?;
+    expect(conf.uldata.is_some()).to_equal(true)?;
+    let uldata_conf = conf.uldata.as_ref().unwrap();
+    expect(uldata_conf.retry_limit).to_equal(Some(0))?;
+    expect(uldata_conf.retry_delay).to_equal(Some(100))?;
+    expect(uldata_conf.dedup_window).to_equal(Some(500))?;
+    expect(conf.device.is_some()).to_equal(true)?;
+    let device_conf = conf.device.as_ref().unwrap();
+    expect(device_conf.flush_interval).to_equal(Some(5))?;
+    expect(device_conf.sweep_interval).to_equal(Some(30))?;
+    expect(conf.api_scopes.as_ref()).to_equal(Some(&api_scopes))?;
+
+    let conf = Config {
+        dldata: Some(config::DlData {
+            expires_in: Some(u64::MAX),
+            max_expires_in: Some(u64::MAX),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let conf = config::apply_default(&conf);
+    let dldata_conf = conf.dldata.as_ref().unwrap();
+    expect(dldata_conf.expires_in).to_equal(Some(config::DLDATA_EXPIRES_IN_LIMIT))?;
+    expect(dldata_conf.max_expires_in).to_equal(Some(config::DLDATA_EXPIRES_IN_LIMIT))
 }
 
 fn set_env_var(key: &str, val: &str) {
@@ -2,12 +2,19 @@ use laboratory::{Suite, describe};
 
 use crate::TestState;
 
+mod codec;
 mod config;
 pub mod libs;
 pub mod mq;
 
 pub fn suite() -> Suite<TestState> {
     describe("libs", |context| {
+        context.describe("codec", |context| {
+            context.it("Codec::new", codec::new);
+            context.it("Codec::decode with fields", codec::decode_fields);
+            context.it("Codec::encode with fields", codec::encode_fields);
+            context.it("Codec with script", codec::script);
+        });
         context.describe("config", |context| {
             context.it("apply_default", config::apply_default);
             context.it("reg_args", config::reg_args);
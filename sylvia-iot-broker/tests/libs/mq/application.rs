@@ -12,7 +12,7 @@ use serde_json::{self, Map, Value};
 use tokio::time;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, RedisQueueOptions,
     queue::{
         EventHandler as MqEventHandler, GmqQueue, Message, MessageHandler as MqMessageHandler,
         Status as MqStatus,
@@ -37,6 +37,8 @@ pub struct AppDlData {
     pub network_code: Option<String>,
     #[serde(rename = "networkAddr", skip_serializing_if = "Option::is_none")]
     pub network_addr: Option<String>,
+    #[serde(rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<i64>,
     pub data: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extension: Option<Map<String, Value>>,
@@ -317,6 +319,7 @@ pub fn new_default(context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_status.dldata_resp == MqStatus::Connected).equals(true)?;
     expect(mq_status.dldata_result == MqStatus::Connected).equals(true)?;
     expect(mq_status.ctrl == MqStatus::Closed).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Closed).equals(true)?;
 
     Ok(())
 }
@@ -358,6 +361,42 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Test new managers with the `device-status` queue.
+pub fn new_device_status(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let mq_engine = state.mq_engine.as_ref().unwrap().as_str();
+
+    let conn_pool = Arc::new(Mutex::new(HashMap::new()));
+    let host_uri = conn_host_uri(mq_engine)?;
+    let handler = Arc::new(TestHandler::new());
+
+    let opts = Options {
+        unit_id: "unit_id".to_string(),
+        unit_code: "unit_code".to_string(),
+        id: "id_application".to_string(),
+        name: "code_application".to_string(),
+        shared_prefix: state.mqtt_shared_prefix.clone(),
+        device_status: true,
+        ..Default::default()
+    };
+    let mgr = ApplicationMgr::new(conn_pool, &host_uri, opts, handler.clone())?;
+    state.app_mgrs = Some(vec![mgr.clone()]);
+
+    for _ in 0..WAIT_COUNT {
+        if *handler.status_changed.lock().unwrap() {
+            break;
+        }
+        runtime.block_on(async { time::sleep(Duration::from_millis(WAIT_TICK)).await });
+    }
+    let status = mgr.status();
+    let mq_status = mgr.mq_status();
+    expect(status == MgrStatus::Ready).equals(true)?;
+    expect(mq_status.uldata == MqStatus::Connected).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Connected).equals(true)
+}
+
 /// Test new managers with wrong options.
 pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let mut state = context.state.borrow_mut();
@@ -497,6 +536,44 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -530,9 +607,10 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             is_public: true,
             profile: "".to_string(),
             data: "da01".to_string(),
+            object: None,
             extension: None,
         };
-        if let Err(e) = mgr.send_uldata(&data1) {
+        if let Err(e) = mgr.send_uldata(&data1).await {
             return Err(format!("send data1 error: {}", e));
         }
         let mut ext = Map::<String, Value>::new();
@@ -548,9 +626,10 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             is_public: false,
             profile: "profile".to_string(),
             data: "da02".to_string(),
+            object: None,
             extension: Some(ext),
         };
-        if let Err(e) = mgr.send_uldata(&data2) {
+        if let Err(e) = mgr.send_uldata(&data2).await {
             return Err(format!("send data2 error: {}", e));
         }
         let data3 = UlData {
@@ -564,9 +643,10 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             is_public: true,
             profile: "".to_string(),
             data: "".to_string(),
+            object: None,
             extension: None,
         };
-        if let Err(e) = mgr.send_uldata(&data3) {
+        if let Err(e) = mgr.send_uldata(&data3).await {
             return Err(format!("send data3 error: {}", e));
         }
 
@@ -736,6 +816,78 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             (queue_send, queue_resp)
         }
+        Connection::Memory(conn, _) => {
+            recv_dldata_count = 2;
+            recv_dldata_resp_count = 1;
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_resp = Queue::new(opts)?;
+            queue_resp.set_handler(queue_handler.clone());
+            queue_resp.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_resp.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            (queue_send, queue_resp)
+        }
+        Connection::Redis(conn, _) => {
+            recv_dldata_count = 3;
+            recv_dldata_resp_count = 2;
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_resp = Queue::new(opts)?;
+            queue_resp.set_handler(queue_handler.clone());
+            queue_resp.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_resp.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            (queue_send, queue_resp)
+        }
     };
 
     runtime.block_on(async move {
@@ -763,6 +915,7 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
         let send_data1 = AppDlData {
             correlation_id: "1".to_string(),
             device_id: Some("device1".to_string()),
+            expires_in: Some(60),
             data: "da01".to_string(),
             extension: Some(ext),
             ..Default::default()
@@ -824,6 +977,7 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 expect(recv_data.device_id.as_ref()).equals(send_data1.device_id.as_ref())?;
                 expect(recv_data.network_code.as_ref()).equals(send_data1.network_code.as_ref())?;
                 expect(recv_data.network_addr.as_ref()).equals(send_data1.network_addr.as_ref())?;
+                expect(recv_data.expires_in).equals(send_data1.expires_in)?;
                 expect(recv_data.data.as_str()).equals(send_data1.data.as_str())?;
                 expect(recv_data.extension.as_ref()).equals(send_data1.extension.as_ref())?;
             } else if correlation_id == "2" {
@@ -834,6 +988,7 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 expect(recv_data.device_id.as_ref()).equals(send_data2.device_id.as_ref())?;
                 expect(recv_data.network_code.as_ref()).equals(send_data2.network_code.as_ref())?;
                 expect(recv_data.network_addr.as_ref()).equals(send_data2.network_addr.as_ref())?;
+                expect(recv_data.expires_in).equals(send_data2.expires_in)?;
                 expect(recv_data.data.as_str()).equals(send_data2.data.as_str())?;
                 expect(recv_data.extension.as_ref()).equals(send_data2.extension.as_ref())?;
             } else {
@@ -976,6 +1131,74 @@ pub fn dldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             (queue_send, queue_resp)
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_resp = Queue::new(opts)?;
+            queue_resp.set_handler(queue_handler.clone());
+            queue_resp.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_resp.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            (queue_send, queue_resp)
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_resp = Queue::new(opts)?;
+            queue_resp.set_handler(queue_handler.clone());
+            queue_resp.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_resp.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            (queue_send, queue_resp)
+        }
     };
 
     runtime.block_on(async move {
@@ -1093,24 +1316,38 @@ pub fn dldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
         if let Err(e) = queue_send.send_msg(payload).await {
             return Err(format!("send DlData 7 error: {}", e));
         }
+        let send_data8 = AppDlData {
+            correlation_id: "8".to_string(),
+            device_id: Some("device".to_string()),
+            expires_in: Some(0),
+            data: "".to_string(),
+            ..Default::default()
+        };
+        let payload = match serde_json::to_vec(&send_data8) {
+            Err(e) => return Err(format!("generate payload 8 error: {}", e)),
+            Ok(data) => data,
+        };
+        if let Err(e) = queue_send.send_msg(payload).await {
+            return Err(format!("send DlData 8 error: {}", e));
+        }
 
         for _ in 0..WAIT_COUNT {
-            if queue_handler.recv_data_resp.lock().unwrap().len() < 8 {
+            if queue_handler.recv_data_resp.lock().unwrap().len() < 9 {
                 time::sleep(Duration::from_millis(WAIT_TICK)).await;
                 continue;
             }
         }
-        if queue_handler.recv_data_resp.lock().unwrap().len() < 8 {
+        if queue_handler.recv_data_resp.lock().unwrap().len() < 9 {
             return Err(format!(
                 "receive {} dldata-resp",
                 queue_handler.recv_data_resp.lock().unwrap().len()
             ));
         }
 
-        for i in 0..8 {
+        for i in 0..9 {
             let resp = { queue_handler.recv_data_resp.lock().unwrap().pop() };
             match resp {
-                None => return Err(format!("only receive {}/8 data", i)),
+                None => return Err(format!("only receive {}/9 data", i)),
                 Some(resp) => expect(resp.error).equals(Some("err_param".to_string()))?,
             }
         }
@@ -1188,6 +1425,44 @@ pub fn dldata_result(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -61,6 +61,8 @@ pub fn new(context: &mut SpecContext<TestState>) -> Result<(), String> {
         conn_pool.clone(),
         &host_uri,
         None,
+        None,
+        None,
         "func1",
         false,
         handler1.clone(),
@@ -70,6 +72,8 @@ pub fn new(context: &mut SpecContext<TestState>) -> Result<(), String> {
         conn_pool.clone(),
         &host_uri,
         Some(1),
+        None,
+        None,
         "func2",
         false,
         handler2.clone(),
@@ -79,6 +83,8 @@ pub fn new(context: &mut SpecContext<TestState>) -> Result<(), String> {
         conn_pool,
         &host_uri,
         Some(0),
+        None,
+        None,
         "func3",
         false,
         handler3.clone(),
@@ -123,6 +129,8 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         conn_pool,
         &host_uri,
         None,
+        None,
+        None,
         "",
         false,
         handler.clone(),
@@ -12,7 +12,7 @@ use serde_json::{self, Map, Value};
 use tokio::time;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, RedisQueueOptions,
     queue::{
         EventHandler as MqEventHandler, GmqQueue, Message, MessageHandler as MqMessageHandler,
         Status as MqStatus,
@@ -244,6 +244,7 @@ pub fn new_default(context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_status.dldata_resp == MqStatus::Closed).equals(true)?;
     expect(mq_status.dldata_result == MqStatus::Connected).equals(true)?;
     expect(mq_status.ctrl == MqStatus::Connected).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Closed).equals(true)?;
 
     Ok(())
 }
@@ -429,6 +430,42 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_send
         }
+        Connection::Memory(conn, _) => {
+            recv_uldata_count = 2;
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_send
+        }
+        Connection::Redis(conn, _) => {
+            recv_uldata_count = 3;
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_send
+        }
     };
 
     runtime.block_on(async move {
@@ -588,6 +625,40 @@ pub fn uldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_send
         }
+        Connection::Memory(conn, _status) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_send
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_send
+        }
     };
 
     runtime.block_on(async move {
@@ -725,6 +796,44 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -879,6 +988,42 @@ pub fn dldata_result(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_send
         }
+        Connection::Memory(conn, _) => {
+            recv_dldata_result_count = 2;
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_send
+        }
+        Connection::Redis(conn, _) => {
+            recv_dldata_result_count = 3;
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_send
+        }
     };
 
     runtime.block_on(async move {
@@ -1034,6 +1179,40 @@ pub fn dldata_result_wrong(context: &mut SpecContext<TestState>) -> Result<(), S
             }
             queue_send
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_send
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_send = Queue::new(opts)?;
+            if let Err(e) = queue_send.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_send
+        }
     };
 
     runtime.block_on(async move {
@@ -1158,6 +1337,44 @@ pub fn ctrl(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(queue_handler.clone());
+            queue_result.set_msg_handler(queue_handler.clone());
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -23,6 +23,11 @@ pub fn add(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
         profile: "profile_get_none".to_string(),
         name: "name_get".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("model.add() none error: {}", e));
@@ -61,6 +66,11 @@ pub fn add(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
         profile: "profile_get_some".to_string(),
         name: "name_get".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("model.add() some error: {}", e));
@@ -95,6 +105,11 @@ pub fn add_dup(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
         profile: "profile_add".to_string(),
         name: "name_add".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("model.add() error: {}", e));
@@ -148,6 +163,11 @@ pub fn add_bulk(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String
             profile: format!("profile_add{:#03}", i),
             name: format!("name_add{:#03}", i),
             info: Map::<String, Value>::new(),
+            last_uplink_at: None,
+            last_downlink_at: None,
+            last_extension: None,
+            offline_at: None,
+            online: None,
         };
         devices.push(device);
     }
@@ -180,6 +200,11 @@ pub fn add_bulk(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String
         profile: "profile_add100".to_string(),
         name: "name_add100".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     });
     if let Err(e) = runtime.block_on(async { model.add_bulk(&devices).await }) {
         return Err(format!("model.add_bulk() with duplicate error: {}", e));
@@ -236,6 +261,11 @@ pub fn del_by_device_id(runtime: &Runtime, model: &dyn DeviceModel) -> Result<()
         profile: "profile_del".to_string(),
         name: "name_del".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     let mut cond = QueryCond {
         device_id: Some(device_id_del),
@@ -283,6 +313,11 @@ pub fn del_twice(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), Strin
         profile: "profile_del".to_string(),
         name: "name_del".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     let cond = QueryCond {
         device_id: Some(device_id_del),
@@ -317,6 +352,11 @@ pub fn del_by_unit_id(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(),
         profile: "profile_del".to_string(),
         name: "name_del".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     let cond = QueryCond {
         unit_id: Some("unit_id_del"),
@@ -392,6 +432,11 @@ pub fn del_by_unit_device(runtime: &Runtime, model: &dyn DeviceModel) -> Result<
         profile: "profile_del".to_string(),
         name: "name_del".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     let mut cond = QueryCond {
         device_id: Some(device_id_del),
@@ -443,6 +488,11 @@ pub fn del_by_network_id(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(
         profile: "profile_del".to_string(),
         name: "name_del".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     let cond = QueryCond {
         network_id: Some("network_id_del"),
@@ -518,6 +568,11 @@ pub fn del_by_network_addrs(runtime: &Runtime, model: &dyn DeviceModel) -> Resul
             profile: "profile_del".to_string(),
             name: format!("name_del{:#03}", i),
             info: Map::<String, Value>::new(),
+            last_uplink_at: None,
+            last_downlink_at: None,
+            last_extension: None,
+            offline_at: None,
+            online: None,
         };
         devices.push(device);
     }
@@ -604,6 +659,11 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
         profile: "profile_update".to_string(),
         name: "name_update".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("model.add() error: {}", e));
@@ -648,8 +708,11 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
 
     // Update all fields.
     let now = now + TimeDelta::try_seconds(1).unwrap();
+    let offline_at = now + TimeDelta::try_seconds(60).unwrap();
     let mut info = Map::<String, Value>::new();
     info.insert("key".to_string(), Value::String("value".to_string()));
+    let mut extension = Map::<String, Value>::new();
+    extension.insert("rssi".to_string(), Value::Number((-80).into()));
     let updates = Updates {
         network: Some(("network_id_update_all", "network_code_update_all")),
         network_addr: Some("network_addr_update_all"),
@@ -657,6 +720,11 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
         profile: Some("profile_update_all"),
         name: Some("name_update_all"),
         info: Some(&info),
+        last_uplink_at: Some(now),
+        last_downlink_at: Some(now),
+        last_extension: Some(&extension),
+        offline_at: Some(Some(offline_at)),
+        online: Some(Some(true)),
     };
     if let Err(e) = runtime.block_on(async { model.update(&update_cond, &updates).await }) {
         return Err(format!("model.update() all error: {}", e));
@@ -679,6 +747,11 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
     expect(get_device.profile.as_str()).to_equal("profile_update_all")?;
     expect(get_device.name.as_str()).to_equal("name_update_all")?;
     expect(get_device.info).to_equal(info)?;
+    expect(get_device.last_uplink_at).to_equal(Some(now))?;
+    expect(get_device.last_downlink_at).to_equal(Some(now))?;
+    expect(get_device.last_extension.as_ref()).to_equal(Some(&extension))?;
+    expect(get_device.offline_at).to_equal(Some(offline_at))?;
+    expect(get_device.online).to_equal(Some(true))?;
 
     // Update all fields back to None.
     let now = now + TimeDelta::try_seconds(1).unwrap();
@@ -690,6 +763,9 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
         profile: Some(""),
         name: Some(""),
         info: Some(&info),
+        offline_at: Some(None),
+        online: Some(None),
+        ..Default::default()
     };
     if let Err(e) = runtime.block_on(async { model.update(&update_cond, &updates).await }) {
         return Err(format!("model.update() none error: {}", e));
@@ -711,7 +787,10 @@ pub fn update(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String>
     expect(get_device.modified_at).to_equal(now)?;
     expect(get_device.profile.as_str()).to_equal("")?;
     expect(get_device.name.as_str()).to_equal("")?;
-    expect(get_device.info).to_equal(info)
+    expect(get_device.info).to_equal(info)?;
+    expect(get_device.last_extension.as_ref()).to_equal(Some(&extension))?;
+    expect(get_device.offline_at).to_equal(None)?;
+    expect(get_device.online).to_equal(None)
 }
 
 /// Test `update()` with a non-exist condition.
@@ -741,6 +820,11 @@ pub fn update_invalid(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(),
         profile: None,
         name: None,
         info: None,
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.update(&cond, &updates).await }) {
         return Err(format!("model.update() error: {}", e));
@@ -748,6 +832,104 @@ pub fn update_invalid(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(),
     Ok(())
 }
 
+/// Test `set_offline()` and the online conditions of `count()` and `list()`.
+pub fn set_offline(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
+    let now = Utc::now().trunc_subsecs(3);
+    let offline_at = now - TimeDelta::try_seconds(1).unwrap();
+    let mut device = Device {
+        device_id: "device_id_offline".to_string(),
+        unit_id: "unit_id".to_string(),
+        unit_code: None,
+        network_id: "network_id".to_string(),
+        network_code: "network_code".to_string(),
+        network_addr: "network_addr_offline".to_string(),
+        created_at: now,
+        modified_at: now,
+        profile: "".to_string(),
+        name: "".to_string(),
+        info: Map::<String, Value>::new(),
+        last_uplink_at: Some(now),
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: Some(offline_at),
+        online: Some(true),
+    };
+    if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
+        return Err(format!("model.add() offline error: {}", e));
+    }
+    device.device_id = "device_id_online".to_string();
+    device.network_addr = "network_addr_online".to_string();
+    device.offline_at = Some(now + TimeDelta::try_seconds(60).unwrap());
+    if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
+        return Err(format!("model.add() online error: {}", e));
+    }
+
+    let cond = ListQueryCond {
+        online: Some(true),
+        offline_at_lte: Some(now),
+        ..Default::default()
+    };
+    let opts = ListOptions {
+        cond: &cond,
+        offset: None,
+        limit: None,
+        sort: None,
+        cursor_max: None,
+    };
+    let list = match runtime.block_on(async { model.list(&opts, None).await }) {
+        Err(e) => return Err(format!("model.list() error: {}", e)),
+        Ok((list, _)) => list,
+    };
+    expect(list.len()).to_equal(1)?;
+    expect(list[0].device_id.as_str()).to_equal("device_id_offline")?;
+    expect(list[0].last_uplink_at).to_equal(Some(now))?;
+    expect(list[0].offline_at).to_equal(Some(offline_at))?;
+    expect(list[0].online).to_equal(Some(true))?;
+
+    let result = runtime.block_on(async {
+        model
+            .set_offline(
+                "device_id_offline",
+                offline_at + TimeDelta::try_milliseconds(1).unwrap(),
+            )
+            .await
+    });
+    match result {
+        Err(e) => return Err(format!("model.set_offline() changed error: {}", e)),
+        Ok(claimed) => expect(claimed).to_equal(false)?,
+    }
+    match runtime.block_on(async { model.set_offline("device_id_offline", offline_at).await }) {
+        Err(e) => return Err(format!("model.set_offline() error: {}", e)),
+        Ok(claimed) => expect(claimed).to_equal(true)?,
+    }
+    match runtime.block_on(async { model.set_offline("device_id_offline", offline_at).await }) {
+        Err(e) => return Err(format!("model.set_offline() twice error: {}", e)),
+        Ok(claimed) => expect(claimed).to_equal(false)?,
+    }
+
+    match runtime.block_on(async { model.count(&cond).await }) {
+        Err(e) => return Err(format!("model.count() online error: {}", e)),
+        Ok(count) => expect(count).to_equal(0)?,
+    }
+    let cond = ListQueryCond {
+        online: Some(false),
+        ..Default::default()
+    };
+    match runtime.block_on(async { model.count(&cond).await }) {
+        Err(e) => return Err(format!("model.count() offline error: {}", e)),
+        Ok(count) => expect(count).to_equal(1)?,
+    }
+    let cond = ListQueryCond {
+        online: Some(true),
+        ..Default::default()
+    };
+    match runtime.block_on(async { model.count(&cond).await }) {
+        Err(e) => return Err(format!("model.count() all online error: {}", e)),
+        Ok(count) => expect(count).to_equal(1)?,
+    }
+    Ok(())
+}
+
 /// Test `count()`.
 pub fn count(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
     let now = Utc::now().trunc_subsecs(3);
@@ -763,6 +945,11 @@ pub fn count(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
         profile: "profile_count_1".to_string(),
         name: "name_count_1".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async {
         model.add(&device).await?;
@@ -988,6 +1175,11 @@ pub fn list(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), String> {
         profile: "profile_list_1".to_string(),
         name: "name_list_1".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async {
         model.add(&device).await?;
@@ -1259,6 +1451,11 @@ pub fn list_sort(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), Strin
         profile: "profile_list1_1".to_string(),
         name: "name_list1_1".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async {
         model.add(&device).await?;
@@ -1574,6 +1771,11 @@ pub fn list_offset_limit(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(
         profile: "profile_list_1".to_string(),
         name: "name_list_1".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async {
         model.add(&device).await?;
@@ -1671,6 +1873,11 @@ pub fn list_cursor(runtime: &Runtime, model: &dyn DeviceModel) -> Result<(), Str
         profile: "profile_list".to_string(),
         name: "name_list".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async {
         model.add(&device).await?;
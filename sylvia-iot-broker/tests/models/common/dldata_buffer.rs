@@ -134,6 +134,52 @@ pub fn del_twice(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(),
     Ok(())
 }
 
+/// Test `del_one()`.
+pub fn del_one(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(), String> {
+    let now = Utc::now().trunc_subsecs(3);
+    let data_id_del = "data_id_del";
+    let data_id_not_del = "data_id_not_del";
+    let mut data = DlDataBuffer {
+        data_id: data_id_del.to_string(),
+        unit_id: "unit_id_del".to_string(),
+        unit_code: "unit_code_del".to_string(),
+        application_id: "application_id_del".to_string(),
+        application_code: "application_code_del".to_string(),
+        network_id: "network_id_del".to_string(),
+        network_addr: "network_addr_del".to_string(),
+        device_id: "device_id_del".to_string(),
+        created_at: now,
+        expired_at: now,
+    };
+    let (first, second) = match runtime.block_on(async {
+        model.add(&data).await?;
+        data.data_id = data_id_not_del.to_string();
+        model.add(&data).await?;
+        let first = model.del_one(data_id_del).await?;
+        let second = model.del_one(data_id_del).await?;
+        Ok::<_, Box<dyn std::error::Error>>((first, second))
+    }) {
+        Err(e) => return Err(format!("model.add/del_one error: {}", e)),
+        Ok(result) => result,
+    };
+    expect(first).to_equal(true)?;
+    expect(second).to_equal(false)?;
+    match runtime.block_on(async { model.get(data_id_del).await }) {
+        Err(e) => return Err(format!("model.get() error: {}", e)),
+        Ok(data) => match data {
+            None => (),
+            Some(_) => return Err("delete fail".to_string()),
+        },
+    }
+    match runtime.block_on(async { model.get(data_id_not_del).await }) {
+        Err(e) => Err(format!("model.get() not delete one error: {}", e)),
+        Ok(data) => match data {
+            None => Err("delete wrong one".to_string()),
+            Some(_) => Ok(()),
+        },
+    }
+}
+
 /// Test `del()` by specifying a unit ID.
 pub fn del_by_unit_id(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(), String> {
     let now = Utc::now().trunc_subsecs(3);
@@ -623,6 +669,54 @@ pub fn count(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(), Str
     expect(count).to_equal(3)
 }
 
+/// Test `count()` with the expiration condition.
+pub fn count_expired(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(), String> {
+    let now = Utc::now().trunc_subsecs(3);
+    let mut data = DlDataBuffer {
+        data_id: "data_id_count1".to_string(),
+        unit_id: "unit_id_count".to_string(),
+        unit_code: "unit_code_count".to_string(),
+        application_id: "application_id_count".to_string(),
+        application_code: "application_code_count".to_string(),
+        network_id: "network_id_count".to_string(),
+        network_addr: "network_addr_count".to_string(),
+        device_id: "device_id_count".to_string(),
+        created_at: now,
+        expired_at: now - TimeDelta::try_seconds(10).unwrap(),
+    };
+    if let Err(e) = runtime.block_on(async {
+        model.add(&data).await?;
+        data.data_id = "data_id_count2".to_string();
+        data.expired_at = now;
+        model.add(&data).await?;
+        data.data_id = "data_id_count3".to_string();
+        data.expired_at = now + TimeDelta::try_seconds(10).unwrap();
+        model.add(&data).await
+    }) {
+        return Err(format!("model.add() error: {}", e));
+    }
+
+    let cond = ListQueryCond {
+        expired_at_lte: Some(now),
+        ..Default::default()
+    };
+    let count = match runtime.block_on(async { model.count(&cond).await }) {
+        Err(e) => return Err(format!("count expired_at_lte result error: {}", e)),
+        Ok(count) => count,
+    };
+    expect(count).to_equal(2)?;
+
+    let cond = ListQueryCond {
+        expired_at_lte: Some(now - TimeDelta::try_seconds(20).unwrap()),
+        ..Default::default()
+    };
+    let count = match runtime.block_on(async { model.count(&cond).await }) {
+        Err(e) => return Err(format!("count expired_at_lte none result error: {}", e)),
+        Ok(count) => count,
+    };
+    expect(count).to_equal(0)
+}
+
 /// Test `list()`.
 pub fn list(runtime: &Runtime, model: &dyn DlDataBufferModel) -> Result<(), String> {
     let now = Utc::now().trunc_subsecs(3);
@@ -46,6 +46,11 @@ pub fn get(context: &mut SpecContext<TestState>) -> Result<(), String> {
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device error: {}", e));
@@ -145,6 +150,11 @@ pub fn del(context: &mut SpecContext<TestState>) -> Result<(), String> {
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device 1 error: {}", e));
@@ -198,6 +198,11 @@ pub fn get_dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device 1 error: {}", e));
@@ -274,6 +279,11 @@ pub fn del_dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device 1 error: {}", e));
@@ -356,6 +366,11 @@ pub fn get_dldata_pub(context: &mut SpecContext<TestState>) -> Result<(), String
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device 1 error: {}", e));
@@ -474,6 +489,11 @@ pub fn del_dldata_pub(context: &mut SpecContext<TestState>) -> Result<(), String
         profile: "".to_string(),
         name: "".to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     };
     if let Err(e) = runtime.block_on(async { model.add(&device).await }) {
         return Err(format!("create device 1 error: {}", e));
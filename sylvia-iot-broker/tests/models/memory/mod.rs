@@ -13,6 +13,7 @@ use crate::TestState;
 mod device;
 mod device_route;
 mod network_route;
+mod uldata_dedup;
 
 pub const STATE: &'static str = "models/memory";
 
@@ -68,6 +69,22 @@ pub fn suite() -> Suite<TestState> {
                     })
                     .after_each(network_route::after_each_fn);
             });
+
+            context.describe("uldata_dedup", |context| {
+                context.it("add() and take()", uldata_dedup::add);
+                context.it("add() after expired", uldata_dedup::add_expired);
+                context.it(
+                    "add() not extending expiration",
+                    uldata_dedup::add_not_extend,
+                );
+                context.it("clear()", uldata_dedup::clear);
+
+                context
+                    .before_all(|state| {
+                        state.insert(STATE, new_state(true));
+                    })
+                    .after_each(uldata_dedup::after_each_fn);
+            });
         }));
 
         context.after_all(after_all);
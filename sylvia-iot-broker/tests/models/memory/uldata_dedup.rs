@@ -0,0 +1,160 @@
+use std::{collections::HashMap, time::Duration};
+
+use laboratory::{SpecContext, expect};
+use serde_json::{Map, Value};
+
+use super::{STATE, TestState};
+
+pub fn after_each_fn(state: &mut HashMap<&'static str, TestState>) -> () {
+    let state = state.get_mut(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let cache = state.cache.as_ref().unwrap();
+
+    runtime.block_on(async {
+        let _ = cache.uldata_dedup().clear().await;
+    });
+}
+
+/// Test `add()` and `take()`.
+pub fn add(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let cache = state.cache.as_ref().unwrap().uldata_dedup();
+
+    let mut ext = Map::<String, Value>::new();
+    ext.insert("rssi".to_string(), Value::Number((-80).into()));
+    match runtime.block_on(async { cache.add("key1", Some(&ext), 5000).await }) {
+        Err(e) => return Err(format!("add() key1 first error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    match runtime.block_on(async { cache.add("key1", None, 5000).await }) {
+        Err(e) => return Err(format!("add() key1 second error: {}", e)),
+        Ok(first) => expect(first).to_equal(false)?,
+    }
+    ext.insert("rssi".to_string(), Value::Number((-70).into()));
+    match runtime.block_on(async { cache.add("key1", Some(&ext), 5000).await }) {
+        Err(e) => return Err(format!("add() key1 third error: {}", e)),
+        Ok(first) => expect(first).to_equal(false)?,
+    }
+    match runtime.block_on(async { cache.add("key2", None, 5000).await }) {
+        Err(e) => return Err(format!("add() key2 first error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+
+    let items = match runtime.block_on(async { cache.take("key1").await }) {
+        Err(e) => return Err(format!("take() key1 error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(3)?;
+    match items[0].as_ref() {
+        None => return Err("key1 first extension should exist".to_string()),
+        Some(ext) => expect(ext.get("rssi")).to_equal(Some(&Value::Number((-80).into())))?,
+    }
+    expect(items[1].is_none()).to_equal(true)?;
+    match items[2].as_ref() {
+        None => return Err("key1 third extension should exist".to_string()),
+        Some(ext) => expect(ext.get("rssi")).to_equal(Some(&Value::Number((-70).into())))?,
+    }
+
+    let items = match runtime.block_on(async { cache.take("key1").await }) {
+        Err(e) => return Err(format!("take() key1 again error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(0)?;
+    match runtime.block_on(async { cache.add("key1", None, 5000).await }) {
+        Err(e) => return Err(format!("add() key1 after take error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+
+    let items = match runtime.block_on(async { cache.take("key2").await }) {
+        Err(e) => return Err(format!("take() key2 error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(1)?;
+    expect(items[0].is_none()).to_equal(true)?;
+
+    let items = match runtime.block_on(async { cache.take("key3").await }) {
+        Err(e) => return Err(format!("take() key3 error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(0)
+}
+
+/// Test `add()` after the item expires.
+pub fn add_expired(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let cache = state.cache.as_ref().unwrap().uldata_dedup();
+
+    match runtime.block_on(async { cache.add("key", None, 100).await }) {
+        Err(e) => return Err(format!("add() first error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    runtime.block_on(async { tokio::time::sleep(Duration::from_millis(200)).await });
+    let items = match runtime.block_on(async { cache.take("key").await }) {
+        Err(e) => return Err(format!("take() expired error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(0)?;
+    match runtime.block_on(async { cache.add("key", None, 100).await }) {
+        Err(e) => return Err(format!("add() again error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    Ok(())
+}
+
+/// Test that duplicates do not extend the expiration of the first copy.
+pub fn add_not_extend(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let cache = state.cache.as_ref().unwrap().uldata_dedup();
+
+    match runtime.block_on(async { cache.add("key", None, 300).await }) {
+        Err(e) => return Err(format!("add() first error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    runtime.block_on(async { tokio::time::sleep(Duration::from_millis(200)).await });
+    match runtime.block_on(async { cache.add("key", None, 300).await }) {
+        Err(e) => return Err(format!("add() second error: {}", e)),
+        Ok(first) => expect(first).to_equal(false)?,
+    }
+    runtime.block_on(async { tokio::time::sleep(Duration::from_millis(200)).await });
+    let items = match runtime.block_on(async { cache.take("key").await }) {
+        Err(e) => return Err(format!("take() expired error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(0)?;
+    match runtime.block_on(async { cache.add("key", None, 300).await }) {
+        Err(e) => return Err(format!("add() again error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    Ok(())
+}
+
+/// Test `clear()`.
+pub fn clear(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let cache = state.cache.as_ref().unwrap().uldata_dedup();
+
+    if let Err(e) = runtime.block_on(async { cache.add("key", None, 5000).await }) {
+        return Err(format!("add() error: {}", e));
+    }
+    if let Err(e) = runtime.block_on(async { cache.clear().await }) {
+        return Err(format!("clear() error: {}", e));
+    }
+    let items = match runtime.block_on(async { cache.take("key").await }) {
+        Err(e) => return Err(format!("take() error: {}", e)),
+        Ok(items) => items,
+    };
+    expect(items.len()).to_equal(0)?;
+    match runtime.block_on(async { cache.add("key", None, 5000).await }) {
+        Err(e) => return Err(format!("add() again error: {}", e)),
+        Ok(first) => expect(first).to_equal(true)?,
+    }
+    Ok(())
+}
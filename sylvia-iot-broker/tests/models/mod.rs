@@ -1,4 +1,6 @@
 mod common;
 pub mod memory;
 pub mod mongodb;
+pub mod postgres;
+pub mod redis;
 pub mod sqlite;
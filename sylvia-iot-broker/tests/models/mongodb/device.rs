@@ -623,6 +623,16 @@ pub fn update_invalid(context: &mut SpecContext<TestState>) -> Result<(), String
     common_test::update_invalid(runtime, model)
 }
 
+/// Test `set_offline()`.
+pub fn set_offline(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let model = state.mongodb.as_ref().unwrap().device();
+
+    common_test::set_offline(runtime, model)
+}
+
 /// Test `count()`.
 pub fn count(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
@@ -152,6 +152,16 @@ pub fn del_twice(context: &mut SpecContext<TestState>) -> Result<(), String> {
     common_test::del_twice(runtime, model)
 }
 
+/// Test `del_one()`.
+pub fn del_one(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let model = state.mongodb.as_ref().unwrap().dldata_buffer();
+
+    common_test::del_one(runtime, model)
+}
+
 /// Test `del()` by specifying a unit ID.
 pub fn del_by_unit_id(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
@@ -222,6 +232,16 @@ pub fn count(context: &mut SpecContext<TestState>) -> Result<(), String> {
     common_test::count(runtime, model)
 }
 
+/// Test `count()` with the expiration condition.
+pub fn count_expired(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let model = state.mongodb.as_ref().unwrap().dldata_buffer();
+
+    common_test::count_expired(runtime, model)
+}
+
 /// Test `list()`.
 pub fn list(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
@@ -14,6 +14,7 @@ mod device_route;
 mod dldata_buffer;
 mod network;
 mod network_route;
+mod profile;
 mod unit;
 
 pub const STATE: &'static str = "models/mongodb";
@@ -79,6 +80,7 @@ pub fn suite() -> Suite<TestState> {
                 context.it("update()", device::update);
                 context.it("update() not exist", device::update_not_exist);
                 context.it("update() with invalid options", device::update_invalid);
+                context.it("set_offline()", device::set_offline);
                 context.it("count()", device::count);
                 context.it("list()", device::list);
                 context.it("list() sort", device::list_sort);
@@ -127,6 +129,7 @@ pub fn suite() -> Suite<TestState> {
                 context.it("add() with duplicate ID", dldata_buffer::add_dup);
                 context.it("del() by data_id", dldata_buffer::del_by_data_id);
                 context.it("del() twice", dldata_buffer::del_twice);
+                context.it("del_one()", dldata_buffer::del_one);
                 context.it("del() by unit_id", dldata_buffer::del_by_unit_id);
                 context.it("del() by unit and data", dldata_buffer::del_by_unit_data);
                 context.it(
@@ -140,6 +143,7 @@ pub fn suite() -> Suite<TestState> {
                     dldata_buffer::del_by_network_addrs,
                 );
                 context.it("count()", dldata_buffer::count);
+                context.it("count() expired", dldata_buffer::count_expired);
                 context.it("list()", dldata_buffer::list);
                 context.it("list() sort", dldata_buffer::list_sort);
                 context.it("list() offset limit", dldata_buffer::list_offset_limit);
@@ -194,6 +198,29 @@ pub fn suite() -> Suite<TestState> {
                 context.after_each(network_route::after_each_fn);
             });
 
+            context.describe("profile", |context| {
+                context.it("init()", profile::init);
+                context.it("get() by profile_id", profile::get_by_profile_id);
+                context.it("get() by code", profile::get_by_code);
+                context.it("get() by unit and profile", profile::get_by_unit_profile);
+                context.it("add()", profile::add);
+                context.it("add() with duplicate ID and code", profile::add_dup);
+                context.it("del() by profile_id", profile::del_by_profile_id);
+                context.it("del() twice", profile::del_twice);
+                context.it("del() by unit_id", profile::del_by_unit_id);
+                context.it("del() by unit and profile", profile::del_by_unit_profile);
+                context.it("update()", profile::update);
+                context.it("update() not exist", profile::update_not_exist);
+                context.it("update() with invalid options", profile::update_invalid);
+                context.it("count()", profile::count);
+                context.it("list()", profile::list);
+                context.it("list() sort", profile::list_sort);
+                context.it("list() offset limit", profile::list_offset_limit);
+                context.it("list() cursor", profile::list_cursor);
+
+                context.after_each(profile::after_each_fn);
+            });
+
             context.describe("unit", |context| {
                 context.it("init()", unit::init);
                 context.it("get() by unti_id", unit::get_by_unit_id);
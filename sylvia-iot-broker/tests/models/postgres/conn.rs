@@ -0,0 +1,45 @@
+use laboratory::{SpecContext, expect};
+
+use sylvia_iot_broker::models::{self, ConnOptions, PostgresOptions, postgres_conn};
+
+use super::{STATE, TestState};
+
+/// Generate spec for models::conn::connect().
+pub fn conn(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+
+    let result = runtime.block_on(async {
+        postgres_conn::connect(&PostgresOptions {
+            url: crate::TEST_POSTGRES_URL.to_string(),
+            pool_size: Some(2),
+        })
+        .await
+    });
+    expect(result.is_ok()).to_equal(true)?;
+    runtime.block_on(async { result.unwrap().close().await });
+    Ok(())
+}
+
+/// Generate spec for models::new().
+pub fn models_new(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+
+    let model = match runtime.block_on(async {
+        models::new(&ConnOptions::Postgres(PostgresOptions {
+            url: crate::TEST_POSTGRES_URL.to_string(),
+            pool_size: None,
+        }))
+        .await
+    }) {
+        Err(e) => return Err(format!("new model error: {}", e)),
+        Ok(model) => model,
+    };
+    match runtime.block_on(async { model.close().await }) {
+        Err(e) => return Err(format!("close model error: {}", e)),
+        Ok(_) => return Ok(()),
+    }
+}
@@ -22,7 +22,7 @@ use sylvia_iot_auth::{
 use sylvia_iot_broker::{
     libs::config::{
         self, Cache as CacheConfig, Config, Db as DbConfig, MongoDb as MongoDbConfig,
-        Sqlite as SqliteConfig,
+        Postgres as PostgresConfig, Sqlite as SqliteConfig,
     },
     models::{
         MongoDbModel, MongoDbOptions, SqliteModel, SqliteOptions,
@@ -32,6 +32,7 @@ use sylvia_iot_broker::{
         dldata_buffer::DlDataBuffer,
         network::{Network, QueryCond as NetworkQueryCond},
         network_route::NetworkRoute,
+        profile::{Profile, QueryCond as ProfileQueryCond},
         unit::{QueryCond as UnitQueryCond, Unit},
     },
     routes,
@@ -167,6 +168,11 @@ pub fn create_device(
         profile: profile.to_string(),
         name: addr.to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     }
 }
 
@@ -240,6 +246,22 @@ pub fn create_dldata_buffer(
     }
 }
 
+pub fn create_profile(name: &str, unit_id: &str) -> Profile {
+    let now = Utc::now();
+    Profile {
+        profile_id: name.to_string(),
+        code: name.to_string(),
+        unit_id: unit_id.to_string(),
+        unit_code: unit_id.to_string(),
+        created_at: now,
+        modified_at: now,
+        name: name.to_string(),
+        codec: Map::<String, Value>::new(),
+        offline_timeout: 0,
+        info: Map::<String, Value>::new(),
+    }
+}
+
 pub fn add_unit_model(
     runtime: &Runtime,
     state: &routes::State,
@@ -419,6 +441,21 @@ pub fn add_dldata_buffer_model(
     }
 }
 
+pub fn add_profile_model(
+    runtime: &Runtime,
+    state: &routes::State,
+    unit_id: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    match runtime.block_on(async {
+        let profile = create_profile(profile_id, unit_id);
+        state.model.profile().add(&profile).await
+    }) {
+        Err(e) => Err(format!("add profile model info error: {}", e)),
+        Ok(_) => Ok(()),
+    }
+}
+
 pub fn add_delete_rsc(runtime: &Runtime, state: &routes::State) -> Result<(), String> {
     add_unit_model(runtime, state, "manager", vec![], "manager")?;
     add_unit_model(runtime, state, "owner", vec![], "owner")?;
@@ -713,6 +750,31 @@ pub fn get_dldata_buffer_model(
     }
 }
 
+pub fn get_profile_model(
+    runtime: &Runtime,
+    state: &routes::State,
+    profile_id: &str,
+    should_exist: bool,
+) -> Result<Option<Profile>, String> {
+    match runtime.block_on(async {
+        let cond = ProfileQueryCond {
+            profile_id: Some(profile_id),
+            ..Default::default()
+        };
+        state.model.profile().get(&cond).await
+    }) {
+        Err(e) => return Err(format!("get profile model info error: {}", e)),
+        Ok(profile) => {
+            if should_exist && profile.is_none() {
+                return Err(format!("should get profile {}", profile_id));
+            } else if !should_exist && profile.is_some() {
+                return Err(format!("should not get profile {}", profile_id));
+            }
+            Ok(profile)
+        }
+    }
+}
+
 pub fn new_state(
     db_engine: Option<&'static str>,
     cache_engine: Option<&'static str>,
@@ -743,6 +805,10 @@ pub fn new_state(
                 database: Some(crate::TEST_MONGODB_DB.to_string()),
                 pool_size: None,
             }),
+            postgres: Some(PostgresConfig {
+                url: Some(crate::TEST_POSTGRES_URL.to_string()),
+                pool_size: None,
+            }),
             sqlite: Some(SqliteConfig {
                 path: Some(sqlite_path.to_str().unwrap().to_string()),
             }),
@@ -764,6 +830,21 @@ pub fn new_state(
                 ..Default::default()
             }),
         },
+        // Data channel tests wait for expired data and offline devices to be swept.
+        dldata: match data_channel_host {
+            None => None,
+            Some(_) => Some(config::DlData {
+                sweep_interval: Some(1),
+                ..Default::default()
+            }),
+        },
+        device: match data_channel_host {
+            None => None,
+            Some(_) => Some(config::Device {
+                sweep_interval: Some(1),
+                ..Default::default()
+            }),
+        },
         ..Default::default()
     };
     let state = match runtime.block_on(async { routes::new_state("/broker", &conf).await }) {
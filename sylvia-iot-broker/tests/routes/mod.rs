@@ -10,13 +10,16 @@ use reqwest;
 use url::Url;
 
 use general_mq::{
+    Compression,
     connection::GmqConnection,
     queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_auth::libs::config::{self as sylvia_iot_auth_config};
 use sylvia_iot_broker::{
     libs::{
+        codec::CodecCache,
         config::{self, Config},
+        device_state::StateBuffer,
         mq::{Connection, control, data},
     },
     models::{self, ConnOptions, Model, MongoDbModel, MongoDbOptions, SqliteModel, SqliteOptions},
@@ -269,6 +272,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "unit",
         false,
         Arc::new(TestHandler {}),
@@ -278,6 +283,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "application",
         false,
         Arc::new(TestHandler {}),
@@ -287,6 +294,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "network",
         false,
         Arc::new(TestHandler {}),
@@ -296,6 +305,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "device",
         false,
         Arc::new(TestHandler {}),
@@ -305,6 +316,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "device-route",
         false,
         Arc::new(TestHandler {}),
@@ -314,6 +327,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "network-route",
         false,
         Arc::new(TestHandler {}),
@@ -323,6 +338,8 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         &mq_conns,
         &url,
         config::DEF_MQ_PERSISTENT,
+        None,
+        None,
         Arc::new(TestHandler {}),
     )?;
     let mut state = routes::State {
@@ -331,9 +348,26 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
         scope_path: "/test",
         model: model.clone(),
         cache,
+        codecs: Arc::new(CodecCache::new(model.clone())),
+        device_states: Arc::new(StateBuffer::new(model.clone())),
         amqp_prefetch: config::DEF_MQ_PREFETCH,
         amqp_persistent: config::DEF_MQ_PERSISTENT,
         mqtt_shared_prefix: config::DEF_MQ_SHAREDPREFIX.to_string(),
+        mq_dead_letter: config::DEF_MQ_DEADLETTER,
+        amqp_args: Default::default(),
+        mq_tls: None,
+        mq_reconnect: None,
+        mq_outbox: None,
+        mq_compression: Compression::None,
+        dldata_expires_in: config::DEF_DLDATA_EXPIRES_IN as i64,
+        dldata_max_expires_in: config::DEF_DLDATA_MAX_EXPIRES_IN as i64,
+        dldata_sweep_interval: config::DEF_DLDATA_SWEEP_INTERVAL,
+        uldata_retry_limit: config::DEF_ULDATA_RETRY_LIMIT,
+        uldata_retry_delay: config::DEF_ULDATA_RETRY_DELAY,
+        uldata_retry_queues: routes::UlDataRetryQueues::default(),
+        uldata_dedup_window: config::DEF_ULDATA_DEDUP_WINDOW,
+        device_flush_interval: config::DEF_DEVICE_FLUSH_INTERVAL,
+        device_sweep_interval: config::DEF_DEVICE_SWEEP_INTERVAL,
         client: reqwest::Client::new(),
         mq_conns,
         application_mgrs: Arc::new(Mutex::new(HashMap::new())),
@@ -348,6 +382,7 @@ fn fn_new_service(context: &mut SpecContext<TestState>) -> Result<(), String> {
             network_route: Arc::new(Mutex::new(netr_ctrl)),
         },
         data_sender: Some(data_sender),
+        tasks: routes::Tasks::default(),
     };
     let _ = routes::new_service(&state);
     if let Err(e) = runtime.block_on(async { model.close().await }) {
@@ -433,6 +468,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "unit",
         false,
         Arc::new(TestHandler {}),
@@ -442,6 +479,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "application",
         false,
         Arc::new(TestHandler {}),
@@ -451,6 +490,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "network",
         false,
         Arc::new(TestHandler {}),
@@ -460,6 +501,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "device",
         false,
         Arc::new(TestHandler {}),
@@ -469,6 +512,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "device-route",
         false,
         Arc::new(TestHandler {}),
@@ -478,6 +523,8 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "network-route",
         false,
         Arc::new(TestHandler {}),
@@ -489,9 +536,26 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
         scope_path: "/test",
         model: model.clone(),
         cache,
+        codecs: Arc::new(CodecCache::new(model.clone())),
+        device_states: Arc::new(StateBuffer::new(model.clone())),
         amqp_prefetch: config::DEF_MQ_PREFETCH,
         amqp_persistent: config::DEF_MQ_PERSISTENT,
         mqtt_shared_prefix: config::DEF_MQ_SHAREDPREFIX.to_string(),
+        mq_dead_letter: config::DEF_MQ_DEADLETTER,
+        amqp_args: Default::default(),
+        mq_tls: None,
+        mq_reconnect: None,
+        mq_outbox: None,
+        mq_compression: Compression::None,
+        dldata_expires_in: config::DEF_DLDATA_EXPIRES_IN as i64,
+        dldata_max_expires_in: config::DEF_DLDATA_MAX_EXPIRES_IN as i64,
+        dldata_sweep_interval: config::DEF_DLDATA_SWEEP_INTERVAL,
+        uldata_retry_limit: config::DEF_ULDATA_RETRY_LIMIT,
+        uldata_retry_delay: config::DEF_ULDATA_RETRY_DELAY,
+        uldata_retry_queues: routes::UlDataRetryQueues::default(),
+        uldata_dedup_window: config::DEF_ULDATA_DEDUP_WINDOW,
+        device_flush_interval: config::DEF_DEVICE_FLUSH_INTERVAL,
+        device_sweep_interval: config::DEF_DEVICE_SWEEP_INTERVAL,
         client: reqwest::Client::new(),
         mq_conns,
         application_mgrs: Arc::new(Mutex::new(HashMap::new())),
@@ -506,6 +570,7 @@ fn fn_api_scopes(context: &mut SpecContext<TestState>) -> Result<(), String> {
             network_route: Arc::new(Mutex::new(netr_ctrl)),
         },
         data_sender: None,
+        tasks: routes::Tasks::default(),
     };
     let _ = routes::new_service(&state);
     if let Err(e) = runtime.block_on(async { model.close().await }) {
@@ -572,6 +637,16 @@ async fn clear_state(state: &mut routes::State) {
                     println!("close connection error {}", e);
                 }
             }
+            Connection::Memory(mut c, _) => {
+                if let Err(e) = c.close().await {
+                    println!("close connection error {}", e);
+                }
+            }
+            Connection::Redis(mut c, _) => {
+                if let Err(e) = c.close().await {
+                    println!("close connection error {}", e);
+                }
+            }
         }
     }
     {
@@ -58,6 +58,38 @@ pub fn post(context: &mut SpecContext<TestState>) -> Result<(), String> {
     test_post(runtime, routes_state, TOKEN_OWNER, &param, "")
 }
 
+/// Test that `hostUri` can list several hosts that share one port, but not one port for each host.
+pub fn post_multi_host(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+
+    let param = request::PostApplication {
+        data: request::PostApplicationData {
+            code: "manager".to_string(),
+            unit_id: "manager".to_string(),
+            host_uri: "amqp://host1,host2,host3:5672".to_string(),
+            name: None,
+            info: None,
+        },
+    };
+    test_post(runtime, routes_state, TOKEN_MANAGER, &param, "")?;
+
+    let param = request::PostApplication {
+        data: request::PostApplicationData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            host_uri: "amqp://host1:5672,host2:5672".to_string(),
+            name: None,
+            info: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))
+}
+
 pub fn post_dup(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
     let state = state.get(STATE).unwrap();
@@ -203,6 +235,32 @@ pub fn post_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), St
             info: Some(info),
         },
     };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert("dldataExpiresIn".to_string(), Value::from(0));
+    let param = request::PostApplication {
+        data: request::PostApplicationData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            host_uri: "amqp://host".to_string(),
+            name: None,
+            info: Some(info),
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert("deviceStatus".to_string(), Value::from(1));
+    let param = request::PostApplication {
+        data: request::PostApplicationData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            host_uri: "amqp://host".to_string(),
+            name: None,
+            info: Some(info),
+        },
+    };
     test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))
 }
 
@@ -996,6 +1054,44 @@ pub fn patch_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), S
             ..Default::default()
         },
     };
+    test_patch_invalid_param(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        Some(&param),
+    )?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert(
+        "dldataExpiresIn".to_string(),
+        Value::String("60".to_string()),
+    );
+    let param = request::PatchApplication {
+        data: request::PatchApplicationData {
+            info: Some(info),
+            ..Default::default()
+        },
+    };
+    test_patch_invalid_param(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        Some(&param),
+    )?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert(
+        "deviceStatus".to_string(),
+        Value::String("true".to_string()),
+    );
+    let param = request::PatchApplication {
+        data: request::PatchApplicationData {
+            info: Some(info),
+            ..Default::default()
+        },
+    };
     test_patch_invalid_param(
         runtime,
         routes_state,
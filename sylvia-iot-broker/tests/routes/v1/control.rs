@@ -68,6 +68,8 @@ pub fn test_wrong_data(context: &mut SpecContext<TestState>) -> Result<(), Strin
         routes_state.mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "application",
         false,
         Arc::new(TestHandler {}),
@@ -78,6 +80,8 @@ pub fn test_wrong_data(context: &mut SpecContext<TestState>) -> Result<(), Strin
         routes_state.mq_conns.clone(),
         &url,
         None,
+        None,
+        None,
         "network",
         false,
         Arc::new(TestHandler {}),
@@ -6,9 +6,9 @@ use std::{
 };
 
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{TimeDelta, Utc};
 use hex;
-use laboratory::SpecContext;
+use laboratory::{SpecContext, expect};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use tokio::time;
@@ -20,13 +20,19 @@ use general_mq::{
     connection::GmqConnection,
     queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
 };
-use sylvia_iot_broker::libs::{
-    config,
-    mq::{Connection, MgrStatus, data},
+use sylvia_iot_broker::{
+    libs::{
+        config,
+        mq::{Connection, MgrStatus, application::ApplicationMgr, data},
+    },
+    models::device::{QueryCond, UpdateQueryCond, Updates},
 };
 use sylvia_iot_corelib::strings::time_str;
 
-use super::{STATE, TOKEN_MANAGER, application, device, device_route, libs, network, unit};
+use super::{
+    super::libs::{create_dldata_buffer, get_dldata_buffer_model},
+    STATE, TOKEN_MANAGER, application, device, device_route, libs, network, unit,
+};
 use crate::{TestState, WAIT_COUNT, WAIT_TICK};
 
 /// Uplink data from network to broker.
@@ -79,11 +85,27 @@ struct QueueNetDlDataResult {
     status: isize,
 }
 
+#[derive(Deserialize)]
+struct QueueAppDlDataResult {
+    #[serde(rename = "dataId")]
+    data_id: String,
+    status: isize,
+}
+
+#[derive(Deserialize)]
+struct QueueAppDeviceStatus {
+    #[serde(rename = "deviceId")]
+    device_id: String,
+    status: String,
+}
+
 #[derive(Deserialize)]
 #[serde(tag = "kind")]
 enum RecvDataMsg {
     #[serde(rename = "application-uldata")]
     AppUlData { data: AppUlData },
+    #[serde(rename = "application-uldata-failed")]
+    AppUlDataFailed { data: AppUlDataFailed },
     #[serde(rename = "application-dldata")]
     AppDlData { data: AppDlData },
     #[serde(rename = "application-dldata-result")]
@@ -117,8 +139,16 @@ struct AppUlData {
     time: String,
     profile: String,
     data: String,
-    #[serde(rename = "extension")]
-    _extension: Option<Map<String, Value>>,
+    extension: Option<Map<String, Value>>,
+}
+
+#[derive(Deserialize)]
+struct AppUlDataFailed {
+    #[serde(flatten)]
+    data: AppUlData,
+    retries: u64,
+    #[serde(rename = "error")]
+    _error: String,
 }
 
 #[derive(Deserialize)]
@@ -145,11 +175,10 @@ struct AppDlData {
 #[derive(Deserialize)]
 struct AppDlDataResult {
     #[serde(rename = "dataId")]
-    _data_id: String,
+    data_id: String,
     #[serde(rename = "resp")]
     _resp: String,
-    #[serde(rename = "status")]
-    _status: isize,
+    status: isize,
 }
 
 #[derive(Deserialize)]
@@ -213,6 +242,8 @@ struct TestResources {
     net_prv_uldata: Queue,
     net_pub_uldata: Queue,
     data_recv_handler: TestHandler,
+    app_dldata_result_handler: AppDlDataResultHandler,
+    app_device_status_handler: AppDeviceStatusHandler,
 }
 
 #[derive(Clone)]
@@ -220,6 +251,18 @@ struct TestHandler {
     recv_data: Arc<Mutex<Vec<RecvDataMsg>>>,
 }
 
+/// To receive downlink data results from `broker.application.*.*.dldata-result` queues.
+#[derive(Clone)]
+struct AppDlDataResultHandler {
+    recv_data: Arc<Mutex<Vec<QueueAppDlDataResult>>>,
+}
+
+/// To receive device status from `broker.application.*.*.device-status` queues.
+#[derive(Clone)]
+struct AppDeviceStatusHandler {
+    recv_data: Arc<Mutex<Vec<QueueAppDeviceStatus>>>,
+}
+
 /// To consume routed data from applications or networks.
 struct AppNetConsumerHandler {
     result_queue: Option<Queue>, // for "broker.network.*.*.dldata" queues.
@@ -233,6 +276,8 @@ const NET_ADDR_PRV: &'static str = "manager";
 const NET_ADDR_PRV_NOT_ROUTE: &'static str = "manager-not-route";
 const NET_ADDR_PUB: &'static str = "public";
 const NET_ADDR_PUB_NOT_ROUTE: &'static str = "public-not-route";
+const ULDATA_RETRY_LIMIT: u64 = 2;
+const ULDATA_DEDUP_WINDOW: u64 = 500;
 
 impl TestHandler {
     fn new() -> Self {
@@ -267,6 +312,66 @@ impl MessageHandler for TestHandler {
     }
 }
 
+impl AppDlDataResultHandler {
+    fn new() -> Self {
+        AppDlDataResultHandler {
+            recv_data: Arc::new(Mutex::new(vec![])),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for AppDlDataResultHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, _status: Status) {}
+}
+
+#[async_trait]
+impl MessageHandler for AppDlDataResultHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        let _ = msg.ack().await;
+
+        let data = match serde_json::from_slice::<QueueAppDlDataResult>(msg.payload()) {
+            Err(_) => return,
+            Ok(data) => data,
+        };
+        {
+            self.recv_data.lock().unwrap().push(data);
+        }
+    }
+}
+
+impl AppDeviceStatusHandler {
+    fn new() -> Self {
+        AppDeviceStatusHandler {
+            recv_data: Arc::new(Mutex::new(vec![])),
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for AppDeviceStatusHandler {
+    async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
+
+    async fn on_status(&self, _queue: Arc<dyn GmqQueue>, _status: Status) {}
+}
+
+#[async_trait]
+impl MessageHandler for AppDeviceStatusHandler {
+    async fn on_message(&self, _queue: Arc<dyn GmqQueue>, msg: Box<dyn Message>) {
+        let _ = msg.ack().await;
+
+        let data = match serde_json::from_slice::<QueueAppDeviceStatus>(msg.payload()) {
+            Err(_) => return,
+            Ok(data) => data,
+        };
+        {
+            self.recv_data.lock().unwrap().push(data);
+        }
+    }
+}
+
 #[async_trait]
 impl EventHandler for AppNetConsumerHandler {
     async fn on_error(&self, _queue: Arc<dyn GmqQueue>, _err: Box<dyn StdError + Send + Sync>) {}
@@ -317,11 +422,16 @@ pub fn before_all_fn(state: &mut HashMap<&'static str, TestState>) -> () {
     // data_sender during creating ApplicationMgr/NetworkMgr.
     let url = Url::parse(data_ch_host).unwrap();
     let handler = Arc::new(TestHandler::new());
+    routes_state.uldata_retry_limit = ULDATA_RETRY_LIMIT;
+    routes_state.uldata_retry_delay = 10;
+    routes_state.uldata_dedup_window = ULDATA_DEDUP_WINDOW;
     routes_state.data_sender = Some(
         data::new(
             &routes_state.mq_conns,
             &url,
             config::DEF_MQ_PERSISTENT,
+            None,
+            None,
             handler,
         )
         .unwrap(),
@@ -341,13 +451,15 @@ pub fn before_all_fn(state: &mut HashMap<&'static str, TestState>) -> () {
         }
         Ok(unit_id) => unit_id,
     };
+    let mut info = Map::<String, Value>::new();
+    info.insert("deviceStatus".to_string(), Value::Bool(true));
     let application = application::request::PostApplication {
         data: application::request::PostApplicationData {
             code: APP_CODE.to_string(),
             unit_id: unit_id.clone(),
             host_uri: data_ch_host.clone(),
             name: None,
-            info: None,
+            info: Some(info),
         },
     };
     let application_id =
@@ -498,6 +610,12 @@ pub fn after_all_fn(state: &mut HashMap<&'static str, TestState>) -> () {
                 Connection::Mqtt(mut conn, _) => {
                     let _ = conn.close().await;
                 }
+                Connection::Memory(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
+                Connection::Redis(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
             }
         })
     }
@@ -938,6 +1056,296 @@ pub fn downlink(context: &mut SpecContext<TestState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Test the following cases:
+/// - add an expired downlink data buffer.
+/// - the application should receive the expired result and the data channel should receive the
+///   application result.
+/// - the buffer should be removed.
+pub fn downlink_expired(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let rsc = create_connections(state)?;
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    let data_id = "downlink-expired";
+    let mut data = create_dldata_buffer(data_id, UNIT_CODE, APP_CODE, NET_CODE_PRV, NET_ADDR_PRV);
+    data.expired_at = Utc::now() - TimeDelta::try_seconds(1).unwrap();
+    runtime.block_on(async {
+        if let Err(e) = routes_state.model.dldata_buffer().add(&data).await {
+            return Err(format!("add dldata buffer {} error: {}", data_id, e));
+        }
+
+        let mut is_app_result_recv = false;
+        let mut is_data_result_recv = false;
+        for _ in 0..WAIT_COUNT {
+            if let Some(data) = {
+                rsc.app_dldata_result_handler
+                    .recv_data
+                    .lock()
+                    .unwrap()
+                    .pop()
+            } {
+                if data.data_id.as_str() == data_id && data.status == 408 {
+                    is_app_result_recv = true;
+                }
+            }
+            if let Some(data) = { rsc.data_recv_handler.recv_data.lock().unwrap().pop() } {
+                if let RecvDataMsg::AppDlDataResult { data } = data {
+                    if data.data_id.as_str() == data_id && data.status == 408 {
+                        is_data_result_recv = true;
+                    }
+                }
+            }
+            if is_app_result_recv && is_data_result_recv {
+                break;
+            }
+            time::sleep(Duration::from_millis(WAIT_TICK)).await;
+        }
+        if !is_app_result_recv || !is_data_result_recv {
+            return Err(format!(
+                "recv expired result error. app: {}, data: {}",
+                is_app_result_recv, is_data_result_recv
+            ));
+        }
+        Ok(())
+    })?;
+    let _ = get_dldata_buffer_model(runtime, routes_state, data_id, false)?;
+
+    Ok(())
+}
+
+/// Test the following cases:
+/// - mark the public device online with an offline time in the past.
+/// - the application should receive the `offline` status.
+/// - the device should be offline.
+pub fn device_offline(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let rsc = create_connections(state)?;
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+    let test_values = state.test_values.as_ref().unwrap();
+
+    let device_id = test_values.get(NET_ADDR_PUB).unwrap().as_str();
+    runtime.block_on(async {
+        // Write buffered states of previous uplink data first to keep the online state.
+        routes_state.device_states.flush().await;
+        let cond = UpdateQueryCond { device_id };
+        let updates = Updates {
+            offline_at: Some(Some(Utc::now() - TimeDelta::try_seconds(1).unwrap())),
+            online: Some(Some(true)),
+            ..Default::default()
+        };
+        if let Err(e) = routes_state.model.device().update(&cond, &updates).await {
+            return Err(format!("update device {} error: {}", device_id, e));
+        }
+
+        for _ in 0..WAIT_COUNT {
+            if let Some(data) = {
+                rsc.app_device_status_handler
+                    .recv_data
+                    .lock()
+                    .unwrap()
+                    .pop()
+            } {
+                if data.device_id.as_str() == device_id && data.status.as_str() == "offline" {
+                    return Ok(());
+                }
+            }
+            time::sleep(Duration::from_millis(WAIT_TICK)).await;
+        }
+        Err("recv offline status error".to_string())
+    })?;
+    let device = match runtime.block_on(async {
+        let cond = QueryCond {
+            device_id: Some(device_id),
+            ..Default::default()
+        };
+        routes_state.model.device().get(&cond).await
+    }) {
+        Err(e) => return Err(format!("get device {} error: {}", device_id, e)),
+        Ok(device) => match device {
+            None => return Err(format!("should get device {}", device_id)),
+            Some(device) => device,
+        },
+    };
+    expect(device.online).to_equal(Some(false))
+}
+
+/// Test the following cases:
+/// - send two copies of uplink data with different extensions in the deduplication window.
+/// - check data channel, only one application data with `duplicates` should be received.
+pub fn uplink_dedup(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let rsc = create_connections(state)?;
+    let runtime = state.runtime.as_ref().unwrap();
+
+    let now = Utc::now();
+    let payload_hex = hex::encode(NET_ADDR_PRV);
+    let mut data = QueueNetUlData {
+        time: time_str(&now),
+        network_addr: NET_ADDR_PRV.to_string(),
+        data: payload_hex.clone(),
+        extension: None,
+    };
+    runtime.block_on(async {
+        for gateway in ["gateway1", "gateway2"] {
+            let mut extension = Map::new();
+            extension.insert("gateway".to_string(), Value::String(gateway.to_string()));
+            data.extension = Some(extension);
+            let payload = match serde_json::to_vec(&data) {
+                Err(e) => return Err(format!("marshal private data error: {}", e)),
+                Ok(payload) => payload,
+            };
+            if let Err(e) = rsc.net_prv_uldata.send_msg(payload).await {
+                return Err(format!("send private data error: {}", e));
+            }
+        }
+
+        let mut app_count = 0;
+        let mut net_count = 0;
+        let mut duplicates = vec![];
+        for _ in 0..WAIT_COUNT {
+            if let Some(data) = { rsc.data_recv_handler.recv_data.lock().unwrap().pop() } {
+                match data {
+                    RecvDataMsg::NetUlData { data } => {
+                        if data.data.as_str().eq(payload_hex.as_str())
+                            && data.time.as_str().eq(time_str(&now).as_str())
+                        {
+                            net_count += 1;
+                        }
+                    }
+                    RecvDataMsg::AppUlData { data } => {
+                        if data.data.as_str().eq(payload_hex.as_str())
+                            && data.time.as_str().eq(time_str(&now).as_str())
+                        {
+                            app_count += 1;
+                            if let Some(Value::Array(values)) = data
+                                .extension
+                                .as_ref()
+                                .and_then(|extension| extension.get("duplicates"))
+                            {
+                                duplicates = values.clone();
+                            }
+                        }
+                    }
+                    _ => (),
+                }
+                continue;
+            }
+            if app_count > 0 && net_count == 2 {
+                break;
+            }
+            time::sleep(Duration::from_millis(WAIT_TICK)).await;
+        }
+        // Wait another window to make sure that the duplicate is not sent again.
+        time::sleep(Duration::from_millis(ULDATA_DEDUP_WINDOW * 2)).await;
+        while let Some(data) = { rsc.data_recv_handler.recv_data.lock().unwrap().pop() } {
+            if let RecvDataMsg::AppUlData { data } = data
+                && data.data.as_str().eq(payload_hex.as_str())
+                && data.time.as_str().eq(time_str(&now).as_str())
+            {
+                app_count += 1;
+            }
+        }
+        if app_count != 1 || net_count != 2 {
+            return Err(format!(
+                "recv data count error. app: {}, net: {}",
+                app_count, net_count
+            ));
+        }
+        if duplicates.len() != 1 || !duplicates[0].is_object() {
+            return Err(format!("duplicates error: {:?}", duplicates));
+        }
+        Ok(())
+    })
+}
+
+/// Test the following cases:
+/// - close application managers to fail sending uplink data.
+/// - check data channel, the failed data should be received after retries instead of the
+///   application data.
+pub fn uplink_failed(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    let rsc = create_connections(state)?;
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    let managers: Vec<ApplicationMgr> = {
+        let managers = routes_state.application_mgrs.lock().unwrap();
+        managers.values().cloned().collect()
+    };
+    runtime.block_on(async {
+        for mgr in managers {
+            if let Err(e) = mgr.close().await {
+                return Err(format!("close application manager error: {}", e));
+            }
+        }
+        Ok(())
+    })?;
+
+    let now = Utc::now();
+    let payload_hex = hex::encode(NET_ADDR_PRV);
+    let data = QueueNetUlData {
+        time: time_str(&now),
+        network_addr: NET_ADDR_PRV.to_string(),
+        data: payload_hex.clone(),
+        extension: None,
+    };
+    runtime.block_on(async {
+        let payload = match serde_json::to_vec(&data) {
+            Err(e) => return Err(format!("marshal private data error: {}", e)),
+            Ok(payload) => payload,
+        };
+        if let Err(e) = rsc.net_prv_uldata.send_msg(payload).await {
+            return Err(format!("send private data error: {}", e));
+        }
+
+        let mut is_app_recv = false;
+        let mut is_failed_recv = false;
+        for _ in 0..WAIT_COUNT {
+            if let Some(data) = { rsc.data_recv_handler.recv_data.lock().unwrap().pop() } {
+                match data {
+                    RecvDataMsg::AppUlData { data } => {
+                        if data.data.as_str().eq(payload_hex.as_str())
+                            && data.time.as_str().eq(time_str(&now).as_str())
+                        {
+                            is_app_recv = true;
+                        }
+                    }
+                    RecvDataMsg::AppUlDataFailed { data } => {
+                        if data.data.data.as_str().eq(payload_hex.as_str())
+                            && data.data.time.as_str().eq(time_str(&now).as_str())
+                            && data.retries == ULDATA_RETRY_LIMIT
+                        {
+                            is_failed_recv = true;
+                        }
+                    }
+                    _ => (),
+                }
+                if is_failed_recv {
+                    break;
+                }
+            }
+            time::sleep(Duration::from_millis(WAIT_TICK)).await;
+        }
+        if is_app_recv || !is_failed_recv {
+            return Err(format!(
+                "recv failed data error. app: {}, failed: {}",
+                is_app_recv, is_failed_recv
+            ));
+        }
+        Ok(())
+    })
+}
+
 /// Create application/network/data side connection and queues.
 fn create_connections(state: &mut TestState) -> Result<TestResources, String> {
     let host = state.data_ch_host.as_ref().unwrap();
@@ -1047,13 +1455,25 @@ fn create_connections(state: &mut TestState) -> Result<TestResources, String> {
             UNIT_CODE, APP_CODE
         );
         let mut q = Queue::new(QueueOptions::Mqtt(opts.clone(), &conn))?;
-        let handler = Arc::new(AppNetConsumerHandler { result_queue: None });
-        q.set_handler(handler.clone());
-        q.set_msg_handler(handler);
+        let app_dldata_result_handler = AppDlDataResultHandler::new();
+        q.set_handler(Arc::new(app_dldata_result_handler.clone()));
+        q.set_msg_handler(Arc::new(app_dldata_result_handler.clone()));
         if let Err(e) = q.connect() {
             return Err(format!("app dldata-result queue connection error: {}", e));
         }
         routing_queues.push(Box::new(q));
+        opts.name = format!(
+            "broker.application.{}.{}.device-status",
+            UNIT_CODE, APP_CODE
+        );
+        let mut q = Queue::new(QueueOptions::Mqtt(opts.clone(), &conn))?;
+        let app_device_status_handler = AppDeviceStatusHandler::new();
+        q.set_handler(Arc::new(app_device_status_handler.clone()));
+        q.set_msg_handler(Arc::new(app_device_status_handler.clone()));
+        if let Err(e) = q.connect() {
+            return Err(format!("app device-status queue connection error: {}", e));
+        }
+        routing_queues.push(Box::new(q));
         opts.name = format!("broker.network.{}.{}.dldata", UNIT_CODE, NET_CODE_PRV);
         let mut q = Queue::new(QueueOptions::Mqtt(opts.clone(), &conn))?;
         let handler = Arc::new(AppNetConsumerHandler {
@@ -1088,6 +1508,8 @@ fn create_connections(state: &mut TestState) -> Result<TestResources, String> {
             net_prv_uldata,
             net_pub_uldata,
             data_recv_handler,
+            app_dldata_result_handler,
+            app_device_status_handler,
         })
     } else {
         // Create connection.
@@ -1195,13 +1617,25 @@ fn create_connections(state: &mut TestState) -> Result<TestResources, String> {
             UNIT_CODE, APP_CODE
         );
         let mut q = Queue::new(QueueOptions::Amqp(opts.clone(), &conn))?;
-        let handler = Arc::new(AppNetConsumerHandler { result_queue: None });
-        q.set_handler(handler.clone());
-        q.set_msg_handler(handler);
+        let app_dldata_result_handler = AppDlDataResultHandler::new();
+        q.set_handler(Arc::new(app_dldata_result_handler.clone()));
+        q.set_msg_handler(Arc::new(app_dldata_result_handler.clone()));
         if let Err(e) = q.connect() {
             return Err(format!("app dldata-result queue connection error: {}", e));
         }
         routing_queues.push(Box::new(q));
+        opts.name = format!(
+            "broker.application.{}.{}.device-status",
+            UNIT_CODE, APP_CODE
+        );
+        let mut q = Queue::new(QueueOptions::Amqp(opts.clone(), &conn))?;
+        let app_device_status_handler = AppDeviceStatusHandler::new();
+        q.set_handler(Arc::new(app_device_status_handler.clone()));
+        q.set_msg_handler(Arc::new(app_device_status_handler.clone()));
+        if let Err(e) = q.connect() {
+            return Err(format!("app device-status queue connection error: {}", e));
+        }
+        routing_queues.push(Box::new(q));
         opts.name = format!("broker.network.{}.{}.dldata", UNIT_CODE, NET_CODE_PRV);
         let mut q = Queue::new(QueueOptions::Amqp(opts.clone(), &conn))?;
         let handler = Arc::new(AppNetConsumerHandler {
@@ -1236,6 +1670,8 @@ fn create_connections(state: &mut TestState) -> Result<TestResources, String> {
             net_prv_uldata,
             net_pub_uldata,
             data_recv_handler,
+            app_dldata_result_handler,
+            app_device_status_handler,
         })
     }
 }
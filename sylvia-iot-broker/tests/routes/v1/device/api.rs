@@ -2049,6 +2049,30 @@ pub fn get_count(context: &mut SpecContext<TestState>) -> Result<(), String> {
     test_get_count(runtime, &routes_state, TOKEN_MEMBER, Some(&param), 0)
 }
 
+pub fn get_count_online(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    online_dataset(runtime, &routes_state)?;
+
+    test_get_count(runtime, &routes_state, TOKEN_MANAGER, None, 3)?;
+
+    let param = request::GetDeviceCount {
+        online: Some(true),
+        ..Default::default()
+    };
+    test_get_count(runtime, &routes_state, TOKEN_MANAGER, Some(&param), 1)?;
+
+    let param = request::GetDeviceCount {
+        unit: Some("manager".to_string()),
+        online: Some(false),
+        ..Default::default()
+    };
+    test_get_count(runtime, &routes_state, TOKEN_MANAGER, Some(&param), 1)
+}
+
 pub fn get_count_not_exist_unit(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
     let state = state.get(STATE).unwrap();
@@ -2807,6 +2831,30 @@ pub fn get_list_format_array(context: &mut SpecContext<TestState>) -> Result<(),
     )
 }
 
+pub fn get_list_online(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    online_dataset(runtime, &routes_state)?;
+
+    test_get_list(runtime, &routes_state, TOKEN_MANAGER, None, 3)?;
+
+    let param = request::GetDeviceList {
+        online: Some(true),
+        ..Default::default()
+    };
+    test_get_list_online(runtime, &routes_state, &param, "online")?;
+
+    let param = request::GetDeviceList {
+        unit: Some("manager".to_string()),
+        online: Some(false),
+        ..Default::default()
+    };
+    test_get_list_online(runtime, &routes_state, &param, "offline")
+}
+
 pub fn get_list_not_exist_unit(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let state = context.state.borrow();
     let state = state.get(STATE).unwrap();
@@ -4053,6 +4101,30 @@ fn test_get_list(
     Ok(())
 }
 
+fn test_get_list_online(
+    runtime: &Runtime,
+    state: &routes::State,
+    param: &request::GetDeviceList,
+    expect_device_id: &str,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .get("/broker/api/v1/device/list")
+        .add_query_params(param)
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", TOKEN_MANAGER).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::OK)?;
+    let body: response::GetDeviceList = resp.json();
+    expect(body.data.len()).to_equal(1)?;
+    expect(body.data[0].device_id.as_str()).to_equal(expect_device_id)?;
+    expect(body.data[0].online).to_equal(param.online)
+}
+
 fn test_get_list_sort(
     runtime: &Runtime,
     state: &routes::State,
@@ -4216,7 +4288,13 @@ fn test_get(
     .to_equal(device_info.modified_at.timestamp_millis())?;
     expect(body.data.profile.as_str()).to_equal(device_info.profile.as_str())?;
     expect(body.data.name.as_str()).to_equal(device_info.name.as_str())?;
-    expect(body.data.info).to_equal(device_info.info)
+    expect(body.data.info).to_equal(device_info.info)?;
+    expect(body.data.last_uplink_at.is_none()).to_equal(device_info.last_uplink_at.is_none())?;
+    expect(body.data.last_downlink_at.is_none())
+        .to_equal(device_info.last_downlink_at.is_none())?;
+    expect(body.data.last_extension).to_equal(device_info.last_extension)?;
+    expect(body.data.offline_at.is_none()).to_equal(device_info.offline_at.is_none())?;
+    expect(body.data.online).to_equal(device_info.online)
 }
 
 fn test_get_wrong_id(
@@ -4490,6 +4568,42 @@ fn count_list_dataset(
     Ok((8, 4, 2, 3))
 }
 
+/// Generate dataset for the online condition with an online, an offline and an unknown status
+/// device.
+fn online_dataset(runtime: &Runtime, state: &routes::State) -> Result<(), String> {
+    add_unit_model(runtime, state, "manager", vec!["member"], "manager")?;
+    add_network_model(runtime, state, "manager", "manager", "amqp://host")?;
+    let now = Utc::now();
+
+    let mut device = create_device("manager", "manager", "online", true, "");
+    device.offline_at = Some(now + TimeDelta::try_seconds(60).unwrap());
+    device.online = Some(true);
+    runtime.block_on(async {
+        if let Err(e) = state.model.device().add(&device).await {
+            return Err(format!("add device {} error: {}", device.device_id, e));
+        }
+        Ok(())
+    })?;
+
+    let mut device = create_device("manager", "manager", "offline", true, "");
+    device.offline_at = Some(now);
+    device.online = Some(false);
+    runtime.block_on(async {
+        if let Err(e) = state.model.device().add(&device).await {
+            return Err(format!("add device {} error: {}", device.device_id, e));
+        }
+        Ok(())
+    })?;
+
+    let device = create_device("manager", "manager", "unknown", true, "");
+    runtime.block_on(async {
+        if let Err(e) = state.model.device().add(&device).await {
+            return Err(format!("add device {} error: {}", device.device_id, e));
+        }
+        Ok(())
+    })
+}
+
 /// Generate dataset for extra conditions (network, addr).
 /// Returns (manager_public, manager, manager_same-addr, owner_public, owner, owner_same-addr)
 /// tuple.
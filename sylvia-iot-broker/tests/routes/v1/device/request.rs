@@ -70,6 +70,8 @@ pub struct GetDeviceCount {
     pub profile: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub online: Option<bool>,
 }
 
 #[derive(Debug, Default, Serialize)]
@@ -85,6 +87,8 @@ pub struct GetDeviceList {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub contains: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub online: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<usize>,
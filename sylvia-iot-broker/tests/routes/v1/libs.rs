@@ -24,6 +24,7 @@ pub fn clear_all_data(runtime: &Runtime, state: &TestState) -> () {
     const DEVICE_ROUTE_NAME2: &'static str = "device_route";
     const DLDATA_BUFFER_NAME1: &'static str = "dldataBuffer";
     const DLDATA_BUFFER_NAME2: &'static str = "dldata_buffer";
+    const PROFILE_NAME: &'static str = "profile";
 
     if let Some(model) = state.mongodb.as_ref() {
         runtime.block_on(async {
@@ -58,6 +59,10 @@ pub fn clear_all_data(runtime: &Runtime, state: &TestState) -> () {
                 .collection::<Doc>(DLDATA_BUFFER_NAME1)
                 .delete_many(Document::new())
                 .await;
+            let _ = conn
+                .collection::<Doc>(PROFILE_NAME)
+                .delete_many(Document::new())
+                .await;
         });
     }
     if let Some(model) = state.sqlite.as_ref() {
@@ -77,6 +82,8 @@ pub fn clear_all_data(runtime: &Runtime, state: &TestState) -> () {
             let _ = sqlx::query(AssertSqlSafe(sql)).execute(conn).await;
             let sql = SqlBuilder::delete_from(DLDATA_BUFFER_NAME2).sql().unwrap();
             let _ = sqlx::query(AssertSqlSafe(sql)).execute(conn).await;
+            let sql = SqlBuilder::delete_from(PROFILE_NAME).sql().unwrap();
+            let _ = sqlx::query(AssertSqlSafe(sql)).execute(conn).await;
         });
     }
     if let Some(state) = state.routes_state.as_ref() {
@@ -23,6 +23,7 @@ mod libs;
 mod network;
 mod network_ctrl;
 mod network_route;
+mod profile;
 mod routing;
 mod unit;
 
@@ -32,6 +33,7 @@ use device_route::api as devicerouteapi;
 use dldata_buffer::api as dldatabufferapi;
 use network::api as networkapi;
 use network_route::api as networkrouteapi;
+use profile::api as profileapi;
 use unit::api as unitapi;
 
 pub const STATE: &'static str = "routes/v1";
@@ -44,6 +46,10 @@ pub fn suite(db_engine: &'static str, cache_engine: &'static str) -> Suite<TestS
     describe(suite_name, move |context| {
         context.describe("application", |context| {
             context.it("POST /application", applicationapi::post);
+            context.it(
+                "POST /application with multiple hosts",
+                applicationapi::post_multi_host,
+            );
             context.it(
                 "POST /application with duplicate code",
                 applicationapi::post_dup,
@@ -197,6 +203,7 @@ pub fn suite(db_engine: &'static str, cache_engine: &'static str) -> Suite<TestS
                 deviceapi::post_range_del_invalid_token,
             );
             context.it("GET /device/count", deviceapi::get_count);
+            context.it("GET /device/count with online", deviceapi::get_count_online);
             context.it(
                 "GET /device/count with not exist unit",
                 deviceapi::get_count_not_exist_unit,
@@ -210,6 +217,7 @@ pub fn suite(db_engine: &'static str, cache_engine: &'static str) -> Suite<TestS
                 deviceapi::get_count_invalid_token,
             );
             context.it("GET /device/list", deviceapi::get_list);
+            context.it("GET /device/list with online", deviceapi::get_list_online);
             context.it("GET /device/list sort", deviceapi::get_list_sort);
             context.it(
                 "GET /device/list offset limit",
@@ -588,6 +596,74 @@ pub fn suite(db_engine: &'static str, cache_engine: &'static str) -> Suite<TestS
             context.after_each(after_each_fn);
         });
 
+        context.describe("profile", |context| {
+            context.it("POST /profile", profileapi::post);
+            context.it("POST /profile with duplicate code", profileapi::post_dup);
+            context.it(
+                "POST /profile with not exist unit",
+                profileapi::post_not_exist_unit,
+            );
+            context.it(
+                "POST /profile with invalid parameters",
+                profileapi::post_invalid_param,
+            );
+            context.it(
+                "POST /profile with invalid token",
+                profileapi::post_invalid_token,
+            );
+            context.it("GET /profile/count", profileapi::get_count);
+            context.it(
+                "GET /profile/count with not exist unit",
+                profileapi::get_count_not_exist_unit,
+            );
+            context.it(
+                "GET /profile/count with invalid parameters",
+                profileapi::get_count_invalid_param,
+            );
+            context.it(
+                "GET /profile/count with invalid token",
+                profileapi::get_count_invalid_token,
+            );
+            context.it("GET /profile/list", profileapi::get_list);
+            context.it(
+                "GET /profile/list with invalid parameters",
+                profileapi::get_list_invalid_param,
+            );
+            context.it(
+                "GET /profile/list with invalid token",
+                profileapi::get_list_invalid_token,
+            );
+            context.it("GET /profile/{profileId}", profileapi::get);
+            context.it(
+                "GET /profile/{profileId} with wrong ID",
+                profileapi::get_wrong_id,
+            );
+            context.it(
+                "GET /profile/{profileId} with invalid token",
+                profileapi::get_invalid_token,
+            );
+            context.it("PATCH /profile/{profileId}", profileapi::patch);
+            context.it(
+                "PATCH /profile/{profileId} with wrong ID",
+                profileapi::patch_wrong_id,
+            );
+            context.it(
+                "PATCH /profile/{profileId} with invalid parameters",
+                profileapi::patch_invalid_param,
+            );
+            context.it(
+                "PATCH /profile/{profileId} with invalid token",
+                profileapi::patch_invalid_token,
+            );
+            context.it("DELETE /profile/{profileId}", profileapi::delete);
+            context.it(
+                "DELETE /profile/{profileId} with invalid token",
+                profileapi::delete_invalid_token,
+            );
+
+            context.after_each(after_each_fn);
+        });
+
         context.describe("unit", |context| {
             context.it("POST /unit", unitapi::post);
             context.it("POST /unit with duplicate code", unitapi::post_dup);
@@ -715,6 +791,10 @@ pub fn suite_data(
         context.describe("data channel", |context| {
             context.it("uplink", data::uplink);
             context.it("downlink", data::downlink);
+            context.it("downlink with expired data", data::downlink_expired);
+            context.it("device offline", data::device_offline);
+            context.it("uplink with duplicates", data::uplink_dedup);
+            context.it("uplink with failed application", data::uplink_failed);
 
             context
                 .before_all(data::before_all_fn)
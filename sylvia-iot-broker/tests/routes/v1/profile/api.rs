@@ -0,0 +1,966 @@
+use axum::{
+    Router,
+    http::{HeaderValue, Method, StatusCode, header},
+};
+use axum_test::TestServer;
+use chrono::{DateTime, SubsecRound, Utc};
+use laboratory::{SpecContext, expect};
+use serde_json::{Map, Value, json};
+use tokio::runtime::Runtime;
+
+use sylvia_iot_broker::routes;
+use sylvia_iot_corelib::err;
+
+use super::{
+    super::{
+        super::libs::{
+            ApiError, add_profile_model, add_unit_model, get_profile_model, test_get_400,
+            test_invalid_token,
+        },
+        STATE, TOKEN_MANAGER, TOKEN_MEMBER, TOKEN_OWNER, TestState,
+    },
+    request, response,
+};
+
+pub fn post(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+    add_unit_model(runtime, routes_state, "owner", vec![], "owner")?;
+
+    let codec = json!({
+        "fields": [{"name": "temperature", "offset": 0, "type": "i16", "scale": 0.1}],
+        "script": "fn decode(bytes, object) { object }",
+    });
+    let mut info = Map::<String, Value>::new();
+    info.insert("info".to_string(), Value::String("value".to_string()));
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "manager".to_string(),
+            unit_id: "manager".to_string(),
+            name: Some("manager".to_string()),
+            codec: Some(codec.as_object().unwrap().clone()),
+            info: Some(info),
+            offline_timeout: Some(600),
+        },
+    };
+    test_post(runtime, routes_state, TOKEN_MANAGER, &param, "")?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "owner".to_string(),
+            unit_id: "owner".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post(runtime, routes_state, TOKEN_OWNER, &param, "")
+}
+
+pub fn post_dup(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "manager".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post(runtime, routes_state, TOKEN_MANAGER, &param, "")?;
+    test_post(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        &param,
+        "err_broker_profile_exist",
+    )
+}
+
+pub fn post_not_exist_unit(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec!["member"], "manager")?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "manager".to_string(),
+            unit_id: "not-exist".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        &param,
+        "err_broker_unit_not_exist",
+    )?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "manager".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post(
+        runtime,
+        routes_state,
+        TOKEN_MEMBER,
+        &param,
+        "err_broker_unit_not_exist",
+    )
+}
+
+pub fn post_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, None)?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "code".to_string(),
+            unit_id: "".to_string(),
+            name: None,
+            codec: None,
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let codec = json!({"fields": [{"name": "a", "offset": 0, "type": "u128"}]});
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: Some(codec.as_object().unwrap().clone()),
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let codec = json!({"script": "fn decode(bytes, object) {"});
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: Some(codec.as_object().unwrap().clone()),
+            info: None,
+            offline_timeout: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert("".to_string(), Value::String("value".to_string()));
+    let param = request::PostProfile {
+        data: request::PostProfileData {
+            code: "code".to_string(),
+            unit_id: "manager".to_string(),
+            name: None,
+            codec: None,
+            info: Some(info),
+            offline_timeout: None,
+        },
+    };
+    test_post_invalid_param(runtime, &routes_state, TOKEN_MANAGER, Some(&param))
+}
+
+pub fn post_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::POST,
+        "/broker/api/v1/profile",
+    )
+}
+
+pub fn get_count(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    count_list_dataset(runtime, routes_state)?;
+
+    test_get_count(runtime, routes_state, TOKEN_MANAGER, None, 4)?;
+
+    let param = request::GetProfileCount {
+        unit: Some("owner1".to_string()),
+        ..Default::default()
+    };
+    test_get_count(runtime, routes_state, TOKEN_MANAGER, Some(&param), 2)?;
+    test_get_count(runtime, routes_state, TOKEN_OWNER, Some(&param), 2)?;
+
+    let param = request::GetProfileCount {
+        unit: Some("owner2".to_string()),
+        ..Default::default()
+    };
+    test_get_count(runtime, routes_state, TOKEN_MEMBER, Some(&param), 1)?;
+
+    let param = request::GetProfileCount {
+        unit: Some("owner1".to_string()),
+        code: Some("owner1-1".to_string()),
+        ..Default::default()
+    };
+    test_get_count(runtime, routes_state, TOKEN_OWNER, Some(&param), 1)?;
+
+    let param = request::GetProfileCount {
+        contains: Some("1-".to_string()),
+        ..Default::default()
+    };
+    test_get_count(runtime, routes_state, TOKEN_MANAGER, Some(&param), 2)
+}
+
+pub fn get_count_not_exist_unit(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "owner1", vec![], "owner")?;
+
+    let uri = "/broker/api/v1/profile/count";
+    let code = "err_broker_unit_not_exist";
+
+    let mut query = Map::<String, Value>::new();
+    query.insert("unit".to_string(), Value::String("not-exist".to_string()));
+    test_get_400(runtime, routes_state, TOKEN_MANAGER, uri, &query, code)?;
+
+    let mut query = Map::<String, Value>::new();
+    query.insert("unit".to_string(), Value::String("owner1".to_string()));
+    test_get_400(runtime, routes_state, TOKEN_MEMBER, uri, &query, code)
+}
+
+pub fn get_count_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    let uri = "/broker/api/v1/profile/count";
+    let query = Map::<String, Value>::new();
+    test_get_400(
+        runtime,
+        routes_state,
+        TOKEN_OWNER,
+        uri,
+        &query,
+        err::E_PARAM,
+    )
+}
+
+pub fn get_count_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::GET,
+        "/broker/api/v1/profile/count",
+    )
+}
+
+pub fn get_list(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    count_list_dataset(runtime, routes_state)?;
+
+    test_get_list(runtime, routes_state, TOKEN_MANAGER, None, 4)?;
+
+    let param = request::GetProfileList {
+        unit: Some("owner1".to_string()),
+        ..Default::default()
+    };
+    test_get_list(runtime, routes_state, TOKEN_OWNER, Some(&param), 2)?;
+
+    let param = request::GetProfileList {
+        unit: Some("owner2".to_string()),
+        ..Default::default()
+    };
+    test_get_list(runtime, routes_state, TOKEN_MEMBER, Some(&param), 1)?;
+
+    let param = request::GetProfileList {
+        offset: Some(1),
+        limit: Some(2),
+        ..Default::default()
+    };
+    test_get_list(runtime, routes_state, TOKEN_MANAGER, Some(&param), 2)?;
+
+    let param = request::GetProfileList {
+        sort: Some("code:desc".to_string()),
+        ..Default::default()
+    };
+    test_get_list(runtime, routes_state, TOKEN_MANAGER, Some(&param), 4)?;
+
+    let param = request::GetProfileList {
+        format: Some("array".to_string()),
+        ..Default::default()
+    };
+    let app = Router::new().merge(routes::new_service(routes_state));
+    let server = TestServer::new(app);
+    let req = server
+        .get("/broker/api/v1/profile/list")
+        .add_query_params(&param)
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", TOKEN_MANAGER).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::OK)?;
+    let body: Vec<response::GetProfileListData> = resp.json();
+    expect(body.len()).to_equal(4)
+}
+
+pub fn get_list_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    let uri = "/broker/api/v1/profile/list";
+    let code = err::E_PARAM;
+
+    let query = Map::<String, Value>::new();
+    test_get_400(runtime, routes_state, TOKEN_OWNER, uri, &query, code)?;
+
+    let mut query = Map::<String, Value>::new();
+    query.insert("sort".to_string(), Value::String("hostUri".to_string()));
+    test_get_400(runtime, routes_state, TOKEN_MANAGER, uri, &query, code)?;
+
+    let mut query = Map::<String, Value>::new();
+    query.insert("sort".to_string(), Value::String("code:up".to_string()));
+    test_get_400(runtime, routes_state, TOKEN_MANAGER, uri, &query, code)
+}
+
+pub fn get_list_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::GET,
+        "/broker/api/v1/profile/list",
+    )
+}
+
+pub fn get(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec!["member"], "manager")?;
+    add_unit_model(runtime, routes_state, "owner1", vec![], "owner")?;
+    add_unit_model(runtime, routes_state, "owner2", vec!["member"], "owner")?;
+    add_profile_model(runtime, routes_state, "manager", "manager")?;
+    add_profile_model(runtime, routes_state, "owner1", "owner1")?;
+    add_profile_model(runtime, routes_state, "owner2", "owner2")?;
+
+    test_get(runtime, routes_state, TOKEN_MANAGER, "manager")?;
+    test_get(runtime, routes_state, TOKEN_MANAGER, "owner1")?;
+    test_get(runtime, routes_state, TOKEN_OWNER, "owner1")?;
+    test_get(runtime, routes_state, TOKEN_OWNER, "owner2")?;
+    test_get(runtime, routes_state, TOKEN_MEMBER, "manager")?;
+    test_get(runtime, routes_state, TOKEN_MEMBER, "owner2")
+}
+
+pub fn get_wrong_id(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec!["member"], "manager")?;
+    add_unit_model(runtime, routes_state, "owner1", vec![], "owner")?;
+    add_profile_model(runtime, routes_state, "manager", "manager")?;
+    add_profile_model(runtime, routes_state, "owner1", "owner1")?;
+
+    test_get_wrong_id(runtime, routes_state, TOKEN_MANAGER, "manager1")?;
+    test_get_wrong_id(runtime, routes_state, TOKEN_OWNER, "manager")?;
+    test_get_wrong_id(runtime, routes_state, TOKEN_MEMBER, "owner1")
+}
+
+pub fn get_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::GET,
+        "/broker/api/v1/profile/id",
+    )
+}
+
+pub fn patch(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+    add_unit_model(runtime, routes_state, "owner", vec![], "owner")?;
+
+    test_patch(runtime, routes_state, TOKEN_MANAGER, "manager", "manager")?;
+    test_patch(runtime, routes_state, TOKEN_OWNER, "owner", "owner")
+}
+
+pub fn patch_wrong_id(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec!["member"], "manager")?;
+    add_profile_model(runtime, routes_state, "manager", "manager")?;
+
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            name: Some("".to_string()),
+            ..Default::default()
+        },
+    };
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager1",
+        Some(&body),
+        StatusCode::NOT_FOUND,
+    )?;
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_OWNER,
+        "manager",
+        Some(&body),
+        StatusCode::NOT_FOUND,
+    )?;
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MEMBER,
+        "manager",
+        Some(&body),
+        StatusCode::NOT_FOUND,
+    )
+}
+
+pub fn patch_invalid_param(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec![], "manager")?;
+    add_profile_model(runtime, routes_state, "manager", "manager")?;
+
+    let status = StatusCode::BAD_REQUEST;
+
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        None,
+        status,
+    )?;
+
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            ..Default::default()
+        },
+    };
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        Some(&body),
+        status,
+    )?;
+
+    let codec = json!({"fields": [{"name": "a", "offset": 0, "type": "u8", "scale": 0}]});
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            codec: Some(codec.as_object().unwrap().clone()),
+            ..Default::default()
+        },
+    };
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        Some(&body),
+        status,
+    )?;
+
+    let mut info = Map::<String, Value>::new();
+    info.insert("".to_string(), Value::String("value".to_string()));
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            info: Some(info),
+            ..Default::default()
+        },
+    };
+    test_patch_status(
+        runtime,
+        routes_state,
+        TOKEN_MANAGER,
+        "manager",
+        Some(&body),
+        status,
+    )
+}
+
+pub fn patch_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::PATCH,
+        "/broker/api/v1/profile/id",
+    )
+}
+
+pub fn delete(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    add_unit_model(runtime, routes_state, "manager", vec!["member"], "manager")?;
+    add_unit_model(runtime, routes_state, "owner", vec!["member"], "owner")?;
+    add_profile_model(runtime, routes_state, "manager", "manager")?;
+    add_profile_model(runtime, routes_state, "owner", "owner")?;
+
+    test_delete(runtime, routes_state, TOKEN_MANAGER, "id")?;
+    test_delete(runtime, routes_state, TOKEN_OWNER, "manager")?;
+    test_delete(runtime, routes_state, TOKEN_MEMBER, "owner")?;
+    let _ = get_profile_model(runtime, routes_state, "manager", true)?;
+    let _ = get_profile_model(runtime, routes_state, "owner", true)?;
+
+    test_delete(runtime, routes_state, TOKEN_OWNER, "owner")?;
+    let _ = get_profile_model(runtime, routes_state, "owner", false)?;
+    test_delete(runtime, routes_state, TOKEN_MANAGER, "manager")?;
+    let _ = get_profile_model(runtime, routes_state, "manager", false)?;
+    Ok(())
+}
+
+pub fn delete_invalid_token(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::DELETE,
+        "/broker/api/v1/profile/id",
+    )
+}
+
+fn test_post(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    param: &request::PostProfile,
+    expect_code: &str,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let time_before = Utc::now().trunc_subsecs(3);
+    let req = server
+        .post("/broker/api/v1/profile")
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        )
+        .json(param);
+    let resp = runtime.block_on(async { req.await });
+    let time_after = Utc::now().trunc_subsecs(3);
+    let status = resp.status_code();
+    if status != StatusCode::OK {
+        let body: ApiError = resp.json();
+        let message = match body.message.as_ref() {
+            None => "",
+            Some(message) => message.as_str(),
+        };
+        if expect_code == body.code.as_str() {
+            return Ok(());
+        }
+        return Err(format!(
+            "API not 200, status: {}, code: {}, message: {}",
+            status,
+            body.code.as_str(),
+            message
+        ));
+    }
+    let body: response::PostProfile = resp.json();
+    expect(body.data.profile_id.len() > 0).to_equal(true)?;
+
+    let profile_info =
+        get_profile_model(runtime, state, body.data.profile_id.as_str(), true)?.unwrap();
+    expect(profile_info.code.as_str()).to_equal(param.data.code.as_str())?;
+    expect(profile_info.unit_id.as_str()).to_equal(param.data.unit_id.as_str())?;
+    expect(profile_info.unit_code.as_str()).to_equal(param.data.unit_id.as_str())?;
+    expect(profile_info.created_at.ge(&time_before)).to_equal(true)?;
+    expect(profile_info.created_at.le(&time_after)).to_equal(true)?;
+    expect(profile_info.modified_at.ge(&time_before)).to_equal(true)?;
+    expect(profile_info.modified_at.le(&time_after)).to_equal(true)?;
+    match param.data.name.as_ref() {
+        None => expect(profile_info.name.len()).to_equal(0)?,
+        Some(name) => expect(profile_info.name.as_str()).to_equal(name.as_str())?,
+    }
+    match param.data.codec.as_ref() {
+        None => expect(profile_info.codec.clone()).to_equal(Map::<String, Value>::new())?,
+        Some(codec) => expect(profile_info.codec.clone()).to_equal(codec.clone())?,
+    }
+    expect(profile_info.offline_timeout).to_equal(param.data.offline_timeout.unwrap_or(0))?;
+    match param.data.info.as_ref() {
+        None => expect(profile_info.info).to_equal(Map::<String, Value>::new()),
+        Some(info) => expect(profile_info.info).to_equal(info.clone()),
+    }
+}
+
+fn test_post_invalid_param(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    param: Option<&request::PostProfile>,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .post("/broker/api/v1/profile")
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        )
+        .json(&param);
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::BAD_REQUEST)?;
+    let body: ApiError = resp.json();
+    if body.code.as_str() != err::E_PARAM {
+        return Err(format!("unexpected 400 error: {}", body.code.as_str()));
+    }
+    Ok(())
+}
+
+fn test_get_count(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    param: Option<&request::GetProfileCount>,
+    expect_count: usize,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .get("/broker/api/v1/profile/count")
+        .add_query_params(&param)
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::OK)?;
+    let body: response::GetProfileCount = resp.json();
+    expect(body.data.count).to_equal(expect_count)
+}
+
+fn test_get_list(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    param: Option<&request::GetProfileList>,
+    expect_count: usize,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .get("/broker/api/v1/profile/list")
+        .add_query_params(&param)
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::OK)?;
+    let body: response::GetProfileList = resp.json();
+    expect(body.data.len()).to_equal(expect_count)?;
+
+    let desc = match param {
+        None => false,
+        Some(param) => param.sort.as_ref().is_some_and(|s| s.ends_with(":desc")),
+    };
+    for i in 1..body.data.len() {
+        let prev = body.data[i - 1].code.as_str();
+        let curr = body.data[i].code.as_str();
+        if (!desc && prev > curr) || (desc && prev < curr) {
+            return Err(format!("code order error: {} - {}", prev, curr));
+        }
+    }
+    Ok(())
+}
+
+fn test_get(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let profile_info = get_profile_model(runtime, state, profile_id, true)?.unwrap();
+
+    let req = server
+        .get(format!("/broker/api/v1/profile/{}", profile_id).as_str())
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    if let Err(_) = expect(resp.status_code()).to_equal(StatusCode::OK) {
+        return Err(format!("token:{}, profile: {}", token, profile_id));
+    }
+    let body: response::GetProfile = resp.json();
+    expect(body.data.profile_id.as_str()).to_equal(profile_info.profile_id.as_str())?;
+    expect(body.data.code.as_str()).to_equal(profile_info.code.as_str())?;
+    expect(body.data.unit_id.as_str()).to_equal(profile_info.unit_id.as_str())?;
+    expect(body.data.unit_code.as_str()).to_equal(profile_info.unit_code.as_str())?;
+    expect(
+        DateTime::parse_from_rfc3339(body.data.created_at.as_str())
+            .unwrap()
+            .timestamp_millis(),
+    )
+    .to_equal(profile_info.created_at.timestamp_millis())?;
+    expect(
+        DateTime::parse_from_rfc3339(body.data.modified_at.as_str())
+            .unwrap()
+            .timestamp_millis(),
+    )
+    .to_equal(profile_info.modified_at.timestamp_millis())?;
+    expect(body.data.name.as_str()).to_equal(profile_info.name.as_str())?;
+    expect(body.data.codec).to_equal(profile_info.codec)?;
+    expect(body.data.offline_timeout).to_equal(profile_info.offline_timeout)?;
+    expect(body.data.info).to_equal(profile_info.info)
+}
+
+fn test_get_wrong_id(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .get(format!("/broker/api/v1/profile/{}", profile_id).as_str())
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::NOT_FOUND)?;
+    let body: ApiError = resp.json();
+    expect(body.code.as_str()).to_equal(err::E_NOT_FOUND)
+}
+
+fn test_patch(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    unit_id: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    add_profile_model(runtime, state, unit_id, profile_id)?;
+
+    let time_before = Utc::now().trunc_subsecs(3);
+    let codec = json!({"fields": [{"name": "battery", "offset": 0, "type": "u8"}]});
+    let codec = codec.as_object().unwrap().clone();
+    let mut info = Map::<String, Value>::new();
+    info.insert(
+        "key_update".to_string(),
+        Value::String("updated".to_string()),
+    );
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            name: Some("name changes".to_string()),
+            codec: Some(codec.clone()),
+            info: Some(info.clone()),
+            offline_timeout: Some(300),
+        },
+    };
+    test_patch_status(
+        runtime,
+        state,
+        token,
+        profile_id,
+        Some(&body),
+        StatusCode::NO_CONTENT,
+    )?;
+
+    let time_after = Utc::now().trunc_subsecs(3);
+    let profile_info = get_profile_model(runtime, state, profile_id, true)?.unwrap();
+    expect(profile_info.modified_at.ge(&time_before)).to_equal(true)?;
+    expect(profile_info.modified_at.le(&time_after)).to_equal(true)?;
+    expect(profile_info.name.as_str()).to_equal("name changes")?;
+    expect(profile_info.codec).to_equal(codec.clone())?;
+    expect(profile_info.offline_timeout).to_equal(300)?;
+    expect(profile_info.info).to_equal(info)?;
+
+    let body = request::PatchProfile {
+        data: request::PatchProfileData {
+            name: Some("".to_string()),
+            info: Some(Map::<String, Value>::new()),
+            ..Default::default()
+        },
+    };
+    test_patch_status(
+        runtime,
+        state,
+        token,
+        profile_id,
+        Some(&body),
+        StatusCode::NO_CONTENT,
+    )?;
+
+    let profile_info = get_profile_model(runtime, state, profile_id, true)?.unwrap();
+    expect(profile_info.name.as_str()).to_equal("")?;
+    expect(profile_info.codec).to_equal(codec)?;
+    expect(profile_info.offline_timeout).to_equal(300)?;
+    expect(profile_info.info).to_equal(Map::<String, Value>::new())
+}
+
+fn test_patch_status(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    profile_id: &str,
+    param: Option<&request::PatchProfile>,
+    expect_status: StatusCode,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .patch(format!("/broker/api/v1/profile/{}", profile_id).as_str())
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        )
+        .json(&param);
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(expect_status)?;
+    if expect_status == StatusCode::BAD_REQUEST {
+        let body: ApiError = resp.json();
+        if body.code.as_str() != err::E_PARAM {
+            return Err(format!("unexpected 400 error: {}", body.code.as_str()));
+        }
+    }
+    Ok(())
+}
+
+fn test_delete(
+    runtime: &Runtime,
+    state: &routes::State,
+    token: &str,
+    profile_id: &str,
+) -> Result<(), String> {
+    let app = Router::new().merge(routes::new_service(state));
+    let server = TestServer::new(app);
+
+    let req = server
+        .delete(format!("/broker/api/v1/profile/{}", profile_id).as_str())
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", token).as_str()).unwrap(),
+        );
+    let resp = runtime.block_on(async { req.await });
+    expect(resp.status_code()).to_equal(StatusCode::NO_CONTENT)
+}
+
+/// Creates 4 profiles: manager (1), owner1 (2), owner2 (1).
+fn count_list_dataset(runtime: &Runtime, state: &routes::State) -> Result<(), String> {
+    add_unit_model(runtime, state, "manager", vec!["member"], "manager")?;
+    add_unit_model(runtime, state, "owner1", vec![], "owner")?;
+    add_unit_model(runtime, state, "owner2", vec!["member"], "owner")?;
+    add_profile_model(runtime, state, "manager", "manager")?;
+    add_profile_model(runtime, state, "owner1", "owner1-1")?;
+    add_profile_model(runtime, state, "owner1", "owner1-2")?;
+    add_profile_model(runtime, state, "owner2", "owner2-1")
+}
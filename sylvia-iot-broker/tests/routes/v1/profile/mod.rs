@@ -0,0 +1,3 @@
+pub mod api;
+pub(super) mod request;
+pub(super) mod response;
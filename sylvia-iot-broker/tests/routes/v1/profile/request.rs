@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+#[derive(Debug, Serialize)]
+pub struct PostProfile {
+    pub data: PostProfileData,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostProfileData {
+    pub code: String,
+    #[serde(rename = "unitId")]
+    pub unit_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec: Option<Map<String, Value>>,
+    #[serde(rename = "offlineTimeout", skip_serializing_if = "Option::is_none")]
+    pub offline_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<Map<String, Value>>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GetProfileCount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct GetProfileList {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PatchProfile {
+    pub data: PatchProfileData,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PatchProfileData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub codec: Option<Map<String, Value>>,
+    #[serde(rename = "offlineTimeout", skip_serializing_if = "Option::is_none")]
+    pub offline_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub info: Option<Map<String, Value>>,
+}
@@ -0,0 +1,53 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Deserialize)]
+pub struct PostProfile {
+    pub data: PostProfileData,
+}
+
+#[derive(Deserialize)]
+pub struct PostProfileData {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+}
+
+#[derive(Deserialize)]
+pub struct GetProfileCount {
+    pub data: GetProfileCountData,
+}
+
+#[derive(Deserialize)]
+pub struct GetProfileCountData {
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetProfileList {
+    pub data: Vec<GetProfileListData>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetProfileListData {
+    #[serde(rename = "profileId")]
+    pub profile_id: String,
+    pub code: String,
+    #[serde(rename = "unitId")]
+    pub unit_id: String,
+    #[serde(rename = "unitCode")]
+    pub unit_code: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: String,
+    pub name: String,
+    pub codec: Map<String, Value>,
+    #[serde(rename = "offlineTimeout")]
+    pub offline_timeout: u64,
+    pub info: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetProfile {
+    pub data: GetProfileListData,
+}
@@ -13,8 +13,8 @@ use serde_json::{Map, Value};
 use tokio::time;
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MqttConnection, MqttConnectionOptions,
-    MqttQueueOptions, Queue, QueueOptions,
+    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MemoryQueueOptions, MqttConnection,
+    MqttConnectionOptions, MqttQueueOptions, Queue, QueueOptions, RedisQueueOptions,
     connection::GmqConnection,
     queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
 };
@@ -1863,6 +1863,116 @@ fn new_data_queues(
             };
             dldata_result = Queue::new(dldata_result_opts)?;
         }
+        Connection::Memory(conn, _) => {
+            let uldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    is_recv: is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Queue::new(uldata_opts)?;
+            dldata = Queue::new(dldata_opts)?;
+            dldata_resp = match is_network {
+                false => Some(Queue::new(dldata_resp_opts)?),
+                true => None,
+            };
+            dldata_result = Queue::new(dldata_result_opts)?;
+        }
+        Connection::Redis(conn, _) => {
+            let prefetch = match opts.prefetch {
+                None => 100,
+                Some(prefetch) => match prefetch {
+                    0 => 100,
+                    _ => prefetch,
+                },
+            };
+
+            let uldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    is_recv: is_network,
+                    reliable: true,
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Queue::new(uldata_opts)?;
+            dldata = Queue::new(dldata_opts)?;
+            dldata_resp = match is_network {
+                false => Some(Queue::new(dldata_resp_opts)?),
+                true => None,
+            };
+            dldata_result = Queue::new(dldata_result_opts)?;
+        }
     }
 
     let routing_queues = state.routing_queues.as_mut().unwrap();
@@ -8,6 +8,7 @@ pub struct MqEngine;
 impl CacheEngine {
     pub const NONE: &'static str = "none";
     pub const MEMORY: &'static str = "memory";
+    pub const REDIS: &'static str = "redis";
 }
 
 impl ContentType {
@@ -17,6 +18,7 @@ impl ContentType {
 
 impl DbEngine {
     pub const MONGODB: &'static str = "mongodb";
+    pub const POSTGRESQL: &'static str = "postgresql";
     pub const SQLITE: &'static str = "sqlite";
 }
 
@@ -1,6 +1,9 @@
 use std::{error::Error as StdError, fs};
 
-use clap::{Arg, ArgMatches, Command, builder::RangedU64ValueParser};
+use clap::{
+    Arg, ArgMatches, Command,
+    builder::{BoolValueParser, RangedU64ValueParser},
+};
 use reqwest::{Client, Method, StatusCode, header};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -89,6 +92,8 @@ struct GetCountReq<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     profile: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    online: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     contains: Option<&'a str>,
 }
 
@@ -121,6 +126,8 @@ struct GetListReq<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     profile: Option<&'a str>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    online: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     contains: Option<&'a str>,
 }
 
@@ -174,6 +181,15 @@ struct GetResData {
     profile: String,
     name: String,
     info: Map<String, Value>,
+    #[serde(rename = "lastUplinkAt")]
+    last_uplink_at: Option<String>,
+    #[serde(rename = "lastDownlinkAt")]
+    last_downlink_at: Option<String>,
+    #[serde(rename = "lastExtension")]
+    last_extension: Option<Map<String, Value>>,
+    #[serde(rename = "offlineAt")]
+    offline_at: Option<String>,
+    online: Option<bool>,
 }
 
 pub fn reg_args(cmd: Command) -> Command {
@@ -329,6 +345,13 @@ pub fn reg_args(cmd: Command) -> Command {
                         .help("Device profile")
                         .num_args(1),
                 )
+                .arg(
+                    Arg::new("online")
+                        .long("online")
+                        .help("Online (true) or offline (false) devices")
+                        .num_args(1)
+                        .value_parser(BoolValueParser::new()),
+                )
                 .arg(
                     Arg::new("contains")
                         .long("contains")
@@ -406,6 +429,13 @@ pub fn reg_args(cmd: Command) -> Command {
                         .help("Device profile")
                         .num_args(1),
                 )
+                .arg(
+                    Arg::new("online")
+                        .long("online")
+                        .help("Online (true) or offline (false) devices")
+                        .num_args(1)
+                        .value_parser(BoolValueParser::new()),
+                )
                 .arg(
                     Arg::new("contains")
                         .long("contains")
@@ -836,6 +866,10 @@ async fn count(config: &Config, args: &ArgMatches) -> Result<GetCountResData, Er
             None => None,
             Some(v) => Some(v.as_str()),
         },
+        online: match args.get_one::<bool>("online") {
+            None => None,
+            Some(v) => Some(*v),
+        },
         contains: match args.get_one::<String>("contains") {
             None => None,
             Some(v) => Some(v.as_str()),
@@ -943,6 +977,10 @@ async fn list(config: &Config, args: &ArgMatches) -> Result<Vec<GetResData>, Err
             None => None,
             Some(v) => Some(v.as_str()),
         },
+        online: match args.get_one::<bool>("online") {
+            None => None,
+            Some(v) => Some(*v),
+        },
         contains: match args.get_one::<String>("contains") {
             None => None,
             Some(v) => Some(v.as_str()),
@@ -23,6 +23,7 @@ mod dldata_buffer;
 mod login;
 mod network;
 mod network_route;
+mod profile;
 mod unit;
 mod user;
 
@@ -78,6 +79,7 @@ pub fn reg_args(cmd: Command) -> Command {
         .subcommand(device_route::reg_args(Command::new("device-route")))
         .subcommand(network_route::reg_args(Command::new("network-route")))
         .subcommand(dldata_buffer::reg_args(Command::new("dldata-buffer")))
+        .subcommand(profile::reg_args(Command::new("profile")))
         .subcommand(data_app_uldata::reg_args(Command::new("data.app-uldata")))
         .subcommand(data_app_dldata::reg_args(Command::new("data.app-dldata")))
         .subcommand(data_net_uldata::reg_args(Command::new("data.net-uldata")))
@@ -100,6 +102,7 @@ pub async fn run(conf: &Config, args: &ArgMatches) -> Result<Option<()>, Box<dyn
         Some(("device-route", args)) => device_route::run(conf, args).await,
         Some(("network-route", args)) => network_route::run(conf, args).await,
         Some(("dldata-buffer", args)) => dldata_buffer::run(conf, args).await,
+        Some(("profile", args)) => profile::run(conf, args).await,
         Some(("data.app-uldata", args)) => data_app_uldata::run(conf, args).await,
         Some(("data.app-dldata", args)) => data_app_dldata::run(conf, args).await,
         Some(("data.net-uldata", args)) => data_net_uldata::run(conf, args).await,
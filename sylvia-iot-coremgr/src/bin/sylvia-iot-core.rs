@@ -58,6 +58,9 @@ async fn main() -> std::io::Result<()> {
     };
 
     logger::init(PROJ_NAME, &conf.log);
+    // Install the recorder before creating queues to export queue metrics.
+    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+    general_mq::metrics::register();
     let _rumqttd_handle = {
         let engine = conf.coremgr.mq.as_ref().unwrap().engine.as_ref().unwrap();
         let engine = engine.mqtt.as_ref().unwrap();
@@ -92,7 +95,6 @@ async fn main() -> std::io::Result<()> {
         }
         Ok(state) => state,
     };
-    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
 
     let static_path = match conf.server.static_path.as_ref() {
         None => STATIC_PATH,
@@ -50,6 +50,9 @@ async fn main() -> std::io::Result<()> {
     };
 
     logger::init(PROJ_NAME, &conf.log);
+    // Install the recorder before creating queues to export queue metrics.
+    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+    general_mq::metrics::register();
 
     let coremgr_state = match routes::new_state("/coremgr", &conf.coremgr).await {
         Err(e) => {
@@ -58,7 +61,6 @@ async fn main() -> std::io::Result<()> {
         }
         Ok(state) => state,
     };
-    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
 
     let app = Router::new()
         .merge(routes::new_service(&coremgr_state))
@@ -104,6 +104,10 @@ pub struct MqHost {
 #[derive(Default, Deserialize)]
 pub struct MqChannels {
     pub data: Option<CoremgrData>,
+    /// TLS options of `amqps` and `mqtts` connections.
+    pub tls: Option<MqTls>,
+    /// The reconnect policy of connections and queues.
+    pub reconnect: Option<MqReconnect>,
 }
 
 /// Channel `coremgr.data` configuration object.
@@ -115,6 +119,38 @@ pub struct CoremgrData {
     pub persistent: Option<bool>,
 }
 
+/// Message channels TLS configuration object.
+#[derive(Default, Deserialize)]
+pub struct MqTls {
+    /// CA certificate file path to verify the message broker.
+    #[serde(rename = "cacertFile")]
+    pub cacert_file: Option<String>,
+    /// Client certificate file path for mutual TLS.
+    #[serde(rename = "certFile")]
+    pub cert_file: Option<String>,
+    /// Client private key file path for mutual TLS.
+    #[serde(rename = "keyFile")]
+    pub key_file: Option<String>,
+    /// The server name to verify the message broker certificate.
+    #[serde(rename = "serverName")]
+    pub server_name: Option<String>,
+    /// Skip verifying the message broker certificate. This is insecure and is only for testing.
+    pub insecure: Option<bool>,
+}
+
+/// Message channels reconnect configuration object. Delays are doubled for each failed attempt
+/// with random jitter.
+#[derive(Default, Deserialize)]
+pub struct MqReconnect {
+    /// The delay in milliseconds before the first attempt. None or zero use default value
+    /// **1000**.
+    #[serde(rename = "initialMillis")]
+    pub initial_millis: Option<u64>,
+    /// The maximum delay in milliseconds. None or zero use default value **30000**.
+    #[serde(rename = "maxMillis")]
+    pub max_millis: Option<u64>,
+}
+
 pub const DEF_AUTH: &'static str = "http://localhost:1080/auth";
 pub const DEF_BROKER: &'static str = "http://localhost:2080/broker";
 pub const DEF_ENGINE_AMQP: &'static str = MqEngine::RABBITMQ;
@@ -128,6 +164,9 @@ pub const DEF_RUMQTTD_MQTTS_PORT: u16 = 8883;
 pub const DEF_RUMQTTD_CONSOLE_PORT: u16 = 18083;
 pub const DEF_MQ_CHANNEL_URL: &'static str = "amqp://localhost";
 pub const DEF_MQ_PERSISTENT: bool = false;
+pub const DEF_MQ_TLS_INSECURE: bool = false;
+pub const DEF_MQ_RECONNECT_INITIAL_MILLIS: u64 = 1000;
+pub const DEF_MQ_RECONNECT_MAX_MILLIS: u64 = 30000;
 
 /// To register Clap arguments.
 pub fn reg_args(cmd: Command) -> Command {
@@ -241,6 +280,51 @@ pub fn reg_args(cmd: Command) -> Command {
             .num_args(1)
             .value_parser(BoolValueParser::new()),
     )
+    .arg(
+        Arg::new("coremgr.mq-channels.tls.cacertfile")
+            .long("coremgr.mq-channels.tls.cacertfile")
+            .help("CA certificate file path for `amqps` and `mqtts` channels")
+            .num_args(1),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.tls.certfile")
+            .long("coremgr.mq-channels.tls.certfile")
+            .help("Client certificate file path for `amqps` and `mqtts` channels")
+            .num_args(1),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.tls.keyfile")
+            .long("coremgr.mq-channels.tls.keyfile")
+            .help("Client private key file path for `amqps` and `mqtts` channels")
+            .num_args(1),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.tls.servername")
+            .long("coremgr.mq-channels.tls.servername")
+            .help("Server name to verify certificates for `amqps` and `mqtts` channels")
+            .num_args(1),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.tls.insecure")
+            .long("coremgr.mq-channels.tls.insecure")
+            .help("Skip verifying certificates for `amqps` and `mqtts` channels")
+            .num_args(1)
+            .value_parser(BoolValueParser::new()),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.reconnect.initialmillis")
+            .long("coremgr.mq-channels.reconnect.initialmillis")
+            .help("Delay in milliseconds before the first reconnect attempt of channels")
+            .num_args(1)
+            .value_parser(RangedU64ValueParser::<u64>::new().range(1..)),
+    )
+    .arg(
+        Arg::new("coremgr.mq-channels.reconnect.maxmillis")
+            .long("coremgr.mq-channels.reconnect.maxmillis")
+            .help("Maximum delay in milliseconds of reconnect attempts of channels")
+            .num_args(1)
+            .value_parser(RangedU64ValueParser::<u64>::new().range(1..)),
+    )
 }
 
 /// To read input arguments from command-line arguments and environment variables.
@@ -423,6 +507,61 @@ pub fn read_args(args: &ArgMatches) -> Config {
                     Some(v) => Some(*v as bool),
                 },
             }),
+            tls: Some(MqTls {
+                cacert_file: match args.get_one::<String>("coremgr.mq-channels.tls.cacertfile") {
+                    None => match env::var("COREMGR_MQCHANNELS_TLS_CACERTFILE") {
+                        Err(_) => None,
+                        Ok(v) => Some(v),
+                    },
+                    Some(v) => Some(v.clone()),
+                },
+                cert_file: match args.get_one::<String>("coremgr.mq-channels.tls.certfile") {
+                    None => match env::var("COREMGR_MQCHANNELS_TLS_CERTFILE") {
+                        Err(_) => None,
+                        Ok(v) => Some(v),
+                    },
+                    Some(v) => Some(v.clone()),
+                },
+                key_file: match args.get_one::<String>("coremgr.mq-channels.tls.keyfile") {
+                    None => match env::var("COREMGR_MQCHANNELS_TLS_KEYFILE") {
+                        Err(_) => None,
+                        Ok(v) => Some(v),
+                    },
+                    Some(v) => Some(v.clone()),
+                },
+                server_name: match args.get_one::<String>("coremgr.mq-channels.tls.servername") {
+                    None => match env::var("COREMGR_MQCHANNELS_TLS_SERVERNAME") {
+                        Err(_) => None,
+                        Ok(v) => Some(v),
+                    },
+                    Some(v) => Some(v.clone()),
+                },
+                insecure: match args.get_one::<bool>("coremgr.mq-channels.tls.insecure") {
+                    None => match env::var("COREMGR_MQCHANNELS_TLS_INSECURE") {
+                        Err(_) => None,
+                        Ok(v) => v.parse::<bool>().ok(),
+                    },
+                    Some(v) => Some(*v),
+                },
+            }),
+            reconnect: Some(MqReconnect {
+                initial_millis: match args
+                    .get_one::<u64>("coremgr.mq-channels.reconnect.initialmillis")
+                {
+                    None => match env::var("COREMGR_MQCHANNELS_RECONNECT_INITIALMILLIS") {
+                        Err(_) => None,
+                        Ok(v) => v.parse::<u64>().ok(),
+                    },
+                    Some(v) => Some(*v),
+                },
+                max_millis: match args.get_one::<u64>("coremgr.mq-channels.reconnect.maxmillis") {
+                    None => match env::var("COREMGR_MQCHANNELS_RECONNECT_MAXMILLIS") {
+                        Err(_) => None,
+                        Ok(v) => v.parse::<u64>().ok(),
+                    },
+                    Some(v) => Some(*v),
+                },
+            }),
         }),
     })
 }
@@ -556,7 +695,14 @@ pub fn apply_default(config: &Config) -> Config {
             }),
         },
         mq_channels: match config.mq_channels.as_ref() {
-            None => Some(MqChannels { data: None }),
+            None => Some(MqChannels {
+                data: None,
+                tls: None,
+                reconnect: Some(MqReconnect {
+                    initial_millis: Some(DEF_MQ_RECONNECT_INITIAL_MILLIS),
+                    max_millis: Some(DEF_MQ_RECONNECT_MAX_MILLIS),
+                }),
+            }),
             Some(mq_channels) => Some(MqChannels {
                 data: match mq_channels.data.as_ref() {
                     None => None,
@@ -571,6 +717,35 @@ pub fn apply_default(config: &Config) -> Config {
                         },
                     }),
                 },
+                tls: match mq_channels.tls.as_ref() {
+                    None => None,
+                    Some(tls) => Some(MqTls {
+                        cacert_file: tls.cacert_file.clone(),
+                        cert_file: tls.cert_file.clone(),
+                        key_file: tls.key_file.clone(),
+                        server_name: tls.server_name.clone(),
+                        insecure: match tls.insecure {
+                            None => Some(DEF_MQ_TLS_INSECURE),
+                            Some(insecure) => Some(insecure),
+                        },
+                    }),
+                },
+                reconnect: match mq_channels.reconnect.as_ref() {
+                    None => Some(MqReconnect {
+                        initial_millis: Some(DEF_MQ_RECONNECT_INITIAL_MILLIS),
+                        max_millis: Some(DEF_MQ_RECONNECT_MAX_MILLIS),
+                    }),
+                    Some(reconnect) => Some(MqReconnect {
+                        initial_millis: match reconnect.initial_millis {
+                            None | Some(0) => Some(DEF_MQ_RECONNECT_INITIAL_MILLIS),
+                            Some(initial_millis) => Some(initial_millis),
+                        },
+                        max_millis: match reconnect.max_millis {
+                            None | Some(0) => Some(DEF_MQ_RECONNECT_MAX_MILLIS),
+                            Some(max_millis) => Some(max_millis),
+                        },
+                    }),
+                },
             }),
         },
     }
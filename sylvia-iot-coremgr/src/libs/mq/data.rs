@@ -6,7 +6,8 @@ use std::{
 use url::Url;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, ReconnectPolicy,
+    RedisQueueOptions, TlsOptions,
     queue::{EventHandler, GmqQueue},
 };
 
@@ -19,9 +20,11 @@ pub fn new(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     host_uri: &Url,
     persistent: bool,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     handler: Arc<dyn EventHandler>,
 ) -> Result<Queue, String> {
-    let conn = get_connection(&conn_pool, host_uri)?;
+    let conn = get_connection(&conn_pool, host_uri, tls, reconnect)?;
     let mut queue = match conn {
         Connection::Amqp(conn, counter) => {
             let opts = QueueOptions::Amqp(
@@ -29,6 +32,7 @@ pub fn new(
                     name: QUEUE_NAME.to_string(),
                     is_recv: false,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     persistent,
                     broadcast: false,
                     ..Default::default()
@@ -46,6 +50,40 @@ pub fn new(
                     name: QUEUE_NAME.to_string(),
                     is_recv: false,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
+        Connection::Memory(conn, counter) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: QUEUE_NAME.to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
+        Connection::Redis(conn, counter) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: QUEUE_NAME.to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     broadcast: false,
                     ..Default::default()
                 },
@@ -1,13 +1,14 @@
 //! Wrapper APIs for controlling EMQX.
 //!
-//! - `hostname` of all APIs are host name or IP address of the broker.
+//! - `hostname` of all APIs are host name or IP address of the broker. The first host of a host
+//!   list such as `host1,host2` is used.
 
 use reqwest::{self, Client, Method, StatusCode};
 use serde::{Deserialize, Serialize};
 
 use sylvia_iot_corelib::{err::ErrResp, strings::randomstring};
 
-use super::QueueType;
+use super::{QueueType, mgmt_host};
 
 /// EMQX management information.
 #[derive(Clone)]
@@ -113,7 +114,8 @@ pub async fn post_user(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:18083/api/v5/authentication/{}/users",
-        hostname, AUTH_ID
+        mgmt_host(hostname),
+        AUTH_ID
     );
     let req = match client
         .request(Method::POST, uri)
@@ -157,7 +159,9 @@ pub async fn put_user(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:18083/api/v5/authentication/{}/users/{}",
-        hostname, AUTH_ID, username
+        mgmt_host(hostname),
+        AUTH_ID,
+        username
     );
     let req = match client
         .request(Method::PUT, uri)
@@ -195,7 +199,9 @@ pub async fn delete_user(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:18083/api/v5/authentication/{}/users/{}",
-        hostname, AUTH_ID, username
+        mgmt_host(hostname),
+        AUTH_ID,
+        username
     );
     let req = match client
         .request(Method::DELETE, uri)
@@ -233,7 +239,7 @@ pub async fn post_acl(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:18083/api/v5/authorization/sources/built_in_database/rules/users",
-        hostname
+        mgmt_host(hostname)
     );
     let rules = match q_type {
         QueueType::Application => vec![
@@ -257,6 +263,11 @@ pub async fn post_acl(
                 action: "subscribe",
                 permission: "allow",
             },
+            PostAclRuleItem {
+                topic: format!("broker.{}.device-status", username),
+                action: "subscribe",
+                permission: "allow",
+            },
         ],
         QueueType::Network => vec![
             PostAclRuleItem {
@@ -347,7 +358,8 @@ pub async fn delete_acl(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:18083/api/v5/authorization/sources/built_in_database/rules/users/{}",
-        hostname, username
+        mgmt_host(hostname),
+        username
     );
     let req = match client
         .request(Method::DELETE, uri)
@@ -386,7 +398,7 @@ pub async fn publish_message(
     queue: &str,     // uldata,dldata
     payload: String, // Base64
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:18083/api/v5/publish", hostname);
+    let uri = format!("http://{}:18083/api/v5/publish", mgmt_host(hostname));
     let body = PostPublishBody {
         topic: format!("broker.{}.{}", username, queue),
         clientid: format!("sylvia-{}", randomstring(12)),
@@ -429,10 +441,19 @@ pub async fn post_topic_metrics(
     q_type: QueueType,
     username: &str,
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:18083/api/v5/mqtt/topic_metrics", hostname);
+    let uri = format!(
+        "http://{}:18083/api/v5/mqtt/topic_metrics",
+        mgmt_host(hostname)
+    );
     let q_name_prefix = format!("broker.{}.", username);
     let queues = match q_type {
-        QueueType::Application => vec!["uldata", "dldata", "dldata-resp", "dldata-result"],
+        QueueType::Application => vec![
+            "uldata",
+            "dldata",
+            "dldata-resp",
+            "dldata-result",
+            "device-status",
+        ],
         QueueType::Network => vec!["uldata", "dldata", "dldata-result", "ctrl"],
     };
     for queue in queues {
@@ -498,10 +519,17 @@ pub async fn delete_topic_metrics(
 ) -> Result<(), ErrResp> {
     let uri_prefix = format!(
         "http://{}:18083/api/v5/mqtt/topic_metrics/broker.{}.",
-        hostname, username
+        mgmt_host(hostname),
+        username
     );
     let queues = match q_type {
-        QueueType::Application => vec!["uldata", "dldata", "dldata-resp", "dldata-result"],
+        QueueType::Application => vec![
+            "uldata",
+            "dldata",
+            "dldata-resp",
+            "dldata-result",
+            "device-status",
+        ],
         QueueType::Network => vec!["uldata", "dldata", "dldata-result", "ctrl"],
     };
     for queue in queues {
@@ -542,12 +570,13 @@ pub async fn stats(
     opts: &ManagementOpts,
     hostname: &str,
     username: &str,
-    queue: &str, // uldata,dldata,dldata-resp,dldata-result,ctrl
+    queue: &str, // uldata,dldata,dldata-resp,dldata-result,ctrl,device-status
 ) -> Result<Stats, ErrResp> {
     let queue_name = format!("broker.{}.{}", username, queue);
     let uri = format!(
         "http://{}:18083/api/v5/subscriptions?topic={}",
-        hostname, queue_name
+        mgmt_host(hostname),
+        queue_name
     );
     let req = match client
         .request(Method::GET, uri)
@@ -590,7 +619,8 @@ pub async fn stats(
 
     let uri = format!(
         "http://{}:18083/api/v5/mqtt/topic_metrics/{}",
-        hostname, queue_name
+        mgmt_host(hostname),
+        queue_name
     );
     let req = match client
         .request(Method::GET, uri.as_str())
@@ -7,8 +7,9 @@ use std::{
 use url::Url;
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, MqttConnection, MqttConnectionOptions,
-    connection::GmqConnection,
+    AmqpConnection, AmqpConnectionOptions, MemoryConnection, MemoryConnectionOptions,
+    MqttConnection, MqttConnectionOptions, ReconnectPolicy, RedisConnection,
+    RedisConnectionOptions, TlsOptions, connection::GmqConnection,
 };
 
 pub mod data;
@@ -21,6 +22,8 @@ pub mod rumqttd;
 pub enum Connection {
     Amqp(AmqpConnection, Arc<Mutex<isize>>),
     Mqtt(MqttConnection, Arc<Mutex<isize>>),
+    Memory(MemoryConnection, Arc<Mutex<isize>>),
+    Redis(RedisConnection, Arc<Mutex<isize>>),
 }
 
 /// Broker message queue type.
@@ -45,6 +48,15 @@ pub fn to_username(q_type: QueueType, unit: &str, code: &str) -> String {
     format!("{}.{}.{}", q_type, unit_code(unit), code)
 }
 
+/// To get the host of management APIs. Resources of a cluster can be managed by any node, so the
+/// first host of a host list such as `host1,host2` is used.
+pub fn mgmt_host(hostname: &str) -> &str {
+    match hostname.split_once(',') {
+        None => hostname,
+        Some((host, _)) => host,
+    }
+}
+
 /// Unit code part for queue name.
 fn unit_code(code: &str) -> &str {
     match code {
@@ -54,10 +66,12 @@ fn unit_code(code: &str) -> &str {
 }
 
 /// Utility function to get the message queue connection instance. A new connection will be created
-/// if the host does not exist.
+/// with `tls` and `reconnect` options if the host does not exist.
 fn get_connection(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     host_uri: &Url,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Connection, String> {
     let uri = host_uri.to_string();
     {
@@ -71,6 +85,8 @@ fn get_connection(
         "amqp" | "amqps" => {
             let opts = AmqpConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = AmqpConnection::new(opts)?;
@@ -84,6 +100,8 @@ fn get_connection(
         "mqtt" | "mqtts" => {
             let opts = MqttConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = MqttConnection::new(opts)?;
@@ -94,6 +112,33 @@ fn get_connection(
             }
             Ok(conn)
         }
+        "mem" => {
+            let opts = MemoryConnectionOptions {
+                uri: host_uri.to_string(),
+                ..Default::default()
+            };
+            let mut conn = MemoryConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Memory(conn, Arc::new(Mutex::new(0)));
+            {
+                conn_pool.lock().unwrap().insert(uri, conn.clone());
+            }
+            Ok(conn)
+        }
+        "redis" | "rediss" => {
+            let opts = RedisConnectionOptions {
+                uri: host_uri.to_string(),
+                reconnect_policy: reconnect.cloned(),
+                ..Default::default()
+            };
+            let mut conn = RedisConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Redis(conn, Arc::new(Mutex::new(0)));
+            {
+                conn_pool.lock().unwrap().insert(uri, conn.clone());
+            }
+            Ok(conn)
+        }
         s => Err(format!("unsupport scheme {}", s)),
     }
 }
@@ -1,6 +1,7 @@
 //! Wrapper APIs for controlling RabbitMQ.
 //!
-//! - `hostname` of all APIs are host name or IP address of the broker.
+//! - `hostname` of all APIs are host name or IP address of the broker. The first host of a host
+//!   list such as `host1,host2` is used.
 
 use reqwest::{self, Client, Method, StatusCode};
 use serde::{Deserialize, Serialize};
@@ -8,7 +9,7 @@ use serde_json::{Map, Value};
 
 use sylvia_iot_corelib::err::ErrResp;
 
-use super::QueueType;
+use super::{QueueType, mgmt_host};
 
 /// RabbitMQ management information.
 #[derive(Clone)]
@@ -29,8 +30,14 @@ pub struct BrokerPolicies {
     pub ttl: Option<usize>,
     /// Queue length.
     pub length: Option<usize>,
+    /// The behavior when queues reach the length. Empty for the default behavior.
+    pub overflow: Option<String>,
 }
 
+/// Supported values of the `overflow` policy.
+pub const OVERFLOWS: &'static [&'static str] =
+    &["drop-head", "reject-publish", "reject-publish-dlx"];
+
 /// Statistics.
 #[derive(Default)]
 pub struct Stats {
@@ -71,6 +78,8 @@ struct PoliciesDefinition {
     message_ttl: Option<usize>,
     #[serde(rename = "max-length", skip_serializing_if = "Option::is_none")]
     max_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    overflow: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -107,7 +116,11 @@ pub async fn put_user(
     username: &str,
     password: &str,
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:15672/api/users/{}", hostname, username);
+    let uri = format!(
+        "http://{}:15672/api/users/{}",
+        mgmt_host(hostname),
+        username
+    );
     let req = match client
         .request(Method::PUT, uri)
         .basic_auth(opts.username.as_str(), Some(opts.password.as_str()))
@@ -142,7 +155,11 @@ pub async fn delete_user(
     hostname: &str,
     username: &str,
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:15672/api/users/{}", hostname, username);
+    let uri = format!(
+        "http://{}:15672/api/users/{}",
+        mgmt_host(hostname),
+        username
+    );
     let req = match client
         .request(Method::DELETE, uri)
         .basic_auth(opts.username.as_str(), Some(opts.password.as_str()))
@@ -176,7 +193,11 @@ pub async fn put_vhost(
     hostname: &str,
     username: &str,
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:15672/api/vhosts/{}", hostname, username);
+    let uri = format!(
+        "http://{}:15672/api/vhosts/{}",
+        mgmt_host(hostname),
+        username
+    );
     let req = match client
         .request(Method::PUT, uri)
         .basic_auth(opts.username.as_str(), Some(opts.password.as_str()))
@@ -210,7 +231,11 @@ pub async fn delete_vhost(
     hostname: &str,
     username: &str,
 ) -> Result<(), ErrResp> {
-    let uri = format!("http://{}:15672/api/vhosts/{}", hostname, username);
+    let uri = format!(
+        "http://{}:15672/api/vhosts/{}",
+        mgmt_host(hostname),
+        username
+    );
     let req = match client
         .request(Method::DELETE, uri)
         .basic_auth(opts.username.as_str(), Some(opts.password.as_str()))
@@ -247,11 +272,13 @@ pub async fn put_permissions(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:15672/api/permissions/{}/{}",
-        hostname, username, username
+        mgmt_host(hostname),
+        username,
+        username
     );
     let config_pattern = match q_type {
         QueueType::Application => format!(
-            "^broker.{}.(uldata|dldata|dldata-resp|dldata-result)$",
+            "^broker.{}.(uldata|dldata|dldata-resp|dldata-result|device-status)$",
             username
         )
         .replace(".", "\\."),
@@ -260,9 +287,11 @@ pub async fn put_permissions(
         }
     };
     let read_pattern = match q_type {
-        QueueType::Application => {
-            format!("^broker.{}.(uldata|dldata-resp|dldata-result)$", username).replace(".", "\\.")
-        }
+        QueueType::Application => format!(
+            "^broker.{}.(uldata|dldata-resp|dldata-result|device-status)$",
+            username
+        )
+        .replace(".", "\\."),
         QueueType::Network => format!("^broker.{}.(dldata|ctrl)$", username).replace(".", "\\."),
     };
     let body = PutPermissionsBody {
@@ -297,7 +326,7 @@ pub async fn put_permissions(
     }
 }
 
-/// To get TTL/length policies for the user.
+/// To get TTL/length/overflow policies for the user.
 pub async fn get_policies(
     client: &Client,
     opts: &ManagementOpts,
@@ -306,7 +335,8 @@ pub async fn get_policies(
 ) -> Result<BrokerPolicies, ErrResp> {
     let uri = format!(
         "http://{}:15672/api/policies/{}/sylvia-iot-broker",
-        hostname, username
+        mgmt_host(hostname),
+        username
     );
     let req = match client
         .request(Method::GET, uri)
@@ -330,6 +360,7 @@ pub async fn get_policies(
                 return Ok(BrokerPolicies {
                     ttl: Some(0),
                     length: Some(0),
+                    overflow: Some("".to_string()),
                 });
             }
             _ => {
@@ -352,11 +383,15 @@ pub async fn get_policies(
                 None => Some(0),
                 _ => body.definition.max_length,
             },
+            overflow: match body.definition.overflow {
+                None => Some("".to_string()),
+                _ => body.definition.overflow,
+            },
         }),
     }
 }
 
-/// To update TTL/length policies for the user.
+/// To update TTL/length/overflow policies for the user.
 pub async fn put_policies(
     client: &Client,
     opts: &ManagementOpts,
@@ -366,11 +401,15 @@ pub async fn put_policies(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:15672/api/policies/{}/sylvia-iot-broker",
-        hostname, username
+        mgmt_host(hostname),
+        username
     );
     let is_delete = match policies.ttl {
         None | Some(0) => match policies.length {
-            None | Some(0) => true,
+            None | Some(0) => match policies.overflow.as_deref() {
+                None | Some("") => true,
+                _ => false,
+            },
             _ => false,
         },
         _ => false,
@@ -389,6 +428,10 @@ pub async fn put_policies(
                 Some(0) => None,
                 _ => policies.length,
             },
+            overflow: match policies.overflow.as_deref() {
+                Some("") => None,
+                _ => policies.overflow.clone(),
+            },
         };
         let body = Policies {
             pattern: "^broker.".to_string(),
@@ -439,7 +482,8 @@ pub async fn publish_message(
 ) -> Result<(), ErrResp> {
     let uri = format!(
         "http://{}:15672/api/exchanges/{}/amq.default/publish",
-        hostname, username
+        mgmt_host(hostname),
+        username
     );
     let body = PostExchangesBody {
         properties: Map::<String, Value>::new(),
@@ -480,11 +524,14 @@ pub async fn stats(
     opts: &ManagementOpts,
     hostname: &str,
     username: &str,
-    queue: &str, // uldata,dldata,dldata-resp,dldata-result,ctrl
+    queue: &str, // uldata,dldata,dldata-resp,dldata-result,ctrl,device-status
 ) -> Result<Stats, ErrResp> {
     let uri = format!(
         "http://{}:15672/api/queues/{}/broker.{}.{}?msg_rates_age=60&msg_rates_incr=5",
-        hostname, username, username, queue
+        mgmt_host(hostname),
+        username,
+        username,
+        queue
     );
     let req = match client
         .request(Method::GET, uri)
@@ -12,7 +12,7 @@ use reqwest;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 use sylvia_iot_corelib::constants::MqEngine;
@@ -140,11 +140,18 @@ pub async fn new_state(
     };
     let mq_conns = Arc::new(Mutex::new(HashMap::new()));
     let ch_conf = conf.mq_channels.as_ref().unwrap();
+    let mq_tls = new_mq_tls(ch_conf);
+    let mq_reconnect = new_mq_reconnect(ch_conf);
     let data_sender = match ch_conf.data.as_ref() {
         None => None,
         Some(conf) => match conf.url.as_ref() {
             None => None,
-            Some(_) => Some(new_data_sender(&mq_conns, conf)?),
+            Some(_) => Some(new_data_sender(
+                &mq_conns,
+                conf,
+                mq_tls.as_ref(),
+                mq_reconnect.as_ref(),
+            )?),
         },
     };
     let state = State {
@@ -185,6 +192,7 @@ pub fn new_service(state: &State) -> Router {
                 "/api/v1/dldata-buffer",
                 state,
             ))
+            .merge(v1::profile::new_service("/api/v1/profile", state))
             .layer(middleware::LogService::new(
                 state.client.clone(),
                 auth_uri,
@@ -193,10 +201,39 @@ pub fn new_service(state: &State) -> Router {
     )
 }
 
+/// To create TLS options of message channels. Returns `None` if no TLS option is set.
+fn new_mq_tls(ch_conf: &config::MqChannels) -> Option<TlsOptions> {
+    let tls = ch_conf.tls.as_ref()?;
+    TlsOptions::from_config(
+        tls.cacert_file.clone(),
+        tls.cert_file.clone(),
+        tls.key_file.clone(),
+        tls.server_name.clone(),
+        tls.insecure.unwrap_or(config::DEF_MQ_TLS_INSECURE),
+    )
+}
+
+/// To create the reconnect policy with jitter of message channels. Returns `None` to reconnect with
+/// fixed delays if no reconnect option is set.
+fn new_mq_reconnect(ch_conf: &config::MqChannels) -> Option<ReconnectPolicy> {
+    let reconnect = ch_conf.reconnect.as_ref()?;
+    Some(ReconnectPolicy {
+        initial_millis: reconnect
+            .initial_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_INITIAL_MILLIS),
+        max_millis: reconnect
+            .max_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_MAX_MILLIS),
+        ..Default::default()
+    })
+}
+
 /// Create data channel sender queue.
 fn new_data_sender(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     config: &config::CoremgrData,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Queue, Box<dyn StdError>> {
     let url = match config.url.as_ref() {
         None => {
@@ -215,7 +252,14 @@ fn new_data_sender(
         Some(persistent) => persistent,
     };
 
-    match mq::data::new(conn_pool, &url, persistent, Arc::new(DataSenderHandler {})) {
+    match mq::data::new(
+        conn_pool,
+        &url,
+        persistent,
+        tls,
+        reconnect,
+        Arc::new(DataSenderHandler {}),
+    ) {
         Err(e) => Err(Box::new(IoError::new(ErrorKind::InvalidInput, e))),
         Ok(q) => Ok(q),
     }
@@ -93,6 +93,8 @@ struct DlData {
     network_code: Option<String>,
     #[serde(rename = "networkAddr")]
     network_addr: Option<String>,
+    #[serde(rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    expires_in: Option<i64>,
     data: String,
     extension: Option<Map<String, Value>>,
 }
@@ -169,6 +171,12 @@ async fn post_application(
         ))
         .into_response();
     }
+    if let Some(overflow) = body.data.overflow.as_ref()
+        && overflow.len() > 0
+        && !rabbitmq::OVERFLOWS.contains(&overflow.as_str())
+    {
+        return ErrResp::ErrParam(Some("invalid `overflow`".to_string())).into_response();
+    }
     match check_application_code_inner(FN_NAME, &client, broker_base, unit_id, code, &token).await {
         Err(e) => return e,
         Ok(count) => match count {
@@ -212,6 +220,7 @@ async fn post_application(
         password,
         ttl: body.data.ttl,
         length: body.data.length,
+        overflow: body.data.overflow.clone(),
         q_type: QueueType::Application,
     };
     if let Err(e) = create_queue_rsc(FN_NAME, &state, &create_rsc).await {
@@ -499,6 +508,7 @@ async fn get_application(
             Ok(policies) => {
                 application.ttl = policies.ttl;
                 application.length = policies.length;
+                application.overflow = policies.overflow;
             }
         }
     }
@@ -530,10 +540,17 @@ async fn patch_application(
         && data.info.is_none()
         && data.ttl.is_none()
         && data.length.is_none()
+        && data.overflow.is_none()
         && data.password.is_none()
     {
         return ErrResp::ErrParam(Some("at least one parameter".to_string())).into_response();
     }
+    if let Some(overflow) = data.overflow.as_ref()
+        && overflow.len() > 0
+        && !rabbitmq::OVERFLOWS.contains(&overflow.as_str())
+    {
+        return ErrResp::ErrParam(Some("invalid `overflow`".to_string())).into_response();
+    }
 
     let (application, uri, hostname) = match get_application_inner(
         FN_NAME,
@@ -597,6 +614,7 @@ async fn patch_application(
                 password: password.as_str(),
                 ttl: data.ttl,
                 length: data.length,
+                overflow: data.overflow.clone(),
                 q_type: QueueType::Application,
             };
             if let Err(e) = create_queue_rsc(FN_NAME, &state, &resource).await {
@@ -669,11 +687,17 @@ async fn patch_application(
         };
         let _ = clear_queue_rsc(FN_NAME, &state, &resource).await;
         return StatusCode::NO_CONTENT.into_response();
-    } else if data.ttl.is_none() && data.length.is_none() && data.password.is_none() {
+    } else if data.info.is_none()
+        && data.ttl.is_none()
+        && data.length.is_none()
+        && data.overflow.is_none()
+        && data.password.is_none()
+    {
         return StatusCode::NO_CONTENT.into_response();
     }
 
-    // Update broker information without changing hostUri.
+    // Update broker information without changing hostUri. Permissions are re-synchronized when
+    // `info` is patched so that existing applications can consume queues such as `device-status`.
     if let Some(password) = data.password.as_ref() {
         if password.len() == 0 {
             let e = "missing `password`".to_string();
@@ -688,10 +712,21 @@ async fn patch_application(
     match uri.scheme() {
         "amqp" | "amqps" => match &state.amqp {
             AmqpState::RabbitMq(opts) => {
-                if data.ttl.is_some() || data.length.is_some() {
+                if data.info.is_some() {
+                    let q_type = QueueType::Application;
+                    if let Err(e) =
+                        rabbitmq::put_permissions(&client, opts, hostname, q_type, username).await
+                    {
+                        let e = format!("patch RabbitMQ permission error: {}", e);
+                        error!("[{}] {}", FN_NAME, e);
+                        return ErrResp::ErrIntMsg(Some(e)).into_response();
+                    }
+                }
+                if data.ttl.is_some() || data.length.is_some() || data.overflow.is_some() {
                     let policies = rabbitmq::BrokerPolicies {
                         ttl: data.ttl,
                         length: data.length,
+                        overflow: data.overflow.clone(),
                     };
                     if let Err(e) =
                         rabbitmq::put_policies(&client, opts, hostname, username, &policies).await
@@ -715,6 +750,15 @@ async fn patch_application(
         },
         "mqtt" | "mqtts" => match &state.mqtt {
             MqttState::Emqx(opts) => {
+                if data.info.is_some() {
+                    let q_type = QueueType::Application;
+                    if let Err(e) = emqx::post_acl(&client, opts, hostname, q_type, username).await
+                    {
+                        let e = format!("patch EMQX ACL error: {}", e);
+                        error!("[{}] {}", FN_NAME, e);
+                        return ErrResp::ErrIntMsg(Some(e)).into_response();
+                    }
+                }
                 if let Some(password) = data.password.as_ref() {
                     let password = password.as_str();
                     if let Err(e) =
@@ -980,6 +1024,12 @@ async fn post_application_dldata(
         let e = format!("`payload` is not hexadecimal string: {}", e);
         return ErrResp::ErrParam(Some(e)).into_response();
     }
+    if let Some(expires_in) = body.data.expires_in {
+        if expires_in <= 0 {
+            let e = "`expiresIn` must be a positive integer".to_string();
+            return ErrResp::ErrParam(Some(e)).into_response();
+        }
+    }
 
     let (application, uri, hostname) = match get_application_inner(
         FN_NAME,
@@ -1021,6 +1071,7 @@ async fn post_application_dldata(
     let payload = match serde_json::to_string(&DlData {
         correlation_id: "1".to_string(),
         device_id: Some(body.data.device_id.clone()),
+        expires_in: body.data.expires_in,
         data: body.data.payload.clone(),
         ..Default::default()
     }) {
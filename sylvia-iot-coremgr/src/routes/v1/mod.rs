@@ -22,6 +22,7 @@ pub mod device_route;
 pub mod dldata_buffer;
 pub mod network;
 pub mod network_route;
+pub mod profile;
 mod request;
 mod response;
 pub mod unit;
@@ -42,6 +43,7 @@ struct CreateQueueResource<'a> {
     password: &'a str,
     ttl: Option<usize>,
     length: Option<usize>,
+    overflow: Option<String>,
     q_type: QueueType,
 }
 
@@ -344,6 +346,8 @@ fn cmp_host_uri(src: &str, dst: &str) -> bool {
             "amqps" => 5671,
             "mqtt" => 1883,
             "mqtts" => 8883,
+            "mem" => 0,
+            "redis" | "rediss" => 6379,
             _ => return false,
         },
         Some(port) => port,
@@ -354,6 +358,8 @@ fn cmp_host_uri(src: &str, dst: &str) -> bool {
             "amqps" => 5671,
             "mqtt" => 1883,
             "mqtts" => 8883,
+            "mem" => 0,
+            "redis" | "rediss" => 6379,
             _ => return false,
         },
         Some(port) => port,
@@ -400,10 +406,11 @@ async fn create_queue_rsc<'a>(
                     );
                     return Err(e.into_response());
                 }
-                if rsc.ttl.is_some() && rsc.length.is_some() {
+                if (rsc.ttl.is_some() && rsc.length.is_some()) || rsc.overflow.is_some() {
                     let policies = rabbitmq::BrokerPolicies {
                         ttl: rsc.ttl,
                         length: rsc.length,
+                        overflow: rsc.overflow.clone(),
                     };
                     if let Err(e) =
                         rabbitmq::put_policies(&client, opts, host, username, &policies).await
@@ -461,6 +468,10 @@ async fn create_queue_rsc<'a>(
             }
             MqttState::Rumqttd => {}
         },
+        // The in-process memory broker has no users or ACLs to manage.
+        "mem" => {}
+        // Redis streams are created by queues and use the credentials of the host URI.
+        "redis" | "rediss" => {}
         _ => return Err(ErrResp::ErrParam(Some("unsupport scheme".to_string())).into_response()),
     }
     Ok(())
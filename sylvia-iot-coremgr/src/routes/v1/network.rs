@@ -187,6 +187,12 @@ async fn post_network(
         None => "",
         Some(unit_id) => unit_id.as_str(),
     };
+    if let Some(overflow) = body.data.overflow.as_ref()
+        && overflow.len() > 0
+        && !rabbitmq::OVERFLOWS.contains(&overflow.as_str())
+    {
+        return ErrResp::ErrParam(Some("invalid `overflow`".to_string())).into_response();
+    }
     match check_network_code_inner(FN_NAME, &client, broker_base, unit_id, code, &token).await {
         Err(e) => return e,
         Ok(count) => match count {
@@ -226,6 +232,7 @@ async fn post_network(
         password,
         ttl: body.data.ttl,
         length: body.data.length,
+        overflow: body.data.overflow.clone(),
         q_type: QueueType::Network,
     };
     if let Err(e) = create_queue_rsc(FN_NAME, &state, &create_rsc).await {
@@ -513,6 +520,7 @@ async fn get_network(
             Ok(policies) => {
                 network.ttl = policies.ttl;
                 network.length = policies.length;
+                network.overflow = policies.overflow;
             }
         }
     }
@@ -544,10 +552,17 @@ async fn patch_network(
         && data.info.is_none()
         && data.ttl.is_none()
         && data.length.is_none()
+        && data.overflow.is_none()
         && data.password.is_none()
     {
         return ErrResp::ErrParam(Some("at least one parameter".to_string())).into_response();
     }
+    if let Some(overflow) = data.overflow.as_ref()
+        && overflow.len() > 0
+        && !rabbitmq::OVERFLOWS.contains(&overflow.as_str())
+    {
+        return ErrResp::ErrParam(Some("invalid `overflow`".to_string())).into_response();
+    }
 
     let (network, uri, hostname) = match get_network_inner(
         FN_NAME,
@@ -614,6 +629,7 @@ async fn patch_network(
                 password: password.as_str(),
                 ttl: data.ttl,
                 length: data.length,
+                overflow: data.overflow.clone(),
                 q_type: QueueType::Network,
             };
             if let Err(e) = create_queue_rsc(FN_NAME, &state, &resource).await {
@@ -693,7 +709,11 @@ async fn patch_network(
         };
         let _ = clear_queue_rsc(FN_NAME, &state, &resource).await;
         return StatusCode::NO_CONTENT.into_response();
-    } else if data.ttl.is_none() && data.length.is_none() && data.password.is_none() {
+    } else if data.ttl.is_none()
+        && data.length.is_none()
+        && data.overflow.is_none()
+        && data.password.is_none()
+    {
         return StatusCode::NO_CONTENT.into_response();
     }
 
@@ -715,10 +735,11 @@ async fn patch_network(
     match uri.scheme() {
         "amqp" | "amqps" => match &state.amqp {
             AmqpState::RabbitMq(opts) => {
-                if data.ttl.is_some() || data.length.is_some() {
+                if data.ttl.is_some() || data.length.is_some() || data.overflow.is_some() {
                     let policies = rabbitmq::BrokerPolicies {
                         ttl: data.ttl,
                         length: data.length,
+                        overflow: data.overflow.clone(),
                     };
                     if let Err(e) =
                         rabbitmq::put_policies(&client, opts, hostname, username, &policies).await
@@ -0,0 +1,218 @@
+use std::error::Error as StdError;
+
+use axum::{
+    Router,
+    body::Body,
+    extract::{Path, Request, State},
+    response::IntoResponse,
+    routing,
+};
+use bytes::{Bytes, BytesMut};
+use csv::WriterBuilder;
+use futures_util::StreamExt;
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::{Deserializer, Map, Value};
+
+use sylvia_iot_corelib::err::ErrResp;
+
+use super::{super::State as AppState, ListResp, api_bridge, list_api_bridge};
+
+#[derive(Deserialize)]
+struct ProfileIdPath {
+    profile_id: String,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Profile {
+    #[serde(rename = "profileId")]
+    profile_id: String,
+    code: String,
+    #[serde(rename = "unitId")]
+    unit_id: String,
+    #[serde(rename = "unitCode")]
+    unit_code: String,
+    #[serde(rename = "createdAt")]
+    created_at: String,
+    #[serde(rename = "modifiedAt")]
+    modified_at: String,
+    name: String,
+    #[serde(skip_serializing)]
+    codec: Map<String, Value>,
+    #[serde(rename(serialize = "codec"))]
+    codec_str: Option<String>,
+    #[serde(rename = "offlineTimeout")]
+    offline_timeout: u64,
+    #[serde(skip_serializing)]
+    info: Map<String, Value>,
+    #[serde(rename(serialize = "info"))]
+    info_str: Option<String>,
+}
+
+const CSV_FIELDS: &'static [u8] =
+    b"\xEF\xBB\xBFprofileId,code,unitId,unitCode,createdAt,modifiedAt,name,codec,offlineTimeout,info\n";
+
+pub fn new_service(scope_path: &str, state: &AppState) -> Router {
+    Router::new().nest(
+        scope_path,
+        Router::new()
+            .route("/", routing::post(post_profile))
+            .route("/count", routing::get(get_profile_count))
+            .route("/list", routing::get(get_profile_list))
+            .route(
+                "/{profile_id}",
+                routing::get(get_profile)
+                    .patch(patch_profile)
+                    .delete(delete_profile),
+            )
+            .with_state(state.clone()),
+    )
+}
+
+/// `POST /{base}/api/v1/profile`
+async fn post_profile(state: State<AppState>, req: Request) -> impl IntoResponse {
+    const FN_NAME: &'static str = "post_profile";
+    let api_path = format!("{}/api/v1/profile", state.broker_base);
+    let client = state.client.clone();
+
+    api_bridge(FN_NAME, &client, req, api_path.as_str()).await
+}
+
+/// `GET /{base}/api/v1/profile/count`
+async fn get_profile_count(state: State<AppState>, req: Request) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile_count";
+    let api_path = format!("{}/api/v1/profile/count", state.broker_base.as_str());
+    let client = state.client.clone();
+
+    api_bridge(FN_NAME, &client, req, api_path.as_str()).await
+}
+
+/// `GET /{base}/api/v1/profile/list`
+async fn get_profile_list(state: State<AppState>, req: Request) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile_list";
+    let api_path = format!("{}/api/v1/profile/list", state.broker_base.as_str());
+    let api_path = api_path.as_str();
+    let client = state.client.clone();
+
+    let (api_resp, resp_builder) =
+        match list_api_bridge(FN_NAME, &client, req, api_path, false, "profile").await {
+            ListResp::Axum(resp) => return resp,
+            ListResp::ArrayStream(api_resp, resp_builder) => (api_resp, resp_builder),
+        };
+
+    let mut resp_stream = api_resp.bytes_stream();
+    let body = Body::from_stream(async_stream::stream! {
+        yield Ok(Bytes::from(CSV_FIELDS));
+
+        let mut buffer = BytesMut::new();
+        while let Some(body) = resp_stream.next().await {
+            match body {
+                Err(e) => {
+                    error!("[{}] get body error: {}", FN_NAME, e);
+                    let err: Box<dyn StdError + Send + Sync> = Box::new(e);
+                    yield Err(err);
+                    break;
+                }
+                Ok(body) => buffer.extend_from_slice(&body[..]),
+            }
+
+            let mut json_stream = Deserializer::from_slice(&buffer[..]).into_iter::<Profile>();
+            let mut index = 0;
+            let mut finish = false;
+            loop {
+                if let Some(Ok(mut v)) = json_stream.next() {
+                    if let Ok(codec_str) = serde_json::to_string(&v.codec) {
+                        v.codec_str = Some(codec_str);
+                    }
+                    if let Ok(info_str) = serde_json::to_string(&v.info) {
+                        v.info_str = Some(info_str);
+                    }
+                    let mut writer = WriterBuilder::new().has_headers(false).from_writer(vec![]);
+                    if let Err(e) = writer.serialize(v) {
+                        let err: Box<dyn StdError + Send + Sync> = Box::new(e);
+                        yield Err(err);
+                        finish = true;
+                        break;
+                    }
+                    match writer.into_inner() {
+                        Err(e) => {
+                            let err: Box<dyn StdError + Send + Sync> = Box::new(e);
+                            yield Err(err);
+                            finish = true;
+                            break;
+                        }
+                        Ok(row) => yield Ok(Bytes::copy_from_slice(row.as_slice())),
+                    }
+                    continue;
+                }
+                let offset = json_stream.byte_offset();
+                if buffer.len() <= index + offset {
+                    index = buffer.len();
+                    break;
+                }
+                match buffer[index+offset] {
+                    b'[' | b',' => {
+                        index += offset + 1;
+                        if buffer.len() <= index {
+                            break;
+                        }
+                        json_stream =
+                            Deserializer::from_slice(&buffer[index..]).into_iter::<Profile>();
+                    }
+                    b']' => {
+                        finish = true;
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+            if finish {
+                break;
+            }
+            buffer = buffer.split_off(index);
+        }
+    });
+    match resp_builder.body(body) {
+        Err(e) => ErrResp::ErrRsc(Some(e.to_string())).into_response(),
+        Ok(resp) => resp,
+    }
+}
+
+/// `GET /{base}/api/v1/profile/{profileId}`
+async fn get_profile(
+    state: State<AppState>,
+    Path(param): Path<ProfileIdPath>,
+    req: Request,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "get_profile";
+    let api_path = format!("{}/api/v1/profile/{}", state.broker_base, param.profile_id);
+    let client = state.client.clone();
+
+    api_bridge(FN_NAME, &client, req, api_path.as_str()).await
+}
+
+/// `PATCH /{base}/api/v1/profile/{profileId}`
+async fn patch_profile(
+    state: State<AppState>,
+    Path(param): Path<ProfileIdPath>,
+    req: Request,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "patch_profile";
+    let api_path = format!("{}/api/v1/profile/{}", state.broker_base, param.profile_id);
+    let client = state.client.clone();
+
+    api_bridge(FN_NAME, &client, req, api_path.as_str()).await
+}
+
+/// `DELETE /{base}/api/v1/profile/{profileId}`
+async fn delete_profile(
+    state: State<AppState>,
+    Path(param): Path<ProfileIdPath>,
+    req: Request,
+) -> impl IntoResponse {
+    const FN_NAME: &'static str = "delete_profile";
+    let api_path = format!("{}/api/v1/profile/{}", state.broker_base, param.profile_id);
+    let client = state.client.clone();
+
+    api_bridge(FN_NAME, &client, req, api_path.as_str()).await
+}
@@ -19,6 +19,8 @@ pub struct PostApplicationData {
     pub ttl: Option<usize>,
     #[serde(skip_serializing)]
     pub length: Option<usize>,
+    #[serde(skip_serializing)]
+    pub overflow: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -39,6 +41,8 @@ pub struct PatchApplicationData {
     #[serde(skip_serializing)]
     pub length: Option<usize>,
     #[serde(skip_serializing)]
+    pub overflow: Option<String>,
+    #[serde(skip_serializing)]
     pub password: Option<String>,
 }
 
@@ -52,6 +56,8 @@ pub struct PostApplicationDlData {
     #[serde(rename = "deviceId")]
     pub device_id: String,
     pub payload: String,
+    #[serde(rename = "expiresIn")]
+    pub expires_in: Option<i64>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -72,6 +78,8 @@ pub struct PostNetworkData {
     pub ttl: Option<usize>,
     #[serde(skip_serializing)]
     pub length: Option<usize>,
+    #[serde(skip_serializing)]
+    pub overflow: Option<String>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -92,6 +100,8 @@ pub struct PatchNetworkData {
     #[serde(skip_serializing)]
     pub length: Option<usize>,
     #[serde(skip_serializing)]
+    pub overflow: Option<String>,
+    #[serde(skip_serializing)]
     pub password: Option<String>,
 }
 
@@ -99,6 +99,8 @@ pub struct GetApplicationData {
     pub ttl: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -153,6 +155,8 @@ pub struct GetNetworkData {
     pub ttl: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<String>,
 }
 
 #[derive(Serialize)]
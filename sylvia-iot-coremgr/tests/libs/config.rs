@@ -55,6 +55,16 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.is_none()).to_equal(true)?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.cert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.key_file.is_none()).to_equal(true)?;
+    expect(tls_conf.server_name.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     // Modified default by command-line arguments.
     let args = vec![
@@ -93,6 +103,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "url1",
         "--coremgr.mq-channels.data.persistent",
         "false",
+        "--coremgr.mq-channels.tls.cacertfile",
+        "cacert1",
+        "--coremgr.mq-channels.tls.certfile",
+        "cert1",
+        "--coremgr.mq-channels.tls.keyfile",
+        "key1",
+        "--coremgr.mq-channels.tls.servername",
+        "server1",
+        "--coremgr.mq-channels.tls.insecure",
+        "true",
+        "--coremgr.mq-channels.reconnect.initialmillis",
+        "100",
+        "--coremgr.mq-channels.reconnect.maxmillis",
+        "10000",
     ];
     let args = config::reg_args(Command::new("test")).get_matches_from(args);
     let conf = config::read_args(&args);
@@ -145,6 +169,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url1")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert1"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert1"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key1"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server1"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(100))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(10000))?;
 
     let args = vec![
         "test",
@@ -214,6 +247,13 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("COREMGR_MQ_RUMQTTD_CONSOLE_PORT", "123");
     set_env_var("COREMGR_MQCHANNELS_DATA_URL", "url2");
     set_env_var("COREMGR_MQCHANNELS_DATA_PERSISTENT", "false");
+    set_env_var("COREMGR_MQCHANNELS_TLS_CACERTFILE", "cacert2");
+    set_env_var("COREMGR_MQCHANNELS_TLS_CERTFILE", "cert2");
+    set_env_var("COREMGR_MQCHANNELS_TLS_KEYFILE", "key2");
+    set_env_var("COREMGR_MQCHANNELS_TLS_SERVERNAME", "server2");
+    set_env_var("COREMGR_MQCHANNELS_TLS_INSECURE", "true");
+    set_env_var("COREMGR_MQCHANNELS_RECONNECT_INITIALMILLIS", "200");
+    set_env_var("COREMGR_MQCHANNELS_RECONNECT_MAXMILLIS", "20000");
     let conf = config::read_args(&args);
     expect(conf.auth.is_some()).to_equal(true)?;
     expect(conf.auth.as_ref().unwrap().as_str()).to_equal("sylvia21")?;
@@ -266,6 +306,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url2")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert2"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert2"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key2"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server2"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(200))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(20000))?;
 
     set_env_var("COREMGR_MQ_RABBITMQ_HOSTS", "");
     set_env_var("COREMGR_MQ_EMQX_HOSTS", "");
@@ -288,6 +337,9 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("COREMGR_MQ_RUMQTTD_MQTTS_PORT", "12_000");
     set_env_var("COREMGR_MQ_RUMQTTD_CONSOLE_PORT", "12_000");
     set_env_var("COREMGR_MQCHANNELS_DATA_PERSISTENT", "0");
+    set_env_var("COREMGR_MQCHANNELS_TLS_INSECURE", "0");
+    set_env_var("COREMGR_MQCHANNELS_RECONNECT_INITIALMILLIS", "0");
+    set_env_var("COREMGR_MQCHANNELS_RECONNECT_MAXMILLIS", "x");
     let conf = config::read_args(&args);
     expect(conf.mq.is_some()).to_equal(true)?;
     expect(conf.mq.as_ref().unwrap().engine.is_some()).to_equal(true)?;
@@ -312,6 +364,12 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     let data_conf = mq_channels_conf.data.as_ref().unwrap();
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     // Test command-line arguments overwrite environment variables.
     let args = vec![
@@ -464,6 +522,11 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(rumqttd.console_port).to_equal(Some(config::DEF_RUMQTTD_CONSOLE_PORT))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.data.is_none()).to_equal(true)?;
+    expect(mq_channels_conf.tls.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         mq: Some(config::Mq {
@@ -542,6 +605,11 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(rumqttd.console_port).to_equal(Some(config::DEF_RUMQTTD_CONSOLE_PORT))?;
     let mq_channels_conf = conf.mq_channels.as_ref().unwrap();
     expect(mq_channels_conf.data.is_none()).to_equal(true)?;
+    expect(mq_channels_conf.tls.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         mq: Some(config::Mq {
@@ -563,6 +631,13 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
             data: Some(config::CoremgrData {
                 ..Default::default()
             }),
+            tls: Some(config::MqTls {
+                ..Default::default()
+            }),
+            reconnect: Some(config::MqReconnect {
+                initial_millis: Some(0),
+                ..Default::default()
+            }),
         }),
         ..Default::default()
     };
@@ -580,6 +655,13 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.url.is_none()).to_equal(true)?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
     expect(data_conf.persistent.unwrap()).to_equal(config::DEF_MQ_PERSISTENT)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         auth: Some("sylvia2".to_string()),
@@ -638,6 +720,17 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 url: Some("url9".to_string()),
                 persistent: Some(false),
             }),
+            tls: Some(config::MqTls {
+                cacert_file: Some("cacert9".to_string()),
+                cert_file: Some("cert9".to_string()),
+                key_file: Some("key9".to_string()),
+                server_name: Some("server9".to_string()),
+                insecure: Some(true),
+            }),
+            reconnect: Some(config::MqReconnect {
+                initial_millis: Some(300),
+                max_millis: Some(30000),
+            }),
         }),
     };
     let conf = config::apply_default(&conf);
@@ -699,7 +792,16 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.url.is_some()).to_equal(true)?;
     expect(data_conf.url.as_ref().unwrap().as_str()).to_equal("url9")?;
     expect(data_conf.persistent.is_some()).to_equal(true)?;
-    expect(data_conf.persistent.unwrap()).to_equal(false)
+    expect(data_conf.persistent.unwrap()).to_equal(false)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert9"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert9"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key9"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server9"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(300))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(30000))
 }
 
 fn set_env_var(key: &str, val: &str) {
@@ -565,8 +565,8 @@ pub fn post_acl(context: &mut SpecContext<TestState>) -> Result<(), String> {
             (StatusCode::OK, body) => match serde_json::from_str::<GetAclBody>(body.as_str()) {
                 Err(e) => return Err(format!("unexpected response: {}, body: {}", e, body)),
                 Ok(resp) => match resp.rules.len() {
-                    4 => (),
-                    _ => return Err(format!("post_acl number wrong: 4/{}", resp.rules.len())),
+                    5 => (),
+                    _ => return Err(format!("post_acl number wrong: 5/{}", resp.rules.len())),
                 },
             },
             (status, body) => {
@@ -43,6 +43,7 @@ struct GetPoliciesDefinition {
     message_ttl: Option<usize>,
     #[serde(rename = "max-length")]
     max_length: Option<usize>,
+    overflow: Option<String>,
 }
 
 pub fn after_each_fn(state: &mut HashMap<&'static str, TestState>) -> () {
@@ -397,6 +398,7 @@ pub fn put_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(10),
             length: Some(20),
+            overflow: Some("reject-publish".to_string()),
         };
         if let Err(e) = rabbitmq::put_vhost(client, opts, host, user).await {
             return Err(format!("put_vhost error: {}", e));
@@ -420,6 +422,10 @@ pub fn put_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
                                 Some(20) => (),
                                 _ => return Err("set length error".to_string()),
                             }
+                            match policy.definition.overflow.as_deref() {
+                                Some("reject-publish") => (),
+                                _ => return Err("set overflow error".to_string()),
+                            }
                         }
                     }
                 }
@@ -434,6 +440,7 @@ pub fn put_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(0),
             length: Some(0),
+            overflow: Some("".to_string()),
         };
         if let Err(e) = rabbitmq::put_policies(client, opts, host, user, &policies).await {
             return Err(format!("put_policies to None error: {}", e));
@@ -454,6 +461,10 @@ pub fn put_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
                                 None => (),
                                 _ => return Err("set length None error".to_string()),
                             }
+                            match policy.definition.overflow {
+                                None => (),
+                                _ => return Err("set overflow None error".to_string()),
+                            }
                             Ok(())
                         }
                     }
@@ -479,6 +490,7 @@ pub fn put_policies_error(context: &mut SpecContext<TestState>) -> Result<(), St
         let mut policies = rabbitmq::BrokerPolicies {
             ttl: Some(0),
             length: Some(0),
+            overflow: Some("".to_string()),
         };
         match rabbitmq::put_policies(client, opts, host, "%2f", &policies).await {
             Err(e) => return Err(format!("unexpected response: {}", e)),
@@ -516,6 +528,7 @@ pub fn get_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(10),
             length: Some(20),
+            overflow: Some("reject-publish".to_string()),
         };
         if let Err(e) = rabbitmq::put_vhost(client, opts, host, user).await {
             return Err(format!("put_vhost error: {}", e));
@@ -530,12 +543,18 @@ pub fn get_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
                     return Err(format!("get TTL {:?}, not 10", value.ttl));
                 } else if value.length != Some(20) {
                     return Err(format!("get length {:?}, not 20", value.length));
+                } else if value.overflow.as_deref() != Some("reject-publish") {
+                    return Err(format!(
+                        "get overflow {:?}, not reject-publish",
+                        value.overflow
+                    ));
                 }
             }
         }
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(0),
             length: Some(0),
+            overflow: Some("".to_string()),
         };
         if let Err(e) = rabbitmq::put_policies(client, opts, host, user, &policies).await {
             return Err(format!("put_policies to None error: {}", e));
@@ -547,6 +566,8 @@ pub fn get_policies(context: &mut SpecContext<TestState>) -> Result<(), String>
                     return Err(format!("get TTL {:?}, not Some(0)", value.ttl));
                 } else if value.length != Some(0) {
                     return Err(format!("get length {:?}, not Some(0)", value.length));
+                } else if value.overflow.as_deref() != Some("") {
+                    return Err(format!("get overflow {:?}, not empty", value.overflow));
                 }
                 Ok(())
             }
@@ -749,6 +770,7 @@ pub fn scenario(context: &mut SpecContext<TestState>) -> Result<(), String> {
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(ttl),
             length: Some(length),
+            overflow: None,
         };
         if let Err(e) = rabbitmq::put_user(client, opts, host, user, pass).await {
             return Err(format!("put_user error: {}", e));
@@ -877,6 +899,7 @@ pub fn scenario(context: &mut SpecContext<TestState>) -> Result<(), String> {
         let policies = rabbitmq::BrokerPolicies {
             ttl: Some(ttl),
             length: Some(length),
+            overflow: None,
         };
         if let Err(e) = rabbitmq::put_policies(client, opts, host, user, &policies).await {
             return Err(format!("put_policies error: {}", e));
@@ -24,7 +24,7 @@ use sylvia_iot_broker::{
     models::{
         self as sylvia_iot_broker_models, application::Application, device::Device,
         device_route::DeviceRoute, dldata_buffer::DlDataBuffer, network::Network,
-        network_route::NetworkRoute, unit::Unit,
+        network_route::NetworkRoute, profile::Profile, unit::Unit,
     },
     routes as sylvia_iot_broker_routes,
 };
@@ -184,6 +184,11 @@ pub fn create_device(unit: &str, network: &str, addr: &str, is_public: bool) ->
         profile: "".to_string(),
         name: addr.to_string(),
         info: Map::<String, Value>::new(),
+        last_uplink_at: None,
+        last_downlink_at: None,
+        last_extension: None,
+        offline_at: None,
+        online: None,
     }
 }
 
@@ -256,6 +261,22 @@ pub fn create_dldata_buffer(
     }
 }
 
+pub fn create_profile(name: &str, unit_id: &str) -> Profile {
+    let now = Utc::now();
+    Profile {
+        profile_id: name.to_string(),
+        code: name.to_string(),
+        unit_id: unit_id.to_string(),
+        unit_code: unit_id.to_string(),
+        created_at: now,
+        modified_at: now,
+        name: name.to_string(),
+        codec: Map::<String, Value>::new(),
+        offline_timeout: 0,
+        info: Map::<String, Value>::new(),
+    }
+}
+
 pub fn create_users_tokens(state: &TestState) -> () {
     let runtime = state.runtime.as_ref().unwrap();
     let auth_db = state.auth_db.as_ref().unwrap();
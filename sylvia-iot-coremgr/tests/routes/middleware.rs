@@ -137,6 +137,12 @@ fn after_all_fn(state: &mut HashMap<&'static str, TestState>) -> () {
                 Connection::Mqtt(mut conn, _) => {
                     let _ = conn.close().await;
                 }
+                Connection::Memory(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
+                Connection::Redis(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
             }
         })
     }
@@ -95,6 +95,8 @@ pub struct PostApplicationDlData {
     #[serde(rename = "deviceId")]
     pub device_id: String,
     pub payload: String,
+    #[serde(rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -1084,6 +1086,7 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
         data: PostApplicationDlData {
             device_id: device.to_string(),
             payload: hex::encode("amqp"),
+            expires_in: Some(60),
         },
     };
     test_dldata(runtime, routes_state, application_id, &body, false)?;
@@ -1128,6 +1131,7 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
         data: PostApplicationDlData {
             device_id: device.to_string(),
             payload: hex::encode("mqtt"),
+            expires_in: None,
         },
     };
     runtime.block_on(async { time::sleep(Duration::from_secs(2)).await });
@@ -1165,6 +1169,7 @@ pub fn dldata_invalid(context: &mut SpecContext<TestState>) -> Result<(), String
         data: PostApplicationDlData {
             device_id: "device".to_string(),
             payload: hex::encode("payload"),
+            expires_in: None,
         },
     };
     let req = server
@@ -1242,8 +1247,19 @@ pub fn dldata_invalid(context: &mut SpecContext<TestState>) -> Result<(), String
         .json(&body);
     test_invalid_param(runtime, req, "err_param")?;
 
-    body.data.device_id = "test".to_string();
     body.data.payload = hex::encode("payload");
+    body.data.expires_in = Some(0);
+    let req = server
+        .post(format!("/coremgr/api/v1/application/{}/dldata", application_id).as_str())
+        .add_header(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(format!("Bearer {}", TOKEN_MANAGER).as_str()).unwrap(),
+        )
+        .json(&body);
+    test_invalid_param(runtime, req, "err_param")?;
+
+    body.data.device_id = "test".to_string();
+    body.data.expires_in = None;
     let req = server
         .post(format!("/coremgr/api/v1/application/{}/dldata", application_id).as_str())
         .add_header(
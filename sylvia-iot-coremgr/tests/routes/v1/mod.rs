@@ -11,8 +11,8 @@ use sylvia_iot_broker::models::Model as BrokerModel;
 use super::{
     libs::{
         TOKEN_MANAGER, create_application, create_client, create_device, create_device_route,
-        create_dldata_buffer, create_network, create_network_route, create_unit, create_user,
-        create_users_tokens, new_state,
+        create_dldata_buffer, create_network, create_network_route, create_profile, create_unit,
+        create_user, create_users_tokens, new_state,
     },
     stop_auth_broker_svc,
 };
@@ -26,6 +26,7 @@ mod device_route;
 mod dldata_buffer;
 mod network;
 mod network_route;
+mod profile;
 mod unit;
 mod user;
 
@@ -125,6 +126,15 @@ pub fn suite(mqtt_engine: &'static str) -> Suite<TestState> {
                 context.it("DELETE /network-route/{routeId}", network_route::delete);
             });
 
+            context.describe("profile", |context| {
+                context.it("POST /profile", profile::post);
+                context.it("GET /profile/count", profile::get_count);
+                context.it("GET /profile/list", profile::get_list);
+                context.it("GET /profile/{profileId}", profile::get);
+                context.it("PATCH /profile/{profileId}", profile::patch);
+                context.it("DELETE /profile/{profileId}", profile::delete);
+            });
+
             context.describe("unit", |context| {
                 context.it("POST /unit", unit::post);
                 context.it("GET /unit/count", unit::get_count);
@@ -295,6 +305,10 @@ fn create_list_rsc(state: &mut HashMap<&'static str, TestState>) -> () {
         if let Err(e) = runtime.block_on(async { broker_db.dldata_buffer().add(&item).await }) {
             panic!("create list dldata buffer error: {}", e);
         }
+        let item = create_profile(name, name);
+        if let Err(e) = runtime.block_on(async { broker_db.profile().add(&item).await }) {
+            panic!("create list profile error: {}", e);
+        }
     }
 }
 
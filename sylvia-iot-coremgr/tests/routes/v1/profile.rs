@@ -0,0 +1,93 @@
+use axum::http::Method;
+use laboratory::SpecContext;
+
+use super::{
+    super::libs::{TOKEN_MANAGER, test_invalid_token, test_list},
+    STATE,
+};
+use crate::TestState;
+
+pub fn post(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::POST,
+        "/coremgr/api/v1/profile",
+    )
+}
+
+pub fn get_count(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::GET,
+        "/coremgr/api/v1/profile/count",
+    )
+}
+
+pub fn get_list(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_list(
+        runtime,
+        routes_state,
+        "/coremgr/api/v1/profile/list",
+        TOKEN_MANAGER,
+        "profileId,code,unitId,unitCode,createdAt,modifiedAt,name,codec,offlineTimeout,info",
+    )
+}
+
+pub fn get(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::GET,
+        "/coremgr/api/v1/profile/id",
+    )
+}
+
+pub fn patch(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::PATCH,
+        "/coremgr/api/v1/profile/id",
+    )
+}
+
+pub fn delete(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let state = context.state.borrow();
+    let state = state.get(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let routes_state = state.routes_state.as_ref().unwrap();
+
+    test_invalid_token(
+        runtime,
+        &routes_state,
+        Method::DELETE,
+        "/coremgr/api/v1/profile/id",
+    )
+}
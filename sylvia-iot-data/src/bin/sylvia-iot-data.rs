@@ -12,7 +12,11 @@ use clap::{Arg as ClapArg, Command};
 use json5;
 use log::{self, error, info};
 use serde::Deserialize;
-use tokio::{self, net::TcpListener};
+use tokio::{
+    self,
+    net::TcpListener,
+    signal::{self, unix::SignalKind},
+};
 use tower_http::{cors::CorsLayer, normalize_path::NormalizePathLayer, timeout::TimeoutLayer};
 
 use sylvia_iot_corelib::{
@@ -32,6 +36,8 @@ const PROJ_NAME: &'static str = env!("CARGO_BIN_NAME");
 const PROJ_VER: &'static str = env!("CARGO_PKG_VERSION");
 const HTTP_PORT: u16 = 4080;
 const HTTPS_PORT: u16 = 4443;
+/// Time to wait for in-process messages and HTTP requests while shutting down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
@@ -50,6 +56,9 @@ async fn main() -> std::io::Result<()> {
     };
 
     logger::init(PROJ_NAME, &conf.log);
+    // Install the recorder before creating queues to export queue metrics.
+    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+    general_mq::metrics::register();
 
     let data_state = match routes::new_state("/data", &conf.data).await {
         Err(e) => {
@@ -58,7 +67,6 @@ async fn main() -> std::io::Result<()> {
         }
         Ok(state) => state,
     };
-    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
 
     let app = Router::new()
         .merge(routes::new_service(&data_state))
@@ -104,24 +112,36 @@ async fn main() -> std::io::Result<()> {
                 None => SocketAddr::V6(SocketAddrV6::new(ipv6_addr, HTTPS_PORT, 0, 0)),
                 Some(port) => SocketAddr::V6(SocketAddrV6::new(ipv6_addr, port, 0, 0)),
             };
+            let http_handle = axum_server::Handle::new();
+            let https_handle = axum_server::Handle::new();
             let http_app = app.clone();
+            let handle = http_handle.clone();
             let http_serv = tokio::spawn(async move {
                 axum_server::bind(http_addr)
+                    .handle(handle)
                     .serve(http_app.into_make_service_with_connect_info::<SocketAddr>())
                     .await
                     .unwrap()
             });
+            let handle = https_handle.clone();
             let https_serv = tokio::spawn(async move {
                 axum_server::bind_rustls(addr, config)
+                    .handle(handle)
                     .serve(app.into_make_service_with_connect_info::<SocketAddr>())
                     .await
                     .unwrap()
             });
+            tokio::spawn(async move {
+                shutdown_signal().await;
+                http_handle.graceful_shutdown(Some(SHUTDOWN_TIMEOUT));
+                https_handle.graceful_shutdown(Some(SHUTDOWN_TIMEOUT));
+            });
             info!(
                 "[{}] running {} service (v{})",
                 FN_NAME, PROJ_NAME, PROJ_VER
             );
             let _ = tokio::join!(http_serv, https_serv);
+            close_mq(&data_state).await;
             return Ok(());
         }
     }
@@ -141,14 +161,42 @@ async fn main() -> std::io::Result<()> {
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await
     {
         error!("[{}] launch server error: {}", FN_NAME, e);
         return Ok(());
     }
+    close_mq(&data_state).await;
     Ok(())
 }
 
+/// To wait for SIGTERM or Ctrl-C.
+async fn shutdown_signal() {
+    let terminate = async {
+        match signal::unix::signal(SignalKind::terminate()) {
+            Err(_) => std::future::pending::<()>().await,
+            Ok(mut terminate) => {
+                let _ = terminate.recv().await;
+            }
+        }
+    };
+    tokio::select! {
+        _ = signal::ctrl_c() => {},
+        _ = terminate => {},
+    }
+}
+
+/// To close message queues after in-process messages are done.
+async fn close_mq(state: &routes::State) {
+    const FN_NAME: &'static str = "close_mq";
+
+    info!("[{}] closing message queues", FN_NAME);
+    if let Err(e) = libs::mq::close_graceful(&state.mq_conns, SHUTDOWN_TIMEOUT).await {
+        error!("[{}] close message queues error: {}", FN_NAME, e);
+    }
+}
+
 fn init_config() -> Result<AppConfig, Box<dyn StdError>> {
     let mut args = Command::new(PROJ_NAME).version(PROJ_VER).arg(
         ClapArg::new("file")
@@ -27,7 +27,7 @@ use crate::models::{
     network_uldata::NetworkUlData,
 };
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 
@@ -41,6 +41,8 @@ struct DataHandler {
 enum RecvDataMsg {
     #[serde(rename = "application-uldata")]
     AppUlData { data: AppUlData },
+    #[serde(rename = "application-uldata-failed")]
+    AppUlDataFailed { data: AppUlData },
     #[serde(rename = "application-dldata")]
     AppDlData { data: AppDlData },
     #[serde(rename = "application-dldata-result")]
@@ -74,6 +76,10 @@ struct AppUlData {
     profile: String,
     data: String,
     extension: Option<Map<String, Value>>,
+    /// The number of retries of `application-uldata-failed` data.
+    retries: Option<u64>,
+    /// The last error of `application-uldata-failed` data.
+    error: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -154,15 +160,27 @@ struct NetDlDataResult {
 }
 
 const QUEUE_NAME: &'static str = "broker.data";
+/// The extension key of application uplink data that the broker failed to send.
+const ULDATA_FAILED_KEY: &'static str = "failed";
 
 /// Create a receive queue to receive data from `broker.data` queue.
 pub fn new(
     model: Arc<dyn Model>,
     mq_conns: &mut HashMap<String, Connection>,
     config: &DataMqConfig,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Queue, Box<dyn StdError>> {
     let handler = Arc::new(DataHandler { model });
-    match new_data_queue(mq_conns, config, QUEUE_NAME, handler.clone(), handler) {
+    match new_data_queue(
+        mq_conns,
+        config,
+        QUEUE_NAME,
+        tls,
+        reconnect,
+        handler.clone(),
+        handler,
+    ) {
         Err(e) => Err(Box::new(IoError::new(ErrorKind::Other, e))),
         Ok(q) => Ok(q),
     }
@@ -294,7 +312,7 @@ impl MessageHandler for DataHandler {
                     return;
                 }
             }
-            RecvDataMsg::AppUlData { data } => {
+            RecvDataMsg::AppUlData { data } | RecvDataMsg::AppUlDataFailed { data } => {
                 let data = ApplicationUlData {
                     data_id: data.data_id,
                     proc: match DateTime::parse_from_rfc3339(data.proc.as_str()) {
@@ -343,7 +361,7 @@ impl MessageHandler for DataHandler {
                     },
                     profile: data.profile,
                     data: data.data,
-                    extension: data.extension,
+                    extension: uldata_extension(data.extension, data.retries, data.error),
                 };
                 let mut is_err = false;
                 if let Err(e) = self.model.application_uldata().add(&data).await {
@@ -515,3 +533,22 @@ impl MessageHandler for DataHandler {
         }
     }
 }
+
+/// To add the `failed` extension with `retries` and `error` into application uplink data that the
+/// broker failed to send.
+fn uldata_extension(
+    extension: Option<Map<String, Value>>,
+    retries: Option<u64>,
+    error: Option<String>,
+) -> Option<Map<String, Value>> {
+    let error = match error {
+        None => return extension,
+        Some(error) => error,
+    };
+    let mut failed = Map::new();
+    failed.insert("retries".to_string(), Value::from(retries.unwrap_or(0)));
+    failed.insert("error".to_string(), Value::String(error));
+    let mut extension = extension.unwrap_or_default();
+    extension.insert(ULDATA_FAILED_KEY.to_string(), Value::Object(failed));
+    Some(extension)
+}
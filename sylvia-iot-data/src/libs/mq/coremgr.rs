@@ -14,7 +14,7 @@ use serde_json::{Map, Value};
 use tokio::time;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy, TlsOptions,
     queue::{EventHandler, GmqQueue, Message, MessageHandler, Status},
 };
 
@@ -66,9 +66,19 @@ pub fn new(
     model: Arc<dyn Model>,
     mq_conns: &mut HashMap<String, Connection>,
     config: &DataMqConfig,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Queue, Box<dyn StdError>> {
     let handler = Arc::new(DataHandler { model });
-    match new_data_queue(mq_conns, config, QUEUE_NAME, handler.clone(), handler) {
+    match new_data_queue(
+        mq_conns,
+        config,
+        QUEUE_NAME,
+        tls,
+        reconnect,
+        handler.clone(),
+        handler,
+    ) {
         Err(e) => Err(Box::new(IoError::new(ErrorKind::Other, e))),
         Ok(q) => Ok(q),
     }
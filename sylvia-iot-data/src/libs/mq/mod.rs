@@ -1,13 +1,18 @@
 use std::{
     collections::HashMap,
+    error::Error as StdError,
     sync::{Arc, Mutex},
+    time::{Duration, Instant},
 };
 
+use futures::future;
 use url::Url;
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MqttConnection, MqttConnectionOptions,
-    MqttQueueOptions, Queue, QueueOptions,
+    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MemoryConnection,
+    MemoryConnectionOptions, MemoryQueueOptions, MqttConnection, MqttConnectionOptions,
+    MqttQueueOptions, Queue, QueueOptions, ReconnectPolicy, RedisConnection,
+    RedisConnectionOptions, RedisQueueOptions, TlsOptions,
     connection::GmqConnection,
     queue::{EventHandler, GmqQueue, MessageHandler},
 };
@@ -22,16 +27,58 @@ use super::config::DataData as DataMqConfig;
 pub enum Connection {
     Amqp(AmqpConnection, Arc<Mutex<isize>>),
     Mqtt(MqttConnection, Arc<Mutex<isize>>),
+    Memory(MemoryConnection, Arc<Mutex<isize>>),
+    Redis(RedisConnection, Arc<Mutex<isize>>),
 }
 
-/// The default prefetch value for AMQP.
+/// The default prefetch value for AMQP and Redis, and the maximum concurrency for MQTT.
 const DEF_PREFETCH: u16 = 100;
 
+/// To close all connections gracefully. All receivers stop receiving and wait for in-process
+/// messages first, and then queues wait for publisher confirms and close. The `timeout` is shared
+/// by all connections.
+pub async fn close_graceful(
+    conn_pool: &HashMap<String, Connection>,
+    timeout: Duration,
+) -> Result<(), Box<dyn StdError + Send + Sync>> {
+    let conns: Vec<Connection> = conn_pool.values().cloned().collect();
+    let deadline = Instant::now() + timeout;
+
+    // Drain all receivers before closing any connection, so that no connection is closed while
+    // messages of other connections are in process.
+    let futures = conns.iter().map(|conn| async move {
+        match conn {
+            Connection::Amqp(conn, _) => conn.drain(timeout).await,
+            Connection::Mqtt(conn, _) => conn.drain(timeout).await,
+            Connection::Memory(conn, _) => conn.drain(timeout).await,
+            Connection::Redis(conn, _) => conn.drain(timeout).await,
+        }
+    });
+    let drained = future::join_all(futures).await;
+
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    let futures = conns.into_iter().map(|conn| async move {
+        match conn {
+            Connection::Amqp(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Mqtt(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Memory(mut conn, _) => conn.close_graceful(timeout).await,
+            Connection::Redis(mut conn, _) => conn.close_graceful(timeout).await,
+        }
+    });
+    let closed = future::join_all(futures).await;
+    for result in drained.into_iter().chain(closed) {
+        result?;
+    }
+    Ok(())
+}
+
 /// To create a reliable unicast queue to receive data messages.
 fn new_data_queue(
     conn_pool: &mut HashMap<String, Connection>,
     config: &DataMqConfig,
     queue_name: &str,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
     handler: Arc<dyn EventHandler>,
     msg_handler: Arc<dyn MessageHandler>,
 ) -> Result<Queue, String> {
@@ -42,7 +89,7 @@ fn new_data_queue(
             Ok(uri) => uri,
         },
     };
-    let conn = get_connection(conn_pool, &host_uri)?;
+    let conn = get_connection(conn_pool, &host_uri, tls, reconnect)?;
     let mut queue = match conn {
         Connection::Amqp(conn, counter) => {
             let opts = QueueOptions::Amqp(
@@ -50,6 +97,7 @@ fn new_data_queue(
                     name: queue_name.to_string(),
                     is_recv: true,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     broadcast: false,
                     prefetch: match config.prefetch {
                         None => DEF_PREFETCH,
@@ -70,8 +118,50 @@ fn new_data_queue(
                     name: queue_name.to_string(),
                     is_recv: true,
                     reliable: true,
+                    reconnect_policy: reconnect.cloned(),
                     broadcast: false,
                     shared_prefix: config.shared_prefix.clone(),
+                    max_concurrency: match config.prefetch {
+                        None => DEF_PREFETCH as usize,
+                        Some(prefetch) => prefetch as usize,
+                    },
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
+        Connection::Memory(conn, counter) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: queue_name.to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                &conn,
+            );
+            {
+                *counter.lock().unwrap() += 1;
+            }
+            Queue::new(opts)?
+        }
+        Connection::Redis(conn, counter) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: queue_name.to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    reconnect_policy: reconnect.cloned(),
+                    broadcast: false,
+                    prefetch: match config.prefetch {
+                        None => DEF_PREFETCH,
+                        Some(prefetch) => prefetch,
+                    },
                     ..Default::default()
                 },
                 &conn,
@@ -91,10 +181,12 @@ fn new_data_queue(
 }
 
 /// Utility function to get the message queue connection instance. A new connection will be created
-/// if the host does not exist.
+/// with `tls` and `reconnect` options if the host does not exist.
 fn get_connection(
     conn_pool: &mut HashMap<String, Connection>,
     host_uri: &Url,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Connection, String> {
     let uri = host_uri.to_string();
     if let Some(conn) = conn_pool.get(&uri) {
@@ -105,6 +197,8 @@ fn get_connection(
         "amqp" | "amqps" => {
             let opts = AmqpConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = AmqpConnection::new(opts)?;
@@ -116,6 +210,8 @@ fn get_connection(
         "mqtt" | "mqtts" => {
             let opts = MqttConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = MqttConnection::new(opts)?;
@@ -124,6 +220,29 @@ fn get_connection(
             conn_pool.insert(uri, conn.clone());
             Ok(conn)
         }
+        "mem" => {
+            let opts = MemoryConnectionOptions {
+                uri: host_uri.to_string(),
+                ..Default::default()
+            };
+            let mut conn = MemoryConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Memory(conn, Arc::new(Mutex::new(0)));
+            conn_pool.insert(uri, conn.clone());
+            Ok(conn)
+        }
+        "redis" | "rediss" => {
+            let opts = RedisConnectionOptions {
+                uri: host_uri.to_string(),
+                reconnect_policy: reconnect.cloned(),
+                ..Default::default()
+            };
+            let mut conn = RedisConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Redis(conn, Arc::new(Mutex::new(0)));
+            conn_pool.insert(uri, conn.clone());
+            Ok(conn)
+        }
         s => Err(format!("unsupport scheme {}", s)),
     }
 }
@@ -3,7 +3,7 @@ use std::{collections::HashMap, error::Error as StdError, sync::Arc};
 use axum::Router;
 use reqwest;
 
-use general_mq::Queue;
+use general_mq::{Queue, ReconnectPolicy, TlsOptions};
 use sylvia_iot_corelib::constants::DbEngine;
 
 use crate::{
@@ -127,14 +127,55 @@ pub fn new_data_receivers(
     ch_conf: &config::MqChannels,
 ) -> Result<HashMap<String, Queue>, Box<dyn StdError>> {
     let mut data_receivers = HashMap::<String, Queue>::new();
+    let mq_tls = new_mq_tls(ch_conf);
+    let mq_reconnect = new_mq_reconnect(ch_conf);
 
     let conf = ch_conf.broker.as_ref().unwrap();
-    let q = mq::broker::new(model.clone(), mq_conns, &conf)?;
+    let q = mq::broker::new(
+        model.clone(),
+        mq_conns,
+        &conf,
+        mq_tls.as_ref(),
+        mq_reconnect.as_ref(),
+    )?;
     data_receivers.insert("broker.data".to_string(), q);
 
     let conf = ch_conf.coremgr.as_ref().unwrap();
-    let q = mq::coremgr::new(model.clone(), mq_conns, &conf)?;
+    let q = mq::coremgr::new(
+        model.clone(),
+        mq_conns,
+        &conf,
+        mq_tls.as_ref(),
+        mq_reconnect.as_ref(),
+    )?;
     data_receivers.insert("coremgr.data".to_string(), q);
 
     Ok(data_receivers)
 }
+
+/// To create TLS options of message channels. Returns `None` if no TLS option is set.
+fn new_mq_tls(ch_conf: &config::MqChannels) -> Option<TlsOptions> {
+    let tls = ch_conf.tls.as_ref()?;
+    TlsOptions::from_config(
+        tls.cacert_file.clone(),
+        tls.cert_file.clone(),
+        tls.key_file.clone(),
+        tls.server_name.clone(),
+        tls.insecure.unwrap_or(config::DEF_MQ_TLS_INSECURE),
+    )
+}
+
+/// To create the reconnect policy with jitter of message channels. Returns `None` to reconnect with
+/// fixed delays if no reconnect option is set.
+fn new_mq_reconnect(ch_conf: &config::MqChannels) -> Option<ReconnectPolicy> {
+    let reconnect = ch_conf.reconnect.as_ref()?;
+    Some(ReconnectPolicy {
+        initial_millis: reconnect
+            .initial_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_INITIAL_MILLIS),
+        max_millis: reconnect
+            .max_millis
+            .unwrap_or(config::DEF_MQ_RECONNECT_MAX_MILLIS),
+        ..Default::default()
+    })
+}
@@ -54,6 +54,16 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.cert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.key_file.is_none()).to_equal(true)?;
+    expect(tls_conf.server_name.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     // Modified default by command-line arguments.
     let args = vec![
@@ -84,6 +94,20 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
         "13",
         "--data.mq-channels.coremgr.sharedprefix",
         "prefix13",
+        "--data.mq-channels.tls.cacertfile",
+        "cacert1",
+        "--data.mq-channels.tls.certfile",
+        "cert1",
+        "--data.mq-channels.tls.keyfile",
+        "key1",
+        "--data.mq-channels.tls.servername",
+        "server1",
+        "--data.mq-channels.tls.insecure",
+        "true",
+        "--data.mq-channels.reconnect.initialmillis",
+        "100",
+        "--data.mq-channels.reconnect.maxmillis",
+        "10000",
     ];
     let args = config::reg_args(Command::new("test")).get_matches_from(args);
     let conf = config::read_args(&args);
@@ -120,6 +144,15 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.prefetch.unwrap()).to_equal(13)?;
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("prefix13")?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert1"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert1"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key1"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server1"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(100))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(10000))?;
 
     // Clear command-line arguments.
     let args = config::reg_args(Command::new("test")).get_matches_from(vec!["test"]);
@@ -137,6 +170,13 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     set_env_var("DATA_MQCHANNELS_COREMGR_URL", "url23");
     set_env_var("DATA_MQCHANNELS_COREMGR_PREFETCH", "23");
     set_env_var("DATA_MQCHANNELS_COREMGR_SHAREDPREFIX", "prefix23");
+    set_env_var("DATA_MQCHANNELS_TLS_CACERTFILE", "cacert2");
+    set_env_var("DATA_MQCHANNELS_TLS_CERTFILE", "cert2");
+    set_env_var("DATA_MQCHANNELS_TLS_KEYFILE", "key2");
+    set_env_var("DATA_MQCHANNELS_TLS_SERVERNAME", "server2");
+    set_env_var("DATA_MQCHANNELS_TLS_INSECURE", "true");
+    set_env_var("DATA_MQCHANNELS_RECONNECT_INITIALMILLIS", "200");
+    set_env_var("DATA_MQCHANNELS_RECONNECT_MAXMILLIS", "20000");
     let conf = config::read_args(&args);
     expect(conf.auth.is_some()).to_equal(true)?;
     expect(conf.auth.as_ref().unwrap().as_str()).to_equal("sylvia21")?;
@@ -171,12 +211,24 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(data_conf.prefetch.unwrap()).to_equal(23)?;
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("prefix23")?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert2"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert2"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key2"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server2"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(200))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(20000))?;
 
     // Test wrong environment variables.
     set_env_var("DATA_DB_ENGINE", "mongodb1");
     set_env_var("DATA_DB_MONGODB_POOLSIZE", "12_000");
     set_env_var("DATA_MQCHANNELS_BROKER_PREFETCH", "12_000");
     set_env_var("DATA_MQCHANNELS_COREMGR_PREFETCH", "12_000");
+    set_env_var("DATA_MQCHANNELS_TLS_INSECURE", "0");
+    set_env_var("DATA_MQCHANNELS_RECONNECT_INITIALMILLIS", "0");
+    set_env_var("DATA_MQCHANNELS_RECONNECT_MAXMILLIS", "x");
     let conf = config::read_args(&args);
     expect(conf.db.is_some()).to_equal(true)?;
     expect(conf.db.as_ref().unwrap().engine.as_ref().unwrap().as_str())
@@ -193,6 +245,12 @@ pub fn read_args(_context: &mut SpecContext<TestState>) -> Result<(), String> {
     let data_conf = mq_channels_conf.coremgr.as_ref().unwrap();
     expect(data_conf.prefetch.is_some()).to_equal(true)?;
     expect(data_conf.prefetch.unwrap()).to_equal(config::DEF_MQ_PREFETCH)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     // Test command-line arguments overwrite environment variables.
     let args = vec![
@@ -315,6 +373,11 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_channels_conf.tls.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         db: Some(config::Db {
@@ -361,6 +424,11 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    expect(mq_channels_conf.tls.is_none()).to_equal(true)?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         auth: Some("sylvia2".to_string()),
@@ -376,6 +444,13 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
             coremgr: Some(config::DataData {
                 ..Default::default()
             }),
+            tls: Some(config::MqTls {
+                ..Default::default()
+            }),
+            reconnect: Some(config::MqReconnect {
+                initial_millis: Some(0),
+                ..Default::default()
+            }),
         }),
     };
     let conf = config::apply_default(&conf);
@@ -406,6 +481,13 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
     expect(data_conf.shared_prefix.as_ref().unwrap().as_str())
         .to_equal(config::DEF_MQ_SHAREDPREFIX)?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.is_none()).to_equal(true)?;
+    expect(tls_conf.insecure).to_equal(Some(config::DEF_MQ_TLS_INSECURE))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis)
+        .to_equal(Some(config::DEF_MQ_RECONNECT_INITIAL_MILLIS))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(config::DEF_MQ_RECONNECT_MAX_MILLIS))?;
 
     let conf = Config {
         auth: Some("sylvia3".to_string()),
@@ -432,6 +514,17 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
                 prefetch: Some(14),
                 shared_prefix: Some("$shared/group4".to_string()),
             }),
+            tls: Some(config::MqTls {
+                cacert_file: Some("cacert9".to_string()),
+                cert_file: Some("cert9".to_string()),
+                key_file: Some("key9".to_string()),
+                server_name: Some("server9".to_string()),
+                insecure: Some(true),
+            }),
+            reconnect: Some(config::MqReconnect {
+                initial_millis: Some(300),
+                max_millis: Some(30000),
+            }),
         }),
     };
     let conf = config::apply_default(&conf);
@@ -467,7 +560,16 @@ pub fn apply_default(_context: &mut SpecContext<TestState>) -> Result<(), String
     expect(data_conf.prefetch.is_some()).to_equal(true)?;
     expect(data_conf.prefetch.unwrap()).to_equal(14)?;
     expect(data_conf.shared_prefix.is_some()).to_equal(true)?;
-    expect(data_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("$shared/group4")
+    expect(data_conf.shared_prefix.as_ref().unwrap().as_str()).to_equal("$shared/group4")?;
+    let tls_conf = mq_channels_conf.tls.as_ref().unwrap();
+    expect(tls_conf.cacert_file.as_deref()).to_equal(Some("cacert9"))?;
+    expect(tls_conf.cert_file.as_deref()).to_equal(Some("cert9"))?;
+    expect(tls_conf.key_file.as_deref()).to_equal(Some("key9"))?;
+    expect(tls_conf.server_name.as_deref()).to_equal(Some("server9"))?;
+    expect(tls_conf.insecure).to_equal(Some(true))?;
+    let reconnect_conf = mq_channels_conf.reconnect.as_ref().unwrap();
+    expect(reconnect_conf.initial_millis).to_equal(Some(300))?;
+    expect(reconnect_conf.max_millis).to_equal(Some(30000))
 }
 
 fn set_env_var(key: &str, val: &str) {
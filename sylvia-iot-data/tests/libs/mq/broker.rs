@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use chrono::{TimeZone, Utc};
-use laboratory::SpecContext;
+use laboratory::{SpecContext, expect};
 use serde::Serialize;
 use serde_json::{Map, Value};
 use tokio::time;
@@ -31,6 +31,7 @@ use crate::{TestState, WAIT_COUNT, WAIT_TICK};
 #[serde(untagged)]
 enum SendDataMsg {
     AppUlData { kind: String, data: AppUlData },
+    AppUlDataFailed { kind: String, data: AppUlDataFailed },
     AppDlData { kind: String, data: AppDlData },
     AppDlDataResult { kind: String, data: AppDlDataResult },
     NetUlData { kind: String, data: NetUlData },
@@ -62,6 +63,14 @@ struct AppUlData {
     extension: Option<Map<String, Value>>,
 }
 
+#[derive(Clone, Serialize)]
+struct AppUlDataFailed {
+    #[serde(flatten)]
+    data: AppUlData,
+    retries: u64,
+    error: String,
+}
+
 #[derive(Clone, Serialize)]
 struct AppDlData {
     #[serde(rename = "dataId")]
@@ -261,7 +270,7 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -272,7 +281,7 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -306,7 +315,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -317,7 +326,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -348,7 +357,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -359,7 +368,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -390,7 +399,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -400,7 +409,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -410,7 +419,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = broker::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -510,6 +519,90 @@ pub fn application_uldata(context: &mut SpecContext<TestState>) -> Result<(), St
     })
 }
 
+/// Test application-uldata-failed kind data.
+pub fn application_uldata_failed(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+
+    create_default_queue(state)?;
+    let send_queue = state.data_queue.as_mut().unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let model = state.sqlite.as_ref().unwrap();
+
+    runtime.block_on(async {
+        let now = Utc::now();
+        let mut extension = Map::new();
+        extension.insert("key".to_string(), Value::String("value".to_string()));
+        let data = SendDataMsg::AppUlDataFailed {
+            kind: "application-uldata-failed".to_string(),
+            data: AppUlDataFailed {
+                data: AppUlData {
+                    data_id: "data_id_failed".to_string(),
+                    proc: strings::time_str(&now),
+                    publish: strings::time_str(&now),
+                    unit_code: None,
+                    network_code: "network_code_failed".to_string(),
+                    network_addr: "network_addr_failed".to_string(),
+                    unit_id: "unit_id_failed".to_string(),
+                    device_id: "device_id_failed".to_string(),
+                    time: strings::time_str(&now),
+                    profile: "profile_failed".to_string(),
+                    data: "03".to_string(),
+                    extension: Some(extension),
+                },
+                retries: 3,
+                error: "not connected".to_string(),
+            },
+        };
+        let payload = match serde_json::to_vec(&data) {
+            Err(e) => return Err(format!("marshal data error: {}", e)),
+            Ok(payload) => payload,
+        };
+        if let Err(e) = send_queue.send_msg(payload).await {
+            return Err(format!("send data error: {}", e));
+        }
+
+        for _ in 0..20 {
+            let cond = AppUlDataCond {
+                device_id: Some("device_id_failed"),
+                ..Default::default()
+            };
+            let opts = AppUlDataOpts {
+                cond: &cond,
+                offset: None,
+                limit: None,
+                sort: None,
+                cursor_max: None,
+            };
+            let list = match model.application_uldata().list(&opts, None).await {
+                Err(e) => return Err(format!("get data error: {}", e)),
+                Ok((list, _cursor)) => list,
+            };
+            let data = match list.first() {
+                None => {
+                    time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+                Some(data) => data,
+            };
+            expect(data.data_id.as_str()).to_equal("data_id_failed")?;
+            let extension = match data.extension.as_ref() {
+                None => return Err("extension should contain the failure".to_string()),
+                Some(extension) => extension,
+            };
+            expect(extension.get("key")).to_equal(Some(&Value::String("value".to_string())))?;
+            let failed = match extension.get("failed") {
+                Some(Value::Object(failed)) => failed,
+                _ => return Err("failed extension should be an object".to_string()),
+            };
+            expect(failed.get("retries")).to_equal(Some(&Value::from(3)))?;
+            return expect(failed.get("error"))
+                .to_equal(Some(&Value::String("not connected".to_string())));
+        }
+        Err("no failed data".to_string())
+    })
+}
+
 /// Test application-uldata kind data with wrong content.
 pub fn application_uldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let mut state = context.state.borrow_mut();
@@ -1113,7 +1206,7 @@ fn create_default_queue(state: &mut TestState) -> Result<(), String> {
                 url: Some(crate::TEST_AMQP_HOST_URI.to_string()),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -1124,7 +1217,7 @@ fn create_default_queue(state: &mut TestState) -> Result<(), String> {
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match broker::new(model, mq_conns, &conf) {
+            match broker::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -177,7 +177,7 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -188,7 +188,7 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -222,7 +222,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -233,7 +233,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -264,7 +264,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 prefetch: Some(1),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -275,7 +275,7 @@ pub fn new_same_host(context: &mut SpecContext<TestState>) -> Result<(), String>
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -306,7 +306,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -316,7 +316,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -326,7 +326,7 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         prefetch: Some(1),
         ..Default::default()
     };
-    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf) {
+    if let Ok(q) = coremgr::new(Arc::new(model.clone()), mq_conns, &conf, None, None) {
         state.recv_queue = Some(q);
         return Err("data queue should not be created".to_string());
     }
@@ -501,7 +501,7 @@ fn create_default_queue(state: &mut TestState) -> Result<(), String> {
                 url: Some(crate::TEST_AMQP_HOST_URI.to_string()),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -512,7 +512,7 @@ fn create_default_queue(state: &mut TestState) -> Result<(), String> {
                 shared_prefix: Some("$share/sylvia-iot-data/".to_string()),
                 ..Default::default()
             };
-            match coremgr::new(model, mq_conns, &conf) {
+            match coremgr::new(model, mq_conns, &conf, None, None) {
                 Err(e) => return Err(e.to_string()),
                 Ok(q) => q,
             }
@@ -36,6 +36,10 @@ pub fn suite(mq_engine: &'static str) -> Suite<TestState> {
             context.it("new() with the same host", broker::new_same_host);
             context.it("new() with wrong opts", broker::new_wrong_opts);
             context.it("application_uldata()", broker::application_uldata);
+            context.it(
+                "application_uldata() with failed data",
+                broker::application_uldata_failed,
+            );
             context.it(
                 "application_uldata() with wrong content",
                 broker::application_uldata_wrong,
@@ -136,6 +140,12 @@ fn after_each_fn(state: &mut HashMap<&'static str, TestState>) -> () {
                     Connection::Mqtt(conn, _) => {
                         let _ = conn.close().await;
                     }
+                    Connection::Memory(conn, _) => {
+                        let _ = conn.close().await;
+                    }
+                    Connection::Redis(conn, _) => {
+                        let _ = conn.close().await;
+                    }
                 }
             }
             conns.clear();
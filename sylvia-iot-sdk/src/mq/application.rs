@@ -15,7 +15,7 @@ use tokio::task;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy,
     queue::{
         EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status as QueueStatus,
     },
@@ -37,6 +37,8 @@ pub struct UlData {
     pub network_addr: String,
     pub is_public: bool,
     pub data: Vec<u8>,
+    /// Data decoded by the codec of the device profile.
+    pub object: Option<Map<String, Value>>,
     pub extension: Option<Map<String, Value>>,
 }
 
@@ -46,7 +48,11 @@ pub struct DlData {
     pub device_id: Option<String>,
     pub network_code: Option<String>,
     pub network_addr: Option<String>,
+    /// Expiration in seconds. None to use the default value of the application or the broker.
+    pub expires_in: Option<i64>,
     pub data: Vec<u8>,
+    /// Data to be encoded by the codec of the device profile when `data` is empty.
+    pub object: Option<Map<String, Value>>,
     pub extension: Option<Map<String, Value>>,
 }
 
@@ -70,6 +76,20 @@ pub struct DlDataResult {
     pub message: Option<String>,
 }
 
+/// Device status event from broker to application.
+pub struct DeviceStatus {
+    pub device_id: String,
+    pub network_id: String,
+    pub network_code: String,
+    pub network_addr: String,
+    pub is_public: bool,
+    pub profile: String,
+    /// Device status. Now only `offline`.
+    pub status: String,
+    pub time: DateTime<Utc>,
+    pub last_uplink_at: Option<DateTime<Utc>>,
+}
+
 /// The manager for application queues.
 #[derive(Clone)]
 pub struct ApplicationMgr {
@@ -83,6 +103,7 @@ pub struct ApplicationMgr {
     dldata: Arc<Mutex<Queue>>,
     dldata_resp: Arc<Mutex<Queue>>,
     dldata_result: Arc<Mutex<Queue>>,
+    device_status: Option<Arc<Mutex<Queue>>>,
 
     status: Arc<Mutex<MgrStatus>>,
     handler: Arc<Mutex<Arc<dyn EventHandler>>>,
@@ -118,6 +139,18 @@ pub trait EventHandler: Send + Sync {
         mgr: &ApplicationMgr,
         data: Box<DlDataResult>,
     ) -> Result<(), ()>;
+
+    /// Fired when a [`DeviceStatus`] data is received. The default implementation ignores it.
+    ///
+    /// Return [`Err`] will NACK the data.
+    /// The data may will be received again depending on the protocol (such as AMQP).
+    async fn on_device_status(
+        &self,
+        _mgr: &ApplicationMgr,
+        _data: Box<DeviceStatus>,
+    ) -> Result<(), ()> {
+        Ok(())
+    }
 }
 
 /// The event handler for [`general_mq::queue::GmqQueue`].
@@ -143,9 +176,29 @@ struct UlDataInner {
     #[serde(rename = "isPublic")]
     is_public: bool,
     data: String,
+    object: Option<Map<String, Value>>,
     extension: Option<Map<String, Value>>,
 }
 
+#[derive(Deserialize)]
+struct DeviceStatusInner {
+    #[serde(rename = "deviceId")]
+    device_id: String,
+    #[serde(rename = "networkId")]
+    network_id: String,
+    #[serde(rename = "networkCode")]
+    network_code: String,
+    #[serde(rename = "networkAddr")]
+    network_addr: String,
+    #[serde(rename = "isPublic")]
+    is_public: bool,
+    profile: String,
+    status: String,
+    time: String,
+    #[serde(rename = "lastUplinkAt")]
+    last_uplink_at: Option<String>,
+}
+
 #[derive(Clone, Serialize)]
 struct DlDataInner<'a> {
     #[serde(rename = "correlationId")]
@@ -156,8 +209,12 @@ struct DlDataInner<'a> {
     network_code: Option<&'a String>,
     #[serde(rename = "networkAddr", skip_serializing_if = "Option::is_none")]
     network_addr: Option<&'a String>,
+    #[serde(rename = "expiresIn", skip_serializing_if = "Option::is_none")]
+    expires_in: Option<i64>,
     data: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    object: &'a Option<Map<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     extension: &'a Option<Map<String, Value>>,
 }
 
@@ -170,16 +227,24 @@ impl ApplicationMgr {
     pub fn new(
         conn_pool: Arc<Mutex<HashMap<String, Connection>>>,
         host_uri: &Url,
-        opts: Options,
+        mut opts: Options,
         handler: Arc<dyn EventHandler>,
     ) -> Result<Self, String> {
         if opts.unit_id.len() == 0 {
             return Err("`unit_id` cannot be empty for application".to_string());
         }
 
-        let conn = get_connection(&conn_pool, host_uri)?;
-
-        let (uldata, dldata, dldata_resp, dldata_result, _) =
+        if opts.reconnect_policy.is_none() {
+            opts.reconnect_policy = Some(ReconnectPolicy::default());
+        }
+        let conn = get_connection(
+            &conn_pool,
+            host_uri,
+            opts.tls.as_ref(),
+            opts.reconnect_policy.as_ref(),
+        )?;
+
+        let (uldata, dldata, dldata_resp, dldata_result, _, device_status) =
             new_data_queues(&conn, &opts, QUEUE_PREFIX, false)?;
 
         let mgr = ApplicationMgr {
@@ -190,6 +255,7 @@ impl ApplicationMgr {
             dldata,
             dldata_resp: dldata_resp.unwrap(),
             dldata_result,
+            device_status,
             status: Arc::new(Mutex::new(MgrStatus::NotReady)),
             handler: Arc::new(Mutex::new(handler)),
         };
@@ -217,12 +283,27 @@ impl ApplicationMgr {
         if let Err(e) = q.connect() {
             return Err(e.to_string());
         }
+        if let Some(q) = mgr.device_status.as_ref() {
+            let mut q = { q.lock().unwrap().clone() };
+            q.set_handler(mq_handler.clone());
+            q.set_msg_handler(mq_handler.clone());
+            if let Err(e) = q.connect() {
+                return Err(e.to_string());
+            }
+        }
+        let count = mgr.queue_count();
         match conn {
             Connection::Amqp(_, counter) => {
-                *counter.lock().unwrap() += 4;
+                *counter.lock().unwrap() += count;
             }
             Connection::Mqtt(_, counter) => {
-                *counter.lock().unwrap() += 4;
+                *counter.lock().unwrap() += count;
+            }
+            Connection::Memory(_, counter) => {
+                *counter.lock().unwrap() += count;
+            }
+            Connection::Redis(_, counter) => {
+                *counter.lock().unwrap() += count;
             }
         }
         Ok(mgr)
@@ -261,6 +342,10 @@ impl ApplicationMgr {
             dldata_resp: { self.dldata_resp.lock().unwrap().status() },
             dldata_result: { self.dldata_result.lock().unwrap().status() },
             ctrl: QueueStatus::Closed,
+            device_status: match self.device_status.as_ref() {
+                None => QueueStatus::Closed,
+                Some(q) => q.lock().unwrap().status(),
+            },
         }
     }
 
@@ -275,8 +360,20 @@ impl ApplicationMgr {
         q.close().await?;
         let mut q = { self.dldata_result.lock().unwrap().clone() };
         q.close().await?;
+        if let Some(q) = self.device_status.as_ref() {
+            let mut q = { q.lock().unwrap().clone() };
+            q.close().await?;
+        }
 
-        remove_connection(&self.conn_pool, &self.host_uri, 4).await
+        remove_connection(&self.conn_pool, &self.host_uri, self.queue_count()).await
+    }
+
+    /// The number of queues of the manager that use the connection.
+    fn queue_count(&self) -> isize {
+        match self.device_status {
+            None => 4,
+            Some(_) => 5,
+        }
     }
 
     /// Send downlink data [`DlData`] to the broker.
@@ -305,7 +402,9 @@ impl ApplicationMgr {
             device_id: data.device_id.as_ref(),
             network_code: data.network_code.as_ref(),
             network_addr: data.network_addr.as_ref(),
+            expires_in: data.expires_in,
             data: hex::encode(&data.data),
+            object: &data.object,
             extension: &data.extension,
         })?;
         let queue = { (*self.dldata.lock().unwrap()).clone() };
@@ -328,6 +427,11 @@ impl QueueEventHandler for MgrMqEventHandler {
             && { self.mgr.dldata.lock().unwrap().status() } == QueueStatus::Connected
             && { self.mgr.dldata_resp.lock().unwrap().status() } == QueueStatus::Connected
             && { self.mgr.dldata_result.lock().unwrap().status() } == QueueStatus::Connected
+            && self
+                .mgr
+                .device_status
+                .as_ref()
+                .is_none_or(|q| q.lock().unwrap().status() == QueueStatus::Connected)
         {
             false => MgrStatus::NotReady,
             true => MgrStatus::Ready,
@@ -396,6 +500,7 @@ impl MessageHandler for MgrMqEventHandler {
                         network_addr: data.network_addr,
                         is_public: data.is_public,
                         data: data_bytes,
+                        object: data.object,
                         extension: data.extension,
                     }
                 }
@@ -431,6 +536,53 @@ impl MessageHandler for MgrMqEventHandler {
                 Err(_) => msg.nack().await,
                 Ok(_) => msg.ack().await,
             };
+        } else if self
+            .mgr
+            .device_status
+            .as_ref()
+            .is_some_and(|q| queue_name.cmp(q.lock().unwrap().name()) == Ordering::Equal)
+        {
+            let data = match serde_json::from_slice::<DeviceStatusInner>(msg.payload()) {
+                Err(_) => {
+                    let _ = msg.ack().await;
+                    return;
+                }
+                Ok(data) => {
+                    let time = match DateTime::parse_from_rfc3339(data.time.as_str()) {
+                        Err(_) => {
+                            let _ = msg.ack().await;
+                            return;
+                        }
+                        Ok(time) => time.into(),
+                    };
+                    let last_uplink_at = match data.last_uplink_at.as_ref() {
+                        None => None,
+                        Some(time) => match DateTime::parse_from_rfc3339(time.as_str()) {
+                            Err(_) => {
+                                let _ = msg.ack().await;
+                                return;
+                            }
+                            Ok(time) => Some(time.into()),
+                        },
+                    };
+                    DeviceStatus {
+                        device_id: data.device_id,
+                        network_id: data.network_id,
+                        network_code: data.network_code,
+                        network_addr: data.network_addr,
+                        is_public: data.is_public,
+                        profile: data.profile,
+                        status: data.status,
+                        time,
+                        last_uplink_at,
+                    }
+                }
+            };
+            let handler = { self.mgr.handler.lock().unwrap().clone() };
+            let _ = match handler.on_device_status(&self.mgr, Box::new(data)).await {
+                Err(_) => msg.nack().await,
+                Ok(_) => msg.ack().await,
+            };
         }
     }
 }
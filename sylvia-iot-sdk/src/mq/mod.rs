@@ -5,6 +5,7 @@
 //! - dldata: downlink data from the application to the broker.
 //! - dldata-resp: the response of downlink data.
 //! - dldata-result: the data process result from the network.
+//! - device-status: the device status events such as offline.
 //!
 //! For networks, the [`network::NetworkMgr`] manages the following kind of queues:
 //! - uldata: device uplink data from the network to the broker.
@@ -15,6 +16,7 @@
 use std::{
     collections::HashMap,
     error::Error as StdError,
+    path::Path,
     sync::{Arc, Mutex},
 };
 
@@ -22,8 +24,11 @@ use serde::{Deserialize, Serialize};
 use url::Url;
 
 use general_mq::{
-    AmqpConnection, AmqpConnectionOptions, AmqpQueueOptions, MqttConnection, MqttConnectionOptions,
-    MqttQueueOptions, Queue, QueueOptions, connection::GmqConnection, queue::Status,
+    AmqpConnection, AmqpConnectionOptions, AmqpOverflow, AmqpQueueOptions, AmqpQueueType,
+    Compression, MemoryConnection, MemoryConnectionOptions, MemoryQueueOptions, MqttConnection,
+    MqttConnectionOptions, MqttQueueOptions, OutboxOptions, Queue, QueueOptions, ReconnectPolicy,
+    RedisConnection, RedisConnectionOptions, RedisQueueOptions, TlsOptions,
+    connection::GmqConnection, queue::Status,
 };
 
 pub mod application;
@@ -34,6 +39,8 @@ pub mod network;
 pub enum Connection {
     Amqp(AmqpConnection, Arc<Mutex<isize>>),
     Mqtt(MqttConnection, Arc<Mutex<isize>>),
+    Memory(MemoryConnection, Arc<Mutex<isize>>),
+    Redis(RedisConnection, Arc<Mutex<isize>>),
 }
 
 /// Manager status.
@@ -57,6 +64,8 @@ pub struct DataMqStatus {
     pub dldata_result: Status,
     /// For `ctrl`.
     pub ctrl: Status,
+    /// For `device-status`.
+    pub device_status: Status,
 }
 
 /// The options of the application/network manager.
@@ -81,10 +90,56 @@ pub struct Options {
     /// MQTT shared queue prefix option.
     #[serde(rename = "sharedPrefix", skip_serializing_if = "Option::is_none")]
     pub shared_prefix: Option<String>,
+    /// AMQP dead-letter option. This **MUST** be the same as the `broker.mq.deadLetter` option of
+    /// the broker.
+    #[serde(rename = "deadLetter", skip_serializing_if = "Option::is_none")]
+    pub dead_letter: Option<bool>,
+    /// TLS options of `amqps` and `mqtts` connections. This is not serialized.
+    #[serde(skip)]
+    pub tls: Option<TlsOptions>,
+    /// The reconnect policy of connections and queues. None to use [`ReconnectPolicy::default`]
+    /// with jittered exponential backoff. This is not serialized.
+    #[serde(skip)]
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// The outbox to keep data of sender queues while disconnected. Each queue uses the
+    /// `[path]/[queue]` directory. This is not serialized.
+    #[serde(skip)]
+    pub outbox: Option<OutboxOptions>,
+    /// The compression algorithm of data of sender queues. This is not serialized.
+    #[serde(skip)]
+    pub compression: Compression,
+    /// AMQP arguments of data queues. These **MUST** be the same as the `broker.mq.queueType`,
+    /// `broker.mq.messageTtl`, `broker.mq.maxLength` and `broker.mq.overflow` options of the
+    /// broker.
+    #[serde(rename = "amqpArgs", default)]
+    pub amqp_args: AmqpQueueArgs,
+    /// To receive device status events with the `device-status` queue (for applications). This
+    /// **MUST** be the same as `info.deviceStatus` of the application.
+    #[serde(rename = "deviceStatus", skip_serializing_if = "Option::is_none")]
+    pub device_status: Option<bool>,
+}
+
+/// AMQP arguments for declaring data queues.
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct AmqpQueueArgs {
+    /// Queue type: `classic` or `quorum`. None for `classic`.
+    #[serde(rename = "queueType", skip_serializing_if = "Option::is_none")]
+    pub queue_type: Option<String>,
+    /// Message TTL in milliseconds.
+    #[serde(rename = "messageTtl", skip_serializing_if = "Option::is_none")]
+    pub message_ttl: Option<u32>,
+    /// Maximum number of ready messages.
+    #[serde(rename = "maxLength", skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<u32>,
+    /// The behavior when a queue reaches `maxLength`: `drop-head`, `reject-publish` or
+    /// `reject-publish-dlx`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub overflow: Option<String>,
 }
 
 /// Support application/network host schemes.
-pub const SUPPORT_SCHEMES: &'static [&'static str] = &["amqp", "amqps", "mqtt", "mqtts"];
+pub const SUPPORT_SCHEMES: &'static [&'static str] =
+    &["amqp", "amqps", "mqtt", "mqtts", "mem", "redis", "rediss"];
 
 /// The default prefetch value for AMQP.
 const DEF_PREFETCH: u16 = 100;
@@ -92,10 +147,12 @@ const DEF_PREFETCH: u16 = 100;
 const DEF_PERSISTENT: bool = false;
 
 /// Utility function to get the message queue connection instance. A new connection will be created
-/// if the host does not exist.
+/// with `tls` and `reconnect` options if the host does not exist.
 fn get_connection(
     conn_pool: &Arc<Mutex<HashMap<String, Connection>>>,
     host_uri: &Url,
+    tls: Option<&TlsOptions>,
+    reconnect: Option<&ReconnectPolicy>,
 ) -> Result<Connection, String> {
     let uri = host_uri.to_string();
     let mut mutex = conn_pool.lock().unwrap();
@@ -107,6 +164,8 @@ fn get_connection(
         "amqp" | "amqps" => {
             let opts = AmqpConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = AmqpConnection::new(opts)?;
@@ -118,6 +177,8 @@ fn get_connection(
         "mqtt" | "mqtts" => {
             let opts = MqttConnectionOptions {
                 uri: host_uri.to_string(),
+                tls: tls.cloned(),
+                reconnect_policy: reconnect.cloned(),
                 ..Default::default()
             };
             let mut conn = MqttConnection::new(opts)?;
@@ -126,6 +187,29 @@ fn get_connection(
             mutex.insert(uri, conn.clone());
             Ok(conn)
         }
+        "mem" => {
+            let opts = MemoryConnectionOptions {
+                uri: host_uri.to_string(),
+                ..Default::default()
+            };
+            let mut conn = MemoryConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Memory(conn, Arc::new(Mutex::new(0)));
+            mutex.insert(uri, conn.clone());
+            Ok(conn)
+        }
+        "redis" | "rediss" => {
+            let opts = RedisConnectionOptions {
+                uri: host_uri.to_string(),
+                reconnect_policy: reconnect.cloned(),
+                ..Default::default()
+            };
+            let mut conn = RedisConnection::new(opts)?;
+            let _ = conn.connect();
+            let conn = Connection::Redis(conn, Arc::new(Mutex::new(0)));
+            mutex.insert(uri, conn.clone());
+            Ok(conn)
+        }
         s => Err(format!("unsupport scheme {}", s)),
     }
 }
@@ -155,6 +239,20 @@ async fn remove_connection(
                         return Ok(());
                     }
                 }
+                Connection::Memory(_, counter) => {
+                    let mut mutex = counter.lock().unwrap();
+                    *mutex -= count;
+                    if *mutex > 0 {
+                        return Ok(());
+                    }
+                }
+                Connection::Redis(_, counter) => {
+                    let mut mutex = counter.lock().unwrap();
+                    *mutex -= count;
+                    if *mutex > 0 {
+                        return Ok(());
+                    }
+                }
             },
         }
         mutex.remove(host_uri)
@@ -167,6 +265,12 @@ async fn remove_connection(
             Connection::Mqtt(mut conn, _) => {
                 conn.close().await?;
             }
+            Connection::Memory(mut conn, _) => {
+                conn.close().await?;
+            }
+            Connection::Redis(mut conn, _) => {
+                conn.close().await?;
+            }
         }
     }
     Ok(())
@@ -177,7 +281,9 @@ async fn remove_connection(
 /// - `[prefix].[unit].[code].dldata`
 /// - `[prefix].[unit].[code].dldata-resp`: `Some` for applications and `None` for networks.
 /// - `[prefix].[unit].[code].dldata-result`
-/// - `[prefix].[unit].[code].ctrl`
+/// - `[prefix].[unit].[code].ctrl`: `Some` for networks and `None` for applications.
+/// - `[prefix].[unit].[code].device-status`: `Some` for applications with the `device_status`
+///   option and `None` for others.
 fn new_data_queues(
     conn: &Connection,
     opts: &Options,
@@ -190,6 +296,7 @@ fn new_data_queues(
         Option<Arc<Mutex<Queue>>>,
         Arc<Mutex<Queue>>,
         Option<Arc<Mutex<Queue>>>,
+        Option<Arc<Mutex<Queue>>>,
     ),
     String,
 > {
@@ -198,6 +305,7 @@ fn new_data_queues(
     let dldata_resp: Option<Arc<Mutex<Queue>>>;
     let dldata_result: Arc<Mutex<Queue>>;
     let ctrl: Option<Arc<Mutex<Queue>>>;
+    let device_status: Option<Arc<Mutex<Queue>>>;
 
     if opts.unit_id.len() == 0 {
         if opts.unit_code.len() != 0 {
@@ -219,6 +327,9 @@ fn new_data_queues(
         0 => "_",
         _ => opts.unit_code.as_str(),
     };
+    let uldata_name = format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str());
+    let dldata_name = format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str());
+    let dldata_result_name = format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str());
 
     match conn {
         Connection::Amqp(conn, _) => {
@@ -233,28 +344,37 @@ fn new_data_queues(
                 None => DEF_PERSISTENT,
                 Some(persistent) => persistent,
             };
+            let args = amqp_queue_args(&opts.amqp_args)?;
 
             let uldata_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    name: uldata_name.clone(),
                     is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, uldata_name.as_str(), is_network),
+                    outbox: outbox_options(opts, uldata_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
             let dldata_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    name: dldata_name.clone(),
                     is_recv: is_network,
+                    dead_letter: dead_letter_name(opts, dldata_name.as_str(), !is_network),
+                    outbox: outbox_options(opts, dldata_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
@@ -263,22 +383,40 @@ fn new_data_queues(
                     name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
                     is_recv: !is_network,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Amqp(
+                AmqpQueueOptions {
+                    name: format!("{}.{}.{}.device-status", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    persistent,
+                    broadcast: false,
+                    prefetch,
+                    ..args.clone()
                 },
                 conn,
             );
             let dldata_result_opts = QueueOptions::Amqp(
                 AmqpQueueOptions {
-                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    name: dldata_result_name.clone(),
                     is_recv: !is_network,
+                    dead_letter: dead_letter_name(opts, dldata_result_name.as_str(), is_network),
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent,
                     broadcast: false,
                     prefetch,
-                    ..Default::default()
+                    ..args.clone()
                 },
                 conn,
             );
@@ -287,6 +425,7 @@ fn new_data_queues(
                     name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
                     is_recv: true,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     persistent,
                     broadcast: false,
                     prefetch,
@@ -305,13 +444,20 @@ fn new_data_queues(
                 false => None,
                 true => Some(Arc::new(Mutex::new(Queue::new(ctrl_opts)?))),
             };
+            device_status = match !is_network && opts.device_status.unwrap_or(false) {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
         }
         Connection::Mqtt(conn, _) => {
             let uldata_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.uldata", prefix, unit, opts.name.as_str()),
+                    name: uldata_name.clone(),
                     is_recv: !is_network,
+                    outbox: outbox_options(opts, uldata_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -320,9 +466,12 @@ fn new_data_queues(
             );
             let dldata_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.dldata", prefix, unit, opts.name.as_str()),
+                    name: dldata_name.clone(),
                     is_recv: is_network,
+                    outbox: outbox_options(opts, dldata_name.as_str(), is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -334,6 +483,19 @@ fn new_data_queues(
                     name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
                     is_recv: !is_network,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    shared_prefix: opts.shared_prefix.clone(),
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Mqtt(
+                MqttQueueOptions {
+                    name: format!("{}.{}.{}.device-status", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -342,9 +504,12 @@ fn new_data_queues(
             );
             let dldata_result_opts = QueueOptions::Mqtt(
                 MqttQueueOptions {
-                    name: format!("{}.{}.{}.dldata-result", prefix, unit, opts.name.as_str()),
+                    name: dldata_result_name.clone(),
                     is_recv: !is_network,
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), !is_network),
+                    compression: opts.compression,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -356,6 +521,7 @@ fn new_data_queues(
                     name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
                     is_recv: true,
                     reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
                     broadcast: false,
                     shared_prefix: opts.shared_prefix.clone(),
                     ..Default::default()
@@ -373,8 +539,255 @@ fn new_data_queues(
                 false => None,
                 true => Some(Arc::new(Mutex::new(Queue::new(ctrl_opts)?))),
             };
+            device_status = match !is_network && opts.device_status.unwrap_or(false) {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
+        }
+        Connection::Memory(conn, _) => {
+            let uldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: uldata_name.clone(),
+                    is_recv: !is_network,
+                    outbox: outbox_options(opts, uldata_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: dldata_name.clone(),
+                    is_recv: is_network,
+                    outbox: outbox_options(opts, dldata_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.device-status", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: dldata_result_name.clone(),
+                    is_recv: !is_network,
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let ctrl_opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Arc::new(Mutex::new(Queue::new(uldata_opts)?));
+            dldata = Arc::new(Mutex::new(Queue::new(dldata_opts)?));
+            dldata_resp = match is_network {
+                false => Some(Arc::new(Mutex::new(Queue::new(dldata_resp_opts)?))),
+                true => None,
+            };
+            dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            ctrl = match is_network {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(ctrl_opts)?))),
+            };
+            device_status = match !is_network && opts.device_status.unwrap_or(false) {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
+        }
+        Connection::Redis(conn, _) => {
+            let prefetch = match opts.prefetch {
+                None => DEF_PREFETCH,
+                Some(prefetch) => match prefetch {
+                    0 => DEF_PREFETCH,
+                    _ => prefetch,
+                },
+            };
+
+            let uldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: uldata_name.clone(),
+                    is_recv: !is_network,
+                    outbox: outbox_options(opts, uldata_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: dldata_name.clone(),
+                    is_recv: is_network,
+                    outbox: outbox_options(opts, dldata_name.as_str(), is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_resp_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.dldata-resp", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let device_status_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.device-status", prefix, unit, opts.name.as_str()),
+                    is_recv: !is_network,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let dldata_result_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: dldata_result_name.clone(),
+                    is_recv: !is_network,
+                    outbox: outbox_options(opts, dldata_result_name.as_str(), !is_network),
+                    compression: opts.compression,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let ctrl_opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: format!("{}.{}.{}.ctrl", prefix, unit, opts.name.as_str()),
+                    is_recv: true,
+                    reliable: true,
+                    reconnect_policy: opts.reconnect_policy.clone(),
+                    broadcast: false,
+                    prefetch,
+                    ..Default::default()
+                },
+                conn,
+            );
+            uldata = Arc::new(Mutex::new(Queue::new(uldata_opts)?));
+            dldata = Arc::new(Mutex::new(Queue::new(dldata_opts)?));
+            dldata_resp = match is_network {
+                false => Some(Arc::new(Mutex::new(Queue::new(dldata_resp_opts)?))),
+                true => None,
+            };
+            dldata_result = Arc::new(Mutex::new(Queue::new(dldata_result_opts)?));
+            ctrl = match is_network {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(ctrl_opts)?))),
+            };
+            device_status = match !is_network && opts.device_status.unwrap_or(false) {
+                false => None,
+                true => Some(Arc::new(Mutex::new(Queue::new(device_status_opts)?))),
+            };
         }
     }
 
-    Ok((uldata, dldata, dldata_resp, dldata_result, ctrl))
+    Ok((
+        uldata,
+        dldata,
+        dldata_resp,
+        dldata_result,
+        ctrl,
+        device_status,
+    ))
+}
+
+/// To get the dead-letter queue name `[queue].dead-letter` of the queue that is received by the
+/// broker. Senders **MUST** declare AMQP queues with the same arguments as the broker.
+fn dead_letter_name(opts: &Options, queue: &str, is_broker_recv: bool) -> Option<String> {
+    match opts.dead_letter.unwrap_or(false) && is_broker_recv {
+        false => None,
+        true => Some(format!("{}.dead-letter", queue)),
+    }
+}
+
+/// To get AMQP queue options with arguments of data queues.
+fn amqp_queue_args(args: &AmqpQueueArgs) -> Result<AmqpQueueOptions, String> {
+    let queue_type = match args.queue_type.as_deref() {
+        None | Some("classic") => AmqpQueueType::Classic,
+        Some("quorum") => AmqpQueueType::Quorum,
+        Some(v) => return Err(format!("invalid AMQP queue type: {}", v)),
+    };
+    let overflow = match args.overflow.as_deref() {
+        None => None,
+        Some("drop-head") => Some(AmqpOverflow::DropHead),
+        Some("reject-publish") => Some(AmqpOverflow::RejectPublish),
+        Some("reject-publish-dlx") => Some(AmqpOverflow::RejectPublishDlx),
+        Some(v) => return Err(format!("invalid AMQP overflow: {}", v)),
+    };
+    Ok(AmqpQueueOptions {
+        queue_type,
+        message_ttl_millis: args.message_ttl,
+        max_length: args.max_length,
+        overflow,
+        ..Default::default()
+    })
+}
+
+/// To get the outbox options with the `[path]/[queue]` directory of the sender queue if the outbox
+/// option is enabled.
+fn outbox_options(opts: &Options, queue: &str, is_recv: bool) -> Option<OutboxOptions> {
+    match opts.outbox.as_ref() {
+        None => None,
+        Some(outbox) => match is_recv {
+            true => None,
+            false => Some(OutboxOptions {
+                path: Path::new(outbox.path.as_str())
+                    .join(queue)
+                    .to_string_lossy()
+                    .to_string(),
+                ..outbox.clone()
+            }),
+        },
+    }
 }
@@ -15,7 +15,7 @@ use tokio::task;
 use url::Url;
 
 use general_mq::{
-    Queue,
+    Queue, ReconnectPolicy,
     queue::{
         EventHandler as QueueEventHandler, GmqQueue, Message, MessageHandler, Status as QueueStatus,
     },
@@ -212,12 +212,20 @@ impl NetworkMgr {
     pub fn new(
         conn_pool: Arc<Mutex<HashMap<String, Connection>>>,
         host_uri: &Url,
-        opts: Options,
+        mut opts: Options,
         handler: Arc<dyn EventHandler>,
     ) -> Result<Self, String> {
-        let conn = get_connection(&conn_pool, host_uri)?;
-
-        let (uldata, dldata, _, dldata_result, ctrl) =
+        if opts.reconnect_policy.is_none() {
+            opts.reconnect_policy = Some(ReconnectPolicy::default());
+        }
+        let conn = get_connection(
+            &conn_pool,
+            host_uri,
+            opts.tls.as_ref(),
+            opts.reconnect_policy.as_ref(),
+        )?;
+
+        let (uldata, dldata, _, dldata_result, ctrl, _) =
             new_data_queues(&conn, &opts, QUEUE_PREFIX, true)?;
 
         let mgr = NetworkMgr {
@@ -261,6 +269,12 @@ impl NetworkMgr {
             Connection::Mqtt(_, counter) => {
                 *counter.lock().unwrap() += 4;
             }
+            Connection::Memory(_, counter) => {
+                *counter.lock().unwrap() += 4;
+            }
+            Connection::Redis(_, counter) => {
+                *counter.lock().unwrap() += 4;
+            }
         }
         Ok(mgr)
     }
@@ -290,7 +304,7 @@ impl NetworkMgr {
         *self.status.lock().unwrap()
     }
 
-    /// Detail status of each message queue. Please ignore `dldata_resp`.
+    /// Detail status of each message queue. Please ignore `dldata_resp` and `device_status`.
     pub fn mq_status(&self) -> DataMqStatus {
         DataMqStatus {
             uldata: { self.uldata.lock().unwrap().status() },
@@ -298,6 +312,7 @@ impl NetworkMgr {
             dldata_resp: QueueStatus::Closed,
             dldata_result: { self.dldata_result.lock().unwrap().status() },
             ctrl: { self.ctrl.lock().unwrap().status() },
+            device_status: QueueStatus::Closed,
         }
     }
 
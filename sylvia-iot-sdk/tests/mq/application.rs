@@ -14,7 +14,7 @@ use serde_json::{self, Map, Value};
 use tokio::time;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, RedisQueueOptions,
     queue::{
         EventHandler as MqEventHandler, GmqQueue, Message, MessageHandler as MqMessageHandler,
         Status as MqStatus,
@@ -22,7 +22,7 @@ use general_mq::{
 };
 use sylvia_iot_sdk::{
     mq::{
-        Connection, MgrStatus, Options,
+        AmqpQueueArgs, Connection, MgrStatus, Options,
         application::{ApplicationMgr, DlData, DlDataResp, DlDataResult, EventHandler, UlData},
     },
     util::strings,
@@ -65,6 +65,8 @@ struct AppDlData {
     network_code: Option<String>,
     #[serde(rename = "networkAddr")]
     network_addr: Option<String>,
+    #[serde(rename = "expiresIn")]
+    expires_in: Option<i64>,
     data: String,
     extension: Option<Map<String, Value>>,
 }
@@ -251,6 +253,7 @@ pub fn new_default(context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_status.dldata == MqStatus::Connected).equals(true)?;
     expect(mq_status.dldata_resp == MqStatus::Connected).equals(true)?;
     expect(mq_status.dldata_result == MqStatus::Connected).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Closed).equals(true)?;
 
     Ok(())
 }
@@ -294,6 +297,42 @@ pub fn new_manual(context: &mut SpecContext<TestState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Test new managers with the `device-status` queue.
+pub fn new_device_status(context: &mut SpecContext<TestState>) -> Result<(), String> {
+    let mut state = context.state.borrow_mut();
+    let state = state.get_mut(STATE).unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+    let mq_engine = state.mq_engine.as_ref().unwrap().as_str();
+
+    let conn_pool = Arc::new(Mutex::new(HashMap::new()));
+    let host_uri = conn_host_uri(mq_engine)?;
+    let handler = Arc::new(TestHandler::new());
+
+    let opts = Options {
+        unit_id: "unit_id".to_string(),
+        unit_code: "unit_code".to_string(),
+        id: "id_application".to_string(),
+        name: "code_application".to_string(),
+        shared_prefix: state.mqtt_shared_prefix.clone(),
+        device_status: Some(true),
+        ..Default::default()
+    };
+    let mgr = ApplicationMgr::new(conn_pool, &host_uri, opts, handler.clone())?;
+    state.app_mgrs = Some(vec![mgr.clone()]);
+
+    for _ in 0..WAIT_COUNT {
+        if *handler.status_changed.lock().unwrap() {
+            break;
+        }
+        runtime.block_on(async { time::sleep(Duration::from_millis(WAIT_TICK)).await });
+    }
+    let status = mgr.status();
+    let mq_status = mgr.mq_status();
+    expect(status == MgrStatus::Ready).equals(true)?;
+    expect(mq_status.uldata == MqStatus::Connected).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Connected).equals(true)
+}
+
 /// Test new managers with wrong options.
 pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String> {
     let mut state = context.state.borrow_mut();
@@ -336,6 +375,22 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         id: "id".to_string(),
         ..Default::default()
     };
+    expect(ApplicationMgr::new(conn_pool.clone(), &host_uri, opts, handler.clone()).is_err())
+        .equals(true)?;
+    if mq_engine != MqEngine::RABBITMQ {
+        return Ok(());
+    }
+    let opts = Options {
+        unit_id: "unit_id".to_string(),
+        unit_code: "unit_code".to_string(),
+        id: "id".to_string(),
+        name: "name".to_string(),
+        amqp_args: AmqpQueueArgs {
+            queue_type: Some("stream".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
     expect(ApplicationMgr::new(conn_pool.clone(), &host_uri, opts, handler.clone()).is_err())
         .equals(true)
 }
@@ -428,6 +483,40 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -639,6 +728,40 @@ pub fn uldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.uldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -777,6 +900,44 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -806,7 +967,9 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             device_id: Some("device1".to_string()),
             network_code: None,
             network_addr: None,
+            expires_in: Some(60),
             data: vec![1],
+            object: None,
             extension: Some(ext),
         };
         if let Err(e) = mgr.send_dldata(&data1) {
@@ -817,7 +980,9 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             device_id: None,
             network_code: Some("code".to_string()),
             network_addr: Some("addr2".to_string()),
+            expires_in: None,
             data: vec![2],
+            object: None,
             extension: None,
         };
         if let Err(e) = mgr.send_dldata(&data2) {
@@ -847,12 +1012,14 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
                 expect(data.device_id.as_ref()).equals(data1.device_id.as_ref())?;
                 expect(data.network_code.as_ref()).equals(data1.network_code.as_ref())?;
                 expect(data.network_addr.as_ref()).equals(data1.network_addr.as_ref())?;
+                expect(data.expires_in).equals(data1.expires_in)?;
                 expect(data.data.as_str()).equals(hex::encode(&data1.data).as_str())?;
                 expect(data.extension.as_ref()).equals(data1.extension.as_ref())?;
             } else if correlation_id == "2" {
                 expect(data.device_id.as_ref()).equals(data2.device_id.as_ref())?;
                 expect(data.network_code.as_ref()).equals(data2.network_code.as_ref())?;
                 expect(data.network_addr.as_ref()).equals(data2.network_addr.as_ref())?;
+                expect(data.expires_in).equals(data2.expires_in)?;
                 expect(data.data.as_str()).equals(hex::encode(&data2.data).as_str())?;
                 expect(data.extension.as_ref()).equals(data2.extension.as_ref())?;
             } else {
@@ -904,7 +1071,9 @@ pub fn dldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
             device_id: Some("device".to_string()),
             network_code: None,
             network_addr: None,
+            expires_in: None,
             data: vec![0],
+            object: None,
             extension: None,
         };
         expect(mgr.send_dldata(&data).is_err()).equals(true)?;
@@ -985,6 +1154,40 @@ pub fn dldata_resp(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -1153,6 +1356,40 @@ pub fn dldata_resp_wrong(context: &mut SpecContext<TestState>) -> Result<(), Str
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-resp".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-resp queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -1247,6 +1484,40 @@ pub fn dldata_result(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -1409,6 +1680,40 @@ pub fn dldata_result_wrong(context: &mut SpecContext<TestState>) -> Result<(), S
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.application.unit_code.code_application.dldata-result".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -41,6 +41,10 @@ pub fn suite(mq_engine: &'static str) -> Suite<TestState> {
         context.describe("ApplicationMgr", |context| {
             context.it("new() with default options", application::new_default);
             context.it("new() with manual options", application::new_manual);
+            context.it(
+                "new() with the device-status queue",
+                application::new_device_status,
+            );
             context.it("new() with wrong opts", application::new_wrong_opts);
             context.it("close()", application::close);
 
@@ -176,6 +180,12 @@ fn after_each_fn(state: &mut HashMap<&'static str, TestState>) -> () {
                     Connection::Mqtt(mut conn, _) => {
                         let _ = conn.close().await;
                     }
+                    Connection::Memory(mut conn, _) => {
+                        let _ = conn.close().await;
+                    }
+                    Connection::Redis(mut conn, _) => {
+                        let _ = conn.close().await;
+                    }
                 }
             }
             (*mq_conns.lock().unwrap()).clear();
@@ -197,6 +207,12 @@ fn after_each_fn(state: &mut HashMap<&'static str, TestState>) -> () {
                 Connection::Mqtt(mut conn, _) => {
                     let _ = conn.close().await;
                 }
+                Connection::Memory(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
+                Connection::Redis(mut conn, _) => {
+                    let _ = conn.close().await;
+                }
             }
         });
     }
@@ -13,7 +13,7 @@ use serde_json::{self, Map, Value};
 use tokio::time;
 
 use general_mq::{
-    AmqpQueueOptions, MqttQueueOptions, Queue, QueueOptions,
+    AmqpQueueOptions, MemoryQueueOptions, MqttQueueOptions, Queue, QueueOptions, RedisQueueOptions,
     queue::{
         EventHandler as MqEventHandler, GmqQueue, Message, MessageHandler as MqMessageHandler,
         Status as MqStatus,
@@ -21,7 +21,7 @@ use general_mq::{
 };
 use sylvia_iot_sdk::{
     mq::{
-        Connection, MgrStatus, Options,
+        AmqpQueueArgs, Connection, MgrStatus, Options,
         network::{DlData, DlDataResult, EventHandler, NetworkCtrlMsg, NetworkMgr, UlData},
     },
     util::strings,
@@ -315,6 +315,7 @@ pub fn new_default(context: &mut SpecContext<TestState>) -> Result<(), String> {
     expect(mq_status.dldata_resp == MqStatus::Closed).equals(true)?;
     expect(mq_status.dldata_result == MqStatus::Connected).equals(true)?;
     expect(mq_status.ctrl == MqStatus::Connected).equals(true)?;
+    expect(mq_status.device_status == MqStatus::Closed).equals(true)?;
 
     Ok(())
 }
@@ -407,6 +408,22 @@ pub fn new_wrong_opts(context: &mut SpecContext<TestState>) -> Result<(), String
         id: "id".to_string(),
         ..Default::default()
     };
+    expect(NetworkMgr::new(conn_pool.clone(), &host_uri, opts, handler.clone()).is_err())
+        .equals(true)?;
+    if mq_engine != MqEngine::RABBITMQ {
+        return Ok(());
+    }
+    let opts = Options {
+        unit_id: "unit_id".to_string(),
+        unit_code: "unit_code".to_string(),
+        id: "id".to_string(),
+        name: "name".to_string(),
+        amqp_args: AmqpQueueArgs {
+            queue_type: Some("stream".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
     expect(NetworkMgr::new(conn_pool.clone(), &host_uri, opts, handler.clone()).is_err())
         .equals(true)
 }
@@ -504,6 +521,44 @@ pub fn uldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.uldata".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect uldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -697,6 +752,40 @@ pub fn dldata(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -884,6 +973,40 @@ pub fn dldata_wrong(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -999,6 +1122,44 @@ pub fn dldata_result(context: &mut SpecContext<TestState>) -> Result<(), String>
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.dldata-result".to_string(),
+                    is_recv: true,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            queue_result.set_handler(Arc::new(handler.clone()));
+            queue_result.set_msg_handler(Arc::new(handler.clone()));
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect dldata-result queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -1182,6 +1343,40 @@ pub fn ctrl(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {
@@ -1435,6 +1630,40 @@ pub fn ctrl_wrong(context: &mut SpecContext<TestState>) -> Result<(), String> {
             }
             queue_result
         }
+        Connection::Memory(conn, _) => {
+            let opts = QueueOptions::Memory(
+                MemoryQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
+        Connection::Redis(conn, _) => {
+            let opts = QueueOptions::Redis(
+                RedisQueueOptions {
+                    name: "broker.network.unit_code.code_network.ctrl".to_string(),
+                    is_recv: false,
+                    reliable: true,
+                    broadcast: false,
+                    ..Default::default()
+                },
+                conn,
+            );
+            let mut queue_result = Queue::new(opts)?;
+            if let Err(e) = queue_result.connect() {
+                return Err(format!("connect ctrl queue error: {}", e));
+            }
+            queue_result
+        }
     };
 
     runtime.block_on(async move {